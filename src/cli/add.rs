@@ -1,21 +1,48 @@
 //! Add metadata to existing sidecar files
 
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use owo_colors::OwoColorize;
+use crate::tag_vocabulary::TagVocabulary;
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
 use serde_yaml;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::{Verbosity, create_multi_progress};
+use zim_studio::utils::project::find_project_dir;
+use zim_studio::utils::sidecar::{
+    SidecarLocation, audio_path_for_sidecar, get_sidecar_path, split_frontmatter,
+    update_frontmatter_fields, write_sidecar,
+};
 use zim_studio::utils::validation::validate_path_exists;
 
+/// Replaced with the take number from each file's name when stamping
+const TAKE_PLACEHOLDER: &str = "{{take}}";
+
+/// Fields that identify the audio file, which a stamp never overwrites
+const STAMP_PROTECTED_FIELDS: &[&str] = &["file", "path", "uuid"];
+
+/// Existing tags listed for a new tag that looks like a near-duplicate
+const MAX_TAG_SUGGESTIONS: usize = 5;
+
 /// Handle the 'add tag' command
-pub fn handle_add_tag(path: &str, tags: &[String], recursive: bool) -> Result<(), Box<dyn Error>> {
+pub fn handle_add_tag(
+    path: &str,
+    tags: &[String],
+    recursive: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
     let path = Path::new(path);
 
     validate_path_exists(path)?;
 
-    let mp = MultiProgress::new();
+    if !verbosity.is_quiet() {
+        suggest_existing_tags(path, tags);
+    }
+
+    let mp = create_multi_progress(verbosity);
     let spinner_style = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
         .unwrap()
         .tick_chars("⣾⣽⣻⢿⡿⣟⣯⣷");
@@ -38,7 +65,9 @@ pub fn handle_add_tag(path: &str, tags: &[String], recursive: bool) -> Result<()
             tags.len(),
             path.display()
         ));
-        println!("{} Added tags to: {}", "✓".green().bold(), path.display());
+        if !verbosity.is_quiet() {
+            println!("{} Added tags to: {}", "✓".green().bold(), path.display());
+        }
     } else {
         // Handle directory
         let pb = mp.add(ProgressBar::new_spinner());
@@ -98,17 +127,228 @@ pub fn handle_add_tag(path: &str, tags: &[String], recursive: bool) -> Result<()
 
         pb.finish_with_message("Done");
 
+        if !verbosity.is_quiet() {
+            println!(
+                "{} Added tags to {} file(s) ({} errors)",
+                "✓".green().bold(),
+                success_count,
+                error_count
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Point out tags new to the project that look like near-duplicates of tags
+/// it already uses, so the vocabulary stays consistent
+fn suggest_existing_tags(path: &Path, tags: &[String]) {
+    let Ok(path) = fs::canonicalize(path) else {
+        return;
+    };
+    // Search from inside a directory so the directory itself can be the project
+    let probe = if path.is_dir() {
+        path.join(".zimignore")
+    } else {
+        path.clone()
+    };
+    let Some(root) = find_project_dir(&probe).or_else(|| probe.parent().map(Path::to_path_buf))
+    else {
+        return;
+    };
+    let Ok(vocabulary) = TagVocabulary::scan(&root) else {
+        return;
+    };
+
+    for tag in tags.iter().filter(|tag| !vocabulary.contains(tag)) {
+        let existing: Vec<String> = vocabulary
+            .similar(tag)
+            .iter()
+            .take(MAX_TAG_SUGGESTIONS)
+            .map(|(existing, count)| format!("{existing} ({count})"))
+            .collect();
+        if !existing.is_empty() {
+            println!(
+                "{} '{}' is new to this project; existing tags: {}",
+                "Note:".yellow(),
+                tag,
+                existing.join(", ")
+            );
+        }
+    }
+}
+
+/// Handle the 'add stamp' command: fill a template sidecar's `{{take}}`
+/// placeholders from each file's name and merge it into that file's sidecar
+pub fn handle_add_stamp(
+    template: &str,
+    files: &[String],
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let template_path = Path::new(template);
+    validate_path_exists(template_path)?;
+    let mut template = fs::read_to_string(template_path)?;
+    if !template.ends_with('\n') {
+        template.push('\n');
+    }
+    // Catch a broken template before touching any sidecar
+    parse_stamp(&fill_template(&template, 1))
+        .map_err(|e| format!("Invalid template {}: {e}", template_path.display()))?;
+
+    let sidecar_location = SidecarLocation::from_config();
+    let mut stamped = 0;
+    let mut skipped = 0;
+    for file in files {
+        let path = Path::new(file);
+        let audio = if path.extension().is_some_and(|ext| ext == "md") {
+            audio_path_for_sidecar(path)
+        } else {
+            path.to_path_buf()
+        };
+        let skip = |reason: &str| {
+            println!(
+                "  {} {}",
+                format!("{:<8}", "skip").yellow(),
+                format!("{file} ({reason})").bright_black()
+            );
+        };
+
+        let Some(take) = take_number(&audio) else {
+            skip("no take number in the filename");
+            skipped += 1;
+            continue;
+        };
+        let sidecar = get_sidecar_path(&audio, sidecar_location);
+        if !sidecar.is_file() {
+            skip("no sidecar, run zim update first");
+            skipped += 1;
+            continue;
+        }
+
+        let label = format!("{:<8}", format!("take {take}"));
+        if dry_run {
+            println!("  {} {}", label.cyan(), sidecar.display());
+            stamped += 1;
+            continue;
+        }
+        let result = fs::read_to_string(&sidecar)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|content| stamp_sidecar(&content, &fill_template(&template, take)))
+            .and_then(|content| Ok(write_sidecar(&sidecar, &content)?));
+        match result {
+            Ok(()) => {
+                println!("  {} {}", label.green(), sidecar.display());
+                stamped += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to update {}: {}",
+                    "✗".red(),
+                    sidecar.display(),
+                    e
+                );
+                skipped += 1;
+            }
+        }
+    }
+
+    if dry_run {
         println!(
-            "{} Added tags to {} file(s) ({} errors)",
-            "✓".green().bold(),
-            success_count,
-            error_count
+            "{} Would stamp {stamped} sidecar(s) ({skipped} skipped, nothing written)",
+            "→".cyan().bold()
+        );
+    } else {
+        println!(
+            "{} Stamped {stamped} sidecar(s) ({skipped} skipped)",
+            "✓".green().bold()
         );
     }
-
     Ok(())
 }
 
+/// The take number in a filename: the number right after `take` or `tk`
+/// (`bass_take07_48k.wav` is take 7), otherwise a number ending the stem
+/// after `_`, `-` or a space (`vox_12.wav` is take 12). Other numbers, such
+/// as the 48 in `bass_48k.wav`, aren't take numbers.
+fn take_number(audio: &Path) -> Option<u32> {
+    static TAKE: OnceLock<Regex> = OnceLock::new();
+    static TRAILING: OnceLock<Regex> = OnceLock::new();
+    let take = TAKE.get_or_init(|| Regex::new(r"(?:take|tk)[_\-. ]*(\d+)").unwrap());
+    let trailing = TRAILING.get_or_init(|| Regex::new(r"[_\- ](\d+)$").unwrap());
+
+    let stem = audio.file_stem()?.to_string_lossy().to_lowercase();
+    take.captures(&stem)
+        .or_else(|| trailing.captures(&stem))
+        .and_then(|captures| captures[1].parse().ok())
+}
+
+fn fill_template(template: &str, take: u32) -> String {
+    template.replace(TAKE_PLACEHOLDER, &take.to_string())
+}
+
+/// A filled template's frontmatter fields and markdown body
+fn parse_stamp(stamp: &str) -> Result<(serde_yaml::Mapping, &str), Box<dyn Error>> {
+    let (yaml, body) = split_frontmatter(stamp).ok_or("no YAML frontmatter")?;
+    let fields = match serde_yaml::from_str(yaml)? {
+        serde_yaml::Value::Mapping(fields) => fields,
+        serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+        _ => return Err("frontmatter is not a YAML mapping".into()),
+    };
+    Ok((fields, body))
+}
+
+/// Merge a filled template into a sidecar. Template fields replace the
+/// sidecar's, except tags, which are added to the existing ones, and the
+/// fields naming the audio file. The sidecar's notes are kept, with the
+/// template's body added below them unless they already contain it.
+fn stamp_sidecar(content: &str, stamp: &str) -> Result<String, Box<dyn Error>> {
+    let (fields, stamp_body) = parse_stamp(stamp)?;
+    let (yaml, _) = split_frontmatter(content).ok_or("Sidecar file has no YAML frontmatter")?;
+    let existing: serde_yaml::Value = serde_yaml::from_str(yaml)?;
+
+    let mut updates = Vec::new();
+    for (key, value) in fields {
+        let Some(key) = key.as_str().map(str::to_string) else {
+            continue;
+        };
+        if STAMP_PROTECTED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match (key.as_str(), existing.get("tags"), value) {
+            ("tags", Some(serde_yaml::Value::Sequence(tags)), serde_yaml::Value::Sequence(new)) => {
+                let mut tags = tags.clone();
+                for tag in new {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+                serde_yaml::Value::Sequence(tags)
+            }
+            (_, _, value) => value,
+        };
+        updates.push((key, value));
+    }
+    let updated = update_frontmatter_fields(
+        content,
+        updates
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect(),
+    )?;
+
+    let stamp_body = stamp_body.trim();
+    let (_, body) = split_frontmatter(&updated).ok_or("Sidecar file has no YAML frontmatter")?;
+    if stamp_body.is_empty() || body.contains(stamp_body) {
+        return Ok(updated);
+    }
+    let separator = if updated.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    Ok(format!("{updated}{separator}{stamp_body}\n"))
+}
+
 /// Collect markdown files only in the specified directory (not recursive)
 fn collect_markdown_files_in_dir(
     dir: &Path,
@@ -158,49 +398,22 @@ fn collect_markdown_files_recursive(
     Ok(())
 }
 
-/// Add tags to a single sidecar file
+/// Add tags to a sidecar. Other fields keep their values and order, so
+/// block-style lists such as `art` entries come through intact.
 fn add_tags_to_file(path: &Path, new_tags: &[String]) -> Result<(), Box<dyn Error>> {
     let content = fs::read_to_string(path)?;
-
-    // Check if file has YAML frontmatter
-    if !content.starts_with("---\n") {
-        return Err("File does not have YAML frontmatter".into());
-    }
-
-    // Find the end of the frontmatter
-    let frontmatter_end_pos = content[4..]
-        .find("---\n")
-        .ok_or("Invalid YAML frontmatter: no closing delimiter")?
-        + 4;
-
-    let yaml_section = &content[4..frontmatter_end_pos];
-    let markdown_section = &content[frontmatter_end_pos + 4..];
-
-    // Parse YAML
-    let mut yaml_data: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml_section)?;
+    let (yaml_section, _) =
+        split_frontmatter(&content).ok_or("File does not have valid YAML frontmatter")?;
+    let yaml_data: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml_section)?;
 
     // Get existing tags or create empty set
-    let existing_tags: HashSet<String> = if let Some(tags_value) = yaml_data.get("tags") {
-        if let serde_yaml::Value::Sequence(tags_seq) = tags_value {
-            tags_seq
-                .iter()
-                .filter_map(|v| {
-                    if let serde_yaml::Value::String(s) = v {
-                        Some(s.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect()
-        } else if let serde_yaml::Value::String(single_tag) = tags_value {
-            let mut set = HashSet::new();
-            set.insert(single_tag.clone());
-            set
-        } else {
-            HashSet::new()
-        }
-    } else {
-        HashSet::new()
+    let existing_tags: HashSet<String> = match yaml_data.get("tags") {
+        Some(serde_yaml::Value::Sequence(tags_seq)) => tags_seq
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(serde_yaml::Value::String(single_tag)) => HashSet::from([single_tag.clone()]),
+        _ => HashSet::new(),
     };
 
     // Combine existing and new tags
@@ -213,24 +426,18 @@ fn add_tags_to_file(path: &Path, new_tags: &[String]) -> Result<(), Box<dyn Erro
     let mut tags_list: Vec<String> = all_tags.into_iter().collect();
     tags_list.sort();
 
-    // Update YAML data
-    yaml_data.insert(
-        "tags".to_string(),
-        serde_yaml::Value::Sequence(
-            tags_list
-                .into_iter()
-                .map(serde_yaml::Value::String)
-                .collect(),
-        ),
-    );
-
-    // Serialize back to YAML string
-    let updated_yaml = serde_yaml::to_string(&yaml_data)?;
-
-    // Reconstruct the content
-    let updated_content = format!("---\n{updated_yaml}---\n{markdown_section}");
-
-    // Write back to file
+    let updated_content = update_frontmatter_fields(
+        &content,
+        vec![(
+            "tags",
+            serde_yaml::Value::Sequence(
+                tags_list
+                    .into_iter()
+                    .map(serde_yaml::Value::String)
+                    .collect(),
+            ),
+        )],
+    )?;
     fs::write(path, updated_content)?;
 
     Ok(())
@@ -299,4 +506,110 @@ tags:
         let existing1_count = result.matches("- existing1").count();
         assert_eq!(existing1_count, 1);
     }
+
+    #[test]
+    fn test_add_tags_keeps_block_art() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("song.flac.md");
+        fs::write(
+            &file_path,
+            r#"---
+file: "song.flac"
+title: "Song"
+tags: ["mix"]
+art:
+  - path: "song.flac.cover.jpg"
+    description: "Embedded cover art"
+    purpose: "cover_art"
+duration: 180.50
+---
+
+# Notes
+"#,
+        )
+        .unwrap();
+
+        add_tags_to_file(&file_path, &["final".to_string()]).unwrap();
+
+        let result = fs::read_to_string(&file_path).unwrap();
+        let (yaml, body) = split_frontmatter(&result).unwrap();
+        let fields: serde_yaml::Mapping = serde_yaml::from_str(yaml).unwrap();
+        let keys: Vec<&str> = fields.keys().filter_map(|k| k.as_str()).collect();
+        assert_eq!(keys, ["file", "title", "tags", "art", "duration"]);
+        assert_eq!(
+            fields["tags"],
+            serde_yaml::Value::from(vec!["final", "mix"])
+        );
+        let art = fields["art"].as_sequence().unwrap();
+        assert_eq!(art.len(), 1);
+        assert_eq!(art[0]["path"].as_str(), Some("song.flac.cover.jpg"));
+        assert_eq!(art[0]["purpose"].as_str(), Some("cover_art"));
+        assert_eq!(body, "\n# Notes\n");
+    }
+
+    #[test]
+    fn test_take_number() {
+        let take = |name: &str| take_number(Path::new(name));
+        assert_eq!(take("bass_take07_48k.wav"), Some(7));
+        assert_eq!(take("Take 3.wav"), Some(3));
+        assert_eq!(take("kick_tk2_v5.flac"), Some(2));
+        assert_eq!(take("vox-12.wav"), Some(12));
+        assert_eq!(take("takes/room tone.wav"), None);
+        assert_eq!(take("bass_48k.wav"), None);
+        assert_eq!(take("vox_12.wav"), Some(12));
+        assert_eq!(take("song2.wav"), None);
+    }
+
+    #[test]
+    fn test_stamp_sidecar_keeps_notes() {
+        let sidecar = "---\nfile: \"bass_take07.wav\"\ntitle: \"bass_take07\"\ntags: [\"bass\"]\n---\n\n# Notes\n\nBest feel of the night\n";
+        let template = "---\nfile: \"session.wav\"\ntitle: \"Bass take {{take}}\"\ntake: {{take}}\ntags: [\"session-a\", \"bass\"]\n---\n\nMic: SM7B into the 1073\n";
+
+        let stamped = stamp_sidecar(sidecar, &fill_template(template, 7)).unwrap();
+        let (yaml, body) = split_frontmatter(&stamped).unwrap();
+        let fields: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(fields["file"], "bass_take07.wav");
+        assert_eq!(fields["title"], "Bass take 7");
+        assert_eq!(fields["take"], 7);
+        assert_eq!(
+            fields["tags"],
+            serde_yaml::from_str::<serde_yaml::Value>("[bass, session-a]").unwrap()
+        );
+        assert!(body.contains("Best feel of the night"));
+        assert!(body.ends_with("Mic: SM7B into the 1073\n"));
+
+        // Stamping again changes nothing
+        let again = stamp_sidecar(&stamped, &fill_template(template, 7)).unwrap();
+        assert_eq!(again, stamped);
+
+        assert!(parse_stamp("title: no frontmatter\n").is_err());
+    }
+
+    #[test]
+    fn test_handle_add_stamp() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let template = dir.join("session.md");
+        fs::write(&template, "---\ntitle: \"Bass take {{take}}\"\n---\n").unwrap();
+        let sidecar = dir.join("bass_take2.wav.md");
+        let original = "---\ntitle: \"bass_take2\"\n---\n\n# Notes\n";
+        fs::write(dir.join("bass_take2.wav"), b"").unwrap();
+        fs::write(&sidecar, original).unwrap();
+        // No sidecar and no take number are both skipped
+        fs::write(dir.join("bass_take3.wav"), b"").unwrap();
+        fs::write(dir.join("room.wav"), b"").unwrap();
+
+        let files: Vec<String> = ["bass_take2.wav", "bass_take3.wav", "room.wav"]
+            .iter()
+            .map(|name| dir.join(name).to_string_lossy().into_owned())
+            .collect();
+        handle_add_stamp(template.to_str().unwrap(), &files, true).unwrap();
+        assert_eq!(fs::read_to_string(&sidecar).unwrap(), original);
+
+        handle_add_stamp(template.to_str().unwrap(), &files, false).unwrap();
+        let stamped = fs::read_to_string(&sidecar).unwrap();
+        assert!(stamped.contains("title: Bass take 2"));
+        assert!(stamped.ends_with("# Notes\n"));
+        assert!(!dir.join("bass_take3.wav.md").exists());
+    }
 }
@@ -0,0 +1,351 @@
+use crate::cli::lint::{is_sidecar_file, walk_project_files};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use zim_studio::constants::PROJECT_METADATA_FILE;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::sidecar::{audio_path_for_sidecar, split_frontmatter};
+use zim_studio::utils::validation::validate_path_exists;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const ZIMIGNORE_FILE: &str = ".zimignore";
+const INDEX_FILE: &str = "index.yml";
+
+/// What goes into a metadata archive besides sidecars, READMEs and `.zimignore`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArchiveOptions {
+    /// The `index.yml` written by `zim index`
+    pub include_index: bool,
+    /// Files listed in `art` entries
+    pub include_art: bool,
+}
+
+pub fn handle_archive(
+    project_path: &str,
+    output: &str,
+    options: ArchiveOptions,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let project_path = Path::new(project_path);
+    let output = Path::new(output);
+
+    validate_path_exists(project_path)?;
+
+    let spinner = create_progress_spinner(verbosity);
+    spinner.set_message("Collecting metadata files...");
+    let mut warnings = Vec::new();
+    let files = collect_archive_files(project_path, options, &mut warnings)?;
+    spinner.finish_and_clear();
+
+    if files.is_empty() {
+        return Err(format!(
+            "No sidecars or project files found in {}",
+            project_path.display()
+        )
+        .into());
+    }
+
+    write_archive(project_path, &files, output)?;
+
+    if !verbosity.is_quiet() {
+        for warning in &warnings {
+            println!("{} {}", "⚠".yellow(), warning);
+        }
+        println!(
+            "{} Archived {} files to {}",
+            "✓".green().bold(),
+            files.len().to_string().green().bold(),
+            output.display().to_string().cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Metadata files under the project, sorted by path. Problems that don't stop
+/// the archive, such as missing art, are added to `warnings`.
+fn collect_archive_files(
+    project_path: &Path,
+    options: ArchiveOptions,
+    warnings: &mut Vec<String>,
+) -> Result<BTreeSet<PathBuf>, Box<dyn Error>> {
+    let mut metadata = Vec::new();
+    walk_project_files(project_path, &mut |path| {
+        let is_readme = path.file_name().and_then(|n| n.to_str()) == Some(PROJECT_METADATA_FILE);
+        if is_readme || is_sidecar_file(path) {
+            metadata.push(path.to_path_buf());
+        }
+        Ok(())
+    })?;
+
+    let mut files: BTreeSet<PathBuf> = metadata.iter().cloned().collect();
+
+    // The walk skips hidden files, so pick up each project's .zimignore
+    // from beside its README, and the one at the root
+    let readme_dirs = metadata
+        .iter()
+        .filter(|path| !is_sidecar_file(path))
+        .filter_map(|path| path.parent());
+    for dir in readme_dirs.chain([project_path]) {
+        let zimignore = dir.join(ZIMIGNORE_FILE);
+        if zimignore.is_file() {
+            files.insert(zimignore);
+        }
+    }
+
+    if options.include_index {
+        let index = project_path.join(INDEX_FILE);
+        if index.is_file() {
+            files.insert(index);
+        } else {
+            warnings.push(format!("No {INDEX_FILE} found - run 'zim index' first"));
+        }
+    }
+
+    if options.include_art {
+        let root = fs::canonicalize(project_path)?;
+        for path in &metadata {
+            for art in referenced_art(path) {
+                match fs::canonicalize(&art) {
+                    Ok(resolved) if resolved.starts_with(&root) => {
+                        files.insert(project_path.join(resolved.strip_prefix(&root)?));
+                    }
+                    Ok(_) => warnings.push(format!(
+                        "Art outside the project skipped: {}",
+                        art.display()
+                    )),
+                    Err(_) => warnings.push(format!("Art not found: {}", art.display())),
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Paths of the art a sidecar or README lists. Sidecar art is relative to
+/// the audio file's folder, README art to the README's.
+fn referenced_art(metadata_path: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(metadata_path) else {
+        return Vec::new();
+    };
+    let Some((yaml, _)) = split_frontmatter(&content) else {
+        return Vec::new();
+    };
+    let Ok(frontmatter) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+        return Vec::new();
+    };
+    let Some(art) = frontmatter.get("art").and_then(|art| art.as_sequence()) else {
+        return Vec::new();
+    };
+
+    let base = if is_sidecar_file(metadata_path) {
+        audio_path_for_sidecar(metadata_path)
+    } else {
+        metadata_path.to_path_buf()
+    };
+    let base = base.parent().unwrap_or(Path::new(""));
+
+    art.iter()
+        .filter_map(|entry| match entry {
+            serde_yaml::Value::String(path) => Some(path.as_str()),
+            entry => entry.get("path")?.as_str(),
+        })
+        .filter(|path| !path.is_empty())
+        .map(|path| base.join(path))
+        .collect()
+}
+
+/// Write `files` to a zip at `output`, named by their path relative to the
+/// project. The zip is built next to `output` and only moved into place once
+/// complete, so a failure never leaves a truncated archive behind.
+fn write_archive(
+    project_path: &Path,
+    files: &BTreeSet<PathBuf>,
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut temp_name = output.file_name().ok_or("Invalid output path")?.to_owned();
+    temp_name.push(".tmp");
+    let temp_path = output.with_file_name(temp_name);
+    if let Err(e) = write_zip(project_path, files, &temp_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, output)?;
+    Ok(())
+}
+
+fn write_zip(
+    project_path: &Path,
+    files: &BTreeSet<PathBuf>,
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(output)?));
+    for path in files {
+        let relative = path.strip_prefix(project_path).unwrap_or(path);
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut options =
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+            // Zip timestamps can't go before 1980; those files keep the default
+            if let Ok(time) = zip_timestamp(modified) {
+                options = options.last_modified_time(time);
+            }
+        }
+        zip.start_file(name, options)?;
+        zip.write_all(&fs::read(path)?)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Modification time as a zip timestamp, in local time as zip tools expect
+fn zip_timestamp(modified: SystemTime) -> Result<zip::DateTime, Box<dyn Error>> {
+    let local: chrono::DateTime<chrono::Local> = modified.into();
+    Ok(zip::DateTime::try_from(local.naive_local())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::create_dir_all(root.join(".zim/masters")).unwrap();
+        fs::write(root.join("README.md"), "---\nname: Song\n---\n").unwrap();
+        fs::write(root.join(".zimignore"), "*.als\n").unwrap();
+        fs::write(root.join("mixes/a.wav"), "audio").unwrap();
+        fs::write(
+            root.join("mixes/a.wav.md"),
+            "---\ntitle: a\nart:\n  - path: \"a.wav.cover.jpg\"\n    purpose: cover_art\n  - missing.png\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("mixes/a.wav.cover.jpg"), "jpeg").unwrap();
+        fs::write(
+            root.join(".zim/masters/b.flac.md"),
+            "---\ntitle: b\nart:\n  - path: \"b.png\"\n---\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("masters")).unwrap();
+        fs::write(root.join("masters/b.png"), "png").unwrap();
+        temp
+    }
+
+    fn names(root: &Path, files: &BTreeSet<PathBuf>) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_collects_metadata_only() {
+        let temp = project();
+        let root = temp.path();
+        let mut warnings = Vec::new();
+
+        let files = collect_archive_files(root, ArchiveOptions::default(), &mut warnings).unwrap();
+
+        assert_eq!(
+            names(root, &files),
+            vec![
+                ".zim/masters/b.flac.md",
+                ".zimignore",
+                "README.md",
+                "mixes/a.wav.md"
+            ]
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_include_art_and_index() {
+        let temp = project();
+        let root = temp.path();
+        let mut warnings = Vec::new();
+        let options = ArchiveOptions {
+            include_index: true,
+            include_art: true,
+        };
+
+        let files = collect_archive_files(root, options, &mut warnings).unwrap();
+        let names = names(root, &files);
+
+        assert!(names.contains(&"mixes/a.wav.cover.jpg".to_string()));
+        // Art in a .zim/ sidecar is relative to the audio's folder
+        assert!(names.contains(&"masters/b.png".to_string()));
+        assert!(!names.contains(&"mixes/a.wav".to_string()));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|w| w.contains("missing.png")));
+        assert!(warnings.iter().any(|w| w.contains(INDEX_FILE)));
+
+        fs::write(root.join(INDEX_FILE), "tracks: []\n").unwrap();
+        let files = collect_archive_files(root, options, &mut Vec::new()).unwrap();
+        assert!(files.contains(&root.join(INDEX_FILE)));
+    }
+
+    #[test]
+    fn test_archive_preserves_relative_paths() {
+        let temp = project();
+        let root = temp.path();
+        let output = root.join("project.zip");
+
+        handle_archive(
+            root.to_str().unwrap(),
+            output.to_str().unwrap(),
+            ArchiveOptions::default(),
+            Verbosity::Quiet,
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<String> = archive
+            .file_names()
+            .map(|name| name.unwrap().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                ".zim/masters/b.flac.md",
+                ".zimignore",
+                "README.md",
+                "mixes/a.wav.md"
+            ]
+        );
+        let mut sidecar = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("README.md").unwrap(), &mut sidecar)
+            .unwrap();
+        assert_eq!(sidecar, "---\nname: Song\n---\n");
+    }
+
+    #[test]
+    fn test_zip_timestamp() {
+        assert!(zip_timestamp(SystemTime::UNIX_EPOCH).is_err());
+        assert!(zip_timestamp(SystemTime::now()).is_ok());
+    }
+
+    #[test]
+    fn test_failed_archive_leaves_nothing_behind() {
+        let temp = project();
+        let root = temp.path();
+        let output = root.join("project.zip");
+        let files = BTreeSet::from([root.join("mixes/a.wav"), root.join("mixes/gone.wav")]);
+
+        assert!(write_archive(root, &files, &output).is_err());
+        assert!(!output.exists());
+        assert!(!root.join("project.zip.tmp").exists());
+    }
+}
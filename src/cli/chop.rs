@@ -0,0 +1,404 @@
+use crate::config::Config;
+use crate::media::metadata::read_audio_metadata;
+use crate::player::excerpt::save_excerpt;
+use crate::player::mark_prompt::{format_timecode, parse_timecode};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::Verbosity;
+use zim_studio::utils::sidecar::SidecarLocation;
+use zim_studio::utils::validation::validate_path_exists;
+
+/// CUE sheet INDEX times count frames at 75 per second
+const CUE_FRAMES_PER_SECOND: f64 = 75.0;
+
+/// One span of the source to export, in seconds
+#[derive(Debug, Clone, PartialEq)]
+struct Region {
+    start: f64,
+    end: Option<f64>, // None runs to the end of the file (last track of a cue sheet)
+    name: Option<String>,
+}
+
+/// Export every region listed in `regions_path` from `file` into `output_dir`
+/// as WAV excerpts, each with a sidecar cloned from the source
+pub fn handle_chop(
+    file: &str,
+    regions_path: &str,
+    output_dir: &str,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let source = Path::new(file);
+    validate_path_exists(source)?;
+    let regions_path = Path::new(regions_path);
+    validate_path_exists(regions_path)?;
+
+    let text = fs::read_to_string(regions_path)?;
+    let is_cue = regions_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("cue"));
+    let regions = if is_cue {
+        parse_cue(&text)
+    } else {
+        parse_regions(&text)
+    }
+    .map_err(|e| format!("{}: {e}", regions_path.display()))?;
+    if regions.is_empty() {
+        return Err(format!("No regions found in {}", regions_path.display()).into());
+    }
+
+    let duration = read_audio_metadata(source)?
+        .duration_seconds
+        .ok_or_else(|| format!("Can't read the duration of {}", source.display()))?;
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("region");
+    // Check everything before writing anything, so a typo on the last line
+    // doesn't leave a half-exported pack
+    let exports = plan_exports(&regions, duration, stem, Path::new(output_dir))?;
+
+    let config = Config::load()?;
+    fs::create_dir_all(output_dir)?;
+    let mut written = 0;
+    for (region, dest) in &exports {
+        if dest.exists() {
+            if !verbosity.is_quiet() {
+                println!(
+                    "  {} {} {}",
+                    "Skipped:".yellow(),
+                    dest.display().to_string().cyan(),
+                    "(already exists)".bright_black()
+                );
+            }
+            continue;
+        }
+
+        let end = region.end.unwrap_or(duration);
+        let clipped = save_excerpt(
+            source,
+            dest,
+            region.start,
+            end,
+            duration,
+            None,
+            config.inherit_excerpt_tags(),
+            config.embed_notes,
+            SidecarLocation::from_name(&config.sidecar_location),
+        )?;
+        written += 1;
+        if clipped > 0 {
+            eprintln!(
+                "{} {} has {clipped} samples at full scale and may be clipped",
+                "Warning:".yellow(),
+                dest.display()
+            );
+        }
+        if !verbosity.is_quiet() {
+            println!(
+                "  {} {} {}",
+                "Wrote:".green(),
+                dest.display().to_string().cyan(),
+                format!(
+                    "({} - {})",
+                    format_timecode(region.start),
+                    format_timecode(end)
+                )
+                .bright_black()
+            );
+        }
+    }
+
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} Exported {} of {} regions from {}",
+            "✓".green().bold(),
+            written.to_string().green().bold(),
+            exports.len(),
+            source.display().to_string().cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Parse a regions list: one `in out [name]` per line, with timecodes in any
+/// form the player's mark prompt accepts. Blank lines and `#` comments are
+/// skipped.
+fn parse_regions(text: &str) -> Result<Vec<Region>, String> {
+    let mut regions = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at_line = |e: String| format!("line {}: {e}", index + 1);
+
+        let mut fields = line.splitn(3, char::is_whitespace);
+        let start = parse_timecode(fields.next().unwrap_or_default()).map_err(at_line)?;
+        let end = fields
+            .next()
+            .ok_or_else(|| at_line("expected an in and an out time".to_string()))
+            .and_then(|field| parse_timecode(field).map_err(at_line))?;
+        let name = fields
+            .next()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        regions.push(Region {
+            start,
+            end: Some(end),
+            name,
+        });
+    }
+    Ok(regions)
+}
+
+/// Parse the tracks of a CUE sheet. Each track runs from its `INDEX 01` to
+/// the next track's, and the last one to the end of the file.
+fn parse_cue(text: &str) -> Result<Vec<Region>, String> {
+    let mut regions: Vec<Region> = Vec::new();
+    let mut in_track = false;
+    for (index, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next().map(str::to_uppercase).as_deref() {
+            Some("TRACK") => {
+                in_track = true;
+                regions.push(Region {
+                    start: -1.0,
+                    end: None,
+                    name: None,
+                });
+            }
+            Some("TITLE") if in_track => {
+                let title = line.trim()[5..].trim().trim_matches('"');
+                if let Some(region) = regions.last_mut()
+                    && !title.is_empty()
+                {
+                    region.name = Some(title.to_string());
+                }
+            }
+            Some("INDEX") if in_track && words.next() == Some("01") => {
+                let time = words.next().unwrap_or_default();
+                let start = parse_cue_time(time)
+                    .ok_or_else(|| format!("line {}: '{time}' is not mm:ss:ff", index + 1))?;
+                if let Some(region) = regions.last_mut() {
+                    region.start = start;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(position) = regions.iter().position(|region| region.start < 0.0) {
+        return Err(format!("track {} has no INDEX 01", position + 1));
+    }
+    let starts: Vec<f64> = regions.iter().map(|region| region.start).collect();
+    for (region, next_start) in regions.iter_mut().zip(starts.iter().skip(1)) {
+        region.end = Some(*next_start);
+    }
+    Ok(regions)
+}
+
+/// A CUE `mm:ss:ff` time in seconds
+fn parse_cue_time(time: &str) -> Option<f64> {
+    let parts: Vec<u32> = time
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [minutes, seconds, frames] if *seconds < 60 && (*frames as f64) < CUE_FRAMES_PER_SECOND => {
+            Some(*minutes as f64 * 60.0 + *seconds as f64 + *frames as f64 / CUE_FRAMES_PER_SECOND)
+        }
+        _ => None,
+    }
+}
+
+/// Check each region fits the file and pick its output path: the region's
+/// name if it has one, otherwise the source name with the region number
+fn plan_exports(
+    regions: &[Region],
+    duration: f64,
+    stem: &str,
+    output_dir: &Path,
+) -> Result<Vec<(Region, PathBuf)>, String> {
+    let mut exports: Vec<(Region, PathBuf)> = Vec::new();
+    for (index, region) in regions.iter().enumerate() {
+        let number = index + 1;
+        let end = region.end.unwrap_or(duration);
+        if region.start >= end {
+            return Err(format!(
+                "region {number} ends before it starts ({} - {})",
+                format_timecode(region.start),
+                format_timecode(end)
+            ));
+        }
+        if end > duration {
+            return Err(format!(
+                "region {number} runs past the end of the file ({})",
+                format_timecode(duration)
+            ));
+        }
+
+        let filename = match &region.name {
+            Some(name) => format!("{}.wav", safe_filename(name)),
+            None => format!("{stem}_{number:02}.wav"),
+        };
+        let dest = output_dir.join(filename);
+        if exports.iter().any(|(_, other)| *other == dest) {
+            return Err(format!(
+                "region {number} has the same output name as an earlier one: {}",
+                dest.display()
+            ));
+        }
+        exports.push((region.clone(), dest));
+    }
+    Ok(exports)
+}
+
+/// A region name with path separators and other awkward characters replaced
+fn safe_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_regions() {
+        let text = "# kicks\n00:01.000 00:01.500 kick hard\n\n2.5\t3\n1:00 1:02.25 snare/rim\n";
+        let regions = parse_regions(text).unwrap();
+        assert_eq!(
+            regions,
+            vec![
+                Region {
+                    start: 1.0,
+                    end: Some(1.5),
+                    name: Some("kick hard".to_string())
+                },
+                Region {
+                    start: 2.5,
+                    end: Some(3.0),
+                    name: None
+                },
+                Region {
+                    start: 60.0,
+                    end: Some(62.25),
+                    name: Some("snare/rim".to_string())
+                },
+            ]
+        );
+
+        assert_eq!(
+            parse_regions("1.0\n").unwrap_err(),
+            "line 1: expected an in and an out time"
+        );
+        assert!(
+            parse_regions("\n1.0 x\n")
+                .unwrap_err()
+                .starts_with("line 2:")
+        );
+    }
+
+    #[test]
+    fn test_parse_cue() {
+        let cue = r#"FILE "set.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Intro"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Drop"
+    INDEX 00 01:29:00
+    INDEX 01 01:30:37
+"#;
+        let regions = parse_cue(cue).unwrap();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].name.as_deref(), Some("Intro"));
+        assert_eq!(regions[0].start, 0.0);
+        assert_eq!(regions[0].end, Some(90.0 + 37.0 / 75.0));
+        assert_eq!(regions[1].name.as_deref(), Some("Drop"));
+        assert_eq!(regions[1].end, None);
+
+        assert!(parse_cue("TRACK 01 AUDIO\n").is_err());
+        assert!(parse_cue("TRACK 01 AUDIO\nINDEX 01 00:00:75\n").is_err());
+    }
+
+    #[test]
+    fn test_plan_exports_names_and_bounds() {
+        let region = |start, end, name: Option<&str>| Region {
+            start,
+            end,
+            name: name.map(str::to_string),
+        };
+        let out = Path::new("out");
+        let exports = plan_exports(
+            &[
+                region(0.0, Some(1.0), Some("snare/rim")),
+                region(1.0, None, None),
+            ],
+            4.0,
+            "take",
+            out,
+        )
+        .unwrap();
+        assert_eq!(exports[0].1, out.join("snare_rim.wav"));
+        assert_eq!(exports[1].1, out.join("take_02.wav"));
+
+        assert!(plan_exports(&[region(2.0, Some(1.0), None)], 4.0, "take", out).is_err());
+        assert!(plan_exports(&[region(2.0, Some(5.0), None)], 4.0, "take", out).is_err());
+        let duplicate = [
+            region(0.0, Some(1.0), Some("a")),
+            region(1.0, Some(2.0), Some("a")),
+        ];
+        assert!(plan_exports(&duplicate, 4.0, "take", out).is_err());
+    }
+
+    #[test]
+    fn test_chop_exports_each_region_with_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("loop.wav");
+        write_wav(&source, pcm16(1, 1000), 0..4000i16);
+        fs::write(
+            temp_dir.path().join("loop.wav.md"),
+            "---\nfile: loop.wav\ntags: [\"drums\"]\n---\n",
+        )
+        .unwrap();
+        let regions = temp_dir.path().join("regions.txt");
+        fs::write(&regions, "0 1 first\n2.5 3.5\n").unwrap();
+        let out = temp_dir.path().join("pack");
+
+        handle_chop(
+            source.to_str().unwrap(),
+            regions.to_str().unwrap(),
+            out.to_str().unwrap(),
+            Verbosity::Quiet,
+        )
+        .unwrap();
+
+        let mut first = hound::WavReader::open(out.join("first.wav")).unwrap();
+        assert_eq!(first.duration(), 1000);
+        assert_eq!(first.samples::<i16>().next().unwrap().unwrap(), 0);
+        let mut second = hound::WavReader::open(out.join("loop_02.wav")).unwrap();
+        assert_eq!(second.duration(), 1000);
+        assert_eq!(second.samples::<i16>().next().unwrap().unwrap(), 2500);
+
+        let sidecar = fs::read_to_string(out.join("loop_02.wav.md")).unwrap();
+        assert!(sidecar.contains("file: \"loop_02.wav\""));
+        assert!(sidecar.contains("source_time_start: 0:02"));
+        assert!(sidecar.contains("drums"));
+    }
+}
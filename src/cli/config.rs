@@ -1,18 +1,40 @@
 use crate::config::Config;
 use std::error::Error;
+use std::path::Path;
 use std::process::Command;
+use zim_studio::utils::color::Colorize;
 
 pub fn handle_config_view() -> Result<(), Box<dyn Error>> {
     let config = Config::load()?;
 
     println!("Current ZIM configuration:");
+    println!(
+        "  root_dir: {}",
+        config.root_dir.as_deref().unwrap_or("(current directory)")
+    );
     println!("  default_artist: {}", config.default_artist);
     println!("  default_folders: {:?}", config.default_folders);
+    println!("  daw_folders: {:?}", config.daw_folders);
     println!("  include_readmes: {}", config.include_readmes);
     println!(
         "  normalize_project_names: {}",
         config.normalize_project_names
     );
+    println!("  default_gain: {}", config.default_gain);
+    println!("  title_style: {}", config.title_style);
+    println!("  use_trash: {}", config.use_trash);
+    println!("  log_level: {}", config.log_level);
+    if let Some(log_file) = &config.log_file {
+        println!("  log_file: {log_file}");
+    }
+
+    let global_zimignore = Config::global_zimignore_path()?;
+    let status = if global_zimignore.exists() {
+        ""
+    } else {
+        " (not found)"
+    };
+    println!("  global zimignore: {}{status}", global_zimignore.display());
 
     Ok(())
 }
@@ -25,6 +47,31 @@ pub fn handle_config_set(key: &str, value: &str) -> Result<(), Box<dyn Error>> {
 
     println!("Configuration updated: {key} = {value}");
 
+    if key == "root_dir"
+        && let Some(root_dir) = &config.root_dir
+        && !Path::new(shellexpand::tilde(root_dir).as_ref()).is_dir()
+    {
+        println!(
+            "{} {} does not exist yet; it will be created by {}",
+            "Warning:".yellow(),
+            root_dir.cyan(),
+            "zim new".cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Print the config file's path, bare so it can be used in scripts
+/// (`$EDITOR "$(zim config path)"`)
+pub fn handle_config_path() -> Result<(), Box<dyn Error>> {
+    println!("{}", Config::config_path()?.display());
+    Ok(())
+}
+
+/// Print the directory holding the config file and the zimignore templates
+pub fn handle_config_dir() -> Result<(), Box<dyn Error>> {
+    println!("{}", Config::config_dir()?.display());
     Ok(())
 }
 
@@ -0,0 +1,324 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::Verbosity;
+use zim_studio::utils::sidecar::{
+    SidecarCloneMode, SidecarLocation, clone_sidecar, get_sidecar_path, update_frontmatter_fields,
+};
+use zim_studio::utils::validation::validate_path_exists;
+
+/// Channel layouts accepted by `--channels`
+pub const CHANNEL_MODES: &[&str] = &["split", "mono"];
+
+/// Write mono files from a multichannel WAV: one per channel with `split`, or
+/// a single downmix with `mono`. Outputs go next to the source unless
+/// `output_dir` is given, and each gets a copy of the source's sidecar.
+pub fn handle_convert(
+    file: &str,
+    channels: &str,
+    output_dir: Option<&str>,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let source = Path::new(file);
+    validate_path_exists(source)?;
+    let is_wav = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return Err(format!("{} is not a WAV file", source.display()).into());
+    }
+
+    let output_dir = match output_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            PathBuf::from(dir)
+        }
+        None => source.parent().unwrap_or(Path::new(".")).to_path_buf(),
+    };
+
+    let outputs = match channels {
+        "split" => split_channels(source, &output_dir)?,
+        "mono" => vec![downmix_to_mono(source, &output_dir)?],
+        other => return Err(format!("Unknown channel mode: {other}").into()),
+    };
+
+    let sidecar_location = SidecarLocation::from_config();
+    for (path, origin) in &outputs {
+        if let Err(e) = clone_channel_sidecar(source, path, origin, sidecar_location) {
+            eprintln!(
+                "{} Failed to create sidecar for {}: {e}",
+                "Warning:".yellow(),
+                path.display()
+            );
+        }
+        if !verbosity.is_quiet() {
+            println!(
+                "  {} {} {}",
+                "Wrote:".green(),
+                path.display().to_string().cyan(),
+                format!("({origin})").bright_black()
+            );
+        }
+    }
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} Wrote {} mono files from {}",
+            "✓".green().bold(),
+            outputs.len().to_string().green().bold(),
+            source.display().to_string().cyan()
+        );
+    }
+    Ok(())
+}
+
+/// Suffix for channel `index` of `channels`: L/R for stereo, numbers otherwise
+fn channel_suffix(index: usize, channels: usize) -> String {
+    match (channels, index) {
+        (2, 0) => "L".to_string(),
+        (2, 1) => "R".to_string(),
+        _ => (index + 1).to_string(),
+    }
+}
+
+/// `<dir>/<stem>_<suffix>.wav`, refusing to replace an existing file
+fn output_path(source: &Path, output_dir: &Path, suffix: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Source has no file name")?;
+    let path = output_dir.join(format!("{stem}_{suffix}.wav"));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()).into());
+    }
+    Ok(path)
+}
+
+fn open_multichannel(
+    source: &Path,
+) -> Result<hound::WavReader<std::io::BufReader<fs::File>>, Box<dyn Error>> {
+    let reader = hound::WavReader::open(source)?;
+    if reader.spec().channels < 2 {
+        return Err(format!("{} is already mono", source.display()).into());
+    }
+    Ok(reader)
+}
+
+/// Write each channel to its own mono file in the source's sample format.
+/// Returns the files written with the channel each came from.
+fn split_channels(
+    source: &Path,
+    output_dir: &Path,
+) -> Result<Vec<(PathBuf, String)>, Box<dyn Error>> {
+    let mut reader = open_multichannel(source)?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+    let paths = (0..channels)
+        .map(|index| output_path(source, output_dir, &channel_suffix(index, channels)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mono = hound::WavSpec {
+        channels: 1,
+        ..spec
+    };
+    let mut writers = paths
+        .iter()
+        .map(|path| hound::WavWriter::create(path, mono))
+        .collect::<Result<Vec<_>, _>>()?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for (index, sample) in reader.samples::<f32>().enumerate() {
+                writers[index % channels].write_sample(sample?)?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            for (index, sample) in reader.samples::<i32>().enumerate() {
+                writers[index % channels].write_sample(sample?)?;
+            }
+        }
+    }
+    for writer in writers {
+        writer.finalize()?;
+    }
+
+    let source_name = file_name(source);
+    Ok(paths
+        .into_iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let origin = match (channels, index) {
+                (2, 0) => format!("left channel of {source_name}"),
+                (2, 1) => format!("right channel of {source_name}"),
+                _ => format!("channel {} of {source_name}", index + 1),
+            };
+            (path, origin)
+        })
+        .collect())
+}
+
+/// Average every frame's channels into one mono file in the source's format
+fn downmix_to_mono(source: &Path, output_dir: &Path) -> Result<(PathBuf, String), Box<dyn Error>> {
+    let mut reader = open_multichannel(source)?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+    let path = output_path(source, output_dir, "mono")?;
+
+    let mut writer = hound::WavWriter::create(
+        &path,
+        hound::WavSpec {
+            channels: 1,
+            ..spec
+        },
+    )?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            let samples = reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?;
+            for frame in samples.chunks_exact(channels) {
+                writer.write_sample(frame.iter().sum::<f32>() / channels as f32)?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            let samples = reader.samples::<i32>().collect::<Result<Vec<_>, _>>()?;
+            for frame in samples.chunks_exact(channels) {
+                let sum: i64 = frame.iter().map(|&s| s as i64).sum();
+                // The average of in-range samples is in range, so this can't overflow
+                writer.write_sample((sum as f64 / channels as f64).round() as i32)?;
+            }
+        }
+    }
+    writer.finalize()?;
+
+    let origin = format!("{channels}-channel downmix of {}", file_name(source));
+    Ok((path, origin))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Copy the source's sidecar to a converted file and note where its audio
+/// came from. Sources without a sidecar are left without one.
+fn clone_channel_sidecar(
+    source: &Path,
+    dest: &Path,
+    origin: &str,
+    sidecar_location: SidecarLocation,
+) -> Result<(), Box<dyn Error>> {
+    clone_sidecar(
+        source,
+        dest,
+        SidecarCloneMode::FullCopy,
+        None,
+        sidecar_location,
+    )?;
+    let sidecar = get_sidecar_path(dest, sidecar_location);
+    if !sidecar.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&sidecar)?;
+    let updated = update_frontmatter_fields(
+        &content,
+        vec![
+            (
+                "source_file",
+                serde_yaml::Value::String(source.to_string_lossy().into_owned()),
+            ),
+            (
+                "channel_origin",
+                serde_yaml::Value::String(origin.to_string()),
+            ),
+        ],
+    )?;
+    fs::write(&sidecar, updated)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+    use tempfile::TempDir;
+
+    fn write_stereo(path: &Path, frames: &[(i16, i16)]) {
+        let samples = frames.iter().flat_map(|&(left, right)| [left, right]);
+        write_wav(path, pcm16(2, 8000), samples);
+    }
+
+    fn read_mono(path: &Path) -> (hound::WavSpec, Vec<i16>) {
+        let mut reader = hound::WavReader::open(path).unwrap();
+        let samples = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        (reader.spec(), samples)
+    }
+
+    #[test]
+    fn test_channel_suffix() {
+        assert_eq!(channel_suffix(0, 2), "L");
+        assert_eq!(channel_suffix(1, 2), "R");
+        assert_eq!(channel_suffix(0, 6), "1");
+        assert_eq!(channel_suffix(5, 6), "6");
+    }
+
+    #[test]
+    fn test_split_channels() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("pad.wav");
+        write_stereo(&source, &[(1, -1), (2, -2), (3, -3)]);
+        fs::write(
+            temp_dir.path().join("pad.wav.md"),
+            "---\nfile: pad.wav\ntags: [\"synth\"]\n---\n# Notes\n",
+        )
+        .unwrap();
+
+        handle_convert(source.to_str().unwrap(), "split", None, Verbosity::Quiet).unwrap();
+
+        let (spec, left) = read_mono(&temp_dir.path().join("pad_L.wav"));
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.bits_per_sample, 16);
+        assert_eq!(left, vec![1, 2, 3]);
+        let (_, right) = read_mono(&temp_dir.path().join("pad_R.wav"));
+        assert_eq!(right, vec![-1, -2, -3]);
+
+        let sidecar = fs::read_to_string(temp_dir.path().join("pad_R.wav.md")).unwrap();
+        assert!(sidecar.contains("file: pad_R.wav"));
+        assert!(sidecar.contains("synth"));
+        assert!(sidecar.contains("channel_origin: right channel of pad.wav"));
+
+        // Running again doesn't overwrite the first outputs
+        assert!(handle_convert(source.to_str().unwrap(), "split", None, Verbosity::Quiet).is_err());
+    }
+
+    #[test]
+    fn test_downmix_to_mono() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("room.wav");
+        write_stereo(&source, &[(100, 300), (-5, 6), (i16::MAX, i16::MAX)]);
+        let out = temp_dir.path().join("mono");
+
+        handle_convert(
+            source.to_str().unwrap(),
+            "mono",
+            out.to_str(),
+            Verbosity::Quiet,
+        )
+        .unwrap();
+
+        let (spec, samples) = read_mono(&out.join("room_mono.wav"));
+        assert_eq!(spec.channels, 1);
+        assert_eq!(samples, vec![200, 1, i16::MAX]);
+        // No source sidecar, so none for the downmix either
+        assert!(!out.join("room_mono.wav.md").exists());
+    }
+
+    #[test]
+    fn test_convert_rejects_mono_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("kick.wav");
+        write_wav::<i16>(&source, pcm16(1, 8000), []);
+        let error =
+            handle_convert(source.to_str().unwrap(), "mono", None, Verbosity::Quiet).unwrap_err();
+        assert!(error.to_string().contains("already mono"));
+    }
+}
@@ -1,12 +1,15 @@
-use owo_colors::OwoColorize;
+use crate::config::Config;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
-use zim_studio::constants::{AUDIO_EXTENSIONS, YAML_DELIMITER};
+use zim_studio::constants::{DURATION_FIELD, VIDEO_EXTENSIONS, YAML_DELIMITER};
+use zim_studio::utils::color::Colorize;
 use zim_studio::utils::{
-    parallel_scan, progress::create_progress_spinner, sidecar::get_sidecar_path,
+    parallel_scan,
+    progress::{Verbosity, create_progress_spinner},
+    sidecar::{SidecarLocation, get_sidecar_path},
     validation::validate_path_exists,
 };
 use zim_studio::zimignore::ZimIgnore;
@@ -44,37 +47,51 @@ struct AlbumInfo {
 struct IndexData {
     album: AlbumInfo,
     tracks: Vec<TrackInfo>,
+    /// Reference video with sidecars, kept out of the album totals
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    videos: Vec<TrackInfo>,
 }
 
-pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
+pub fn handle_index(project_path: &str, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
     let project_path = Path::new(project_path);
 
     validate_path_exists(project_path)?;
 
-    println!(
-        "{} {}",
-        "Generating index for:".bright_black(),
-        project_path.display().to_string().cyan()
-    );
-    println!();
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Generating index for:".bright_black(),
+            project_path.display().to_string().cyan()
+        );
+        println!();
+    }
 
-    let audio_extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().cloned().collect();
+    let config = Config::load().unwrap_or_default();
+    let sidecar_location = SidecarLocation::from_name(&config.sidecar_location);
+    let audio_extensions = parallel_scan::sidecar_extensions(config.video_sidecars);
     let zimignore = ZimIgnore::load_for_directory(project_path);
+    let skip_dirs = parallel_scan::SkipDirs::from_config();
 
-    let spinner = create_progress_spinner();
+    let spinner = create_progress_spinner(verbosity);
     spinner.set_message("Scanning for audio files with sidecars...");
 
     // Collect all audio files recursively, respecting .zimignore
-    let audio_files =
-        parallel_scan::collect_audio_files(project_path, &audio_extensions, &zimignore)?;
+    let audio_files = parallel_scan::collect_audio_files(
+        project_path,
+        &audio_extensions,
+        &zimignore,
+        &skip_dirs,
+    )?;
 
     // Filter to only files that have sidecars and read their metadata
     let mut tracks = Vec::new();
+    let mut videos = Vec::new();
     let mut skipped_count = 0;
     for audio_path in &audio_files {
-        let sidecar_path = get_sidecar_path(audio_path);
+        let sidecar_path = get_sidecar_path(audio_path, sidecar_location);
         if sidecar_path.exists() {
             match read_track_info(audio_path, &sidecar_path) {
+                Ok(track_info) if is_video(audio_path) => videos.push(track_info),
                 Ok(track_info) => tracks.push(track_info),
                 Err(e) => {
                     skipped_count += 1;
@@ -91,7 +108,7 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
 
     spinner.finish_and_clear();
 
-    if tracks.is_empty() {
+    if tracks.is_empty() && videos.is_empty() {
         println!(
             "{} No audio files with sidecars found in {}",
             "⚠".yellow(),
@@ -102,6 +119,7 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
 
     // Sort tracks by filename
     tracks.sort_by(|a, b| a.file.cmp(&b.file));
+    videos.sort_by(|a, b| a.file.cmp(&b.file));
 
     // Calculate total duration
     let total_duration: Option<f64> = tracks
@@ -120,6 +138,7 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
             generated: generated.clone(),
         },
         tracks: tracks.clone(),
+        videos,
     };
 
     // Generate index.yml content
@@ -129,6 +148,10 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
     let index_path = project_path.join("index.yml");
     fs::write(&index_path, content)?;
 
+    if verbosity.is_quiet() {
+        return Ok(());
+    }
+
     println!(
         "{} {} Created {}",
         "✓".green().bold(),
@@ -140,6 +163,13 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
         "Indexed:".bright_black(),
         tracks.len().to_string().cyan()
     );
+    if !index_data.videos.is_empty() {
+        println!(
+            "  {} {} videos",
+            "Indexed:".bright_black(),
+            index_data.videos.len().to_string().cyan()
+        );
+    }
     if skipped_count > 0 {
         println!(
             "  {} {} files (could not parse metadata)",
@@ -161,6 +191,12 @@ pub fn handle_index(project_path: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
 fn read_track_info(audio_path: &Path, sidecar_path: &Path) -> Result<TrackInfo, Box<dyn Error>> {
     let content = fs::read_to_string(sidecar_path)?;
 
@@ -197,7 +233,7 @@ fn read_track_info(audio_path: &Path, sidecar_path: &Path) -> Result<TrackInfo,
         .map(|s| s.to_string());
 
     // Duration can be either a number or "unknown" - only include numeric values in index
-    let duration = yaml.get("duration").and_then(|v| v.as_f64());
+    let duration = yaml.get(DURATION_FIELD).and_then(|v| v.as_f64());
 
     let sample_rate = yaml
         .get("sample_rate")
@@ -267,6 +303,7 @@ mod tests {
                     tags: None,
                 },
             ],
+            videos: Vec::new(),
         };
 
         // Serialize to YAML
@@ -281,10 +318,41 @@ mod tests {
         assert!(yaml.contains("Main Theme"));
         assert!(yaml.contains("Opening track"));
         assert!(yaml.contains("sample_rate: 44100"));
+        assert!(!yaml.contains("videos"));
 
         // Verify it can be deserialized back
         let deserialized: IndexData = serde_yaml::from_str(&yaml).unwrap();
         assert_eq!(deserialized.album.total_tracks, 2);
         assert_eq!(deserialized.tracks.len(), 2);
+        assert!(deserialized.videos.is_empty());
+    }
+
+    #[test]
+    fn test_videos_are_listed_apart_from_tracks() {
+        assert!(is_video(Path::new("picture/cue_12.MOV")));
+        assert!(!is_video(Path::new("mixes/cue_12.wav")));
+
+        let reel = TrackInfo {
+            file: "reel.mp4".to_string(),
+            title: Some("Reel".to_string()),
+            description: None,
+            duration: Some(90.0),
+            sample_rate: None,
+            channels: None,
+            bit_depth: None,
+            tags: Some(vec!["video".to_string()]),
+        };
+        let data = IndexData {
+            album: AlbumInfo {
+                total_tracks: 0,
+                total_duration: Some(0.0),
+                generated: "2024-01-15T10:30:00Z".to_string(),
+            },
+            tracks: Vec::new(),
+            videos: vec![reel],
+        };
+        let yaml = serde_yaml::to_string(&data).unwrap();
+        assert!(yaml.contains("videos:\n- file: reel.mp4"));
+        assert!(!yaml.contains("sample_rate"));
     }
 }
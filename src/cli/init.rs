@@ -1,9 +1,9 @@
 use crate::config::Config;
 use indicatif::{ProgressBar, ProgressStyle};
-use owo_colors::OwoColorize;
 use std::error::Error;
 use std::thread;
 use std::time::Duration;
+use zim_studio::utils::color::Colorize;
 
 pub fn handle_init() -> Result<(), Box<dyn Error>> {
     // Check if already initialized
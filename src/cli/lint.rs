@@ -1,10 +1,22 @@
-use owo_colors::OwoColorize;
+use crate::config::Config;
 use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
-use zim_studio::constants::{SIDECAR_EXTENSION, SKIP_DIRECTORIES, YAML_DELIMITER};
-use zim_studio::utils::{progress::create_progress_spinner, validation::validate_path_exists};
+use zim_studio::constants::{
+    DURATION_FIELD, PROJECT_METADATA_FILE, SIDECAR_DIR, SIDECAR_EXTENSION, YAML_DELIMITER,
+};
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::{
+    parallel_scan::{SkipDirs, VisitedDirs, should_descend},
+    progress::{Verbosity, create_progress_spinner},
+    sidecar::{
+        audio_path_for_sidecar, duration_value, split_frontmatter, update_frontmatter_fields,
+    },
+    validation::validate_path_exists,
+};
+use zim_studio::zimignore::PathFilter;
 
 // Type for duration field that can be either a number or "unknown"
 #[derive(Debug, Deserialize)]
@@ -56,7 +68,8 @@ struct SidecarMetadata {
     #[serde(default, rename = "description")]
     _description: String,
 
-    // Audio-specific fields (optional)
+    // Audio-specific fields (optional); `duration` is DURATION_FIELD, as
+    // written by the templates and by `zim update`
     #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<DurationField>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "sample_rate")]
@@ -79,36 +92,77 @@ struct SidecarMetadata {
     _art: Vec<ArtReference>,
 }
 
-pub fn handle_lint(project_path: &str) -> Result<(), Box<dyn Error>> {
+/// Frontmatter of a project's README.md as written by `zim new`
+#[derive(Debug, Deserialize)]
+struct ProjectMetadata {
+    #[serde(rename = "name")]
+    _name: String,
+    #[serde(rename = "artist")]
+    _artist: String,
+    #[serde(rename = "created")]
+    _created: String,
+    status: String,
+    #[serde(default, rename = "tags")]
+    _tags: Vec<String>,
+    #[serde(default, rename = "art")]
+    _art: Vec<ArtReference>,
+}
+
+/// Outcome of validating one project metadata file
+type ProjectResult = (PathBuf, Result<(), String>);
+/// A sidecar rewritten by `--fix` and what was changed in it
+type FixedSidecar = (PathBuf, Vec<String>);
+type FileVisitor<'a> = dyn FnMut(&Path) -> Result<(), Box<dyn Error>> + 'a;
+
+pub fn handle_lint(
+    project_path: &str,
+    include: &[String],
+    exclude: &[String],
+    fix: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
     let project_path = Path::new(project_path);
 
     validate_path_exists(project_path)?;
+    let filter = PathFilter::new(project_path, include, exclude);
 
-    println!(
-        "{} {}",
-        "Linting project:".bright_black(),
-        project_path.display().to_string().cyan()
-    );
-    println!();
+    if fix {
+        let fixed = fix_sidecars(project_path, &filter)?;
+        print_fixes(project_path, &fixed, verbosity);
+    }
+
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Linting project:".bright_black(),
+            project_path.display().to_string().cyan()
+        );
+        println!();
+    }
 
-    let spinner = create_progress_spinner();
+    let spinner = create_progress_spinner(verbosity);
     spinner.set_message("Scanning for sidecar files...");
 
     let mut total_files = 0;
     let mut valid_files = 0;
     let mut invalid_files = 0;
     let mut errors = Vec::new();
+    let mut project_results = Vec::new();
 
     scan_directory(
         project_path,
+        &filter,
         &mut total_files,
         &mut valid_files,
         &mut invalid_files,
         &mut errors,
+        &mut project_results,
     )?;
 
     spinner.finish_and_clear();
 
+    let invalid_projects = project_results.iter().filter(|(_, r)| r.is_err()).count();
+
     // Print results
     print_lint_results(
         project_path,
@@ -116,30 +170,269 @@ pub fn handle_lint(project_path: &str) -> Result<(), Box<dyn Error>> {
         total_files,
         valid_files,
         invalid_files,
+        verbosity,
     );
+    print_project_results(project_path, &project_results, verbosity);
 
-    if invalid_files > 0 {
-        Err(format!(
-            "{} Lint check failed: invalid YAML found",
-            "Error:".red().bold()
-        )
-        .into())
+    if let Some(problem) = lint_failure(invalid_files, invalid_projects) {
+        Err(format!("{} Lint check failed: {}", "Error:".red().bold(), problem).into())
     } else {
-        println!(
-            "\n{} {}",
-            "✓".green().bold(),
-            "All YAML frontmatter is valid!".green()
-        );
+        if !verbosity.is_quiet() {
+            println!(
+                "\n{} {}",
+                "✓".green().bold(),
+                "All YAML frontmatter is valid!".green()
+            );
+        }
         Ok(())
     }
 }
 
+/// What failed the lint, if anything: sidecars with invalid frontmatter and
+/// project READMEs with bad metadata are reported separately
+fn lint_failure(invalid_files: u32, invalid_projects: usize) -> Option<&'static str> {
+    match (invalid_files > 0, invalid_projects > 0) {
+        (true, true) => Some("invalid YAML and project metadata found"),
+        (true, false) => Some("invalid YAML found"),
+        (false, true) => Some("invalid project metadata found"),
+        (false, false) => None,
+    }
+}
+
 fn scan_directory(
     dir: &Path,
+    filter: &PathFilter,
     total: &mut u32,
     valid: &mut u32,
     invalid: &mut u32,
     errors: &mut Vec<(PathBuf, String)>,
+    project_results: &mut Vec<ProjectResult>,
+) -> Result<(), Box<dyn Error>> {
+    let statuses = configured_statuses();
+    walk_project_files(dir, &mut |path| {
+        if is_project_metadata_file(path) {
+            if filter.matches(path) {
+                let result = validate_project_metadata(path, &statuses).map_err(|e| e.to_string());
+                project_results.push((path.to_path_buf(), result));
+            }
+        } else if is_sidecar_file(path) && filter.matches(&audio_path_for_sidecar(path)) {
+            // Filter on the media file's path rather than the sidecar's
+            *total += 1;
+            match validate_yaml_frontmatter(path) {
+                Ok(()) => {
+                    *valid += 1;
+                }
+                Err(e) => {
+                    *invalid += 1;
+                    errors.push((path.to_path_buf(), e.to_string()));
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Rewrite the mechanical problems in every invalid sidecar under `dir`.
+/// Returns each file changed with a description of each change.
+fn fix_sidecars(dir: &Path, filter: &PathFilter) -> Result<Vec<FixedSidecar>, Box<dyn Error>> {
+    let mut fixed = Vec::new();
+    walk_project_files(dir, &mut |path| {
+        if is_sidecar_file(path)
+            && filter.matches(&audio_path_for_sidecar(path))
+            && validate_yaml_frontmatter(path).is_err()
+        {
+            let changes = fix_sidecar(path)?;
+            if !changes.is_empty() {
+                fixed.push((path.to_path_buf(), changes));
+            }
+        }
+        Ok(())
+    })?;
+    Ok(fixed)
+}
+
+/// Fix one sidecar's frontmatter in place, keeping the markdown body and
+/// every field it doesn't need to touch. Frontmatter that isn't a YAML
+/// mapping is left for the user.
+fn fix_sidecar(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let Some((yaml_content, _)) = split_frontmatter(&content) else {
+        return Ok(Vec::new());
+    };
+    let Ok(Value::Mapping(mut fields)) = serde_yaml::from_str::<Value>(yaml_content) else {
+        return Ok(Vec::new());
+    };
+
+    let original = fields.clone();
+    let changes = fix_frontmatter(&mut fields, &audio_path_for_sidecar(path));
+    if !changes.is_empty() {
+        let updates = FIXABLE_FIELDS
+            .iter()
+            .filter_map(|&key| {
+                let value = fields.get(key)?;
+                (original.get(key) != Some(value)).then(|| (key, value.clone()))
+            })
+            .collect();
+        fs::write(path, update_frontmatter_fields(&content, updates)?)?;
+    }
+    Ok(changes)
+}
+
+/// The fields `fix_frontmatter` may change
+const FIXABLE_FIELDS: [&str; 6] = [
+    "file",
+    "title",
+    "description",
+    "tags",
+    "art",
+    DURATION_FIELD,
+];
+
+/// Normalize the fields that have one obviously right value: empty lists
+/// for `tags` and `art`, a comma-separated `tags` string split into a list,
+/// bare paths in `art` turned into entries, a `duration` written as text or
+/// a clock time converted to seconds, and the file name from the sidecar's
+/// own name. Anything else is left alone.
+fn fix_frontmatter(fields: &mut Mapping, audio_path: &Path) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if fields.get("file").is_none_or(Value::is_null)
+        && let Some(name) = audio_path.file_name()
+    {
+        let name = name.to_string_lossy().into_owned();
+        changes.push(format!("file: set to '{name}'"));
+        fields.insert("file".into(), Value::String(name));
+    }
+
+    for key in ["title", "description"] {
+        if fields.get(key).is_some_and(Value::is_null) {
+            fields.insert(key.into(), Value::String(String::new()));
+            changes.push(format!("{key}: blank value replaced with \"\""));
+        }
+    }
+
+    match fields.get("tags") {
+        None | Some(Value::Null) => {
+            fields.insert("tags".into(), Value::Sequence(Vec::new()));
+            changes.push("tags: added empty list".to_string());
+        }
+        Some(Value::String(text)) => {
+            let tags = text
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| Value::String(tag.to_string()))
+                .collect();
+            changes.push(format!("tags: converted '{text}' to a list"));
+            fields.insert("tags".into(), Value::Sequence(tags));
+        }
+        Some(Value::Sequence(tags)) if tags.iter().any(|tag| !tag.is_string()) => {
+            let tags: Option<Vec<Value>> = tags.iter().map(scalar_to_string).collect();
+            if let Some(tags) = tags {
+                fields.insert("tags".into(), Value::Sequence(tags));
+                changes.push("tags: quoted non-text entries".to_string());
+            }
+        }
+        _ => {}
+    }
+
+    match fields.get_mut("art") {
+        None | Some(Value::Null) => {
+            fields.insert("art".into(), Value::Sequence(Vec::new()));
+            changes.push("art: added empty list".to_string());
+        }
+        Some(Value::Sequence(art)) if art.iter().any(Value::is_string) => {
+            for entry in art.iter_mut() {
+                if let Value::String(path) = entry {
+                    let mut reference = Mapping::new();
+                    reference.insert("path".into(), Value::String(std::mem::take(path)));
+                    reference.insert("description".into(), Value::String(String::new()));
+                    reference.insert("purpose".into(), Value::String("other".to_string()));
+                    *entry = Value::Mapping(reference);
+                }
+            }
+            changes.push("art: turned bare paths into entries".to_string());
+        }
+        _ => {}
+    }
+
+    let fixed_duration = match fields.get(DURATION_FIELD) {
+        Some(Value::Null) => Some(Value::String("unknown".to_string())),
+        Some(Value::String(text)) if text != "unknown" => {
+            if text.trim().eq_ignore_ascii_case("unknown") {
+                Some(Value::String("unknown".to_string()))
+            } else {
+                parse_duration_text(text).map(duration_value)
+            }
+        }
+        _ => None,
+    };
+    if let Some(duration) = fixed_duration {
+        changes.push(format!(
+            "{DURATION_FIELD}: set to {}",
+            serde_yaml::to_string(&duration).unwrap_or_default().trim()
+        ));
+        fields.insert(DURATION_FIELD.into(), duration);
+    }
+
+    changes
+}
+
+/// A number or boolean as a string; `None` for nested values
+fn scalar_to_string(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(_) => Some(value.clone()),
+        Value::Number(n) => Some(Value::String(n.to_string())),
+        Value::Bool(b) => Some(Value::String(b.to_string())),
+        _ => None,
+    }
+}
+
+/// Seconds from a duration written as text: `"95.5"`, `"95.5s"`, `"1:35.5"`
+/// or `"0:01:35"`
+fn parse_duration_text(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let plain = text.strip_suffix('s').unwrap_or(text);
+    if let Ok(seconds) = plain.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then_some(seconds);
+    }
+
+    let parts: Vec<&str> = text.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let (seconds, whole) = parts.split_last()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let mut total = 0.0;
+    for (index, unit) in whole.iter().enumerate() {
+        let value: u32 = unit.parse().ok()?;
+        if index > 0 && value >= 60 {
+            return None;
+        }
+        total = total * 60.0 + value as f64;
+    }
+    Some(total * 60.0 + seconds)
+}
+
+/// Visit every file under `dir`, skipping hidden entries and the configured
+/// skip directories. The hidden `.zim/` sidecar directory is still visited.
+pub fn walk_project_files(dir: &Path, visit: &mut FileVisitor) -> Result<(), Box<dyn Error>> {
+    walk_dir(
+        dir,
+        &SkipDirs::from_config(),
+        &VisitedDirs::default(),
+        visit,
+    )
+}
+
+fn walk_dir(
+    dir: &Path,
+    skip_dirs: &SkipDirs,
+    visited: &VisitedDirs,
+    visit: &mut FileVisitor,
 ) -> Result<(), Box<dyn Error>> {
     let entries = fs::read_dir(dir)?;
 
@@ -150,6 +443,7 @@ fn scan_directory(
         // Skip hidden files and directories
         if let Some(name) = path.file_name()
             && name.to_string_lossy().starts_with('.')
+            && !(name == SIDECAR_DIR && path.is_dir())
         {
             continue;
         }
@@ -157,35 +451,38 @@ fn scan_directory(
         if path.is_dir() {
             // Skip certain directories
             let dir_name = path.file_name().unwrap().to_string_lossy();
-            if should_skip_directory(&dir_name) {
+            if skip_dirs.contains(&dir_name) || !should_descend(&path, skip_dirs, visited) {
                 continue;
             }
 
             // Recurse into subdirectory
-            scan_directory(&path, total, valid, invalid, errors)?;
-        } else if path.is_file()
-            && path.extension().and_then(|e| e.to_str()) == Some(SIDECAR_EXTENSION)
-        {
-            // Check if this is a sidecar file (has corresponding media file)
-            if is_sidecar_file(&path) {
-                *total += 1;
-                match validate_yaml_frontmatter(&path) {
-                    Ok(()) => {
-                        *valid += 1;
-                    }
-                    Err(e) => {
-                        *invalid += 1;
-                        errors.push((path, e.to_string()));
-                    }
-                }
-            }
+            walk_dir(&path, skip_dirs, visited, visit)?;
+        } else if path.is_file() {
+            visit(&path)?;
         }
     }
 
     Ok(())
 }
 
-fn is_sidecar_file(md_path: &Path) -> bool {
+/// All sidecar files under `dir`, sorted by path
+pub fn find_sidecars(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut sidecars = Vec::new();
+    walk_project_files(dir, &mut |path| {
+        if is_sidecar_file(path) {
+            sidecars.push(path.to_path_buf());
+        }
+        Ok(())
+    })?;
+    sidecars.sort();
+    Ok(sidecars)
+}
+
+pub fn is_sidecar_file(md_path: &Path) -> bool {
+    if md_path.extension().and_then(|e| e.to_str()) != Some(SIDECAR_EXTENSION) {
+        return false;
+    }
+
     // A sidecar file has a name like "audio.flac.md"
     let file_name = md_path.file_name().unwrap().to_string_lossy();
 
@@ -198,9 +495,17 @@ fn is_sidecar_file(md_path: &Path) -> bool {
     }
 }
 
-fn validate_yaml_frontmatter(path: &Path) -> Result<(), Box<dyn Error>> {
-    let content = fs::read_to_string(path)?;
+/// A project metadata file is a README.md with YAML frontmatter. Folder
+/// READMEs created by `zim new` have no frontmatter and are ignored.
+fn is_project_metadata_file(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()) == Some(PROJECT_METADATA_FILE)
+        && fs::read_to_string(path)
+            .map(|content| content.starts_with(YAML_DELIMITER))
+            .unwrap_or(false)
+}
 
+/// Split the YAML frontmatter out of a markdown document
+fn extract_frontmatter(content: &str) -> Result<&str, Box<dyn Error>> {
     // Check if file starts with ---
     if !content.starts_with(YAML_DELIMITER) {
         return Err("Missing YAML frontmatter (file should start with ---)".into());
@@ -213,7 +518,36 @@ fn validate_yaml_frontmatter(path: &Path) -> Result<(), Box<dyn Error>> {
     }
 
     // The YAML content is in parts[1]
-    let yaml_content = parts[1];
+    Ok(parts[1])
+}
+
+/// The user's `project_statuses`, or the defaults if the config can't be read
+fn configured_statuses() -> Vec<String> {
+    Config::load().unwrap_or_default().project_statuses
+}
+
+fn validate_project_metadata(path: &Path, statuses: &[String]) -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let yaml_content = extract_frontmatter(&content)?;
+
+    let metadata: ProjectMetadata =
+        serde_yaml::from_str(yaml_content).map_err(|e| format_validation_error(&e.to_string()))?;
+
+    if !statuses.contains(&metadata.status) {
+        return Err(format!(
+            "Invalid status '{}' - expected one of: {}",
+            metadata.status,
+            statuses.join(", ")
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+pub fn validate_yaml_frontmatter(path: &Path) -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let yaml_content = extract_frontmatter(&content)?;
 
     // Try to parse the YAML with schema validation
     let metadata: SidecarMetadata =
@@ -230,10 +564,6 @@ fn validate_yaml_frontmatter(path: &Path) -> Result<(), Box<dyn Error>> {
 }
 
 // Helper functions
-fn should_skip_directory(name: &str) -> bool {
-    SKIP_DIRECTORIES.contains(&name)
-}
-
 fn format_validation_error(error_msg: &str) -> String {
     // Common error patterns and helpful messages
     if error_msg.contains("missing field") {
@@ -263,22 +593,25 @@ fn print_lint_results(
     total_files: u32,
     valid_files: u32,
     invalid_files: u32,
+    verbosity: Verbosity,
 ) {
-    println!(
-        "\n{} {} files scanned",
-        "Summary:".bright_black(),
-        total_files.to_string().cyan()
-    );
-    println!(
-        "  {} {} valid",
-        "✓".green(),
-        valid_files.to_string().green()
-    );
-    println!(
-        "  {} {} invalid",
-        "✗".red(),
-        invalid_files.to_string().red()
-    );
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} {} files scanned",
+            "Summary:".bright_black(),
+            total_files.to_string().cyan()
+        );
+        println!(
+            "  {} {} valid",
+            "✓".green(),
+            valid_files.to_string().green()
+        );
+        println!(
+            "  {} {} invalid",
+            "✗".red(),
+            invalid_files.to_string().red()
+        );
+    }
 
     if !errors.is_empty() {
         println!("\n{}", "Errors found:".red().bold());
@@ -293,12 +626,68 @@ fn print_lint_results(
     }
 }
 
+fn print_fixes(project_path: &Path, fixed: &[FixedSidecar], verbosity: Verbosity) {
+    if verbosity.is_quiet() {
+        return;
+    }
+    if fixed.is_empty() {
+        println!("{}", "Nothing to fix automatically".bright_black());
+    } else {
+        println!("{}", "Fixed:".green().bold());
+        for (path, changes) in fixed {
+            let relative_path = path.strip_prefix(project_path).unwrap_or(path).display();
+            println!("  {}", relative_path.to_string().yellow());
+            for change in changes {
+                println!("    {} {}", "•".green(), change.bright_black());
+            }
+        }
+    }
+    println!();
+}
+
+fn print_project_results(
+    project_path: &Path,
+    project_results: &[ProjectResult],
+    verbosity: Verbosity,
+) {
+    if project_results.is_empty() {
+        return;
+    }
+
+    let invalid = project_results.iter().filter(|(_, r)| r.is_err()).count();
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} {} project metadata files checked",
+            "Projects:".bright_black(),
+            project_results.len().to_string().cyan()
+        );
+    }
+
+    if invalid > 0 {
+        println!("\n{}", "Project metadata errors:".red().bold());
+        for (path, result) in project_results {
+            if let Err(error) = result {
+                let relative_path = path.strip_prefix(project_path).unwrap_or(path).display();
+                println!(
+                    "  {} {}",
+                    relative_path.to_string().yellow(),
+                    error.bright_black()
+                );
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    fn default_statuses() -> Vec<String> {
+        Config::new().project_statuses
+    }
+
     #[test]
     fn test_duration_field_validate() {
         // Test number duration
@@ -335,12 +724,26 @@ mod tests {
     }
 
     #[test]
-    fn test_should_skip_directory() {
-        assert!(should_skip_directory("node_modules"));
-        assert!(should_skip_directory(".git"));
-        assert!(should_skip_directory("temp"));
-        assert!(!should_skip_directory("src"));
-        assert!(!should_skip_directory("assets"));
+    fn test_walk_skips_configured_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        for dir in ["mixes", "bounces", "node_modules"] {
+            fs::create_dir(temp_dir.path().join(dir)).unwrap();
+            fs::write(temp_dir.path().join(dir).join("take.wav"), b"").unwrap();
+        }
+
+        let skip_dirs = SkipDirs::new(&["bounces".to_string()]);
+        let mut found = Vec::new();
+        walk_dir(
+            temp_dir.path(),
+            &skip_dirs,
+            &VisitedDirs::default(),
+            &mut |path| {
+                found.push(path.strip_prefix(temp_dir.path()).unwrap().to_path_buf());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(found, vec![PathBuf::from("mixes/take.wav")]);
     }
 
     #[test]
@@ -531,13 +934,16 @@ Content after frontmatter"#;
         let mut valid = 0;
         let mut invalid = 0;
         let mut errors = Vec::new();
+        let mut project_results = Vec::new();
 
         let result = scan_directory(
             temp_dir.path(),
+            &PathFilter::default(),
             &mut total,
             &mut valid,
             &mut invalid,
             &mut errors,
+            &mut project_results,
         );
 
         assert!(result.is_ok());
@@ -555,4 +961,290 @@ Content after frontmatter"#;
         assert_eq!(errors.len(), 1);
         assert!(errors[0].0.to_str().unwrap().contains("bad.mp3.md"));
     }
+
+    #[test]
+    fn test_validate_project_metadata_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("README.md");
+
+        let content = r#"---
+name: "my-song"
+artist: "Someone"
+created: "2024-01-01"
+status: "active"
+tags: []
+art:
+  - path: "../shared-assets/mood-board.jpg"
+    description: "Mood board"
+    purpose: "inspiration"
+---
+
+# my-song
+"#;
+        fs::write(&file_path, content).unwrap();
+
+        assert!(is_project_metadata_file(&file_path));
+        assert!(validate_project_metadata(&file_path, &default_statuses()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_project_metadata_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("README.md");
+
+        // Missing artist
+        fs::write(
+            &file_path,
+            "---\nname: x\ncreated: \"2024-01-01\"\nstatus: active\n---\n",
+        )
+        .unwrap();
+        let error = validate_project_metadata(&file_path, &default_statuses()).unwrap_err();
+        assert_eq!(error.to_string(), "Missing required field: 'artist'");
+
+        // Unknown status
+        fs::write(
+            &file_path,
+            "---\nname: x\nartist: y\ncreated: \"2024-01-01\"\nstatus: done\n---\n",
+        )
+        .unwrap();
+        let error = validate_project_metadata(&file_path, &default_statuses()).unwrap_err();
+        assert!(error.to_string().contains("Invalid status 'done'"));
+
+        // ...unless the team's lifecycle includes it
+        let statuses = ["demo".to_string(), "done".to_string()];
+        assert!(validate_project_metadata(&file_path, &statuses).is_ok());
+
+        // Malformed art entry
+        fs::write(
+            &file_path,
+            "---\nname: x\nartist: y\ncreated: \"2024-01-01\"\nstatus: active\nart:\n  - path: a.png\n    purpose: wallpaper\n---\n",
+        )
+        .unwrap();
+        assert!(validate_project_metadata(&file_path, &default_statuses()).is_err());
+    }
+
+    #[test]
+    fn test_scan_directory_project_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        crate::project::create_project_metadata(
+            temp_dir.path(),
+            "my-song",
+            "Someone",
+            None,
+            "active",
+        )
+        .unwrap();
+
+        // Folder READMEs without frontmatter are not project metadata
+        let mixes = temp_dir.path().join("mixes");
+        fs::create_dir(&mixes).unwrap();
+        fs::write(mixes.join("README.md"), "# mixes\n").unwrap();
+
+        // A nested project with a bad status
+        let nested = temp_dir.path().join("other");
+        fs::create_dir(&nested).unwrap();
+        fs::write(
+            nested.join("README.md"),
+            "---\nname: x\nartist: y\ncreated: \"2024-01-01\"\nstatus: wip\n---\n",
+        )
+        .unwrap();
+
+        let (mut total, mut valid, mut invalid) = (0, 0, 0);
+        let mut errors = Vec::new();
+        let mut project_results = Vec::new();
+        scan_directory(
+            temp_dir.path(),
+            &PathFilter::default(),
+            &mut total,
+            &mut valid,
+            &mut invalid,
+            &mut errors,
+            &mut project_results,
+        )
+        .unwrap();
+
+        assert_eq!(total, 0);
+        assert!(errors.is_empty());
+        assert_eq!(project_results.len(), 2);
+        let failed: Vec<_> = project_results.iter().filter(|(_, r)| r.is_err()).collect();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].0.starts_with(&nested));
+    }
+
+    #[test]
+    fn test_lint_failure_names_what_failed() {
+        assert_eq!(lint_failure(0, 0), None);
+        assert_eq!(lint_failure(2, 0), Some("invalid YAML found"));
+        assert_eq!(lint_failure(0, 1), Some("invalid project metadata found"));
+        assert_eq!(
+            lint_failure(1, 1),
+            Some("invalid YAML and project metadata found")
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_text() {
+        assert_eq!(parse_duration_text("95.5"), Some(95.5));
+        assert_eq!(parse_duration_text(" 95.5s "), Some(95.5));
+        assert_eq!(parse_duration_text("1:35.5"), Some(95.5));
+        assert_eq!(parse_duration_text("1:01:35"), Some(3695.0));
+        for text in [
+            "",
+            "about a minute",
+            "1:75",
+            "1:60:00",
+            "-3",
+            "NaN",
+            "1:2:3:4",
+        ] {
+            assert_eq!(parse_duration_text(text), None, "{text} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_fix_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = temp_dir.path().join("take.wav.md");
+        fs::write(
+            &sidecar,
+            "---\npath: /test/take.wav\ntitle:\nduration: \"2:05\"\nfile_size: 1000\nmodified: \"2024-01-01\"\ntags: \"drums, live ,\"\n---\n\n# Notes\n\nKeep this.\n",
+        )
+        .unwrap();
+        assert!(validate_yaml_frontmatter(&sidecar).is_err());
+
+        let changes = fix_sidecar(&sidecar).unwrap();
+        assert_eq!(
+            changes,
+            [
+                "file: set to 'take.wav'",
+                "title: blank value replaced with \"\"",
+                "tags: converted 'drums, live ,' to a list",
+                "art: added empty list",
+                "duration: set to 125.0",
+            ]
+        );
+        validate_yaml_frontmatter(&sidecar).unwrap();
+        let content = fs::read_to_string(&sidecar).unwrap();
+        assert!(content.starts_with("---\npath: /test/take.wav\n"));
+        assert!(content.contains("- drums\n- live\n"));
+        assert!(content.ends_with("---\n\n# Notes\n\nKeep this.\n"));
+
+        // A valid sidecar needs nothing more
+        assert!(fix_sidecar(&sidecar).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fix_sidecar_keeps_block_art() {
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = temp_dir.path().join("song.flac.md");
+        fs::write(
+            &sidecar,
+            r#"---
+file: "song.flac"
+path: "mixes/song.flac"
+file_size: 1000
+modified: "2024-01-01"
+tags:
+  - mix
+art:
+  - path: "song.flac.cover.jpg"
+    description: "Embedded cover art"
+    purpose: "cover_art"
+  - "sketch.png"
+duration: "3:00"
+---
+
+# Notes
+"#,
+        )
+        .unwrap();
+        assert!(validate_yaml_frontmatter(&sidecar).is_err());
+
+        let changes = fix_sidecar(&sidecar).unwrap();
+        assert_eq!(
+            changes,
+            [
+                "art: turned bare paths into entries",
+                "duration: set to 180.0"
+            ]
+        );
+        validate_yaml_frontmatter(&sidecar).unwrap();
+        let content = fs::read_to_string(&sidecar).unwrap();
+        let (yaml, body) = split_frontmatter(&content).unwrap();
+        assert_eq!(body, "\n# Notes\n");
+        let fields: Mapping = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(fields["tags"], Value::from(vec!["mix"]));
+        let art = fields["art"].as_sequence().unwrap();
+        assert_eq!(art[0]["path"].as_str(), Some("song.flac.cover.jpg"));
+        assert_eq!(art[0]["purpose"].as_str(), Some("cover_art"));
+        assert_eq!(art[1]["path"].as_str(), Some("sketch.png"));
+        assert_eq!(art[1]["purpose"].as_str(), Some("other"));
+    }
+
+    #[test]
+    fn test_fix_sidecars_leaves_ambiguous_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        // Numeric tags are quoted, but a missing file_size can't be guessed
+        fs::write(
+            temp_dir.path().join("loop.wav.md"),
+            "---\nfile: loop.wav\npath: /x\nmodified: \"2024-01-01\"\ntags: [2024, live]\nduration: a while\n---\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("bad.wav.md"), "No frontmatter").unwrap();
+
+        let fixed = fix_sidecars(temp_dir.path(), &PathFilter::default()).unwrap();
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(
+            fixed[0].1,
+            ["tags: quoted non-text entries", "art: added empty list"]
+        );
+        let content = fs::read_to_string(temp_dir.path().join("loop.wav.md")).unwrap();
+        assert!(content.contains("duration: a while"));
+        assert!(
+            validate_yaml_frontmatter(&temp_dir.path().join("loop.wav.md"))
+                .unwrap_err()
+                .to_string()
+                .contains("file_size")
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("bad.wav.md")).unwrap(),
+            "No frontmatter"
+        );
+    }
+
+    #[test]
+    fn test_scan_directory_with_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        for dir in ["masters", "sources"] {
+            let dir = temp_dir.path().join(dir);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("take.wav.md"), "No frontmatter").unwrap();
+            fs::write(dir.join("take.flac.md"), "No frontmatter").unwrap();
+        }
+
+        let lint_with = |include: &[&str], exclude: &[&str]| {
+            let to_vec = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            let filter = PathFilter::new(temp_dir.path(), &to_vec(include), &to_vec(exclude));
+            let (mut total, mut valid, mut invalid) = (0, 0, 0);
+            let mut errors = Vec::new();
+            let mut project_results = Vec::new();
+            scan_directory(
+                temp_dir.path(),
+                &filter,
+                &mut total,
+                &mut valid,
+                &mut invalid,
+                &mut errors,
+                &mut project_results,
+            )
+            .unwrap();
+            total
+        };
+
+        assert_eq!(lint_with(&[], &[]), 4);
+        assert_eq!(lint_with(&["masters"], &[]), 2);
+        assert_eq!(lint_with(&[], &["sources"]), 2);
+        assert_eq!(lint_with(&["masters", "*.wav"], &["sources"]), 2);
+        assert_eq!(lint_with(&["*.wav"], &["masters/*"]), 1);
+    }
 }
@@ -0,0 +1,328 @@
+use crate::player::loudness::{Loudness, TRUE_PEAK_CEILING, measure, measurement_pool};
+use crate::player::mixed_source::load_samples;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use zim_studio::constants::PLAYABLE_EXTENSIONS;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::parallel_scan;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::validation::validate_path_exists;
+use zim_studio::zimignore::ZimIgnore;
+
+/// One file's row in the report
+#[derive(Debug, Serialize)]
+struct FileLoudness {
+    path: String,
+    integrated_lufs: Option<f64>,
+    true_peak_dbtp: Option<f64>,
+    /// Distance from the reference level in LU
+    deviation: Option<f64>,
+    outlier: bool,
+    peak_over_ceiling: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct LoudnessReport {
+    /// What files are compared against: "target" or "median"
+    reference: &'static str,
+    reference_lufs: Option<f64>,
+    tolerance_lu: f64,
+    files: Vec<FileLoudness>,
+    unreadable: Vec<(String, String)>,
+}
+
+/// Measure the integrated loudness and true peak of every audio file under
+/// `path` (or of `path` itself), and flag files more than `tolerance` LU
+/// from `target`, or from the median of the files when there's no target
+pub fn handle_loudness(
+    path: &str,
+    target: Option<f64>,
+    tolerance: f64,
+    json: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(path);
+    validate_path_exists(path)?;
+
+    let spinner = create_progress_spinner(if json { Verbosity::Quiet } else { verbosity });
+    spinner.set_message("Measuring loudness...");
+    let files = collect_files(path)?;
+    let measured: Vec<(PathBuf, Result<Loudness, String>)> = measurement_pool()?.install(|| {
+        files
+            .into_par_iter()
+            .map(|file| {
+                let result = load_samples(&file.to_string_lossy())
+                    .map(|(samples, sample_rate, channels)| {
+                        measure(&samples, sample_rate, channels)
+                    })
+                    .map_err(|e| e.to_string());
+                (file, result)
+            })
+            .collect()
+    });
+    spinner.finish_and_clear();
+
+    let display = |file: &Path| {
+        file.strip_prefix(path)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    };
+    let mut unreadable = Vec::new();
+    let mut levels = Vec::new();
+    for (file, result) in measured {
+        match result {
+            Ok(loudness) => levels.push((display(&file), loudness)),
+            Err(e) => unreadable.push((display(&file), e)),
+        }
+    }
+    let report = build_report(levels, unreadable, target, tolerance);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !verbosity.is_quiet() {
+        print_report(&report);
+    }
+
+    let outliers = report.files.iter().filter(|file| file.outlier).count();
+    if outliers > 0 {
+        return Err(format!(
+            "{outliers} of {} files are more than {tolerance} LU from the {}",
+            report.files.len(),
+            report.reference
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn collect_files(path: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let zimignore = ZimIgnore::load_for_directory(path);
+    let mut files = parallel_scan::collect_audio_files(
+        path,
+        &PLAYABLE_EXTENSIONS.iter().copied().collect::<HashSet<_>>(),
+        &zimignore,
+        &parallel_scan::SkipDirs::from_config(),
+    )?;
+    files.sort();
+    Ok(files)
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    })
+}
+
+/// Compare each file with the target, or with the median when there's no
+/// target. Silent files have no loudness and are never outliers.
+fn build_report(
+    levels: Vec<(String, Loudness)>,
+    unreadable: Vec<(String, String)>,
+    target: Option<f64>,
+    tolerance: f64,
+) -> LoudnessReport {
+    let (reference, reference_lufs) = match target {
+        Some(target) => ("target", Some(target)),
+        None => {
+            let mut measured: Vec<f64> = levels.iter().filter_map(|(_, l)| l.integrated).collect();
+            ("median", median(&mut measured))
+        }
+    };
+
+    let files = levels
+        .into_iter()
+        .map(|(path, loudness)| {
+            let deviation = loudness
+                .integrated
+                .zip(reference_lufs)
+                .map(|(lufs, reference)| lufs - reference);
+            FileLoudness {
+                path,
+                integrated_lufs: loudness.integrated,
+                true_peak_dbtp: loudness.true_peak,
+                deviation,
+                outlier: deviation.is_some_and(|d| d.abs() > tolerance),
+                peak_over_ceiling: loudness.true_peak.is_some_and(|p| p > TRUE_PEAK_CEILING),
+            }
+        })
+        .collect();
+
+    LoudnessReport {
+        reference,
+        reference_lufs,
+        tolerance_lu: tolerance,
+        files,
+        unreadable,
+    }
+}
+
+fn format_level(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{v:.1}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_report(report: &LoudnessReport) {
+    println!(
+        "  {:>7}  {:>7}  {:>6}  {}",
+        "LUFS".bright_black(),
+        "dBTP".bright_black(),
+        "Δ LU".bright_black(),
+        "File".bright_black()
+    );
+    for file in &report.files {
+        let deviation = file
+            .deviation
+            .map(|d| format!("{d:+.1}"))
+            .unwrap_or_else(|| "-".to_string());
+        let deviation = if file.outlier {
+            format!("{deviation:>6}").red().bold().to_string()
+        } else {
+            format!("{deviation:>6}").green().to_string()
+        };
+        let peak = format!("{:>7}", format_level(file.true_peak_dbtp));
+        let peak = if file.peak_over_ceiling {
+            peak.yellow().to_string()
+        } else {
+            peak
+        };
+        println!(
+            "  {:>7}  {peak}  {deviation}  {}",
+            format_level(file.integrated_lufs),
+            file.path.cyan()
+        );
+    }
+    for (path, error) in &report.unreadable {
+        println!("  {} {path}: {error}", "Unreadable:".red().bold());
+    }
+
+    if let Some(reference) = report.reference_lufs {
+        println!(
+            "\n{} {reference:.1} LUFS ({}), window ±{} LU",
+            "Reference:".bright_black(),
+            report.reference,
+            report.tolerance_lu
+        );
+    }
+    let hot = report.files.iter().filter(|f| f.peak_over_ceiling).count();
+    if hot > 0 {
+        println!(
+            "{} {hot} files peak above {TRUE_PEAK_CEILING} dBTP",
+            "Warning:".yellow()
+        );
+    }
+    if report.files.iter().all(|f| !f.outlier) {
+        println!(
+            "{} {} files within the loudness window",
+            "✓".green().bold(),
+            report.files.len().to_string().green().bold()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+
+    fn level(integrated: Option<f64>, true_peak: Option<f64>) -> Loudness {
+        Loudness {
+            integrated,
+            true_peak,
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [-9.0, -14.0, -10.0]), Some(-10.0));
+        assert_eq!(median(&mut [-9.0, -14.0, -10.0, -11.0]), Some(-10.5));
+    }
+
+    #[test]
+    fn test_build_report_flags_outliers_from_median() {
+        let report = build_report(
+            vec![
+                ("01.wav".to_string(), level(Some(-10.2), Some(-1.5))),
+                ("02.wav".to_string(), level(Some(-9.8), Some(-0.3))),
+                ("03.wav".to_string(), level(Some(-12.0), Some(-3.0))),
+                ("silence.wav".to_string(), level(None, None)),
+            ],
+            Vec::new(),
+            None,
+            1.0,
+        );
+        assert_eq!(report.reference, "median");
+        assert_eq!(report.reference_lufs, Some(-10.2));
+        let outliers: Vec<&str> = report
+            .files
+            .iter()
+            .filter(|f| f.outlier)
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(outliers, ["03.wav"]);
+        assert!(report.files[1].peak_over_ceiling);
+        assert!(!report.files[0].peak_over_ceiling);
+        assert_eq!(report.files[3].deviation, None);
+    }
+
+    #[test]
+    fn test_build_report_against_target() {
+        let report = build_report(
+            vec![
+                ("loud.wav".to_string(), level(Some(-9.0), Some(-0.5))),
+                ("ok.wav".to_string(), level(Some(-14.4), Some(-2.0))),
+            ],
+            Vec::new(),
+            Some(-14.0),
+            1.0,
+        );
+        assert_eq!(report.reference, "target");
+        assert!(report.files[0].outlier);
+        assert!(!report.files[1].outlier);
+        let deviation = report.files[0].deviation.unwrap();
+        assert!((deviation - 5.0).abs() < 1e-9);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["reference_lufs"], -14.0);
+        assert_eq!(json["files"][0]["path"], "loud.wav");
+        assert_eq!(json["files"][0]["outlier"], true);
+    }
+
+    #[test]
+    fn test_handle_loudness_on_folder() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for (name, amplitude) in [("a.wav", 8000.0), ("b.wav", 8000.0), ("quiet.wav", 800.0)] {
+            let samples = (0..8000).flat_map(|i| {
+                let sample = ((i as f32 / 8.0).sin() * amplitude) as i16;
+                [sample, sample]
+            });
+            write_wav(temp_dir.path().join(name), pcm16(2, 8000), samples);
+        }
+        let folder = temp_dir.path().to_str().unwrap();
+
+        // quiet.wav is 20 LU under the others
+        let error = handle_loudness(folder, None, 1.0, false, Verbosity::Quiet).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1 of 3 files are more than 1 LU from the median"
+        );
+        assert!(handle_loudness(folder, None, 25.0, false, Verbosity::Quiet).is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::cli::lint::find_sidecars;
+use crate::config::Config;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zim_studio::constants::SIDECAR_DIR;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::Verbosity;
+use zim_studio::utils::sidecar::{audio_path_for_sidecar, is_project_root, zim_dir_sidecar_path};
+use zim_studio::utils::validation::validate_path_exists;
+
+/// Sidecars moved and those left in place because `.zim/` already has one
+#[derive(Debug, Default)]
+struct MigrateReport {
+    moved: usize,
+    conflicts: Vec<PathBuf>,
+}
+
+/// Move every sidecar under `path` that sits next to its audio into the
+/// hidden `.zim/` directory at `path`, mirroring the tree
+pub fn handle_migrate_sidecars(path: &str, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(path);
+    validate_path_exists(path)?;
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()).into());
+    }
+    // Sidecars in .zim/ are found from the project root, so the audio must
+    // resolve back to `path`
+    if !is_project_root(path) {
+        return Err(format!(
+            "{} is not a project root (no .zimignore or README.md frontmatter)",
+            path.display()
+        )
+        .into());
+    }
+
+    let report = migrate_sidecars(path)?;
+
+    if !verbosity.is_quiet() {
+        for sidecar in &report.conflicts {
+            println!(
+                "  {} {} {}",
+                "Skipped:".yellow(),
+                sidecar
+                    .strip_prefix(path)
+                    .unwrap_or(sidecar)
+                    .display()
+                    .to_string()
+                    .cyan(),
+                format!("(already in {SIDECAR_DIR}/)").bright_black()
+            );
+        }
+        println!(
+            "\n{} Moved {} sidecar files into {}",
+            "✓".green().bold(),
+            report.moved.to_string().green().bold(),
+            path.join(SIDECAR_DIR).display().to_string().cyan()
+        );
+
+        let location = Config::load()
+            .map(|config| config.sidecar_location)
+            .unwrap_or_default();
+        if location != "zim" {
+            println!(
+                "  {}",
+                "Run 'zim config set sidecar_location zim' so new sidecars go there too"
+                    .bright_black()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_sidecars(path: &Path) -> Result<MigrateReport, Box<dyn Error>> {
+    let adjacent: Vec<PathBuf> = find_sidecars(path)?
+        .into_iter()
+        .filter(|sidecar| {
+            !sidecar
+                .components()
+                .any(|component| component.as_os_str() == SIDECAR_DIR)
+        })
+        .collect();
+
+    let mut report = MigrateReport::default();
+    for sidecar in adjacent {
+        let destination = zim_dir_sidecar_path(&audio_path_for_sidecar(&sidecar));
+        if destination.exists() {
+            report.conflicts.push(sidecar);
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&sidecar, &destination)?;
+        report.moved += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use zim_studio::utils::sidecar::{SidecarLocation, get_sidecar_path};
+
+    #[test]
+    fn test_migrate_sidecars_mirrors_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::write(root.join(".zimignore"), "").unwrap();
+        fs::write(
+            root.join("mixes/final.wav.md"),
+            "---\nfile: final.wav\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("take.flac.md"), "---\nfile: take.flac\n---\n").unwrap();
+        fs::write(root.join("notes.md"), "# Not a sidecar").unwrap();
+
+        // One already migrated by hand is left alone
+        fs::create_dir_all(root.join(".zim")).unwrap();
+        fs::write(root.join("dup.wav.md"), "adjacent").unwrap();
+        fs::write(root.join(".zim/dup.wav.md"), "hidden").unwrap();
+
+        let report = migrate_sidecars(root).unwrap();
+        assert_eq!(report.moved, 2);
+        assert_eq!(report.conflicts, vec![root.join("dup.wav.md")]);
+
+        assert!(!root.join("mixes/final.wav.md").exists());
+        assert!(root.join(".zim/mixes/final.wav.md").exists());
+        assert!(root.join(".zim/take.flac.md").exists());
+        assert!(root.join("notes.md").exists());
+
+        // The resolver and lint's walk both find the moved sidecars
+        assert_eq!(
+            get_sidecar_path(&root.join("mixes/final.wav"), SidecarLocation::Adjacent),
+            root.join(".zim/mixes/final.wav.md")
+        );
+        let found = find_sidecars(root).unwrap();
+        assert!(found.contains(&root.join(".zim/mixes/final.wav.md")));
+        assert!(found.contains(&root.join(".zim/take.flac.md")));
+    }
+}
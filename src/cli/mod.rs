@@ -1,10 +1,24 @@
 pub mod add;
+pub mod archive;
+#[cfg(feature = "player")]
+pub mod chop;
 pub mod config;
+#[cfg(feature = "player")]
+pub mod convert;
 pub mod index;
 pub mod init;
 pub mod lint;
+#[cfg(feature = "player")]
+pub mod loudness;
+pub mod migrate_sidecars;
 pub mod new;
 pub mod play;
+pub mod reindex;
+pub mod snapshot;
+pub mod sources;
 pub mod sync;
 pub mod tag;
+pub mod tags;
+pub mod tree;
 pub mod update;
+pub mod verify;
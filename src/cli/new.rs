@@ -1,12 +1,12 @@
-use crate::config::Config;
+use crate::config::{Config, parse_folder_names};
 use crate::project;
 use dialoguer::{Confirm, Input, theme::ColorfulTheme};
 use indicatif::{ProgressBar, ProgressStyle};
-use owo_colors::OwoColorize;
 use std::error::Error;
 use std::path::Path;
 use std::thread;
 use std::time::Duration;
+use zim_studio::utils::color::Colorize;
 
 pub fn handle_new(
     name: Option<&str>,
@@ -14,9 +14,26 @@ pub fn handle_new(
     zimignore_template: Option<&str>,
     no_zimignore: bool,
     interactive: bool,
+    daw_folders: Option<Vec<String>>,
 ) -> Result<(), Box<dyn Error>> {
     // Load configuration
     let config = Config::load()?;
+    let daw_folders = match daw_folders {
+        Some(names) => parse_folder_names(&names.join(",")).map_err(|e| format!("--daw: {e}"))?,
+        None => config.daw_folders.clone(),
+    };
+    if !config.project_statuses.contains(&config.default_status) {
+        return Err(format!(
+            "default_status '{}' is not one of the project_statuses ({}); \
+             fix it with `zim config set default_status`",
+            config.default_status,
+            config.project_statuses.join(", ")
+        )
+        .into());
+    }
+
+    // Fall back to the configured root directory when no path is given
+    let path = path.or(config.root_dir.as_deref());
 
     // Generate project name if not provided
     let project_name = match name {
@@ -98,7 +115,7 @@ pub fn handle_new(
 
     // Create project structure
     pb.set_message("Creating project structure...");
-    project::create_project_structure(&project_path, &config.default_folders, &config)?;
+    project::create_project_structure(&project_path, &config.default_folders, &daw_folders)?;
     thread::sleep(Duration::from_millis(100));
     pb.inc(1);
 
@@ -130,6 +147,7 @@ pub fn handle_new(
         &project_name,
         &config.default_artist,
         display_name,
+        &config.default_status,
     )?;
     thread::sleep(Duration::from_millis(100));
     pb.inc(1);
@@ -160,6 +178,25 @@ pub fn handle_new(
     Ok(())
 }
 
+/// Print the DAW folders `zim new` creates under `project/`
+pub fn handle_list_daw() -> Result<(), Box<dyn Error>> {
+    let config = Config::load()?;
+    if config.daw_folders.is_empty() {
+        println!("No DAW folders configured");
+    } else {
+        println!("DAW folders created under project/:");
+        for daw in &config.daw_folders {
+            println!("  {}", daw.cyan());
+        }
+    }
+    println!(
+        "\n{}",
+        "Change them with 'zim config set daw_folders live,logic,reaper', or pick per project with 'zim new --daw'"
+            .bright_black()
+    );
+    Ok(())
+}
+
 fn generate_project_name(path: Option<&str>) -> Result<String, Box<dyn Error>> {
     let date = chrono::Local::now().format("%Y%m%d");
 
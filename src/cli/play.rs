@@ -1,18 +1,107 @@
+#[cfg(feature = "player")]
+use crate::config::{Config, MAX_GAIN, MONITOR_FRAMES_RANGE};
 use std::error::Error;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::Path;
+use zim_studio::constants::PLAYABLE_EXTENSIONS;
+use zim_studio::utils::color::Colorize;
 
 // Maximum playlist size to prevent memory issues
 const MAX_PLAYLIST_SIZE: usize = 1000;
 
+/// Replace a `-` argument (or add, for `--stdin`) with the paths piped in on stdin
+pub fn files_with_stdin(files: Vec<String>) -> Result<Vec<String>, Box<dyn Error>> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        eprintln!(
+            "{} Reading file paths from stdin, one per line (Ctrl+D to finish)",
+            "ℹ".blue()
+        );
+    }
+    let piped = read_file_list(stdin.lock())?;
+    if piped.is_empty() {
+        return Err("No playable audio files read from stdin".into());
+    }
+
+    let mut expanded: Vec<String> = files.into_iter().filter(|f| f != "-").collect();
+    expanded.extend(piped);
+    Ok(expanded)
+}
+
+/// Read newline-separated paths, keeping existing files the player can
+/// decode and warning about everything else. Blank lines are ignored.
+fn read_file_list(reader: impl BufRead) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let entry = line.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let path = Path::new(entry);
+        let playable = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| PLAYABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+        let problem = if !path.is_file() {
+            "not found"
+        } else if !playable {
+            "not a playable audio file"
+        } else {
+            files.push(entry.to_string());
+            continue;
+        };
+        eprintln!(
+            "{} Skipping {}: {problem}",
+            "Warning:".yellow(),
+            entry.yellow()
+        );
+    }
+    Ok(files)
+}
+
+/// The pseudo-file the player opens for `--test` and `--tone`
+pub fn test_signal_file(kind: Option<&str>, tone: Option<f32>) -> Result<String, Box<dyn Error>> {
+    #[cfg(feature = "player")]
+    {
+        match crate::player::test_signal::TestSignal::new(kind, tone) {
+            Ok(signal) => Ok(signal.path()),
+            Err(message) => {
+                println!("{} {message}", "Error:".red());
+                Err("Invalid --test/--tone".into())
+            }
+        }
+    }
+
+    #[cfg(not(feature = "player"))]
+    {
+        let _ = (kind, tone);
+        Err("Test signals need the audio player, which this build doesn't include".into())
+    }
+}
+
+/// The part of the file to play from `--start`, `--end` and `--loop`, as
+/// timecodes yet to be parsed
+#[derive(Debug, Default)]
+pub struct PlayRegion {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub looping: bool,
+}
+
 pub fn handle_play(
     files: Vec<String>,
-    gains: Option<Vec<f32>>,
+    gains: Option<Vec<String>>,
+    gain: Option<f32>,
+    reference: Option<(String, f32)>,
+    region: PlayRegion,
     interactive: bool,
 ) -> Result<(), Box<dyn Error>> {
     #[cfg(feature = "player")]
     {
         // Validate inputs
         if files.is_empty() && !interactive {
-            use owo_colors::OwoColorize;
             println!(
                 "{} No files specified. Use {} flag for browser mode.",
                 "Error:".red(),
@@ -21,11 +110,20 @@ pub fn handle_play(
             return Err("No files specified".into());
         }
 
-        // Check file limit for mixing mode (when gains are specified)
-        if gains.is_some() && files.len() > 3 {
-            use owo_colors::OwoColorize;
+        if reference.is_some() && files.is_empty() {
             println!(
-                "{} Maximum 3 files supported for mixing mode (with --gains).",
+                "{} {} needs at least one file to compare against.",
+                "Error:".red(),
+                "--reference".cyan()
+            );
+            return Err("No files to compare with the reference".into());
+        }
+
+        // Check file limit for mixing mode (when gains or a reference are specified)
+        let mixing = gains.is_some() || reference.is_some();
+        if mixing && files.len() > 3 {
+            println!(
+                "{} Maximum 3 files supported for mixing mode (with --gains or --reference).",
                 "Error:".red()
             );
             println!(
@@ -36,7 +134,6 @@ pub fn handle_play(
 
         // Check playlist size limit to prevent memory issues
         if gains.is_none() && files.len() > MAX_PLAYLIST_SIZE {
-            use owo_colors::OwoColorize;
             println!(
                 "{} Playlist too large: {} files (maximum {} supported).",
                 "Error:".red(),
@@ -47,41 +144,92 @@ pub fn handle_play(
         }
 
         // Validate gains if provided
-        if let Some(ref g) = gains {
-            if !files.is_empty() && g.len() != files.len() {
-                use owo_colors::OwoColorize;
-                println!(
-                    "{} Number of gains ({}) must match number of files ({}).",
-                    "Error:".red(),
-                    g.len(),
-                    files.len()
-                );
-                return Err("Gain count mismatch".into());
+        let gains = match gains.map(|values| parse_gains(&values, files.len())) {
+            Some(Ok(gains)) => Some(gains),
+            Some(Err(message)) => {
+                println!("{} {message}", "Error:".red());
+                return Err("Invalid --gains".into());
             }
+            None => None,
+        };
+
+        if let Some((_, reference_gain)) = &reference
+            && !(0.0..=MAX_GAIN).contains(reference_gain)
+        {
+            println!(
+                "{} Reference gain ({reference_gain}) must be between 0.0 and {MAX_GAIN}.",
+                "Error:".red()
+            );
+            return Err("Invalid gain value".into());
+        }
+
+        // Catch files the mix can't combine before the player opens
+        if (gains.is_some() || reference.is_some())
+            && let Err(message) =
+                check_mix_formats(&files, reference.as_ref().map(|(path, _)| path.as_str()))
+        {
+            println!("{} {message}", "Error:".red());
+            return Err("Mixed files differ in format".into());
+        }
 
-            for (i, gain) in g.iter().enumerate() {
-                if *gain < 0.0 || *gain > 2.0 {
-                    use owo_colors::OwoColorize;
-                    println!(
-                        "{} Gain {} ({}) must be between 0.0 and 2.0.",
-                        "Error:".red(),
-                        i + 1,
-                        gain
-                    );
-                    return Err("Invalid gain value".into());
-                }
+        let (start, end) = match parse_region(&region) {
+            Ok(times) => times,
+            Err(message) => {
+                println!("{} {message}", "Error:".red());
+                return Err("Invalid --start/--end".into());
             }
+        };
+        if (start.is_some() || end.is_some() || region.looping) && files.is_empty() {
+            println!(
+                "{} {}, {} and {} need a file to play.",
+                "Error:".red(),
+                "--start".cyan(),
+                "--end".cyan(),
+                "--loop".cyan()
+            );
+            return Err("No file to start playing".into());
         }
 
-        crate::player::run(files, gains, interactive)
+        let config = Config::load()?;
+        let master_gain = resolve_master_gain(gain, config.default_gain);
+        let options = crate::player::PlayerOptions {
+            master_gain,
+            meter_gain: config.meter_gain,
+            embed_notes: config.embed_notes,
+            inherit_excerpt_tags: config.inherit_excerpt_tags(),
+            autoplay_on_load: config.autoplay_on_load,
+            replay_gain: config.replay_gain,
+            search_body: config.search_body,
+            sidecar_location: zim_studio::utils::sidecar::SidecarLocation::from_name(
+                &config.sidecar_location,
+            ),
+            monitor_frames: resolve_monitor_frames(config.monitor_chunk_size),
+            audio_buffer_frames: (config.audio_buffer_frames > 0)
+                .then_some(config.audio_buffer_frames),
+            waveform_image_size: (config.waveform_image_width, config.waveform_image_height),
+            min_selection: std::time::Duration::from_millis(config.min_selection_ms as u64),
+            block_short_selections: config.block_short_selections,
+            true_peak_meter: config.true_peak_meter,
+            scope_gain: config.scope_gain,
+            scope_auto_gain: config.scope_auto_gain,
+            reference: reference
+                .map(|(path, gain)| crate::player::mixed_source::ReferenceTrack { path, gain }),
+            start,
+            end,
+            loop_region: region.looping,
+        };
+
+        crate::player::run(files, gains, interactive, options)
     }
 
     #[cfg(not(feature = "player"))]
     {
         let _ = files;
         let _ = gains;
+        let _ = gain;
+        let _ = reference;
+        let _ = region;
         let _ = interactive;
-        use owo_colors::OwoColorize;
         println!("{} {}", "🎵".cyan(), "Audio Player".bold());
         println!();
         println!(
@@ -98,3 +246,302 @@ pub fn handle_play(
         Ok(())
     }
 }
+
+/// Check that the files to mix and the reference share a sample rate and
+/// channel count. Files whose headers can't be read here are checked again
+/// when the player decodes them.
+#[cfg(feature = "player")]
+fn check_mix_formats(files: &[String], reference: Option<&str>) -> Result<(), String> {
+    use crate::media::metadata::read_audio_metadata;
+    use crate::player::mixed_source::check_matches_mix;
+
+    let formats: Vec<(&str, (u32, u16))> = files
+        .iter()
+        .map(String::as_str)
+        .chain(reference)
+        .filter_map(|path| {
+            let metadata = read_audio_metadata(Path::new(path)).ok()?;
+            Some((path, (metadata.sample_rate, metadata.channels)))
+        })
+        .collect();
+    let Some((_, mix)) = formats.first() else {
+        return Ok(());
+    };
+    for (path, format) in &formats[1..] {
+        check_matches_mix(path, *format, *mix)?;
+    }
+    Ok(())
+}
+
+/// Seconds for `--start` and `--end`. The end must come after the start.
+/// `--loop` on its own loops the whole file.
+#[cfg(feature = "player")]
+fn parse_region(region: &PlayRegion) -> Result<(Option<f64>, Option<f64>), String> {
+    let parse = |flag: &str, text: &Option<String>| {
+        text.as_deref()
+            .map(crate::player::mark_prompt::parse_timecode)
+            .transpose()
+            .map_err(|e| format!("{flag}: {e}"))
+    };
+    let start = parse("--start", &region.start)?;
+    let end = parse("--end", &region.end)?;
+    if let (Some(start), Some(end)) = (start, end)
+        && end <= start
+    {
+        return Err(format!(
+            "--end ({}) must be after --start ({})",
+            region.end.as_deref().unwrap_or_default(),
+            region.start.as_deref().unwrap_or_default()
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Per-file gains from `--gains`, separated by commas, spaces or both. Each
+/// must be a number from 0.0 to `MAX_GAIN`, and there must be one per file
+/// when files are given.
+#[cfg(feature = "player")]
+fn parse_gains(values: &[String], file_count: usize) -> Result<Vec<f32>, String> {
+    let mut gains = Vec::new();
+    for text in values.iter().flat_map(|value| value.split_whitespace()) {
+        let position = gains.len() + 1;
+        let gain: f32 = text
+            .parse()
+            .map_err(|_| format!("Gain {position} ('{text}') is not a number."))?;
+        if !(0.0..=MAX_GAIN).contains(&gain) {
+            return Err(format!(
+                "Gain {position} ({text}) must be between 0.0 and {MAX_GAIN}."
+            ));
+        }
+        gains.push(gain);
+    }
+
+    if gains.is_empty() {
+        return Err("--gains needs at least one value.".to_string());
+    }
+    if file_count > 0 && gains.len() != file_count {
+        return Err(format!(
+            "Got {} gain{} for {file_count} file{}: give one gain per file, in the same order \
+             (e.g. --gains {}).",
+            gains.len(),
+            if gains.len() == 1 { "" } else { "s" },
+            if file_count == 1 { "" } else { "s" },
+            vec!["1.0"; file_count].join(",")
+        ));
+    }
+    Ok(gains)
+}
+
+/// Pick the master gain from the CLI or config, clamping it into range
+#[cfg(feature = "player")]
+fn resolve_master_gain(cli_gain: Option<f32>, config_gain: f32) -> f32 {
+    let gain = cli_gain.unwrap_or(config_gain);
+    if gain.is_nan() {
+        return 1.0;
+    }
+    let clamped = gain.clamp(0.0, MAX_GAIN);
+    if clamped != gain {
+        println!(
+            "{} Master gain {gain} is outside 0.0-{MAX_GAIN}, using {clamped}.",
+            "Warning:".yellow()
+        );
+    }
+    clamped
+}
+
+/// Clamp a hand-edited `monitor_chunk_size` into the range `zim config set` allows
+#[cfg(feature = "player")]
+fn resolve_monitor_frames(frames: usize) -> usize {
+    let (min, max) = (
+        MONITOR_FRAMES_RANGE.0 as usize,
+        MONITOR_FRAMES_RANGE.1 as usize,
+    );
+    let clamped = frames.clamp(min, max);
+    if clamped != frames {
+        println!(
+            "{} monitor_chunk_size {frames} is outside {min}-{max}, using {clamped}.",
+            "Warning:".yellow()
+        );
+    }
+    clamped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+
+    #[test]
+    fn test_read_file_list() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kick = temp_dir.path().join("kick.wav");
+        let snare = temp_dir.path().join("Snare.FLAC");
+        let notes = temp_dir.path().join("notes.txt");
+        for path in [&kick, &snare, &notes] {
+            std::fs::write(path, b"").unwrap();
+        }
+        let missing = temp_dir.path().join("missing.wav");
+
+        let input = format!(
+            "{}\n\n  {}  \n{}\n{}\n",
+            kick.display(),
+            snare.display(),
+            notes.display(),
+            missing.display()
+        );
+        let files = read_file_list(input.as_bytes()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                kick.to_string_lossy().to_string(),
+                snare.to_string_lossy().to_string()
+            ]
+        );
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_resolve_master_gain() {
+        assert_eq!(resolve_master_gain(None, 0.8), 0.8);
+        assert_eq!(resolve_master_gain(Some(0.5), 0.8), 0.5);
+        assert_eq!(resolve_master_gain(Some(3.0), 1.0), MAX_GAIN);
+        assert_eq!(resolve_master_gain(Some(-1.0), 1.0), 0.0);
+        assert_eq!(resolve_master_gain(Some(f32::NAN), 1.0), 1.0);
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_resolve_monitor_frames() {
+        assert_eq!(resolve_monitor_frames(1024), 1024);
+        assert_eq!(resolve_monitor_frames(0), 128);
+        assert_eq!(resolve_monitor_frames(1 << 20), 16384);
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_parse_gains_separators() {
+        let to_vec = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        // Comma-separated values arrive already split by clap
+        assert_eq!(
+            parse_gains(&to_vec(&["0.8", "1.2", "0.6"]), 3),
+            Ok(vec![0.8, 1.2, 0.6])
+        );
+        assert_eq!(
+            parse_gains(&to_vec(&["0.8 1.2  0.6"]), 3),
+            Ok(vec![0.8, 1.2, 0.6])
+        );
+        assert_eq!(
+            parse_gains(&to_vec(&["0.8", " 1.2 0.6"]), 3),
+            Ok(vec![0.8, 1.2, 0.6])
+        );
+        // With no files (browser mode) any count is accepted
+        assert_eq!(parse_gains(&to_vec(&["0.5"]), 0), Ok(vec![0.5]));
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_parse_gains_rejects_invalid() {
+        let to_vec = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let error = parse_gains(&to_vec(&["1.0", "3.0"]), 2).unwrap_err();
+        assert_eq!(error, "Gain 2 (3.0) must be between 0.0 and 2.");
+        assert!(parse_gains(&to_vec(&["-0.1"]), 1).is_err());
+        assert!(parse_gains(&to_vec(&["NaN"]), 1).is_err());
+        assert!(
+            parse_gains(&to_vec(&["loud"]), 1)
+                .unwrap_err()
+                .contains("'loud') is not a number")
+        );
+        assert!(parse_gains(&to_vec(&[" "]), 1).is_err());
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_parse_gains_count_mismatch() {
+        let to_vec = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_gains(&to_vec(&["0.8 1.2"]), 3).unwrap_err(),
+            "Got 2 gains for 3 files: give one gain per file, in the same order \
+             (e.g. --gains 1.0,1.0,1.0)."
+        );
+        assert!(
+            parse_gains(&to_vec(&["0.8", "1.2"]), 1)
+                .unwrap_err()
+                .starts_with("Got 2 gains for 1 file:")
+        );
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_parse_region() {
+        let region = |start: Option<&str>, end: Option<&str>, looping| PlayRegion {
+            start: start.map(String::from),
+            end: end.map(String::from),
+            looping,
+        };
+        assert_eq!(parse_region(&PlayRegion::default()), Ok((None, None)));
+        assert_eq!(
+            parse_region(&region(Some("1:00"), Some("1:30"), false)),
+            Ok((Some(60.0), Some(90.0)))
+        );
+        assert_eq!(
+            parse_region(&region(None, Some("45.5"), true)),
+            Ok((None, Some(45.5)))
+        );
+
+        assert_eq!(
+            parse_region(&region(Some("1:30"), Some("1:00"), false)).unwrap_err(),
+            "--end (1:00) must be after --start (1:30)"
+        );
+        assert!(
+            parse_region(&region(None, Some("soon"), false))
+                .unwrap_err()
+                .starts_with("--end: 'soon' is not a time")
+        );
+        // --loop alone loops the whole file
+        assert_eq!(parse_region(&region(None, None, true)), Ok((None, None)));
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_handle_play_rejects_invalid_region() {
+        let files = vec!["take.wav".to_string()];
+        let start = PlayRegion {
+            start: Some("1:75".to_string()),
+            ..Default::default()
+        };
+        let error = handle_play(files, None, None, None, start, false);
+        assert_eq!(error.unwrap_err().to_string(), "Invalid --start/--end");
+
+        let end = PlayRegion {
+            end: Some("1:30".to_string()),
+            ..Default::default()
+        };
+        let error = handle_play(Vec::new(), None, None, None, end, true);
+        assert_eq!(error.unwrap_err().to_string(), "No file to start playing");
+
+        let looping = PlayRegion {
+            looping: true,
+            ..Default::default()
+        };
+        let error = handle_play(Vec::new(), None, None, None, looping, true);
+        assert_eq!(error.unwrap_err().to_string(), "No file to start playing");
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_check_mix_formats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, sample_rate: u32| {
+            let path = temp_dir.path().join(name);
+            write_wav::<i16>(&path, pcm16(2, sample_rate), []);
+            path.to_string_lossy().into_owned()
+        };
+        let mix = write("mix.wav", 44100);
+        let stem = write("stem.wav", 44100);
+        let reference = write("reference.wav", 48000);
+
+        assert!(check_mix_formats(&[mix.clone(), stem], None).is_ok());
+        let error = check_mix_formats(&[mix], Some(&reference)).unwrap_err();
+        assert!(error.contains("48000Hz/2ch but the mix is 44100Hz/2ch"));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::config::Config;
+use crate::wav_metadata;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use zim_studio::constants::AUDIO_EXTENSIONS;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::parallel_scan;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::sidecar::{
+    SidecarLocation, get_sidecar_path, split_frontmatter, stored_audio_path,
+    update_frontmatter_fields,
+};
+use zim_studio::utils::validation::validate_path_exists;
+use zim_studio::zimignore::ZimIgnore;
+
+/// Counts of what a reindex run changed
+#[derive(Debug, Default, PartialEq)]
+struct ReindexSummary {
+    sidecars_updated: u32,
+    wav_files_updated: u32,
+    unchanged: u32,
+}
+
+/// Relative path and error message for a file that could not be reindexed
+type ReindexError = (String, String);
+
+pub fn handle_reindex(project_path: &str, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
+    let project_path = Path::new(project_path);
+
+    validate_path_exists(project_path)?;
+
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Reindexing project:".bright_black(),
+            project_path.display().to_string().cyan()
+        );
+    }
+
+    let spinner = create_progress_spinner(verbosity);
+    spinner.set_message("Rewriting sidecar paths...");
+    let absolute = Config::load().unwrap_or_default().absolute_sidecar_paths;
+    let (summary, errors) = reindex_project(project_path, absolute)?;
+    spinner.finish_and_clear();
+
+    for (path, error) in &errors {
+        eprintln!(
+            "  {} {}: {}",
+            "Error:".red(),
+            path.yellow(),
+            error.bright_black()
+        );
+    }
+
+    if !verbosity.is_quiet() {
+        println!("\n{} {}", "✓".green().bold(), "Reindex complete!".bold());
+        println!(
+            "  {} {} sidecar files",
+            "Updated:".bright_black(),
+            summary.sidecars_updated.to_string().green().bold()
+        );
+        println!(
+            "  {} {} WAV files",
+            "Retagged:".bright_black(),
+            summary.wav_files_updated.to_string().blue().bold()
+        );
+        println!(
+            "  {} {} files {}",
+            "Unchanged:".bright_black(),
+            summary.unchanged.to_string().yellow().bold(),
+            "(paths already current)".bright_black()
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} files could not be reindexed", errors.len()).into())
+    }
+}
+
+/// Rewrite `file`/`path` in every sidecar under the project, and the embedded
+/// WAV `original_path`, to match where the files live now. With `absolute`
+/// the sidecar `path` is written as an absolute path.
+fn reindex_project(
+    project_path: &Path,
+    absolute: bool,
+) -> Result<(ReindexSummary, Vec<ReindexError>), Box<dyn Error>> {
+    let audio_extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().cloned().collect();
+    let zimignore = ZimIgnore::load_for_directory(project_path);
+    let skip_dirs = parallel_scan::SkipDirs::from_config();
+    let sidecar_location = SidecarLocation::from_config();
+    let mut audio_files = parallel_scan::collect_audio_files(
+        project_path,
+        &audio_extensions,
+        &zimignore,
+        &skip_dirs,
+    )?;
+    audio_files.sort();
+
+    let mut summary = ReindexSummary::default();
+    let mut errors = Vec::new();
+
+    for audio_path in &audio_files {
+        let sidecar_path = get_sidecar_path(audio_path, sidecar_location);
+        if !sidecar_path.exists() {
+            continue;
+        }
+
+        let relative_path = audio_path
+            .strip_prefix(project_path)
+            .unwrap_or(audio_path)
+            .to_string_lossy()
+            .to_string();
+
+        let stored_path = stored_audio_path(audio_path, &relative_path, absolute);
+        match reindex_file(audio_path, &sidecar_path, &stored_path) {
+            Ok((sidecar_changed, wav_changed)) => {
+                if sidecar_changed {
+                    summary.sidecars_updated += 1;
+                }
+                if wav_changed {
+                    summary.wav_files_updated += 1;
+                }
+                if !sidecar_changed && !wav_changed {
+                    summary.unchanged += 1;
+                }
+            }
+            Err(e) => errors.push((relative_path, e.to_string())),
+        }
+    }
+
+    Ok((summary, errors))
+}
+
+/// Reindex one audio file. Returns whether the sidecar and the WAV were changed.
+fn reindex_file(
+    audio_path: &Path,
+    sidecar_path: &Path,
+    stored_path: &str,
+) -> Result<(bool, bool), Box<dyn Error>> {
+    let wav_changed = update_wav_original_path(audio_path)?;
+
+    let content = fs::read_to_string(sidecar_path)?;
+    let (yaml_content, _) =
+        split_frontmatter(&content).ok_or("Sidecar file has no YAML frontmatter")?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_content)?;
+
+    let file_name = audio_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let sidecar_changed = yaml.get("file").and_then(|v| v.as_str()) != Some(file_name.as_str())
+        || yaml.get("path").and_then(|v| v.as_str()) != Some(stored_path);
+
+    if sidecar_changed {
+        let new_content = update_frontmatter_fields(
+            &content,
+            vec![
+                ("file", serde_yaml::Value::String(file_name)),
+                ("path", serde_yaml::Value::String(stored_path.to_string())),
+            ],
+        )?;
+        fs::write(sidecar_path, new_content)?;
+    } else if wav_changed {
+        // Keep the sidecar newer than its audio so `zim update` doesn't flag it
+        fs::write(sidecar_path, content)?;
+    }
+
+    Ok((sidecar_changed, wav_changed))
+}
+
+/// Point embedded ZIM metadata at the WAV's current absolute location.
+/// Returns false for non-WAV files and files without ZIM metadata.
+fn update_wav_original_path(audio_path: &Path) -> Result<bool, Box<dyn Error>> {
+    let is_wav = audio_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return Ok(false);
+    }
+
+    let Some(mut metadata) = wav_metadata::read_metadata(audio_path)? else {
+        return Ok(false);
+    };
+
+    let current_path = fs::canonicalize(audio_path)?.to_string_lossy().to_string();
+    if metadata.original_path == current_path {
+        return Ok(false);
+    }
+    metadata.original_path = current_path;
+
+    // Write to a temp file then replace the original
+    let temp_path = audio_path.with_extension("wav.tmp");
+    if let Err(e) = wav_metadata::write_metadata(audio_path, &temp_path, &metadata) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, audio_path)?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    const SIDECAR: &str = r#"---
+file: "old_kick.wav"
+path: "old/location/sources/old_kick.wav"
+title: "kick"
+description: "a kick"
+duration: 1.50
+tags: ["drums"]
+art: []
+---
+
+# Notes
+
+Keep this.
+"#;
+
+    fn read_yaml(sidecar: &Path) -> (serde_yaml::Value, String) {
+        let content = fs::read_to_string(sidecar).unwrap();
+        let (yaml, body) = split_frontmatter(&content).unwrap();
+        (serde_yaml::from_str(yaml).unwrap(), body.to_string())
+    }
+
+    /// Build a project, then move it to a new location
+    fn relocated_project(temp_dir: &TempDir) -> PathBuf {
+        let original = temp_dir.path().join("old").join("project");
+        fs::create_dir_all(original.join("sources")).unwrap();
+        fs::write(original.join("sources").join("kick.flac"), b"").unwrap();
+        fs::write(original.join("sources").join("kick.flac.md"), SIDECAR).unwrap();
+
+        let moved = temp_dir.path().join("moved");
+        fs::rename(temp_dir.path().join("old"), &moved).unwrap();
+        moved.join("project")
+    }
+
+    #[test]
+    fn test_reindex_rewrites_file_and_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = relocated_project(&temp_dir);
+
+        let (summary, errors) = reindex_project(&project, false).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(summary.sidecars_updated, 1);
+        assert_eq!(summary.wav_files_updated, 0);
+
+        let (yaml, body) = read_yaml(&project.join("sources").join("kick.flac.md"));
+        assert_eq!(yaml["file"].as_str(), Some("kick.flac"));
+        assert_eq!(yaml["path"].as_str(), Some("sources/kick.flac"));
+        assert_eq!(yaml["title"].as_str(), Some("kick"));
+        assert_eq!(yaml["description"].as_str(), Some("a kick"));
+        assert_eq!(yaml["duration"].as_f64(), Some(1.5));
+        assert_eq!(yaml["tags"][0].as_str(), Some("drums"));
+        assert_eq!(body, "\n# Notes\n\nKeep this.\n");
+    }
+
+    #[test]
+    fn test_reindex_writes_absolute_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = relocated_project(&temp_dir);
+
+        let (summary, _) = reindex_project(&project, true).unwrap();
+        assert_eq!(summary.sidecars_updated, 1);
+        let kick = project.join("sources").join("kick.flac");
+        let (yaml, _) = read_yaml(&project.join("sources").join("kick.flac.md"));
+        assert_eq!(
+            yaml["path"].as_str(),
+            Some(fs::canonicalize(&kick).unwrap().to_string_lossy().as_ref())
+        );
+
+        // Switching back restores the relative form
+        reindex_project(&project, false).unwrap();
+        let (yaml, _) = read_yaml(&project.join("sources").join("kick.flac.md"));
+        assert_eq!(yaml["path"].as_str(), Some("sources/kick.flac"));
+    }
+
+    #[test]
+    fn test_reindex_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = relocated_project(&temp_dir);
+
+        reindex_project(&project, false).unwrap();
+        let (summary, _) = reindex_project(&project, false).unwrap();
+        assert_eq!(
+            summary,
+            ReindexSummary {
+                sidecars_updated: 0,
+                wav_files_updated: 0,
+                unchanged: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_reindex_updates_wav_original_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("old").join("project");
+        fs::create_dir_all(&original).unwrap();
+
+        let untagged = original.join("untagged.wav");
+        write_wav(&untagged, pcm16(1, 44100), 0..100i16);
+        let tagged = original.join("take.wav");
+        let metadata = wav_metadata::ZimMetadata::new_original("project", &tagged);
+        wav_metadata::write_metadata(&untagged, &tagged, &metadata).unwrap();
+        fs::remove_file(&untagged).unwrap();
+        fs::write(original.join("take.wav.md"), SIDECAR).unwrap();
+
+        let moved = temp_dir.path().join("moved");
+        fs::rename(temp_dir.path().join("old"), &moved).unwrap();
+        let project = moved.join("project");
+
+        let (summary, errors) = reindex_project(&project, false).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(summary.wav_files_updated, 1);
+
+        let new_path = project.join("take.wav");
+        let updated = wav_metadata::read_metadata(&new_path).unwrap().unwrap();
+        assert_eq!(
+            updated.original_path,
+            fs::canonicalize(&new_path).unwrap().to_string_lossy()
+        );
+        assert_eq!(updated.uuid, metadata.uuid);
+
+        let (yaml, _) = read_yaml(&project.join("take.wav.md"));
+        assert_eq!(yaml["path"].as_str(), Some("take.wav"));
+    }
+
+    #[test]
+    fn test_reindex_reports_broken_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("a.flac"), b"").unwrap();
+        fs::write(project.join("a.flac.md"), "# no frontmatter").unwrap();
+
+        let (_, errors) = reindex_project(&project, false).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "a.flac");
+    }
+}
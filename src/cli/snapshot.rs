@@ -0,0 +1,197 @@
+use crate::cli::lint::find_sidecars;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use zim_studio::constants::SNAPSHOT_FILE;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::validation::validate_path_exists;
+
+/// Manifest of sidecar content hashes, written to the project root
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    created: String,
+    /// Sidecar path relative to the project root, mapped to its MD5
+    sidecars: BTreeMap<String, String>,
+}
+
+/// Sidecars that differ between a snapshot and the project as it is now
+#[derive(Debug, Default, PartialEq)]
+struct SnapshotDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    modified: Vec<String>,
+}
+
+impl SnapshotDiff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.modified.len()
+    }
+}
+
+pub fn handle_snapshot(
+    project_path: &str,
+    check: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let project_path = Path::new(project_path);
+
+    validate_path_exists(project_path)?;
+
+    let spinner = create_progress_spinner(verbosity);
+    spinner.set_message("Hashing sidecar files...");
+    let hashes = hash_sidecars(project_path)?;
+    spinner.finish_and_clear();
+
+    let snapshot_path = project_path.join(SNAPSHOT_FILE);
+
+    if !check {
+        let snapshot = Snapshot {
+            created: chrono::Local::now().to_rfc3339(),
+            sidecars: hashes,
+        };
+        fs::write(&snapshot_path, serde_yaml::to_string(&snapshot)?)?;
+
+        if !verbosity.is_quiet() {
+            println!(
+                "{} Recorded {} sidecar files in {}",
+                "✓".green().bold(),
+                snapshot.sidecars.len().to_string().green().bold(),
+                snapshot_path.display().to_string().cyan()
+            );
+        }
+        return Ok(());
+    }
+
+    if !snapshot_path.exists() {
+        return Err(format!(
+            "No snapshot found at {} - run 'zim snapshot' first",
+            snapshot_path.display()
+        )
+        .into());
+    }
+    let snapshot: Snapshot = serde_yaml::from_str(&fs::read_to_string(&snapshot_path)?)
+        .map_err(|e| format!("Invalid snapshot {}: {e}", snapshot_path.display()))?;
+
+    let diff = compare(&snapshot.sidecars, &hashes);
+
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Comparing against snapshot from".bright_black(),
+            snapshot.created.cyan()
+        );
+        for (label, paths) in [
+            ("Added:", &diff.added),
+            ("Removed:", &diff.removed),
+            ("Modified:", &diff.modified),
+        ] {
+            for path in paths {
+                println!("  {} {}", label.yellow(), path);
+            }
+        }
+    }
+
+    if diff.is_empty() {
+        if !verbosity.is_quiet() {
+            println!(
+                "\n{} {}",
+                "✓".green().bold(),
+                "No sidecar changes since the snapshot".green()
+            );
+        }
+        Ok(())
+    } else {
+        Err(format!("{} sidecar files changed since the snapshot", diff.len()).into())
+    }
+}
+
+/// MD5 of every sidecar under the project, keyed by relative path
+fn hash_sidecars(project_path: &Path) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut hashes = BTreeMap::new();
+    for sidecar in find_sidecars(project_path)? {
+        let relative = sidecar
+            .strip_prefix(project_path)
+            .unwrap_or(&sidecar)
+            .to_string_lossy()
+            .to_string();
+        let digest = md5::compute(fs::read(&sidecar)?);
+        hashes.insert(relative, format!("{digest:x}"));
+    }
+    Ok(hashes)
+}
+
+fn compare(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> SnapshotDiff {
+    let mut diff = SnapshotDiff::default();
+    for (path, hash) in new {
+        match old.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(old_hash) if old_hash != hash => diff.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .keys()
+        .filter(|path| !new.contains_key(*path))
+        .cloned()
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_detects_drift() {
+        // The walk skips hidden directories, so use a visible project folder
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(project.join("mixes")).unwrap();
+        fs::write(project.join("kick.wav.md"), "---\ntitle: kick\n---\n").unwrap();
+        fs::write(project.join("snare.wav.md"), "---\ntitle: snare\n---\n").unwrap();
+        fs::write(project.join("mixes/final.flac.md"), "---\n---\n").unwrap();
+        fs::write(project.join("notes.md"), "not a sidecar").unwrap();
+        let project_str = project.to_str().unwrap();
+
+        handle_snapshot(project_str, false, Verbosity::Quiet).unwrap();
+        let snapshot: Snapshot =
+            serde_yaml::from_str(&fs::read_to_string(project.join(SNAPSHOT_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(
+            snapshot.sidecars.keys().collect::<Vec<_>>(),
+            ["kick.wav.md", "mixes/final.flac.md", "snare.wav.md"]
+        );
+        handle_snapshot(project_str, true, Verbosity::Quiet).unwrap();
+
+        fs::write(project.join("kick.wav.md"), "---\ntitle: big kick\n---\n").unwrap();
+        fs::remove_file(project.join("snare.wav.md")).unwrap();
+        fs::write(project.join("hat.wav.md"), "---\n---\n").unwrap();
+
+        let diff = compare(&snapshot.sidecars, &hash_sidecars(&project).unwrap());
+        assert_eq!(
+            diff,
+            SnapshotDiff {
+                added: vec!["hat.wav.md".to_string()],
+                removed: vec!["snare.wav.md".to_string()],
+                modified: vec!["kick.wav.md".to_string()],
+            }
+        );
+        assert!(handle_snapshot(project_str, true, Verbosity::Quiet).is_err());
+    }
+
+    #[test]
+    fn test_check_without_snapshot_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let error =
+            handle_snapshot(temp_dir.path().to_str().unwrap(), true, Verbosity::Quiet).unwrap_err();
+        assert!(error.to_string().contains("run 'zim snapshot' first"));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::cli::lint::find_sidecars;
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::sidecar::{audio_path_for_sidecar, split_frontmatter};
+use zim_studio::utils::validation::validate_path_exists;
+
+/// `extraction_type` of sidecars written for saved selections
+const SELECTION_EXTRACTION: &str = "selection";
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Source {
+    /// The source as found on disk, or as recorded when it's missing
+    path: String,
+    exists: bool,
+    /// Audio files excerpted from this source, sorted
+    excerpts: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct SourcesReport {
+    excerpts: usize,
+    /// Sorted by path
+    sources: Vec<Source>,
+}
+
+impl SourcesReport {
+    fn missing(&self) -> usize {
+        self.sources.iter().filter(|source| !source.exists).count()
+    }
+}
+
+/// List the source files that the excerpt sidecars under `path` were cut
+/// from, flagging sources that are no longer on disk
+pub fn handle_sources(path: &str, json: bool, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(path);
+    validate_path_exists(path)?;
+
+    let spinner = create_progress_spinner(if json { Verbosity::Quiet } else { verbosity });
+    spinner.set_message("Reading excerpt sidecars...");
+    let report = collect_sources(path)?;
+    spinner.finish_and_clear();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(path, &report);
+    }
+    Ok(())
+}
+
+/// The `source_file` of an excerpt sidecar, or `None` if it isn't one
+fn read_source_file(content: &str) -> Option<String> {
+    let (yaml, _) = split_frontmatter(content)?;
+    let fields: HashMap<String, Value> = serde_yaml::from_str(yaml).ok()?;
+    if fields.get("extraction_type").and_then(Value::as_str) != Some(SELECTION_EXTRACTION) {
+        return None;
+    }
+    let source = fields.get("source_file").and_then(Value::as_str)?;
+    (!source.is_empty()).then(|| source.to_string())
+}
+
+/// Where a recorded source is on disk. Relative sources were recorded from
+/// wherever the player ran, so they are tried against the current
+/// directory, the excerpt's folder and the scanned folder.
+fn locate_source(source: &str, excerpt: &Path, root: &Path) -> Option<PathBuf> {
+    let source = Path::new(source);
+    let candidates = if source.is_absolute() {
+        vec![source.to_path_buf()]
+    } else {
+        let excerpt_dir = excerpt.parent().unwrap_or(Path::new(""));
+        vec![
+            source.to_path_buf(),
+            excerpt_dir.join(source),
+            root.join(source),
+        ]
+    };
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .map(|found| fs::canonicalize(&found).unwrap_or(found))
+}
+
+fn collect_sources(root: &Path) -> Result<SourcesReport, Box<dyn Error>> {
+    let mut sources: BTreeMap<String, Source> = BTreeMap::new();
+    let mut excerpts = 0;
+    for sidecar in find_sidecars(root)? {
+        let Ok(content) = fs::read_to_string(&sidecar) else {
+            continue;
+        };
+        let Some(recorded) = read_source_file(&content) else {
+            continue;
+        };
+        excerpts += 1;
+
+        let excerpt = audio_path_for_sidecar(&sidecar);
+        // The same source recorded through different relative paths is one
+        // source once it's found
+        let found = locate_source(&recorded, &excerpt, root);
+        let path = found
+            .as_ref()
+            .map_or(recorded, |found| found.to_string_lossy().into_owned());
+        sources
+            .entry(path.clone())
+            .or_insert_with(|| Source {
+                path,
+                exists: found.is_some(),
+                excerpts: Vec::new(),
+            })
+            .excerpts
+            .push(excerpt);
+    }
+
+    let mut sources: Vec<Source> = sources.into_values().collect();
+    for source in &mut sources {
+        source.excerpts.sort();
+    }
+    Ok(SourcesReport { excerpts, sources })
+}
+
+fn print_report(path: &Path, report: &SourcesReport) {
+    println!(
+        "{} {} {}",
+        "Excerpt sources in".bright_black(),
+        path.display().to_string().cyan(),
+        format!(
+            "({} sources for {} excerpts)",
+            report.sources.len(),
+            report.excerpts
+        )
+        .bright_black()
+    );
+    if report.sources.is_empty() {
+        println!("  {}", "No excerpt sidecars found".yellow());
+        return;
+    }
+
+    for source in &report.sources {
+        let count = match source.excerpts.len() {
+            1 => "1 excerpt".to_string(),
+            count => format!("{count} excerpts"),
+        };
+        if source.exists {
+            println!(
+                "  {} {}",
+                source.path.cyan(),
+                format!("({count})").bright_black()
+            );
+        } else {
+            println!(
+                "  {} {} {}",
+                source.path.red(),
+                "missing".red().bold(),
+                format!("({count})").bright_black()
+            );
+        }
+        for excerpt in &source.excerpts {
+            println!("    {}", excerpt.display().to_string().bright_black());
+        }
+    }
+
+    match report.missing() {
+        0 => println!("{} Every source is still on disk", "✓".green().bold()),
+        missing => println!(
+            "{} {missing} source(s) no longer on disk",
+            "⚠".yellow().bold()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use zim_studio::utils::sidecar::{
+        SidecarCloneMode, SidecarLocation, clone_sidecar, yaml_quote,
+    };
+
+    fn excerpt_sidecar(source: &str) -> String {
+        format!(
+            "---\nfile: \"cut.wav\"\nsource_file: {}\nextraction_type: \"selection\"\n---\n",
+            yaml_quote(source)
+        )
+    }
+
+    #[test]
+    fn test_read_source_file() {
+        assert_eq!(
+            read_source_file(&excerpt_sidecar("masters/final.wav")).as_deref(),
+            Some("masters/final.wav")
+        );
+        // Backslashes and quotes survive the round trip
+        assert_eq!(
+            read_source_file(&excerpt_sidecar(r#"C:\Users\me\"final".wav"#)).as_deref(),
+            Some(r#"C:\Users\me\"final".wav"#)
+        );
+        // Copies and plain sidecars aren't excerpts
+        assert!(read_source_file("---\nfile: \"a.wav\"\n---\n").is_none());
+        assert!(
+            read_source_file("---\nsource_file: \"a.wav\"\nextraction_type: \"copy\"\n---\n")
+                .is_none()
+        );
+        assert!(read_source_file("# No frontmatter\n").is_none());
+    }
+
+    #[test]
+    fn test_collect_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("masters")).unwrap();
+        fs::create_dir_all(root.join("edits")).unwrap();
+        let master = root.join("masters/final.wav");
+        fs::write(&master, b"").unwrap();
+        // One source with frontmatter and one without, as the save dialog
+        // would clone them
+        fs::write(
+            root.join("masters/final.wav.md"),
+            "---\ntags: [\"master\"]\n---\n",
+        )
+        .unwrap();
+        let bare = root.join("masters/bare.wav");
+        fs::write(&bare, b"").unwrap();
+        fs::write(root.join("masters/bare.wav.md"), "no frontmatter\n").unwrap();
+        for (source, excerpt) in [
+            (&master, "edits/intro.wav"),
+            (&master, "edits/outro.wav"),
+            (&bare, "edits/bare_cut.wav"),
+        ] {
+            let mode = SidecarCloneMode::Selection {
+                start_time: 1.0,
+                end_time: 3.0,
+                duration: 10.0,
+                inherit_tags: true,
+            };
+            clone_sidecar(
+                source,
+                &root.join(excerpt),
+                mode,
+                None,
+                SidecarLocation::Adjacent,
+            )
+            .unwrap();
+        }
+        // An excerpt of a master that has since been deleted
+        fs::write(root.join("edits/old.wav"), b"").unwrap();
+        fs::write(
+            root.join("edits/old.wav.md"),
+            excerpt_sidecar("masters/deleted.wav"),
+        )
+        .unwrap();
+
+        let report = collect_sources(root).unwrap();
+        assert_eq!(report.excerpts, 4);
+        assert_eq!(report.missing(), 1);
+        let master = fs::canonicalize(&master).unwrap();
+        let found = report
+            .sources
+            .iter()
+            .find(|source| Path::new(&source.path) == master)
+            .unwrap();
+        assert!(found.exists);
+        assert_eq!(
+            found.excerpts,
+            [root.join("edits/intro.wav"), root.join("edits/outro.wav")]
+        );
+        let missing = report.sources.iter().find(|source| !source.exists).unwrap();
+        assert_eq!(missing.path, "masters/deleted.wav");
+        assert_eq!(missing.excerpts, [root.join("edits/old.wav")]);
+        assert_eq!(report.sources.len(), 3);
+    }
+}
@@ -1,6 +1,4 @@
 use crate::media::metadata::read_audio_metadata;
-use indicatif::MultiProgress;
-use owo_colors::OwoColorize;
 use rayon::prelude::*;
 use serde_yaml;
 use std::collections::{HashMap, HashSet};
@@ -9,31 +7,44 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use zim_studio::constants::AUDIO_EXTENSIONS;
+use zim_studio::utils::color::Colorize;
 use zim_studio::utils::parallel_scan;
-use zim_studio::utils::progress::{create_progress_bar, create_progress_spinner};
+use zim_studio::utils::progress::{
+    Verbosity, create_multi_progress, create_progress_bar, create_progress_spinner,
+};
+use zim_studio::utils::sidecar::{SidecarLocation, get_sidecar_path};
 use zim_studio::utils::validation::validate_path_exists;
 use zim_studio::zimignore::ZimIgnore;
 
-pub fn handle_sync(project_path: &str) -> Result<(), Box<dyn Error>> {
+pub fn handle_sync(project_path: &str, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
     let project_path = Path::new(project_path);
 
     // Verify this is a valid project directory
     validate_path_exists(project_path)?;
 
-    println!(
-        "{} {}",
-        "Syncing metadata in:".bright_black(),
-        project_path.display().to_string().cyan()
-    );
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Syncing metadata in:".bright_black(),
+            project_path.display().to_string().cyan()
+        );
+    }
 
     let audio_extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().cloned().collect();
     let zimignore = ZimIgnore::load_for_directory(project_path);
+    let skip_dirs = parallel_scan::SkipDirs::from_config();
 
     // Count files that need syncing
-    let spinner = create_progress_spinner();
+    let spinner = create_progress_spinner(verbosity);
     spinner.set_message("Scanning for audio files with sidecars...");
 
-    let files_to_sync = find_files_to_sync(project_path, &audio_extensions, &zimignore)?;
+    let files_to_sync = find_files_to_sync(
+        project_path,
+        &audio_extensions,
+        &zimignore,
+        &skip_dirs,
+        SidecarLocation::from_config(),
+    )?;
     spinner.finish_and_clear();
 
     if files_to_sync.is_empty() {
@@ -41,18 +52,20 @@ pub fn handle_sync(project_path: &str) -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    println!(
-        "{} Found {} files to check for sync\n",
-        "ℹ".blue(),
-        files_to_sync.len().to_string().cyan().bold()
-    );
+    if !verbosity.is_quiet() {
+        println!(
+            "{} Found {} files to check for sync\n",
+            "ℹ".blue(),
+            files_to_sync.len().to_string().cyan().bold()
+        );
+    }
 
     let synced_count = Arc::new(Mutex::new(0));
     let skipped_count = Arc::new(Mutex::new(0));
     let error_count = Arc::new(Mutex::new(0));
 
-    let multi = MultiProgress::new();
-    let pb = multi.add(create_progress_bar(files_to_sync.len() as u64));
+    let multi = create_multi_progress(verbosity);
+    let pb = multi.add(create_progress_bar(files_to_sync.len() as u64, verbosity));
     pb.set_message("Syncing metadata...");
 
     for (audio_path, sidecar_path) in &files_to_sync {
@@ -87,7 +100,10 @@ pub fn handle_sync(project_path: &str) -> Result<(), Box<dyn Error>> {
     let skipped = *skipped_count.lock().unwrap();
     let errors = *error_count.lock().unwrap();
 
-    print_sync_summary(synced, skipped, errors);
+    // Quiet runs only report a summary when something went wrong
+    if !verbosity.is_quiet() || errors > 0 {
+        print_sync_summary(synced, skipped, errors);
+    }
 
     Ok(())
 }
@@ -96,15 +112,17 @@ fn find_files_to_sync(
     dir: &Path,
     audio_exts: &HashSet<&str>,
     zimignore: &ZimIgnore,
+    skip_dirs: &parallel_scan::SkipDirs,
+    sidecar_location: SidecarLocation,
 ) -> Result<Vec<(PathBuf, PathBuf)>, Box<dyn Error>> {
     // Use parallel scanning to collect all audio files
-    let audio_files = parallel_scan::collect_audio_files(dir, audio_exts, zimignore)?;
+    let audio_files = parallel_scan::collect_audio_files(dir, audio_exts, zimignore, skip_dirs)?;
 
     // Filter to only files that have sidecars (in parallel)
     let files_with_sidecars: Vec<(PathBuf, PathBuf)> = audio_files
         .par_iter()
         .filter_map(|audio_path| {
-            let sidecar_path = get_sidecar_path(audio_path);
+            let sidecar_path = get_sidecar_path(audio_path, sidecar_location);
             if sidecar_path.exists() {
                 Some((audio_path.clone(), sidecar_path))
             } else {
@@ -217,10 +235,6 @@ fn update_yaml_fields(
     Ok(format!("---\n{updated_yaml}---\n{markdown_section}"))
 }
 
-fn get_sidecar_path(media_path: &Path) -> PathBuf {
-    zim_studio::utils::sidecar::get_sidecar_path(media_path)
-}
-
 fn print_sync_summary(synced: u32, skipped: u32, errors: u32) {
     println!("\n{} {}", "✓".green().bold(), "Sync complete!".bold());
     println!(
@@ -4,10 +4,10 @@
 //! WAV files using INFO LIST chunks.
 
 use crate::wav_metadata::{self, ZimMetadata};
-use owo_colors::OwoColorize;
 use std::error::Error;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
+use zim_studio::utils::color::Colorize;
 use zim_studio::utils::project::find_project_root;
 
 pub fn handle_tag_edit(
@@ -0,0 +1,163 @@
+use crate::tag_vocabulary::TagVocabulary;
+use serde::Serialize;
+use std::error::Error;
+use std::path::Path;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::progress::{Verbosity, create_progress_spinner};
+use zim_studio::utils::validation::validate_path_exists;
+
+/// Widest bar drawn next to a tag's count
+const MAX_BAR_WIDTH: usize = 30;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct TagCount {
+    tag: String,
+    count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct TagReport {
+    sidecars: usize,
+    /// Most used first, then alphabetical
+    tags: Vec<TagCount>,
+}
+
+/// List every tag used in the sidecars under `path` with how many files
+/// carry it, optionally only tags starting with `prefix`
+pub fn handle_tags(
+    path: &str,
+    prefix: Option<&str>,
+    json: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(path);
+    validate_path_exists(path)?;
+
+    let spinner = create_progress_spinner(if json { Verbosity::Quiet } else { verbosity });
+    spinner.set_message("Reading sidecar tags...");
+    let report = count_tags(path, prefix.unwrap_or_default())?;
+    spinner.finish_and_clear();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(path, &report);
+    }
+    Ok(())
+}
+
+/// Count the tags in the sidecars under `path`, keeping those that start
+/// with `prefix` (case-insensitive)
+fn count_tags(path: &Path, prefix: &str) -> Result<TagReport, Box<dyn Error>> {
+    let vocabulary = TagVocabulary::scan(path)?;
+    let tags = vocabulary
+        .suggestions(prefix, &[])
+        .into_iter()
+        .map(|(tag, count)| TagCount {
+            tag: tag.clone(),
+            count: *count,
+        })
+        .collect();
+    Ok(TagReport {
+        sidecars: vocabulary.files(),
+        tags,
+    })
+}
+
+fn print_report(path: &Path, report: &TagReport) {
+    println!(
+        "{} {} {}",
+        "Tags in".bright_black(),
+        path.display().to_string().cyan(),
+        format!(
+            "({} distinct across {} sidecars)",
+            report.tags.len(),
+            report.sidecars
+        )
+        .bright_black()
+    );
+    let Some(most) = report.tags.first().map(|entry| entry.count) else {
+        println!("  {}", "No tags found".yellow());
+        return;
+    };
+
+    let count_width = most.to_string().len();
+    let tag_width = report
+        .tags
+        .iter()
+        .map(|entry| entry.tag.chars().count())
+        .max()
+        .unwrap_or(0);
+    for entry in &report.tags {
+        let bar_width = (entry.count * MAX_BAR_WIDTH).div_ceil(most);
+        println!(
+            "  {}  {}  {}",
+            format!("{:>count_width$}", entry.count).bold(),
+            format!("{:<tag_width$}", entry.tag).cyan(),
+            "█".repeat(bar_width).bright_black()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tag_count(tag: &str, count: usize) -> TagCount {
+        TagCount {
+            tag: tag.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_count_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::create_dir_all(root.join(".zim/sources")).unwrap();
+        fs::write(
+            root.join("mixes/a.wav.md"),
+            "---\ntags: [\"ambient\", \"pads\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("mixes/b.wav.md"),
+            "---\ntags: [\"ambient\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join(".zim/sources/c.flac.md"),
+            "---\ntags: [\"ambiant\", \"pads\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("mixes/d.wav.md"), "---\ntitle: d\n---\n").unwrap();
+        // Not a sidecar
+        fs::write(root.join("README.md"), "---\ntags: [\"project\"]\n---\n").unwrap();
+
+        let report = count_tags(root, "").unwrap();
+        assert_eq!(report.sidecars, 4);
+        assert_eq!(
+            report.tags,
+            [
+                tag_count("ambient", 2),
+                tag_count("pads", 2),
+                tag_count("ambiant", 1)
+            ]
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["sidecars"], 4);
+        assert_eq!(json["tags"][0]["tag"], "ambient");
+        assert_eq!(json["tags"][0]["count"], 2);
+
+        let report = count_tags(root, "AMB").unwrap();
+        assert_eq!(report.sidecars, 4);
+        assert_eq!(
+            report.tags,
+            [tag_count("ambient", 2), tag_count("ambiant", 1)]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use crate::cli::update::determine_file_type;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zim_studio::constants::AUDIO_EXTENSIONS;
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::parallel_scan::{self, SkipDirs, VisitedDirs};
+use zim_studio::utils::sidecar::{
+    SidecarLocation, get_sidecar_path, split_frontmatter, stored_duration,
+};
+use zim_studio::utils::validation::validate_path_exists;
+use zim_studio::zimignore::ZimIgnore;
+
+/// What a sidecar says about its audio file
+#[derive(Debug, Default, PartialEq)]
+struct AudioSummary {
+    duration: Option<f64>,
+    tags: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum NodeKind {
+    Dir,
+    /// `None` when the file has no readable sidecar
+    Audio(Option<AudioSummary>),
+    Other,
+}
+
+#[derive(Debug)]
+struct TreeNode {
+    name: String,
+    path: PathBuf,
+    kind: NodeKind,
+    children: Vec<TreeNode>,
+}
+
+/// Print the project tree under `path`, annotating each audio file with the
+/// duration and tag count from its sidecar
+pub fn handle_tree(path: &str, audio_only: bool) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(path);
+    validate_path_exists(root)?;
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()).into());
+    }
+
+    let zimignore = ZimIgnore::load_for_directory(root);
+    let children = build_tree(
+        root,
+        audio_only,
+        &zimignore,
+        &SkipDirs::from_config(),
+        &VisitedDirs::default(),
+        SidecarLocation::from_config(),
+    )?;
+
+    println!("{}", root.display().to_string().blue().bold());
+    for line in render_tree(&children, "") {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Whether `path` is the sidecar of an audio file in the same folder, which
+/// the tree already shows through the audio file's annotation
+fn is_sidecar_of_sibling(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    name.strip_suffix(".md").is_some_and(|audio_name| {
+        let audio = path.with_file_name(audio_name);
+        is_audio_file(&audio) && audio.is_file()
+    })
+}
+
+fn read_audio_summary(audio: &Path, sidecar_location: SidecarLocation) -> Option<AudioSummary> {
+    let content = fs::read_to_string(get_sidecar_path(audio, sidecar_location)).ok()?;
+    let (yaml, _) = split_frontmatter(&content)?;
+    let fields: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml).ok()?;
+    let tags = fields
+        .get("tags")
+        .and_then(|tags| tags.as_sequence())
+        .map_or(0, |tags| tags.len());
+    Some(AudioSummary {
+        duration: stored_duration(&fields),
+        tags,
+    })
+}
+
+/// The sorted entries of `dir`, skipping the same hidden, ignored and skipped
+/// folders as a scan. With `audio_only`, other files and folders without any
+/// audio in them are left out. Subfolders that can't be read are skipped
+/// with a warning.
+fn build_tree(
+    dir: &Path,
+    audio_only: bool,
+    zimignore: &ZimIgnore,
+    skip_dirs: &SkipDirs,
+    visited: &VisitedDirs,
+    sidecar_location: SidecarLocation,
+) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    paths.sort();
+
+    let mut nodes = Vec::new();
+    for path in paths {
+        if parallel_scan::is_hidden_file(&path) {
+            continue;
+        }
+        let is_dir = path.is_dir();
+        if zimignore.is_ignored(&path, is_dir) {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let (kind, children) = if is_dir {
+            if skip_dirs.contains(&name)
+                || !parallel_scan::should_descend(&path, skip_dirs, visited)
+            {
+                continue;
+            }
+            let children = match build_tree(
+                &path,
+                audio_only,
+                zimignore,
+                skip_dirs,
+                visited,
+                sidecar_location,
+            ) {
+                Ok(children) => children,
+                Err(e) => {
+                    eprintln!("{} Skipping {}: {e}", "Warning:".yellow(), path.display());
+                    continue;
+                }
+            };
+            if audio_only && children.is_empty() {
+                continue;
+            }
+            (NodeKind::Dir, children)
+        } else if is_audio_file(&path) {
+            (
+                NodeKind::Audio(read_audio_summary(&path, sidecar_location)),
+                Vec::new(),
+            )
+        } else if audio_only || is_sidecar_of_sibling(&path) {
+            continue;
+        } else {
+            (NodeKind::Other, Vec::new())
+        };
+        nodes.push(TreeNode {
+            name,
+            path,
+            kind,
+            children,
+        });
+    }
+    Ok(nodes)
+}
+
+/// `m:ss`, or `h:mm:ss` for an hour or more
+fn format_length(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+fn annotation(summary: &Option<AudioSummary>) -> String {
+    match summary {
+        Some(summary) => {
+            let duration = summary
+                .duration
+                .map(format_length)
+                .unwrap_or_else(|| "?:??".to_string());
+            let tags = match summary.tags {
+                1 => "1 tag".to_string(),
+                count => format!("{count} tags"),
+            };
+            format!("{duration} · {tags}").bright_black().to_string()
+        }
+        None => "(no sidecar)".yellow().to_string(),
+    }
+}
+
+/// Audio files are colored by the role their folder gives them
+fn styled_audio_name(node: &TreeNode) -> String {
+    let role = determine_file_type(&node.path).map(|(singular, _)| singular);
+    match role.as_deref() {
+        Some("master") => node.name.magenta().to_string(),
+        Some("mix") => node.name.cyan().to_string(),
+        Some("source" | "recording" | "take" | "sample") => node.name.green().to_string(),
+        Some("edit" | "bounce" | "stem" | "render") => node.name.yellow().to_string(),
+        _ => node.name.clone(),
+    }
+}
+
+fn render_tree(nodes: &[TreeNode], prefix: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let is_last = index == nodes.len() - 1;
+        let connector = if is_last { "└── " } else { "├── " };
+        let label = match &node.kind {
+            NodeKind::Dir => node.name.blue().bold().to_string(),
+            NodeKind::Audio(summary) => {
+                format!("{}  {}", styled_audio_name(node), annotation(summary))
+            }
+            NodeKind::Other => node.name.bright_black().to_string(),
+        };
+        lines.push(format!("{prefix}{}{label}", connector.bright_black()));
+
+        if node.kind == NodeKind::Dir {
+            let extension = if is_last { "    " } else { "│   " };
+            let child_prefix = format!("{prefix}{}", extension.bright_black());
+            lines.extend(render_tree(&node.children, &child_prefix));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(nodes: &[TreeNode]) -> Vec<&str> {
+        nodes.iter().map(|node| node.name.as_str()).collect()
+    }
+
+    fn tree_of(root: &Path, audio_only: bool) -> Vec<TreeNode> {
+        build_tree(
+            root,
+            audio_only,
+            &ZimIgnore::load_with_global(root, None),
+            &SkipDirs::default(),
+            &VisitedDirs::default(),
+            SidecarLocation::Adjacent,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::create_dir_all(root.join("bounces")).unwrap();
+        fs::write(root.join("README.md"), "# Project\n").unwrap();
+        fs::write(root.join("mixes/v1.wav"), b"").unwrap();
+        fs::write(
+            root.join("mixes/v1.wav.md"),
+            "---\nduration: 205.4\ntags: [\"mix\", \"rough\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("mixes/v2.flac"), b"").unwrap();
+        fs::write(root.join("notes/todo.txt"), b"").unwrap();
+        fs::write(root.join("bounces/old.wav"), b"").unwrap();
+        fs::write(root.join(".zimignore"), "bounces/\n").unwrap();
+
+        let tree = tree_of(&root, false);
+        assert_eq!(names(&tree), ["README.md", "mixes", "notes"]);
+        let mixes = &tree[1].children;
+        // The sidecar shows up as the audio file's annotation instead
+        assert_eq!(names(mixes), ["v1.wav", "v2.flac"]);
+        assert_eq!(
+            mixes[0].kind,
+            NodeKind::Audio(Some(AudioSummary {
+                duration: Some(205.4),
+                tags: 2
+            }))
+        );
+        assert_eq!(mixes[1].kind, NodeKind::Audio(None));
+
+        let tree = tree_of(&root, true);
+        assert_eq!(names(&tree), ["mixes"]);
+        assert_eq!(names(&tree[0].children), ["v1.wav", "v2.flac"]);
+    }
+
+    #[test]
+    fn test_render_tree_annotates_audio() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("masters")).unwrap();
+        fs::write(root.join("masters/final.wav"), b"").unwrap();
+        fs::write(
+            root.join("masters/final.wav.md"),
+            "---\nduration: 3725.0\ntags: [\"master\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("notes.md"), b"").unwrap();
+
+        let lines = render_tree(&tree_of(&root, false), "");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("├── ") && lines[0].contains("masters"));
+        assert!(lines[1].contains("└── ") && lines[1].contains("final.wav"));
+        assert!(lines[1].contains("1:02:05 · 1 tag"));
+        assert!(lines[2].contains("└── ") && lines[2].contains("notes.md"));
+    }
+
+    #[test]
+    fn test_format_length() {
+        assert_eq!(format_length(0.0), "0:00");
+        assert_eq!(format_length(205.4), "3:25");
+        assert_eq!(format_length(3725.0), "1:02:05");
+    }
+}
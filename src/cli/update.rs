@@ -1,9 +1,10 @@
 use crate::config::Config;
-use crate::media::metadata::read_audio_metadata;
-use crate::templates::{self, SidecarMetadata};
+use crate::media::artwork::read_embedded_picture;
+use crate::media::metadata::{AudioMetadata, read_audio_metadata, read_video_duration};
+use crate::templates::{self, ArtEntry, SidecarMetadata};
 use crate::wav_metadata;
-use indicatif::{MultiProgress, ProgressBar};
-use owo_colors::OwoColorize;
+use indicatif::ProgressBar;
+use regex::{Regex, RegexBuilder};
 use serde_yaml;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
@@ -12,15 +13,33 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
-use zim_studio::constants::AUDIO_EXTENSIONS;
+use zim_studio::constants::{DURATION_FIELD, UPDATE_CHECKPOINT_FILE, VIDEO_EXTENSIONS};
+use zim_studio::utils::color::Colorize;
 use zim_studio::utils::parallel_scan;
-use zim_studio::utils::progress::{create_progress_bar, create_progress_spinner};
-use zim_studio::utils::project::find_project_root;
-use zim_studio::utils::sidecar::get_sidecar_path;
+use zim_studio::utils::progress::{
+    Verbosity, create_multi_progress, create_progress_bar, create_progress_spinner,
+};
+use zim_studio::utils::project::{ProjectInfo, find_project_info};
+use zim_studio::utils::sidecar::{
+    SidecarLocation, duration_changed, duration_value, format_duration, get_sidecar_path,
+    split_frontmatter, stored_audio_path, stored_duration, update_frontmatter_fields,
+    write_sidecar,
+};
 use zim_studio::utils::validation::validate_path_exists;
-use zim_studio::zimignore::ZimIgnore;
+use zim_studio::zimignore::{PathFilter, ZimIgnore};
 
-pub fn handle_update(project_path: &str, extra_tags: &[String]) -> Result<(), Box<dyn Error>> {
+#[allow(clippy::too_many_arguments)]
+pub fn handle_update(
+    project_path: &str,
+    extra_tags: &[String],
+    include: &[String],
+    exclude: &[String],
+    extract_art: bool,
+    since: Option<std::time::Duration>,
+    resume: bool,
+    watch: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
     let project_path = Path::new(project_path);
 
     // Verify this is a valid project directory
@@ -28,54 +47,106 @@ pub fn handle_update(project_path: &str, extra_tags: &[String]) -> Result<(), Bo
 
     // Load configuration with tag mappings
     let config = Arc::new(Config::load()?);
+    for (pattern, error) in invalid_tag_regexes(&config) {
+        eprintln!(
+            "{} Ignoring tag_regex pattern '{}': {}",
+            "Warning:".yellow(),
+            pattern.yellow(),
+            error.bright_black()
+        );
+    }
+    let tag_mappings = TagMappings::new(&config);
 
-    println!(
-        "{} {}",
-        "Scanning project:".bright_black(),
-        project_path.display().to_string().cyan()
-    );
+    if !verbosity.is_quiet() {
+        println!(
+            "{} {}",
+            "Scanning project:".bright_black(),
+            project_path.display().to_string().cyan()
+        );
+    }
 
-    // Get audio file extensions we want sidecars for
-    let audio_extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().cloned().collect();
+    // Get the extensions we want sidecars for
+    let audio_extensions = parallel_scan::sidecar_extensions(config.video_sidecars);
 
     // Load .zimignore files for this directory hierarchy
     let zimignore = ZimIgnore::load_for_directory(project_path);
+    let skip_dirs = parallel_scan::SkipDirs::from_config();
 
     // Collect all audio files using parallel scanning
-    let spinner = create_progress_spinner();
+    let spinner = create_progress_spinner(verbosity);
     spinner.set_message("Scanning for audio files...");
 
-    let audio_files =
-        parallel_scan::collect_audio_files(project_path, &audio_extensions, &zimignore)?;
+    let mut audio_files = parallel_scan::collect_audio_files(
+        project_path,
+        &audio_extensions,
+        &zimignore,
+        &skip_dirs,
+    )?;
     spinner.finish_and_clear();
 
-    let total_files = audio_files.len();
+    // Apply one-off --include/--exclude filters
+    let filter = PathFilter::new(project_path, include, exclude);
+    audio_files.retain(|path| filter.matches(path));
+
+    // Incremental runs only look at recently modified files
+    if let Some(window) = since {
+        retain_modified_within(&mut audio_files, window, SystemTime::now());
+        if audio_files.is_empty() {
+            if !verbosity.is_quiet() {
+                println!(
+                    "{} No audio files modified in the last {}",
+                    "ℹ".blue(),
+                    format_window(window)
+                );
+            }
+            return Ok(());
+        }
+    }
 
-    if total_files == 0 {
+    if audio_files.is_empty() {
         println!("{} No audio files found in project", "⚠".yellow());
         return Ok(());
     }
 
-    println!(
-        "{} Found {} audio files\n",
-        "ℹ".blue(),
-        total_files.to_string().cyan().bold()
-    );
+    // Pick up where an interrupted run left off, or start a new checkpoint
+    let mut checkpoint = if resume {
+        UpdateCheckpoint::resume(project_path)?
+    } else {
+        UpdateCheckpoint::start(project_path)
+    };
+    if resume {
+        let before = audio_files.len();
+        audio_files.retain(|path| !checkpoint.contains(path));
+        if !verbosity.is_quiet() {
+            println!(
+                "{} Resuming: {} files were already processed",
+                "ℹ".blue(),
+                (before - audio_files.len()).to_string().cyan()
+            );
+        }
+    }
+    let total_files = audio_files.len();
+
+    if !verbosity.is_quiet() {
+        println!(
+            "{} Found {} audio files\n",
+            "ℹ".blue(),
+            total_files.to_string().cyan().bold()
+        );
+    }
 
     let created_count = Arc::new(Mutex::new(0));
     let skipped_count = Arc::new(Mutex::new(0));
     let updated_count = Arc::new(Mutex::new(0));
 
     // Create project root cache for performance
-    let project_cache = Arc::new(Mutex::new(HashMap::<PathBuf, Option<String>>::new()));
+    let project_cache = Arc::new(Mutex::new(HashMap::<PathBuf, Option<ProjectInfo>>::new()));
 
-    let multi = MultiProgress::new();
-    let pb = multi.add(create_progress_bar(total_files as u64));
+    let multi = create_multi_progress(verbosity);
+    let pb = multi.add(create_progress_bar(total_files as u64, verbosity));
     pb.set_message("Processing audio files...");
 
-    // Process files sequentially but with parallel scanning already done
-    // (Processing itself involves I/O and user interaction which can't be parallelized)
-    for file_path in &audio_files {
+    let mut process = |file_path: &Path| -> Result<(), Box<dyn Error>> {
         let result = process_media_file(
             file_path,
             &created_count,
@@ -84,27 +155,225 @@ pub fn handle_update(project_path: &str, extra_tags: &[String]) -> Result<(), Bo
             &pb,
             &project_cache,
             &config,
+            &tag_mappings,
             extra_tags,
+            extract_art,
         );
 
-        if let Err(e) = result {
-            eprintln!("{} {}", "Error:".red(), e);
+        // Failed files stay out of the checkpoint so a resumed run retries them
+        match result {
+            Ok(()) => checkpoint.record(file_path),
+            Err(e) => eprintln!("{} {}", "Error:".red(), e),
         }
+        Ok(())
+    };
 
+    // Files a DAW may still be rendering are left for later, since their
+    // headers and durations can't be trusted yet
+    let grace = std::time::Duration::from_secs(config.write_grace_secs.into());
+    let mut in_progress = Vec::new();
+
+    // Process files sequentially but with parallel scanning already done
+    // (Processing itself involves I/O and user interaction which can't be parallelized)
+    for file_path in &audio_files {
+        if is_being_written(file_path, grace, SystemTime::now()) {
+            pb.set_message(format!(
+                "In progress: {}",
+                file_path.display().to_string().yellow()
+            ));
+            in_progress.push(file_path.clone());
+        } else {
+            process(file_path)?;
+        }
         pb.inc(1);
     }
 
+    // With --watch, wait for renders to settle and pick them up
+    while watch && !in_progress.is_empty() {
+        pb.set_message(format!(
+            "Waiting for {} file(s) still being written...",
+            in_progress.len()
+        ));
+        std::thread::sleep(grace.max(std::time::Duration::from_secs(1)));
+        // A render written under a temporary name may be gone by now
+        in_progress.retain(|path| path.exists());
+        let mut still_writing = Vec::new();
+        for file_path in in_progress {
+            if is_being_written(&file_path, grace, SystemTime::now()) {
+                still_writing.push(file_path);
+            } else {
+                process(&file_path)?;
+            }
+        }
+        in_progress = still_writing;
+    }
+
     pb.finish_with_message("Done");
+    checkpoint.finish();
 
     let created = *created_count.lock().unwrap();
     let updated = *updated_count.lock().unwrap();
     let skipped = *skipped_count.lock().unwrap();
 
-    print_update_summary(created, updated, skipped, extra_tags);
+    if !verbosity.is_quiet() {
+        print_update_summary(created, updated, skipped, extra_tags);
+        if !in_progress.is_empty() {
+            print_in_progress(&in_progress);
+        }
+    }
 
     Ok(())
 }
 
+/// Whether `path` was modified within `grace` of `now`, so it may be a render
+/// that is still being written. Unreadable times and times in the future (a
+/// clock skewed network drive) count as settled so files aren't skipped
+/// forever.
+fn is_being_written(path: &Path, grace: std::time::Duration, now: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_some_and(|age| age < grace)
+}
+
+fn print_in_progress(files: &[PathBuf]) {
+    println!(
+        "  {} {} files {}",
+        "In progress:".bright_black(),
+        files.len().to_string().yellow().bold(),
+        "(still being written, run again or use --watch)".bright_black()
+    );
+    for file in files {
+        println!("    {}", file.display().to_string().yellow());
+    }
+}
+
+/// The files a `zim update` run has finished, kept in the project root so an
+/// interrupted run can be resumed with `--resume`. Paths are stored relative
+/// to the project, one per line, and written out as each file finishes. The
+/// file is only created once there is something to record, and a checkpoint
+/// that can't be written is warned about rather than stopping the update.
+struct UpdateCheckpoint {
+    root: PathBuf,
+    done: HashSet<PathBuf>,
+    file: Option<fs::File>,
+    append: bool, // Add to the checkpoint on disk rather than replacing it
+    failed: bool,
+}
+
+impl UpdateCheckpoint {
+    fn path(root: &Path) -> PathBuf {
+        root.join(UPDATE_CHECKPOINT_FILE)
+    }
+
+    /// Begin a new checkpoint; any left by an earlier run is replaced once
+    /// the first file finishes
+    fn start(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            done: HashSet::new(),
+            file: None,
+            append: false,
+            failed: false,
+        }
+    }
+
+    /// Load the files an interrupted run finished and keep adding to them.
+    /// Without a checkpoint this is a fresh start.
+    fn resume(root: &Path) -> Result<Self, Box<dyn Error>> {
+        let done = match fs::read_to_string(Self::path(root)) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            done,
+            append: true,
+            ..Self::start(root)
+        })
+    }
+
+    fn relative<'a>(&self, file: &'a Path) -> &'a Path {
+        file.strip_prefix(&self.root).unwrap_or(file)
+    }
+
+    fn contains(&self, file: &Path) -> bool {
+        self.done.contains(self.relative(file))
+    }
+
+    /// Note a finished file, writing it to disk straight away so an
+    /// interrupted run loses nothing
+    fn record(&mut self, file: &Path) {
+        let relative = self.relative(file).to_path_buf();
+        if !self.failed
+            && let Err(e) = self.write_line(&relative)
+        {
+            self.failed = true;
+            eprintln!(
+                "{} Could not write the update checkpoint ({}); --resume won't be able to skip finished files",
+                "Warning:".yellow(),
+                e
+            );
+        }
+        self.done.insert(relative);
+    }
+
+    fn write_line(&mut self, relative: &Path) -> io::Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(
+                fs::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(self.append)
+                    .truncate(!self.append)
+                    .open(Self::path(&self.root))?,
+            ),
+        };
+        writeln!(file, "{}", relative.display())
+    }
+
+    /// The run completed, so there is nothing left to resume
+    fn finish(self) {
+        drop(self.file);
+        match fs::remove_file(Self::path(&self.root)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => eprintln!(
+                "{} Could not remove the update checkpoint: {}",
+                "Warning:".yellow(),
+                e
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Keep the files modified within `window` before `now`. A file whose
+/// modification time can't be read is kept, so it is never silently skipped.
+fn retain_modified_within(files: &mut Vec<PathBuf>, window: std::time::Duration, now: SystemTime) {
+    let cutoff = now.checked_sub(window).unwrap_or(SystemTime::UNIX_EPOCH);
+    files.retain(|path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map(|modified| modified >= cutoff)
+            .unwrap_or(true)
+    });
+}
+
+/// A `--since` window for messages, in its largest whole unit
+fn format_window(window: std::time::Duration) -> String {
+    let seconds = window.as_secs();
+    [(86400, "d"), (3600, "h"), (60, "m")]
+        .iter()
+        .find(|(unit, _)| seconds >= *unit && seconds.is_multiple_of(*unit))
+        .map(|(unit, suffix)| format!("{}{suffix}", seconds / unit))
+        .unwrap_or_else(|| format!("{seconds}s"))
+}
+
 /// Extract a clean title from a filename by removing the extension
 fn extract_title_from_filename(filename: &str) -> String {
     // Remove extension(s) - handles cases like "my.song.wav"
@@ -115,9 +384,37 @@ fn extract_title_from_filename(filename: &str) -> String {
     }
 }
 
+/// Apply the configured title style to a title taken from a filename.
+/// Unknown styles fall back to the raw title.
+fn apply_title_style(title: &str, style: &str) -> String {
+    match style {
+        "spaces" | "title" => {
+            let words = title
+                .split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+                .filter(|w| !w.is_empty());
+
+            if style == "title" {
+                words.map(capitalize_word).collect::<Vec<_>>().join(" ")
+            } else {
+                words.collect::<Vec<_>>().join(" ")
+            }
+        }
+        _ => title.to_string(),
+    }
+}
+
+/// Uppercase the first character of a word, leaving the rest unchanged
+fn capitalize_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 /// Determine the file type based on its directory within the project
 /// Returns (singular_type, tag) e.g., ("edit", "edit") or ("source", "source")
-fn determine_file_type(file_path: &Path) -> Option<(String, String)> {
+pub fn determine_file_type(file_path: &Path) -> Option<(String, String)> {
     // Get the path components
     let components: Vec<&str> = file_path
         .components()
@@ -202,6 +499,290 @@ fn generate_description(file_type: Option<&str>, project: Option<&str>) -> Strin
     }
 }
 
+/// Create or refresh the sidecar for a single audio file, or a reference
+/// video when `video_sidecars` is set, for use as an editor or DAW
+/// post-export hook
+pub fn handle_update_file(
+    file: &str,
+    extra_tags: &[String],
+    extract_art: bool,
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let file_path = Path::new(file);
+    if !file_path.is_file() {
+        return Err(format!("Not a file: {file}").into());
+    }
+    let config = Arc::new(Config::load()?);
+    let extensions = parallel_scan::sidecar_extensions(config.video_sidecars);
+    let is_supported = file_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.contains(e.to_lowercase().as_str()));
+    if !is_supported {
+        let mut expected: Vec<&str> = extensions.into_iter().collect();
+        expected.sort();
+        return Err(format!(
+            "Not a supported audio file: {file} (expected one of: {})",
+            expected.join(", ")
+        )
+        .into());
+    }
+    let created = Arc::new(Mutex::new(0));
+    let skipped = Arc::new(Mutex::new(0));
+    let updated = Arc::new(Mutex::new(0));
+    let project_cache = Arc::new(Mutex::new(HashMap::new()));
+
+    process_media_file(
+        file_path,
+        &created,
+        &skipped,
+        &updated,
+        &ProgressBar::hidden(),
+        &project_cache,
+        &config,
+        &TagMappings::new(&config),
+        extra_tags,
+        extract_art,
+    )?;
+
+    if !verbosity.is_quiet() {
+        let sidecar_location = SidecarLocation::from_name(&config.sidecar_location);
+        let sidecar_path = get_sidecar_path(file_path, sidecar_location)
+            .display()
+            .to_string();
+        if *created.lock().unwrap() > 0 {
+            println!("{} Created {}", "✓".green().bold(), sidecar_path.cyan());
+        } else if *updated.lock().unwrap() > 0 {
+            println!("{} Updated {}", "✓".green().bold(), sidecar_path.cyan());
+        } else {
+            println!("{} Left {} unchanged", "ℹ".blue(), sidecar_path.cyan());
+        }
+    }
+
+    Ok(())
+}
+
+/// Recompute the audio MD5 of every tagged WAV and store it where it no
+/// longer matches, reporting the files whose audio changed since tagging
+pub fn handle_refresh_hash(
+    project_path: &str,
+    include: &[String],
+    exclude: &[String],
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    let project_path = Path::new(project_path);
+    validate_path_exists(project_path)?;
+
+    let spinner = create_progress_spinner(verbosity);
+    spinner.set_message("Scanning for WAV files...");
+    let zimignore = ZimIgnore::load_for_directory(project_path);
+    let skip_dirs = parallel_scan::SkipDirs::from_config();
+    let mut wav_files = parallel_scan::collect_audio_files(
+        project_path,
+        &HashSet::from(["wav"]),
+        &zimignore,
+        &skip_dirs,
+    )?;
+    spinner.finish_and_clear();
+
+    let filter = PathFilter::new(project_path, include, exclude);
+    wav_files.retain(|path| filter.matches(path));
+
+    let pb = create_progress_bar(wav_files.len() as u64, verbosity);
+    pb.set_message("Hashing audio...");
+
+    let mut checked = 0;
+    let mut changed = Vec::new();
+    for wav in &wav_files {
+        match refresh_audio_hash(wav) {
+            Ok(Some(refresh)) => {
+                checked += 1;
+                if let Some(old_md5) = refresh {
+                    changed.push((wav, old_md5));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                pb.suspend(|| eprintln!("{} {}: {}", "Error:".red(), wav.display(), e));
+            }
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    if !verbosity.is_quiet() {
+        for (wav, old_md5) in &changed {
+            let relative = wav.strip_prefix(project_path).unwrap_or(wav);
+            let old_md5 = if old_md5.is_empty() {
+                "none"
+            } else {
+                old_md5.as_str()
+            };
+            println!(
+                "  {} {} {}",
+                "Audio changed:".yellow(),
+                relative.display().to_string().cyan(),
+                format!("(was {old_md5})").bright_black()
+            );
+        }
+        println!(
+            "\n{} Checked {} tagged WAV files, refreshed {} hashes",
+            "✓".green().bold(),
+            checked.to_string().cyan().bold(),
+            changed.len().to_string().yellow().bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Loudness replay gain brings every file to, in LUFS (ReplayGain 2.0)
+#[cfg(feature = "player")]
+const REPLAY_GAIN_REFERENCE_LUFS: f64 = -18.0;
+
+/// Measure every audio file that has a sidecar and store the gain that
+/// brings it to the reference loudness as `replay_gain`, which the player
+/// applies on playback. The audio files themselves are left alone.
+#[cfg(feature = "player")]
+pub fn handle_replay_gain(
+    project_path: &str,
+    include: &[String],
+    exclude: &[String],
+    verbosity: Verbosity,
+) -> Result<(), Box<dyn Error>> {
+    use crate::player::loudness::{measure, measurement_pool};
+    use crate::player::mixed_source::load_samples;
+    use rayon::prelude::*;
+    use zim_studio::constants::PLAYABLE_EXTENSIONS;
+    use zim_studio::utils::sidecar::write_replay_gain;
+
+    let project_path = Path::new(project_path);
+    validate_path_exists(project_path)?;
+
+    let spinner = create_progress_spinner(verbosity);
+    spinner.set_message("Scanning for audio files...");
+    let zimignore = ZimIgnore::load_for_directory(project_path);
+    let mut files = parallel_scan::collect_audio_files(
+        project_path,
+        &PLAYABLE_EXTENSIONS.iter().copied().collect::<HashSet<_>>(),
+        &zimignore,
+        &parallel_scan::SkipDirs::from_config(),
+    )?;
+    spinner.finish_and_clear();
+
+    let filter = PathFilter::new(project_path, include, exclude);
+    files.retain(|path| filter.matches(path));
+    files.sort();
+    let sidecar_location = SidecarLocation::from_config();
+    let (files, without_sidecar): (Vec<PathBuf>, Vec<PathBuf>) = files
+        .into_iter()
+        .partition(|file| get_sidecar_path(file, sidecar_location).exists());
+
+    let pb = create_progress_bar(files.len() as u64, verbosity);
+    pb.set_message("Measuring loudness...");
+    let measured: Vec<(PathBuf, Result<Option<f64>, String>)> = measurement_pool()?.install(|| {
+        files
+            .into_par_iter()
+            .map(|file| {
+                let gain = load_samples(&file.to_string_lossy())
+                    .map(|(samples, sample_rate, channels)| {
+                        replay_gain_for(measure(&samples, sample_rate, channels))
+                    })
+                    .map_err(|e| e.to_string());
+                pb.inc(1);
+                (file, gain)
+            })
+            .collect()
+    });
+    pb.finish_and_clear();
+
+    let mut stored = 0;
+    let mut silent = 0;
+    for (file, gain) in measured {
+        let relative = file.strip_prefix(project_path).unwrap_or(&file);
+        let result = gain.map_err(Box::<dyn Error>::from).and_then(|gain| {
+            let Some(gain) = gain else {
+                return Ok(None);
+            };
+            let sidecar = get_sidecar_path(&file, sidecar_location);
+            let content = fs::read_to_string(&sidecar)?;
+            write_sidecar(&sidecar, &write_replay_gain(&content, gain)?)?;
+            Ok(Some(gain))
+        });
+        match result {
+            Ok(Some(gain)) => {
+                stored += 1;
+                if !verbosity.is_quiet() {
+                    println!(
+                        "  {} {} {}",
+                        "Replay gain:".green(),
+                        relative.display().to_string().cyan(),
+                        format!("{gain:+.2} dB").bright_black()
+                    );
+                }
+            }
+            Ok(None) => silent += 1,
+            Err(e) => eprintln!("{} {}: {}", "Error:".red(), relative.display(), e),
+        }
+    }
+
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} Stored replay gain for {} files (reference {REPLAY_GAIN_REFERENCE_LUFS} LUFS)",
+            "✓".green().bold(),
+            stored.to_string().cyan().bold()
+        );
+        if silent > 0 {
+            println!("  {} {silent} silent files left unset", "Skipped:".yellow());
+        }
+        if !without_sidecar.is_empty() {
+            println!(
+                "  {} {} files have no sidecar yet; run {} first",
+                "Skipped:".yellow(),
+                without_sidecar.len(),
+                "zim update".cyan()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The gain in dB that brings a file to the reference loudness, held down
+/// so the true peak stays under 0 dBTP. `None` for silence.
+#[cfg(feature = "player")]
+fn replay_gain_for(loudness: crate::player::loudness::Loudness) -> Option<f64> {
+    let gain = REPLAY_GAIN_REFERENCE_LUFS - loudness.integrated?;
+    Some(match loudness.true_peak {
+        Some(peak) => gain.min(-peak),
+        None => gain,
+    })
+}
+
+/// Store the current audio MD5 in a tagged WAV, keeping its UUID and lineage.
+/// Returns None for untagged files, Some(None) when the stored hash is
+/// current, and Some(Some(old_md5)) when it was replaced.
+fn refresh_audio_hash(path: &Path) -> Result<Option<Option<String>>, Box<dyn Error>> {
+    let Some(mut metadata) = wav_metadata::read_metadata(path)? else {
+        return Ok(None);
+    };
+
+    let current_md5 = wav_metadata::calculate_audio_md5(path)?;
+    if metadata.audio_md5 == current_md5 {
+        return Ok(Some(None));
+    }
+    let old_md5 = std::mem::replace(&mut metadata.audio_md5, current_md5);
+
+    // Write to a temp file then replace the original
+    let temp_path = path.with_extension("wav.tmp");
+    if let Err(e) = wav_metadata::write_metadata(path, &temp_path, &metadata) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, path)?;
+
+    Ok(Some(Some(old_md5)))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn process_media_file(
     file_path: &Path,
@@ -209,11 +790,14 @@ fn process_media_file(
     skipped: &Arc<Mutex<u32>>,
     updated: &Arc<Mutex<u32>>,
     pb: &ProgressBar,
-    project_cache: &Arc<Mutex<HashMap<PathBuf, Option<String>>>>,
+    project_cache: &Arc<Mutex<HashMap<PathBuf, Option<ProjectInfo>>>>,
     config: &Arc<Config>,
+    tag_mappings: &TagMappings,
     extra_tags: &[String],
+    extract_art: bool,
 ) -> Result<(), Box<dyn Error>> {
-    let sidecar_path = get_sidecar_path(file_path);
+    let sidecar_location = SidecarLocation::from_name(&config.sidecar_location);
+    let sidecar_path = get_sidecar_path(file_path, sidecar_location);
 
     let file_name = file_path.file_name().unwrap().to_string_lossy();
 
@@ -226,9 +810,34 @@ fn process_media_file(
             (audio_metadata.modified(), sidecar_metadata.modified())
             && audio_time > sidecar_time
         {
+            let sidecar_content = fs::read_to_string(&sidecar_path)?;
+            let yaml_data = match read_sidecar_fields(&sidecar_content) {
+                Ok(yaml_data) => yaml_data,
+                Err(e) => {
+                    // Leave the sidecar untouched so it is reported again next run
+                    pb.suspend(|| {
+                        eprintln!(
+                            "  {} Skipping {}: malformed sidecar frontmatter: {}",
+                            "Warning:".yellow(),
+                            sidecar_path.display().to_string().yellow(),
+                            e.to_string().bright_black()
+                        );
+                    });
+                    *skipped.lock().unwrap() += 1;
+                    return Ok(());
+                }
+            };
+
             // Audio file is newer - offer to update
             pb.suspend(|| -> Result<(), Box<dyn Error>> {
-                if offer_metadata_update(file_path, &sidecar_path, updated)? {
+                if offer_metadata_update(
+                    file_path,
+                    &sidecar_path,
+                    &sidecar_content,
+                    &yaml_data,
+                    updated,
+                    extract_art,
+                )? {
                     Ok(())
                 } else {
                     // User declined - touch the sidecar to update its timestamp
@@ -240,6 +849,21 @@ fn process_media_file(
             return Ok(());
         }
 
+        // An otherwise current sidecar still picks up cover art it doesn't list
+        if extract_art {
+            let sidecar_content = fs::read_to_string(&sidecar_path)?;
+            if let Ok(yaml_data) = read_sidecar_fields(&sidecar_content)
+                && let Some((art_name, art)) = missing_cover_art(file_path, &yaml_data)
+                && extract_cover_art(file_path, &file_name).is_some()
+            {
+                let new_content = update_frontmatter_fields(&sidecar_content, vec![("art", art)])?;
+                write_sidecar(&sidecar_path, &new_content)?;
+                pb.set_message(format!("Added cover art: {}", art_name.green()));
+                *updated.lock().unwrap() += 1;
+                return Ok(());
+            }
+        }
+
         *skipped.lock().unwrap() += 1;
         pb.set_message(format!("Skipped: {}", file_name.bright_black()));
         return Ok(());
@@ -249,13 +873,13 @@ fn process_media_file(
     // Get file system metadata
     let (file_size, modified) = extract_file_metadata(file_path)?;
 
-    // Find the project name (with caching)
-    let project_name = {
+    // Find the project's display name and artist (with caching)
+    let project = {
         let mut cache = project_cache.lock().unwrap();
         let parent = file_path.parent().unwrap_or(Path::new("."));
         cache
             .entry(parent.to_path_buf())
-            .or_insert_with(|| find_project_root(file_path))
+            .or_insert_with(|| find_project_info(file_path))
             .clone()
     };
 
@@ -265,12 +889,14 @@ fn process_media_file(
         &relative_path.to_string_lossy(),
         file_size,
         modified.as_deref(),
-        project_name.as_deref(),
+        project.as_ref(),
         config,
+        tag_mappings,
         extra_tags,
+        extract_art,
     );
 
-    fs::write(&sidecar_path, content)?;
+    write_sidecar(&sidecar_path, &content)?;
     pb.set_message(format!("Created: {}", file_name.green()));
     *created.lock().unwrap() += 1;
 
@@ -320,6 +946,84 @@ fn extract_file_metadata(path: &Path) -> Result<(u64, Option<String>), Box<dyn E
     Ok((file_size, modified))
 }
 
+/// Compile a `tag_regex` pattern: case-insensitive, anchored to match the
+/// whole filename
+fn compile_tag_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&format!("^(?:{pattern})$"))
+        .case_insensitive(true)
+        .build()
+}
+
+/// `tag_regex` patterns that fail to compile, with the reason
+fn invalid_tag_regexes(config: &Config) -> Vec<(String, String)> {
+    let mut invalid: Vec<(String, String)> = config
+        .tag_regex
+        .keys()
+        .filter_map(|pattern| {
+            compile_tag_regex(pattern)
+                .err()
+                .map(|e| (pattern.clone(), e.to_string()))
+        })
+        .collect();
+    invalid.sort();
+    invalid
+}
+
+/// The config's `tag_mappings` and `tag_regex`, compiled once per run.
+/// Both match anywhere in the filename, ignoring case: `kick` also tags
+/// `kickback.wav`. A regex can anchor with `^`, `$` or `\b` where that matters.
+struct TagMappings {
+    literals: Vec<(String, String)>, // Lowercased pattern and its tag
+    regexes: Vec<(Regex, String)>,   // Pattern and its tag template
+}
+
+impl TagMappings {
+    /// Invalid regexes are skipped; they are reported once per run by
+    /// `handle_update`
+    fn new(config: &Config) -> Self {
+        let literals = config
+            .tag_mappings
+            .iter()
+            .map(|(pattern, tag)| (pattern.to_lowercase(), tag.clone()))
+            .collect();
+        let mut regex_mappings: Vec<_> = config.tag_regex.iter().collect();
+        regex_mappings.sort();
+        let regexes = regex_mappings
+            .into_iter()
+            .filter_map(|(pattern, tag)| Some((compile_tag_regex(pattern).ok()?, tag.clone())))
+            .collect();
+        Self { literals, regexes }
+    }
+
+    /// Tags inferred from the filename: literal mappings first, then regex
+    /// mappings (expanding `$1`-style captures)
+    fn tags(&self, file_name: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        let mut add = |tag: String| {
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        };
+
+        let filename_lower = file_name.to_lowercase();
+        for (pattern, tag_value) in &self.literals {
+            if filename_lower.contains(pattern.as_str()) {
+                add(tag_value.clone());
+            }
+        }
+
+        for (regex, tag_template) in &self.regexes {
+            if let Some(captures) = regex.captures(file_name) {
+                let mut tag = String::new();
+                captures.expand(tag_template, &mut tag);
+                add(tag.to_lowercase());
+            }
+        }
+
+        tags
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn generate_sidecar_content(
     file_path: &Path,
@@ -327,18 +1031,24 @@ fn generate_sidecar_content(
     relative_path: &str,
     file_size: u64,
     modified: Option<&str>,
-    project: Option<&str>,
+    project_info: Option<&ProjectInfo>,
     config: &Config,
+    tag_mappings: &TagMappings,
     extra_tags: &[String],
+    extract_art: bool,
 ) -> String {
+    let project = project_info.map(|info| info.name.as_str());
+    let artist = project_info.and_then(|info| info.artist.as_deref());
+
     // Calculate smart defaults
-    let title = extract_title_from_filename(file_name);
+    let title = apply_title_style(&extract_title_from_filename(file_name), &config.title_style);
     let file_type_info = determine_file_type(Path::new(relative_path));
     let (file_type, tag) = file_type_info
         .as_ref()
         .map(|(t, tag)| (t.as_str(), tag.as_str()))
         .unwrap_or(("", ""));
     let description = generate_description(Some(file_type).filter(|s| !s.is_empty()), project);
+    let stored_path = stored_audio_path(file_path, relative_path, config.absolute_sidecar_paths);
 
     // Create tags list with the file type tag if available
     let mut tags = if !tag.is_empty() {
@@ -348,10 +1058,9 @@ fn generate_sidecar_content(
     };
 
     // Add tags based on filename mappings from config
-    let filename_lower = file_name.to_lowercase();
-    for (pattern, tag_value) in &config.tag_mappings {
-        if filename_lower.contains(&pattern.to_lowercase()) && !tags.contains(tag_value) {
-            tags.push(tag_value.clone());
+    for tag_value in tag_mappings.tags(file_name) {
+        if !tags.contains(&tag_value) {
+            tags.push(tag_value);
         }
     }
 
@@ -411,25 +1120,41 @@ fn generate_sidecar_content(
         None
     };
 
+    let cover_art = if extract_art {
+        extract_cover_art(file_path, file_name)
+    } else {
+        None
+    };
+    let art: Vec<ArtEntry> = cover_art
+        .iter()
+        .map(|path| ArtEntry {
+            path,
+            description: "Embedded cover art",
+            purpose: "cover_art",
+        })
+        .collect();
+
     match extension.as_deref() {
-        Some("flac") | Some("wav") => {
+        Some("flac") | Some("wav") | Some("mp3") => {
             match read_audio_metadata(file_path) {
                 Ok(metadata) => {
                     // Generate sidecar with metadata
                     templates::generate_audio_sidecar_with_metadata(&SidecarMetadata {
                         file_name,
-                        file_path: relative_path,
+                        file_path: &stored_path,
                         title: &title,
                         description: &description,
                         tags: &tags,
                         sample_rate: metadata.sample_rate,
                         channels: metadata.channels,
                         bits_per_sample: metadata.bits_per_sample,
-                        duration_seconds: metadata.duration_seconds,
+                        duration: metadata.duration_seconds,
                         file_size,
                         modified,
                         project,
+                        artist,
                         uuid: uuid.as_deref(),
+                        art: &art,
                     })
                 }
                 Err(e) => {
@@ -441,35 +1166,160 @@ fn generate_sidecar_content(
                     );
                     templates::generate_minimal_sidecar_with_fs_metadata(
                         file_name,
-                        relative_path,
+                        &stored_path,
                         &title,
                         &description,
                         &tags,
                         file_size,
                         modified,
                         project,
+                        artist,
                         uuid.as_deref(),
+                        &art,
                     )
                 }
             }
         }
+        Some(ext) if VIDEO_EXTENSIONS.contains(&ext) => video_sidecar(
+            file_path,
+            file_name,
+            &stored_path,
+            &title,
+            &description,
+            &tags,
+            file_size,
+            modified,
+            project,
+            artist,
+            &art,
+        ),
         _ => {
             // Unsupported audio format - create minimal sidecar
             templates::generate_minimal_sidecar_with_fs_metadata(
                 file_name,
-                relative_path,
+                &stored_path,
                 &title,
                 &description,
                 &tags,
                 file_size,
                 modified,
                 project,
+                artist,
                 None,
+                &art,
             )
         }
     }
 }
 
+/// Sidecar for a reference video: no audio format fields, a `video` tag, and
+/// the duration when the movie header has one
+#[allow(clippy::too_many_arguments)]
+fn video_sidecar(
+    file_path: &Path,
+    file_name: &str,
+    stored_path: &str,
+    title: &str,
+    description: &str,
+    tags: &[String],
+    file_size: u64,
+    modified: Option<&str>,
+    project: Option<&str>,
+    artist: Option<&str>,
+    art: &[ArtEntry],
+) -> String {
+    let mut tags = tags.to_vec();
+    if !tags.iter().any(|tag| tag == "video") {
+        tags.push("video".to_string());
+    }
+    let content = templates::generate_minimal_sidecar_with_fs_metadata(
+        file_name,
+        stored_path,
+        title,
+        description,
+        &tags,
+        file_size,
+        modified,
+        project,
+        artist,
+        None,
+        art,
+    );
+    let Ok(duration) = read_video_duration(file_path) else {
+        return content;
+    };
+    let rounded = (duration * 100.0).round() / 100.0;
+    update_frontmatter_fields(
+        &content,
+        vec![(DURATION_FIELD, serde_yaml::Value::from(rounded))],
+    )
+    .unwrap_or(content)
+}
+
+/// Write a file's embedded cover art next to it as `<file>.cover.<ext>`,
+/// returning the image's file name for the sidecar `art` list. An existing
+/// image is kept as-is so edits to it survive re-runs.
+fn extract_cover_art(file_path: &Path, file_name: &str) -> Option<String> {
+    let picture = match read_embedded_picture(file_path) {
+        Ok(picture) => picture?,
+        Err(e) => {
+            eprintln!(
+                "  {} Could not read cover art from {}: {}",
+                "Warning:".yellow(),
+                file_name.yellow(),
+                e.to_string().bright_black()
+            );
+            return None;
+        }
+    };
+
+    let art_name = format!("{file_name}.cover.{}", picture.extension());
+    let art_path = file_path.with_file_name(&art_name);
+    if !art_path.exists()
+        && let Err(e) = fs::write(&art_path, &picture.data)
+    {
+        eprintln!(
+            "  {} Could not write cover art {}: {}",
+            "Warning:".yellow(),
+            art_path.display().to_string().yellow(),
+            e.to_string().bright_black()
+        );
+        return None;
+    }
+    Some(art_name)
+}
+
+/// The sidecar's `art` list with the file's embedded cover added, when the
+/// file has one the list doesn't mention yet. Returns the image's file name
+/// and the new list; nothing is written.
+fn missing_cover_art(
+    audio_path: &Path,
+    yaml_data: &HashMap<String, serde_yaml::Value>,
+) -> Option<(String, serde_yaml::Value)> {
+    let file_name = audio_path.file_name()?.to_string_lossy();
+    let picture = read_embedded_picture(audio_path).ok()??;
+    let art_name = format!("{file_name}.cover.{}", picture.extension());
+
+    let mut art = match yaml_data.get("art") {
+        Some(serde_yaml::Value::Sequence(art)) => art.clone(),
+        _ => Vec::new(),
+    };
+    let listed = art.iter().any(|entry| {
+        entry.as_str() == Some(art_name.as_str())
+            || entry.get("path").and_then(|p| p.as_str()) == Some(art_name.as_str())
+    });
+    if listed {
+        return None;
+    }
+
+    let mut entry = serde_yaml::Mapping::new();
+    entry.insert("path".into(), art_name.clone().into());
+    entry.insert("description".into(), "Embedded cover art".into());
+    entry.insert("purpose".into(), "cover_art".into());
+    art.push(serde_yaml::Value::Mapping(entry));
+    Some((art_name, serde_yaml::Value::Sequence(art)))
+}
+
 fn touch_file(path: &Path) -> Result<(), Box<dyn Error>> {
     let now = SystemTime::now();
     fs::File::options()
@@ -481,10 +1331,98 @@ fn touch_file(path: &Path) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Parse a sidecar's YAML frontmatter into its top-level fields
+fn read_sidecar_fields(
+    sidecar_content: &str,
+) -> Result<HashMap<String, serde_yaml::Value>, Box<dyn Error>> {
+    let (yaml_content, _) =
+        split_frontmatter(sidecar_content).ok_or("Sidecar file has no YAML frontmatter")?;
+    Ok(serde_yaml::from_str(yaml_content)?)
+}
+
+/// Describe how a sidecar's audio fields differ from the file's current metadata
+fn audio_metadata_changes(
+    yaml_data: &HashMap<String, serde_yaml::Value>,
+    metadata: &AudioMetadata,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    // Check duration, ignoring differences lost to rounding
+    if let Some(new_duration) = metadata.duration_seconds {
+        let old_duration = stored_duration(yaml_data);
+        if duration_changed(old_duration, new_duration) {
+            changes.push(format!(
+                "  {DURATION_FIELD}: {} → {}",
+                format_duration(old_duration).red(),
+                format_duration(Some(new_duration)).green()
+            ));
+        }
+    }
+
+    // Check sample rate
+    if let Some(old_rate) = yaml_data.get("sample_rate").and_then(|v| v.as_u64())
+        && old_rate != metadata.sample_rate as u64
+    {
+        changes.push(format!(
+            "  sample_rate: {} → {}",
+            old_rate.to_string().red(),
+            metadata.sample_rate.to_string().green()
+        ));
+    }
+
+    // Check channels
+    if let Some(old_channels) = yaml_data.get("channels").and_then(|v| v.as_u64())
+        && old_channels != metadata.channels as u64
+    {
+        changes.push(format!(
+            "  channels: {} → {}",
+            old_channels.to_string().red(),
+            metadata.channels.to_string().green()
+        ));
+    }
+
+    // Check bits per sample
+    if let Some(old_bits) = yaml_data.get("bits_per_sample").and_then(|v| v.as_u64())
+        && old_bits != metadata.bits_per_sample as u64
+    {
+        changes.push(format!(
+            "  bits_per_sample: {} → {}",
+            old_bits.to_string().red(),
+            metadata.bits_per_sample.to_string().green()
+        ));
+    }
+
+    changes
+}
+
+/// Frontmatter values for the audio fields, in the same form `zim update` writes them
+fn audio_metadata_updates(metadata: &AudioMetadata) -> Vec<(&'static str, serde_yaml::Value)> {
+    let mut updates = Vec::new();
+    if let Some(duration) = metadata.duration_seconds {
+        updates.push((DURATION_FIELD, duration_value(duration)));
+    }
+    updates.push((
+        "sample_rate",
+        serde_yaml::Value::Number(metadata.sample_rate.into()),
+    ));
+    updates.push((
+        "channels",
+        serde_yaml::Value::Number(metadata.channels.into()),
+    ));
+    updates.push((
+        "bits_per_sample",
+        serde_yaml::Value::Number(metadata.bits_per_sample.into()),
+    ));
+    updates
+}
+
 fn offer_metadata_update(
     audio_path: &Path,
     sidecar_path: &Path,
+    sidecar_content: &str,
+    yaml_data: &HashMap<String, serde_yaml::Value>,
     updated: &Arc<Mutex<u32>>,
+    extract_art: bool,
 ) -> Result<bool, Box<dyn Error>> {
     let file_name = audio_path.file_name().unwrap().to_string_lossy();
 
@@ -494,21 +1432,6 @@ fn offer_metadata_update(
         file_name.cyan()
     );
 
-    // Read current sidecar content
-    let sidecar_content = fs::read_to_string(sidecar_path)?;
-    let (yaml_data, markdown_content) = if sidecar_content.starts_with("---\n") {
-        let end_index = fs::read_to_string(sidecar_path)?[4..]
-            .find("\n---\n")
-            .ok_or("Invalid YAML frontmatter")?;
-        let yaml_content = &sidecar_content[4..4 + end_index];
-        let markdown = &sidecar_content[4 + end_index + 5..]; // Skip past "\n---\n"
-
-        let yaml: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml_content)?;
-        (yaml, markdown.to_string())
-    } else {
-        return Err("Sidecar file has no YAML frontmatter".into());
-    };
-
     // Get new metadata from audio file
     let (new_file_size, new_modified) = extract_file_metadata(audio_path)?;
     let mut changes = Vec::new();
@@ -543,55 +1466,21 @@ fn offer_metadata_update(
         .map(|e| e.to_lowercase());
 
     let audio_metadata = match extension.as_deref() {
-        Some("flac") | Some("wav") => read_audio_metadata(audio_path).ok(),
+        Some("flac") | Some("wav") | Some("mp3") => read_audio_metadata(audio_path).ok(),
         _ => None,
     };
 
     if let Some(ref metadata) = audio_metadata {
-        // Check duration (field is called "duration" in YAML, not "duration_seconds")
-        if let Some(new_duration) = metadata.duration_seconds
-            && let Some(old_duration) = yaml_data.get("duration").and_then(|v| v.as_f64())
-            && (old_duration - new_duration).abs() > 0.01
-        {
-            changes.push(format!(
-                "  duration: {} → {}",
-                format!("{old_duration:.2}").red(),
-                format!("{new_duration:.2}").green()
-            ));
-        }
-
-        // Check sample rate
-        if let Some(old_rate) = yaml_data.get("sample_rate").and_then(|v| v.as_u64())
-            && old_rate != metadata.sample_rate as u64
-        {
-            changes.push(format!(
-                "  sample_rate: {} → {}",
-                old_rate.to_string().red(),
-                metadata.sample_rate.to_string().green()
-            ));
-        }
-
-        // Check channels
-        if let Some(old_channels) = yaml_data.get("channels").and_then(|v| v.as_u64())
-            && old_channels != metadata.channels as u64
-        {
-            changes.push(format!(
-                "  channels: {} → {}",
-                old_channels.to_string().red(),
-                metadata.channels.to_string().green()
-            ));
-        }
+        changes.extend(audio_metadata_changes(yaml_data, metadata));
+    }
 
-        // Check bits per sample
-        if let Some(old_bits) = yaml_data.get("bits_per_sample").and_then(|v| v.as_u64())
-            && old_bits != metadata.bits_per_sample as u64
-        {
-            changes.push(format!(
-                "  bits_per_sample: {} → {}",
-                old_bits.to_string().red(),
-                metadata.bits_per_sample.to_string().green()
-            ));
-        }
+    let new_art = if extract_art {
+        missing_cover_art(audio_path, yaml_data)
+    } else {
+        None
+    };
+    if let Some((art_name, _)) = &new_art {
+        changes.push(format!("  art: + {}", art_name.green()));
     }
 
     if changes.is_empty() {
@@ -621,42 +1510,24 @@ fn offer_metadata_update(
     io::stdin().read_line(&mut response)?;
 
     if response.trim().to_lowercase() == "y" {
-        // Update the YAML data
-        let mut updated_yaml = yaml_data.clone();
-
-        updated_yaml.insert(
-            "file_size".to_string(),
-            serde_yaml::Value::Number(new_file_size.into()),
-        );
+        // Collect the fields to update
+        let mut updates = vec![("file_size", serde_yaml::Value::Number(new_file_size.into()))];
         if let Some(new_mod) = new_modified {
-            updated_yaml.insert("modified".to_string(), serde_yaml::Value::String(new_mod));
+            updates.push(("modified", serde_yaml::Value::String(new_mod)));
         }
 
-        if let Some(metadata) = audio_metadata {
-            if let Some(duration) = metadata.duration_seconds {
-                // Update "duration" field (not "duration_seconds") to match template output
-                updated_yaml.insert(
-                    "duration".to_string(),
-                    serde_yaml::Value::Number(serde_yaml::Number::from(duration)),
-                );
-            }
-            updated_yaml.insert(
-                "sample_rate".to_string(),
-                serde_yaml::Value::Number(metadata.sample_rate.into()),
-            );
-            updated_yaml.insert(
-                "channels".to_string(),
-                serde_yaml::Value::Number(metadata.channels.into()),
-            );
-            updated_yaml.insert(
-                "bits_per_sample".to_string(),
-                serde_yaml::Value::Number(metadata.bits_per_sample.into()),
-            );
+        if let Some(metadata) = &audio_metadata {
+            updates.extend(audio_metadata_updates(metadata));
+        }
+
+        if let Some((_, art)) = new_art
+            && extract_cover_art(audio_path, &file_name).is_some()
+        {
+            updates.push(("art", art));
         }
 
-        // Reconstruct the file
-        let yaml_string = serde_yaml::to_string(&updated_yaml)?;
-        let new_content = format!("---\n{yaml_string}---\n{markdown_content}");
+        // Reconstruct the file, preserving other fields and the markdown body
+        let new_content = update_frontmatter_fields(sidecar_content, updates)?;
 
         fs::write(sidecar_path, new_content)?;
         println!("  {} Updated metadata", "✓".green());
@@ -671,8 +1542,44 @@ fn offer_metadata_update(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::media::artwork::tests::flac_picture_block;
+    use crate::test_support::{pcm16, write_wav};
     use std::fs;
     use tempfile::TempDir;
+    use zim_studio::constants::AUDIO_EXTENSIONS;
+
+    #[test]
+    fn test_refresh_audio_hash_keeps_identity() {
+        let temp_dir = TempDir::new().unwrap();
+        let wav = temp_dir.path().join("master.wav");
+        write_wav(&wav, pcm16(1, 8000), 0..100i16);
+
+        // Untagged files are left alone
+        assert!(refresh_audio_hash(&wav).unwrap().is_none());
+
+        // Tagged before the master was re-rendered
+        let mut metadata = wav_metadata::ZimMetadata::new_original("album", &wav);
+        metadata.audio_md5 = "stale".to_string();
+        metadata.generation = 2;
+        let tagged = temp_dir.path().join("tagged.wav");
+        wav_metadata::write_metadata(&wav, &tagged, &metadata).unwrap();
+
+        assert_eq!(
+            refresh_audio_hash(&tagged).unwrap(),
+            Some(Some("stale".to_string()))
+        );
+        let refreshed = wav_metadata::read_metadata(&tagged).unwrap().unwrap();
+        assert_eq!(
+            refreshed.audio_md5,
+            wav_metadata::calculate_audio_md5(&tagged).unwrap()
+        );
+        assert_eq!(refreshed.uuid, metadata.uuid);
+        assert_eq!(refreshed.generation, 2);
+        assert!(!temp_dir.path().join("tagged.wav.tmp").exists());
+
+        // A current hash is not rewritten
+        assert_eq!(refresh_audio_hash(&tagged).unwrap(), Some(None));
+    }
 
     #[test]
     fn test_should_skip_directory() {
@@ -698,9 +1605,16 @@ mod tests {
         assert!(extensions.contains("wav"));
         assert!(extensions.contains("flac"));
         assert!(extensions.contains("mp3"));
+        assert!(extensions.contains("aif"));
         assert!(extensions.contains("aiff"));
         assert!(extensions.contains("m4a"));
-        assert_eq!(extensions.len(), 5);
+        assert_eq!(extensions.len(), 6);
+        // Everything the player opens is also scanned
+        assert!(
+            zim_studio::constants::PLAYABLE_EXTENSIONS
+                .iter()
+                .all(|e| extensions.contains(e))
+        );
     }
 
     #[test]
@@ -709,8 +1623,13 @@ mod tests {
         let extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().cloned().collect();
 
         let zimignore = ZimIgnore::new();
-        let files =
-            parallel_scan::collect_audio_files(temp_dir.path(), &extensions, &zimignore).unwrap();
+        let files = parallel_scan::collect_audio_files(
+            temp_dir.path(),
+            &extensions,
+            &zimignore,
+            &parallel_scan::SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 0);
     }
 
@@ -729,8 +1648,13 @@ mod tests {
         fs::write(temp_dir.path().join("README.md"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files =
-            parallel_scan::collect_audio_files(temp_dir.path(), &extensions, &zimignore).unwrap();
+        let files = parallel_scan::collect_audio_files(
+            temp_dir.path(),
+            &extensions,
+            &zimignore,
+            &parallel_scan::SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 3);
     }
 
@@ -744,8 +1668,13 @@ mod tests {
         fs::write(temp_dir.path().join(".hidden.wav"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files =
-            parallel_scan::collect_audio_files(temp_dir.path(), &extensions, &zimignore).unwrap();
+        let files = parallel_scan::collect_audio_files(
+            temp_dir.path(),
+            &extensions,
+            &zimignore,
+            &parallel_scan::SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 1);
     }
 
@@ -765,11 +1694,77 @@ mod tests {
         fs::write(skip_dir.join("test.wav"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files =
-            parallel_scan::collect_audio_files(temp_dir.path(), &extensions, &zimignore).unwrap();
+        let files = parallel_scan::collect_audio_files(
+            temp_dir.path(),
+            &extensions,
+            &zimignore,
+            &parallel_scan::SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 1); // Only from normal_dir
     }
 
+    #[test]
+    fn test_retain_modified_within() {
+        use std::time::Duration;
+
+        let temp_dir = TempDir::new().unwrap();
+        let fresh = temp_dir.path().join("fresh.wav");
+        let stale = temp_dir.path().join("stale.wav");
+        fs::write(&fresh, b"fake").unwrap();
+        fs::write(&stale, b"fake").unwrap();
+
+        let now = SystemTime::now();
+        let three_days_ago = now - Duration::from_secs(3 * 86400);
+        fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(three_days_ago)
+            .unwrap();
+
+        let missing = temp_dir.path().join("missing.wav");
+        let mut files = vec![fresh.clone(), stale.clone(), missing.clone()];
+        retain_modified_within(&mut files, Duration::from_secs(86400), now);
+        assert_eq!(files, vec![fresh.clone(), missing]);
+
+        let mut files = vec![fresh.clone(), stale.clone()];
+        retain_modified_within(&mut files, Duration::from_secs(7 * 86400), now);
+        assert_eq!(files, vec![fresh, stale]);
+    }
+
+    #[test]
+    fn test_is_being_written() {
+        use std::time::Duration;
+
+        let temp_dir = TempDir::new().unwrap();
+        let render = temp_dir.path().join("render.wav");
+        fs::write(&render, b"fake").unwrap();
+        let now = SystemTime::now();
+        let grace = Duration::from_secs(5);
+        assert!(is_being_written(&render, grace, now));
+        assert!(!is_being_written(&render, grace, now + grace));
+        // A grace period of 0 turns the check off
+        assert!(!is_being_written(&render, Duration::ZERO, now));
+        // An mtime ahead of the clock counts as settled
+        assert!(!is_being_written(&render, grace, now - grace));
+        assert!(!is_being_written(
+            &temp_dir.path().join("missing.wav"),
+            grace,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_format_window() {
+        use std::time::Duration;
+
+        assert_eq!(format_window(Duration::from_secs(86400)), "1d");
+        assert_eq!(format_window(Duration::from_secs(90000)), "25h");
+        assert_eq!(format_window(Duration::from_secs(1800)), "30m");
+        assert_eq!(format_window(Duration::from_secs(90)), "90s");
+    }
+
     #[test]
     fn test_extract_file_metadata() {
         let temp_dir = TempDir::new().unwrap();
@@ -785,6 +1780,333 @@ mod tests {
         assert!(modified.unwrap().contains("UTC"));
     }
 
+    #[test]
+    fn test_missing_cover_art_for_existing_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio = temp_dir.path().join("song.flac");
+        // STREAMINFO followed by a final PICTURE block holding a front cover
+        let mut flac = b"fLaC\x00\x00\x00\x22".to_vec();
+        flac.extend([0u8; 34]);
+        flac.extend(flac_picture_block(
+            3,
+            "image/jpeg",
+            &[0xFF, 0xD8, 0xFF],
+            true,
+        ));
+        fs::write(&audio, &flac).unwrap();
+
+        let content =
+            "---\ntitle: \"song\"\nart:\n  - path: \"booklet.png\"\n    purpose: other\n---\n";
+        let yaml_data = read_sidecar_fields(content).unwrap();
+        let (name, art) = missing_cover_art(&audio, &yaml_data).unwrap();
+        assert_eq!(name, "song.flac.cover.jpg");
+        let art = art.as_sequence().unwrap();
+        assert_eq!(art.len(), 2);
+        assert_eq!(art[1]["purpose"].as_str(), Some("cover_art"));
+        // Nothing is written until the update is applied
+        assert!(!temp_dir.path().join(&name).exists());
+
+        // Already listed: nothing to add
+        let content = "---\nart:\n  - path: \"song.flac.cover.jpg\"\n---\n";
+        let yaml_data = read_sidecar_fields(content).unwrap();
+        assert!(missing_cover_art(&audio, &yaml_data).is_none());
+    }
+
+    #[test]
+    fn test_extract_cover_art() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio = temp_dir.path().join("song.flac");
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+
+        // STREAMINFO followed by a final PICTURE block holding a front cover
+        let mut flac = b"fLaC\x00\x00\x00\x22".to_vec();
+        flac.extend([0u8; 34]);
+        flac.extend(flac_picture_block(3, "image/jpeg", &jpeg, true));
+        fs::write(&audio, &flac).unwrap();
+
+        let content = generate_sidecar_content(
+            &audio,
+            "song.flac",
+            "song.flac",
+            flac.len() as u64,
+            None,
+            None,
+            &Config::default(),
+            &TagMappings::new(&Config::default()),
+            &[],
+            true,
+        );
+        assert!(content.contains("- path: \"song.flac.cover.jpg\""));
+        assert!(content.contains("purpose: \"cover_art\""));
+        assert_eq!(
+            fs::read(temp_dir.path().join("song.flac.cover.jpg")).unwrap(),
+            jpeg
+        );
+
+        // Without the flag nothing is extracted
+        let plain = temp_dir.path().join("plain.flac");
+        fs::write(&plain, &flac).unwrap();
+        let content = generate_sidecar_content(
+            &plain,
+            "plain.flac",
+            "plain.flac",
+            0,
+            None,
+            None,
+            &Config::default(),
+            &TagMappings::new(&Config::default()),
+            &[],
+            false,
+        );
+        assert!(content.contains("art: []"));
+        assert!(!temp_dir.path().join("plain.flac.cover.jpg").exists());
+    }
+
+    #[test]
+    fn test_video_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        // A moov box holding a version 0 mvhd: 600 units per second, 42s
+        let mut movie = 36u32.to_be_bytes().to_vec();
+        movie.extend_from_slice(b"moov");
+        movie.extend_from_slice(&28u32.to_be_bytes());
+        movie.extend_from_slice(b"mvhd");
+        movie.extend_from_slice(&[0u8; 12]);
+        movie.extend_from_slice(&600u32.to_be_bytes());
+        movie.extend_from_slice(&25_200u32.to_be_bytes());
+        let video = temp_dir.path().join("cue_12.mov");
+        fs::write(&video, &movie).unwrap();
+
+        let content = generate_sidecar_content(
+            &video,
+            "cue_12.mov",
+            "picture/cue_12.mov",
+            movie.len() as u64,
+            None,
+            None,
+            &Config::default(),
+            &TagMappings::new(&Config::default()),
+            &[],
+            false,
+        );
+        let (yaml, body) = split_frontmatter(&content).unwrap();
+        let fields: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(fields[DURATION_FIELD].as_f64(), Some(42.0));
+        assert_eq!(fields["tags"], serde_yaml::Value::from(vec!["video"]));
+        assert!(!fields.contains_key("sample_rate"));
+        assert!(!fields.contains_key("uuid"));
+        assert!(body.contains("# Notes"));
+
+        // Without a readable movie header the sidecar simply has no duration
+        let broken = temp_dir.path().join("broken.mp4");
+        fs::write(&broken, b"").unwrap();
+        let content = generate_sidecar_content(
+            &broken,
+            "broken.mp4",
+            "broken.mp4",
+            0,
+            None,
+            None,
+            &Config::default(),
+            &TagMappings::new(&Config::default()),
+            &[],
+            false,
+        );
+        assert!(!content.contains(DURATION_FIELD));
+        assert!(content.contains("tags: [\"video\"]"));
+    }
+
+    #[test]
+    fn test_sidecar_uses_project_display_name_and_artist() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio = temp_dir.path().join("take.aiff");
+        fs::write(&audio, b"").unwrap();
+        let project = ProjectInfo {
+            name: "Tape Loops Vol. 2".to_string(),
+            artist: Some("Someone".to_string()),
+        };
+
+        let content = generate_sidecar_content(
+            &audio,
+            "take.aiff",
+            "mixes/take.aiff",
+            0,
+            None,
+            Some(&project),
+            &Config::default(),
+            &TagMappings::new(&Config::default()),
+            &[],
+            false,
+        );
+        assert!(content.contains("project: \"Tape Loops Vol. 2\"\nartist: \"Someone\"\n"));
+        assert!(content.contains("description: \"a mix for Tape Loops Vol. 2\""));
+    }
+
+    #[test]
+    fn test_sidecar_path_relative_or_absolute() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio = temp_dir.path().join("album/sources/drums/kick.aiff");
+        fs::create_dir_all(audio.parent().unwrap()).unwrap();
+        fs::write(&audio, b"").unwrap();
+        let sidecar = |config: &Config| {
+            generate_sidecar_content(
+                &audio,
+                "kick.aiff",
+                "album/sources/drums/kick.aiff",
+                0,
+                None,
+                None,
+                config,
+                &TagMappings::new(config),
+                &[],
+                false,
+            )
+        };
+
+        let mut config = Config::default();
+        let content = sidecar(&config);
+        assert!(content.contains("path: \"album/sources/drums/kick.aiff\""));
+
+        config.set_value("absolute_sidecar_paths", "true").unwrap();
+        let content = sidecar(&config);
+        let absolute = fs::canonicalize(&audio).unwrap();
+        assert!(absolute.is_absolute());
+        assert!(content.contains(&format!("path: \"{}\"", absolute.display())));
+        // The file type still comes from the folder inside the project
+        assert!(content.contains("description: \"a source\""));
+    }
+
+    #[test]
+    fn test_rerendered_duration_is_detected_and_lints() {
+        let tags = Vec::new();
+        let rendered = templates::generate_audio_sidecar_with_metadata(&SidecarMetadata {
+            file_name: "take.wav",
+            file_path: "take.wav",
+            title: "take",
+            description: "",
+            tags: &tags,
+            sample_rate: 44100,
+            channels: 2,
+            bits_per_sample: 16,
+            duration: Some(10.004),
+            file_size: 100,
+            modified: None,
+            project: None,
+            artist: None,
+            uuid: None,
+            art: &[],
+        });
+        let mut metadata = AudioMetadata {
+            sample_rate: 44100,
+            channels: 2,
+            bits_per_sample: 16,
+            duration_seconds: Some(10.001),
+        };
+
+        // Float noise below the written precision is not a change
+        let fields = read_sidecar_fields(&rendered).unwrap();
+        assert!(audio_metadata_changes(&fields, &metadata).is_empty());
+
+        // The file was re-rendered longer
+        metadata.duration_seconds = Some(12.3456);
+        let changes = audio_metadata_changes(&fields, &metadata);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].contains("duration"));
+
+        let updated =
+            update_frontmatter_fields(&rendered, audio_metadata_updates(&metadata)).unwrap();
+        let fields = read_sidecar_fields(&updated).unwrap();
+        assert_eq!(stored_duration(&fields), Some(12.35));
+        assert!(audio_metadata_changes(&fields, &metadata).is_empty());
+
+        let temp_dir = TempDir::new().unwrap();
+        let sidecar = temp_dir.path().join("take.wav.md");
+        fs::write(&sidecar, &updated).unwrap();
+        crate::cli::lint::validate_yaml_frontmatter(&sidecar).unwrap();
+    }
+
+    #[test]
+    fn test_resume_interrupted_update() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let settled = SystemTime::now() - std::time::Duration::from_secs(3600);
+        let files: Vec<PathBuf> = (0..4).map(|i| root.join(format!("take{i}.wav"))).collect();
+        for file in &files {
+            write_wav(file, pcm16(1, 8000), [0i16]);
+        }
+        // Files modified within the write grace period are skipped as still
+        // being recorded
+        let settle = || {
+            for file in &files {
+                let handle = fs::File::options().write(true).open(file).unwrap();
+                handle.set_modified(settled).unwrap();
+            }
+        };
+        settle();
+        let project = root.to_str().unwrap();
+
+        // A plain run leaves no checkpoint behind
+        handle_update(
+            project,
+            &[],
+            &[],
+            &[],
+            false,
+            None,
+            false,
+            false,
+            Verbosity::Quiet,
+        )
+        .unwrap();
+        assert!(!root.join(UPDATE_CHECKPOINT_FILE).exists());
+
+        // A run interrupted after two files: they are in the checkpoint. With
+        // every sidecar removed, only the unfinished files should get one back
+        let mut checkpoint = UpdateCheckpoint::start(root);
+        for file in &files[..2] {
+            checkpoint.record(file);
+        }
+        drop(checkpoint);
+        let sidecars: Vec<PathBuf> = files.iter().map(|f| f.with_extension("wav.md")).collect();
+        for sidecar in &sidecars {
+            fs::remove_file(sidecar).unwrap();
+        }
+        settle();
+
+        handle_update(
+            project,
+            &[],
+            &[],
+            &[],
+            false,
+            None,
+            true,
+            false,
+            Verbosity::Quiet,
+        )
+        .unwrap();
+        for sidecar in &sidecars[..2] {
+            assert!(!sidecar.exists());
+        }
+        for sidecar in &sidecars[2..] {
+            assert!(sidecar.exists());
+        }
+        assert!(!root.join(UPDATE_CHECKPOINT_FILE).exists());
+    }
+
+    #[test]
+    fn test_unknown_duration_is_replaced() {
+        let fields = read_sidecar_fields("---\nduration: unknown\n---\n").unwrap();
+        let metadata = AudioMetadata {
+            sample_rate: 48000,
+            channels: 1,
+            bits_per_sample: 24,
+            duration_seconds: Some(3.5),
+        };
+        let changes = audio_metadata_changes(&fields, &metadata);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].contains("unknown"));
+    }
+
     #[test]
     fn test_touch_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -816,6 +2138,101 @@ mod tests {
         assert_eq!(content, "test content");
     }
 
+    #[test]
+    fn test_update_single_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        let take = project.join("take.flac");
+        let other = project.join("other.flac");
+        fs::write(&take, b"").unwrap();
+        fs::write(&other, b"").unwrap();
+
+        handle_update_file(
+            take.to_str().unwrap(),
+            &["hook".to_string()],
+            false,
+            Verbosity::Quiet,
+        )
+        .unwrap();
+        let sidecar = fs::read_to_string(project.join("take.flac.md")).unwrap();
+        assert!(sidecar.contains("\"hook\""));
+        // Nothing else in the directory is touched
+        assert!(!project.join("other.flac.md").exists());
+
+        // A second run leaves the up-to-date sidecar alone
+        handle_update_file(take.to_str().unwrap(), &[], false, Verbosity::Quiet).unwrap();
+        assert_eq!(
+            fs::read_to_string(project.join("take.flac.md")).unwrap(),
+            sidecar
+        );
+
+        let notes = project.join("notes.txt");
+        fs::write(&notes, b"").unwrap();
+        assert!(handle_update_file(notes.to_str().unwrap(), &[], false, Verbosity::Quiet).is_err());
+        assert!(
+            handle_update_file(
+                project.join("missing.wav").to_str().unwrap(),
+                &[],
+                false,
+                Verbosity::Quiet
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_malformed_sidecar_does_not_stop_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+
+        // A sidecar older than its audio with broken YAML, then a file with no sidecar
+        let broken_audio = project.join("broken.flac");
+        let broken_sidecar = project.join("broken.flac.md");
+        let broken_content = "---\ntitle: [unclosed\ntags: {\n---\n# Notes\n";
+        fs::write(&broken_sidecar, broken_content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&broken_sidecar)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000))
+            .unwrap();
+        fs::write(&broken_audio, b"").unwrap();
+        let fresh_audio = project.join("fresh.flac");
+        fs::write(&fresh_audio, b"").unwrap();
+
+        let created = Arc::new(Mutex::new(0));
+        let skipped = Arc::new(Mutex::new(0));
+        let updated = Arc::new(Mutex::new(0));
+        let project_cache = Arc::new(Mutex::new(HashMap::new()));
+        let config = Arc::new(Config::default());
+        let pb = ProgressBar::hidden();
+
+        for file_path in [&broken_audio, &fresh_audio] {
+            process_media_file(
+                file_path,
+                &created,
+                &skipped,
+                &updated,
+                &pb,
+                &project_cache,
+                &config,
+                &TagMappings::new(&config),
+                &[],
+                false,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(*skipped.lock().unwrap(), 1);
+        assert_eq!(*created.lock().unwrap(), 1);
+        assert_eq!(*updated.lock().unwrap(), 0);
+        assert!(project.join("fresh.flac.md").exists());
+        // The broken sidecar is left as it was
+        assert_eq!(fs::read_to_string(&broken_sidecar).unwrap(), broken_content);
+    }
+
     #[test]
     fn test_extract_title_from_filename() {
         assert_eq!(extract_title_from_filename("song.wav"), "song");
@@ -831,6 +2248,86 @@ mod tests {
         assert_eq!(extract_title_from_filename(""), "");
     }
 
+    #[test]
+    fn test_apply_title_style() {
+        assert_eq!(apply_title_style("Final_Mix_v2", "raw"), "Final_Mix_v2");
+        assert_eq!(apply_title_style("Final_Mix_v2", "spaces"), "Final Mix v2");
+        assert_eq!(apply_title_style("Final_Mix_v2", "title"), "Final Mix V2");
+        assert_eq!(
+            apply_title_style("drum--loop__120 bpm", "spaces"),
+            "drum loop 120 bpm"
+        );
+        assert_eq!(apply_title_style("my.great.song", "title"), "My.great.song");
+        assert_eq!(apply_title_style("", "title"), "");
+        assert_eq!(apply_title_style("Final_Mix", "unknown"), "Final_Mix");
+    }
+
+    #[test]
+    fn test_apply_title_style_unicode() {
+        assert_eq!(apply_title_style("über_café-mix", "title"), "Über Café Mix");
+        assert_eq!(apply_title_style("éte_à_paris", "spaces"), "éte à paris");
+        assert_eq!(apply_title_style("straße_mix", "title"), "Straße Mix");
+        assert_eq!(apply_title_style("東京_night", "title"), "東京 Night");
+    }
+
+    fn config_with_mappings(substrings: &[(&str, &str)], regexes: &[(&str, &str)]) -> Config {
+        let to_map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        Config {
+            tag_mappings: to_map(substrings),
+            tag_regex: to_map(regexes),
+            ..Config::new()
+        }
+    }
+
+    #[test]
+    fn test_mapped_tags_substring() {
+        let config = config_with_mappings(&[("kick", "drums"), ("ES-9", "eurorack")], &[]);
+        let mut tags = TagMappings::new(&config).tags("Big_KICK_es-9.wav");
+        tags.sort();
+        assert_eq!(tags, vec!["drums", "eurorack"]);
+        assert!(TagMappings::new(&config).tags("pad.wav").is_empty());
+    }
+
+    #[test]
+    fn test_mapped_tags_regex() {
+        let config = config_with_mappings(
+            &[("kick", "drums")],
+            &[
+                (r".*_v\d+\.\w+", "versioned"),
+                (r".*\bBPM(\d+)\b.*", "bpm-$1"),
+                (r"take.*", "take"),
+            ],
+        );
+
+        let tags = TagMappings::new(&config).tags("Kick-Loop bpm120.wav");
+        assert_eq!(tags, vec!["drums", "bpm-120"]);
+        let tags = TagMappings::new(&config).tags("Kick-Loop_v3.wav");
+        assert_eq!(tags, vec!["drums", "versioned"]);
+
+        // Patterns must match the whole filename
+        assert_eq!(TagMappings::new(&config).tags("take_01.wav"), vec!["take"]);
+        assert!(TagMappings::new(&config).tags("retake_01.wav").is_empty());
+        assert!(TagMappings::new(&config).tags("songv3.wav").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_tag_regex_is_skipped() {
+        let config = config_with_mappings(&[], &[("(unclosed", "never"), (".*pad.*", "pad")]);
+        assert_eq!(
+            TagMappings::new(&config).tags("(unclosed pad.wav"),
+            vec!["pad"]
+        );
+
+        let invalid = invalid_tag_regexes(&config);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, "(unclosed");
+    }
+
     #[test]
     fn test_get_article() {
         // Vowel starts
@@ -912,4 +2409,48 @@ mod tests {
         assert_eq!(generate_description(None, Some("project")), "");
         assert_eq!(generate_description(None, None), "");
     }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_replay_gain_for() {
+        use crate::player::loudness::Loudness;
+        let level = |integrated, true_peak| Loudness {
+            integrated,
+            true_peak,
+        };
+        assert_eq!(replay_gain_for(level(Some(-9.0), Some(-0.5))), Some(-9.0));
+        // A quiet file is only raised until its peaks reach 0 dBTP
+        assert_eq!(replay_gain_for(level(Some(-30.0), Some(-4.0))), Some(4.0));
+        assert_eq!(replay_gain_for(level(Some(-20.0), Some(-6.0))), Some(2.0));
+        assert_eq!(replay_gain_for(level(None, None)), None);
+    }
+
+    #[cfg(feature = "player")]
+    #[test]
+    fn test_handle_replay_gain_stores_gain_in_sidecars() {
+        use zim_studio::utils::sidecar::read_replay_gain;
+
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["tone.wav", "no-sidecar.wav"] {
+            let samples = (0..8000).map(|i| ((i as f32 / 8.0).sin() * 1000.0) as i16);
+            write_wav(temp_dir.path().join(name), pcm16(1, 8000), samples);
+        }
+        let sidecar = temp_dir.path().join("tone.wav.md");
+        fs::write(&sidecar, "---\nfile: tone.wav\n---\n# Notes\n").unwrap();
+
+        handle_replay_gain(
+            temp_dir.path().to_str().unwrap(),
+            &[],
+            &[],
+            Verbosity::Quiet,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&sidecar).unwrap();
+        let gain = read_replay_gain(&content).unwrap();
+        // A quiet tone is raised, but never past its own peak
+        assert!(gain > 0.0 && gain < 31.0, "stored {gain}");
+        assert!(content.ends_with("# Notes\n"));
+        assert!(!temp_dir.path().join("no-sidecar.wav.md").exists());
+    }
 }
@@ -0,0 +1,202 @@
+use crate::wav_metadata;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use zim_studio::utils::color::Colorize;
+use zim_studio::utils::parallel_scan;
+use zim_studio::utils::progress::{Verbosity, create_progress_bar, create_progress_spinner};
+use zim_studio::utils::validation::validate_path_exists;
+use zim_studio::zimignore::ZimIgnore;
+
+/// Outcome of checking one tagged WAV against its stored audio MD5
+#[derive(Debug, PartialEq)]
+enum Verification {
+    Intact,
+    /// The audio no longer hashes to the stored value
+    Mismatch {
+        stored: String,
+        current: String,
+    },
+    /// Tagged, but with no hash to compare against
+    NoHash,
+}
+
+/// Files grouped by outcome, for the report
+#[derive(Debug, Default)]
+struct VerifyReport {
+    intact: usize,
+    mismatched: Vec<(PathBuf, String, String)>,
+    no_hash: Vec<PathBuf>,
+    errors: Vec<(PathBuf, String)>,
+}
+
+impl VerifyReport {
+    fn failures(&self) -> usize {
+        self.mismatched.len() + self.errors.len()
+    }
+}
+
+/// Recompute the audio MD5 of every tagged WAV under `path` (or of `path`
+/// itself) and compare it with the hash stored in the file
+pub fn handle_verify(path: &str, verbosity: Verbosity) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(path);
+    validate_path_exists(path)?;
+
+    let wav_files = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        let spinner = create_progress_spinner(verbosity);
+        spinner.set_message("Scanning for WAV files...");
+        let zimignore = ZimIgnore::load_for_directory(path);
+        let skip_dirs = parallel_scan::SkipDirs::from_config();
+        let mut files = parallel_scan::collect_audio_files(
+            path,
+            &HashSet::from(["wav"]),
+            &zimignore,
+            &skip_dirs,
+        )?;
+        spinner.finish_and_clear();
+        files.sort();
+        files
+    };
+
+    let pb = create_progress_bar(wav_files.len() as u64, verbosity);
+    pb.set_message("Hashing audio...");
+
+    let mut report = VerifyReport::default();
+    for wav in wav_files {
+        match verify_file(&wav) {
+            Ok(Some(Verification::Intact)) => report.intact += 1,
+            Ok(Some(Verification::Mismatch { stored, current })) => {
+                report.mismatched.push((wav, stored, current))
+            }
+            Ok(Some(Verification::NoHash)) => report.no_hash.push(wav),
+            Ok(None) => {}
+            Err(e) => report.errors.push((wav, e.to_string())),
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    let display = |wav: &Path| {
+        wav.strip_prefix(path)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(wav)
+            .display()
+            .to_string()
+    };
+
+    if !verbosity.is_quiet() {
+        for (wav, stored, current) in &report.mismatched {
+            println!(
+                "  {} {} {}",
+                "Mismatch:".red().bold(),
+                display(wav).cyan(),
+                format!("(stored {stored}, now {current})").bright_black()
+            );
+        }
+        for (wav, error) in &report.errors {
+            println!(
+                "  {} {}: {}",
+                "Unreadable:".red().bold(),
+                display(wav),
+                error
+            );
+        }
+        if !report.no_hash.is_empty() {
+            println!("\n{}", "Tagged without a stored hash:".yellow());
+            for wav in &report.no_hash {
+                println!("  {}", display(wav));
+            }
+            println!(
+                "  {}",
+                "Run 'zim update --refresh-hash' to record one".bright_black()
+            );
+        }
+    }
+
+    if report.failures() > 0 {
+        return Err(format!(
+            "{} of {} tagged WAV files failed verification",
+            report.failures(),
+            report.intact + report.failures() + report.no_hash.len()
+        )
+        .into());
+    }
+
+    if !verbosity.is_quiet() {
+        println!(
+            "\n{} {} tagged WAV files verified",
+            "✓".green().bold(),
+            report.intact.to_string().green().bold()
+        );
+    }
+    Ok(())
+}
+
+/// Check one WAV's audio against its stored MD5. Returns None for files
+/// without ZIM metadata.
+fn verify_file(path: &Path) -> Result<Option<Verification>, Box<dyn Error>> {
+    let Some(metadata) = wav_metadata::read_metadata(path)? else {
+        return Ok(None);
+    };
+    if metadata.audio_md5.is_empty() {
+        return Ok(Some(Verification::NoHash));
+    }
+
+    let current = wav_metadata::calculate_audio_md5(path)?;
+    Ok(Some(if current == metadata.audio_md5 {
+        Verification::Intact
+    } else {
+        Verification::Mismatch {
+            stored: metadata.audio_md5,
+            current,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_verify_detects_changed_audio() {
+        let temp_dir = TempDir::new().unwrap();
+        let untagged = temp_dir.path().join("untagged.wav");
+        write_wav(&untagged, pcm16(1, 8000), 0..100i16);
+        assert_eq!(verify_file(&untagged).unwrap(), None);
+
+        let master = temp_dir.path().join("master.wav");
+        let mut metadata = wav_metadata::ZimMetadata::new_original("album", &untagged);
+        metadata.audio_md5 = wav_metadata::calculate_audio_md5(&untagged).unwrap();
+        wav_metadata::write_metadata(&untagged, &master, &metadata).unwrap();
+        assert_eq!(verify_file(&master).unwrap(), Some(Verification::Intact));
+        handle_verify(temp_dir.path().to_str().unwrap(), Verbosity::Quiet).unwrap();
+
+        // Flip the first audio byte, as a silent edit or bit rot would
+        let mut bytes = fs::read(&master).unwrap();
+        let data = bytes.windows(4).position(|id| id == b"data").unwrap() + 8;
+        bytes[data] ^= 0xFF;
+        fs::write(&master, bytes).unwrap();
+        match verify_file(&master).unwrap() {
+            Some(Verification::Mismatch { stored, current }) => {
+                assert_eq!(stored, metadata.audio_md5);
+                assert_ne!(current, stored);
+            }
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+        assert!(handle_verify(temp_dir.path().to_str().unwrap(), Verbosity::Quiet).is_err());
+        assert!(handle_verify(master.to_str().unwrap(), Verbosity::Quiet).is_err());
+
+        // Tagged without a hash is reported but doesn't fail
+        let unhashed = temp_dir.path().join("unhashed.wav");
+        metadata.audio_md5.clear();
+        wav_metadata::write_metadata(&untagged, &unhashed, &metadata).unwrap();
+        assert_eq!(verify_file(&unhashed).unwrap(), Some(Verification::NoHash));
+        handle_verify(unhashed.to_str().unwrap(), Verbosity::Quiet).unwrap();
+    }
+}
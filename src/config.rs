@@ -10,10 +10,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Parent directory for `zim new` when `--path` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_dir: Option<String>,
     #[serde(default = "default_artist")]
     pub default_artist: String,
     #[serde(default = "default_folders")]
@@ -28,6 +31,399 @@ pub struct Config {
     pub daw_folders: Vec<String>,
     #[serde(default = "default_tag_mappings")]
     pub tag_mappings: HashMap<String, String>,
+    /// Regex → tag mappings matched case-insensitively against whole filenames.
+    /// Tags may use capture groups, e.g. `".*BPM(\\d+).*" = "bpm-$1"`.
+    #[serde(default)]
+    pub tag_regex: HashMap<String, String>,
+    /// Player master output gain (0.0-2.0) used when `--gain` is not given
+    #[serde(default = "default_gain")]
+    pub default_gain: f32,
+    /// Gain applied to RMS before it drives the player's level meters. The
+    /// default of 2.0 is cosmetic, to make the LEDs lively; 1.0 shows true level.
+    #[serde(default = "default_meter_gain")]
+    pub meter_gain: f32,
+    /// Player log level: off, error, warn, info, debug or trace
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Player log file; defaults to zim-player.log in the platform temp dir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<String>,
+    /// How `zim update` turns a filename into a sidecar title (see `TITLE_STYLES`)
+    #[serde(default = "default_title_style")]
+    pub title_style: String,
+    /// Move deleted files to the OS trash instead of removing them permanently
+    #[serde(default = "default_use_trash")]
+    pub use_trash: bool,
+    /// Write the sidecar title, description and tags into the INFO chunk of
+    /// WAV selections saved from the player. Off by default as it changes the
+    /// exported bytes.
+    #[serde(default)]
+    pub embed_notes: bool,
+    /// Whether the player's full-text search matches the markdown body of
+    /// sidecars. Turn off to match only the YAML frontmatter fields.
+    #[serde(default = "default_search_body")]
+    pub search_body: bool,
+    /// Start playing a file as soon as the player loads it. When off, files
+    /// load paused at the start. Browser previews always load paused.
+    #[serde(default = "default_autoplay_on_load")]
+    pub autoplay_on_load: bool,
+    /// Play each file at the `replay_gain` stored in its sidecar by
+    /// `zim update --replaygain`, so files sound equally loud. The audio
+    /// itself is never changed.
+    #[serde(default = "default_replay_gain")]
+    pub replay_gain: bool,
+    /// Extra directory names to skip when scanning for audio, on top of the
+    /// built-in list. Prefix a name with `!` to scan a built-in one.
+    #[serde(default)]
+    pub skip_dirs: Vec<String>,
+    /// Descend into symlinked directories when scanning for audio, such as
+    /// a shared sample folder linked into each project. On by default;
+    /// each directory is scanned once even if links form a loop.
+    #[serde(default = "default_follow_symlinks")]
+    pub follow_symlinks: bool,
+    /// Frames the player collects before updating the oscilloscope and
+    /// meters. Smaller chunks redraw more often at more CPU cost; larger ones
+    /// are cheaper and keep a slow terminal from falling behind.
+    #[serde(default = "default_monitor_frames")]
+    pub monitor_chunk_size: usize,
+    /// Frames in the audio output buffer requested from the device; 0 keeps
+    /// the device default. Larger buffers ride out load at the cost of
+    /// latency, and not every device honors the request.
+    #[serde(default)]
+    pub audio_buffer_frames: u32,
+    /// Where new sidecars are written (see `SIDECAR_LOCATIONS`). Existing
+    /// sidecars are found in either place.
+    #[serde(default = "default_sidecar_location")]
+    pub sidecar_location: String,
+    /// Tags given to the sidecar of a saved selection (see
+    /// `EXCERPT_TAG_MODES`)
+    #[serde(default = "default_excerpt_tags")]
+    pub excerpt_tags: String,
+    /// Size in pixels of the waveform SVG the player can write beside a
+    /// saved selection
+    #[serde(default = "default_waveform_image_width")]
+    pub waveform_image_width: u32,
+    #[serde(default = "default_waveform_image_height")]
+    pub waveform_image_height: u32,
+    /// Selections shorter than this many milliseconds are flagged in the
+    /// player and its save dialog, to catch accidental tiny exports
+    #[serde(default = "default_min_selection_ms")]
+    pub min_selection_ms: u32,
+    /// Refuse to save a selection shorter than `min_selection_ms` instead of
+    /// only warning about it
+    #[serde(default)]
+    pub block_short_selections: bool,
+    /// Record each sidecar's `path` as an absolute path rather than one
+    /// relative to where `zim update` ran
+    #[serde(default)]
+    pub absolute_sidecar_paths: bool,
+    /// Also write sidecars for reference video (`VIDEO_EXTENSIONS`) kept in
+    /// the project tree, and list them in `zim index`
+    #[serde(default)]
+    pub video_sidecars: bool,
+    /// `zim update` leaves files modified within this many seconds alone,
+    /// as a DAW may still be rendering them
+    #[serde(default = "default_write_grace_secs")]
+    pub write_grace_secs: u32,
+    /// Oversample the player's peak readout 4x to show true peak (dBTP)
+    /// instead of the highest sample, at some CPU cost
+    #[serde(default)]
+    pub true_peak_meter: bool,
+    /// How much the player's oscilloscope amplifies the waveform. Only the
+    /// display changes, and peaks past the edge are clipped.
+    #[serde(default = "default_scope_gain")]
+    pub scope_gain: f32,
+    /// Scale the oscilloscope to fit the recent peak instead of using
+    /// `scope_gain`, for material from quiet field recordings to hot masters
+    #[serde(default)]
+    pub scope_auto_gain: bool,
+    /// Statuses `zim lint` accepts in a project's README.md
+    #[serde(default = "default_project_statuses")]
+    pub project_statuses: Vec<String>,
+    /// Status `zim new` gives new projects; one of `project_statuses`
+    #[serde(default = "default_status")]
+    pub default_status: String,
+}
+
+/// Upper bound for per-file mix gains and the player's master gain
+pub const MAX_GAIN: f32 = 2.0;
+
+/// Default for `monitor_chunk_size`: about 23ms of audio at 44.1kHz
+pub const DEFAULT_MONITOR_FRAMES: usize = 1024;
+
+/// Range accepted by `monitor_chunk_size`
+pub const MONITOR_FRAMES_RANGE: (u32, u32) = (128, 16384);
+
+/// Range accepted by `audio_buffer_frames`; 0 uses the device default
+pub const AUDIO_BUFFER_RANGE: (u32, u32) = (0, 16384);
+
+/// Default width and height of waveform images
+pub const DEFAULT_WAVEFORM_IMAGE_SIZE: (u32, u32) = (800, 160);
+
+/// Range accepted by `waveform_image_width` and `waveform_image_height`
+pub const WAVEFORM_IMAGE_RANGE: (u32, u32) = (16, 8192);
+
+/// Default for `min_selection_ms`
+pub const DEFAULT_MIN_SELECTION_MS: u32 = 50;
+
+/// Range accepted by `min_selection_ms`; 0 turns the check off
+pub const MIN_SELECTION_RANGE: (u32, u32) = (0, 60_000);
+
+/// Default for `write_grace_secs`
+pub const DEFAULT_WRITE_GRACE_SECS: u32 = 5;
+
+/// Range accepted by `write_grace_secs`; 0 processes files however fresh
+pub const WRITE_GRACE_RANGE: (u32, u32) = (0, 600);
+
+/// Default for `scope_gain`
+pub const DEFAULT_SCOPE_GAIN: f32 = 1.5;
+
+/// Range accepted by `scope_gain`
+pub const SCOPE_GAIN_RANGE: (f32, f32) = (0.1, 32.0);
+
+/// Log levels accepted by `log_level` and the `ZIM_LOG` environment variable
+pub const LOG_LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+/// Title styles accepted by `title_style`:
+/// - `raw`: the filename without its extension
+/// - `spaces`: underscores and hyphens become spaces
+/// - `title`: as `spaces`, with each word capitalized
+pub const TITLE_STYLES: &[&str] = &["raw", "spaces", "title"];
+
+/// Sidecar locations accepted by `sidecar_location`:
+/// - `adjacent`: `track.wav.md` next to `track.wav`
+/// - `zim`: under a hidden `.zim/` directory at the project root, mirroring
+///   the project tree
+pub const SIDECAR_LOCATIONS: &[&str] = &["adjacent", "zim"];
+
+/// Excerpt tagging accepted by `excerpt_tags`:
+/// - `inherit`: the source sidecar's tags plus `excerpt`
+/// - `fresh`: only `excerpt`
+pub const EXCERPT_TAG_MODES: &[&str] = &["inherit", "fresh"];
+
+/// Default `project_statuses`: the lifecycle a project's README.md `status`
+/// field moves through
+pub const DEFAULT_PROJECT_STATUSES: &[&str] = &["active", "paused", "released", "archived"];
+
+/// Split a comma-separated list of single words, as used for
+/// `project_statuses`. Words are lowercased; at least one is required and
+/// none may repeat.
+pub fn parse_word_list(value: &str) -> Result<Vec<String>, String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in value.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+        let word = word.to_lowercase();
+        if word.contains(char::is_whitespace) {
+            return Err(format!("'{word}' is not a single word"));
+        }
+        if words.contains(&word) {
+            return Err(format!("'{word}' is listed twice"));
+        }
+        words.push(word);
+    }
+    if words.is_empty() {
+        return Err("at least one word is required".to_string());
+    }
+    Ok(words)
+}
+
+/// Split a comma-separated list of folder names, as used for `daw_folders`
+/// and `zim new --daw`. Names must be plain single folders: no path
+/// separators, no leading `.`, and no repeats. Empty entries are dropped.
+pub fn parse_folder_names(value: &str) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("'{name}' is not a plain folder name"));
+        }
+        if names.iter().any(|existing| existing == name) {
+            return Err(format!("'{name}' is listed twice"));
+        }
+        names.push(name.to_string());
+    }
+    Ok(names)
+}
+
+/// The kind of value a configuration key accepts from `zim config set`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    /// Any text
+    Text,
+    /// `true` or `false`
+    Bool,
+    /// A number from 0.0 to `MAX_GAIN`
+    Gain,
+    /// A whole number within the inclusive range
+    Integer(u32, u32),
+    /// A number within the inclusive range
+    Number(f32, f32),
+    /// One of a fixed set of words (case-insensitive)
+    Choice(&'static [&'static str]),
+    /// A file path; an empty value resets to the default
+    OptionalPath,
+    /// A directory path; an empty value resets to the default. A path that
+    /// doesn't exist yet is accepted, an existing file is not.
+    OptionalDirectory,
+    /// Comma-separated folder names, see [`parse_folder_names`]
+    FolderList,
+    /// Comma-separated words, see [`parse_word_list`]
+    WordList,
+    /// One of the configured `project_statuses`, checked when it's set
+    ProjectStatus,
+}
+
+/// A validated configuration value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Text(String),
+    Bool(bool),
+    Number(f32),
+    Integer(u32),
+    Path(Option<String>),
+    List(Vec<String>),
+}
+
+impl ValueKind {
+    /// Human-readable description of accepted values
+    pub fn describe(&self) -> String {
+        match self {
+            ValueKind::Text => "any text".to_string(),
+            ValueKind::Bool => "true or false".to_string(),
+            ValueKind::Gain => format!("a number from 0.0 to {MAX_GAIN}"),
+            ValueKind::Integer(min, max) => format!("a whole number from {min} to {max}"),
+            ValueKind::Number(min, max) => format!("a number from {min} to {max}"),
+            ValueKind::Choice(choices) => format!("one of: {}", choices.join(", ")),
+            ValueKind::OptionalPath => "a file path (empty for the default)".to_string(),
+            ValueKind::OptionalDirectory => "a directory (empty for the default)".to_string(),
+            ValueKind::FolderList => "comma-separated folder names (empty for none)".to_string(),
+            ValueKind::WordList => "comma-separated words".to_string(),
+            ValueKind::ProjectStatus => "one of the project_statuses".to_string(),
+        }
+    }
+
+    /// Validate and convert a raw value from the command line
+    pub fn parse(&self, value: &str) -> Result<ConfigValue, String> {
+        let invalid = || format!("expected {}, got '{value}'", self.describe());
+        match self {
+            ValueKind::Text => Ok(ConfigValue::Text(value.to_string())),
+            ValueKind::Bool => value
+                .trim()
+                .to_lowercase()
+                .parse::<bool>()
+                .map(ConfigValue::Bool)
+                .map_err(|_| invalid()),
+            ValueKind::Gain => match value.trim().parse::<f32>() {
+                Ok(gain) if (0.0..=MAX_GAIN).contains(&gain) => Ok(ConfigValue::Number(gain)),
+                _ => Err(invalid()),
+            },
+            ValueKind::Integer(min, max) => match value.trim().parse::<u32>() {
+                Ok(number) if (*min..=*max).contains(&number) => Ok(ConfigValue::Integer(number)),
+                _ => Err(invalid()),
+            },
+            ValueKind::Number(min, max) => match value.trim().parse::<f32>() {
+                Ok(number) if (*min..=*max).contains(&number) => Ok(ConfigValue::Number(number)),
+                _ => Err(invalid()),
+            },
+            ValueKind::Choice(choices) => {
+                let choice = value.trim().to_lowercase();
+                if choices.contains(&choice.as_str()) {
+                    Ok(ConfigValue::Text(choice))
+                } else {
+                    Err(invalid())
+                }
+            }
+            ValueKind::OptionalPath => Ok(ConfigValue::Path(
+                Some(value.to_string()).filter(|v| !v.is_empty()),
+            )),
+            ValueKind::OptionalDirectory => {
+                if value.is_empty() {
+                    return Ok(ConfigValue::Path(None));
+                }
+                let expanded = shellexpand::tilde(value);
+                let path = Path::new(expanded.as_ref());
+                if path.exists() && !path.is_dir() {
+                    return Err(format!("'{value}' is not a directory"));
+                }
+                Ok(ConfigValue::Path(Some(value.to_string())))
+            }
+            ValueKind::FolderList => parse_folder_names(value).map(ConfigValue::List),
+            ValueKind::WordList => parse_word_list(value)
+                .map(ConfigValue::List)
+                .map_err(|e| format!("{e} in '{value}'")),
+            ValueKind::ProjectStatus => Ok(ConfigValue::Text(value.trim().to_lowercase())),
+        }
+    }
+}
+
+/// A key that can be changed with `zim config set`
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigKey {
+    pub name: &'static str,
+    pub kind: ValueKind,
+}
+
+const fn key(name: &'static str, kind: ValueKind) -> ConfigKey {
+    ConfigKey { name, kind }
+}
+
+/// Every key accepted by `zim config set`, with the values it accepts
+pub const CONFIG_KEYS: &[ConfigKey] = &[
+    key("root_dir", ValueKind::OptionalDirectory),
+    key("daw_folders", ValueKind::FolderList),
+    key("default_artist", ValueKind::Text),
+    key("normalize_project_names", ValueKind::Bool),
+    key("default_gain", ValueKind::Gain),
+    key("meter_gain", ValueKind::Gain),
+    key("log_level", ValueKind::Choice(LOG_LEVELS)),
+    key("log_file", ValueKind::OptionalPath),
+    key("title_style", ValueKind::Choice(TITLE_STYLES)),
+    key("use_trash", ValueKind::Bool),
+    key("embed_notes", ValueKind::Bool),
+    key("search_body", ValueKind::Bool),
+    key("autoplay_on_load", ValueKind::Bool),
+    key("replay_gain", ValueKind::Bool),
+    key("follow_symlinks", ValueKind::Bool),
+    key(
+        "monitor_chunk_size",
+        ValueKind::Integer(MONITOR_FRAMES_RANGE.0, MONITOR_FRAMES_RANGE.1),
+    ),
+    key(
+        "audio_buffer_frames",
+        ValueKind::Integer(AUDIO_BUFFER_RANGE.0, AUDIO_BUFFER_RANGE.1),
+    ),
+    key("sidecar_location", ValueKind::Choice(SIDECAR_LOCATIONS)),
+    key("excerpt_tags", ValueKind::Choice(EXCERPT_TAG_MODES)),
+    key(
+        "waveform_image_width",
+        ValueKind::Integer(WAVEFORM_IMAGE_RANGE.0, WAVEFORM_IMAGE_RANGE.1),
+    ),
+    key(
+        "waveform_image_height",
+        ValueKind::Integer(WAVEFORM_IMAGE_RANGE.0, WAVEFORM_IMAGE_RANGE.1),
+    ),
+    key(
+        "min_selection_ms",
+        ValueKind::Integer(MIN_SELECTION_RANGE.0, MIN_SELECTION_RANGE.1),
+    ),
+    key("block_short_selections", ValueKind::Bool),
+    key("absolute_sidecar_paths", ValueKind::Bool),
+    key("video_sidecars", ValueKind::Bool),
+    key(
+        "write_grace_secs",
+        ValueKind::Integer(WRITE_GRACE_RANGE.0, WRITE_GRACE_RANGE.1),
+    ),
+    key("true_peak_meter", ValueKind::Bool),
+    key(
+        "scope_gain",
+        ValueKind::Number(SCOPE_GAIN_RANGE.0, SCOPE_GAIN_RANGE.1),
+    ),
+    key("scope_auto_gain", ValueKind::Bool),
+    key("project_statuses", ValueKind::WordList),
+    key("default_status", ValueKind::ProjectStatus),
+];
+
+/// Names of the keys accepted by `zim config set`
+pub fn config_key_names() -> Vec<&'static str> {
+    CONFIG_KEYS.iter().map(|k| k.name).collect()
 }
 
 fn default_artist() -> String {
@@ -132,6 +528,85 @@ fn default_tag_mappings() -> HashMap<String, String> {
     mappings
 }
 
+fn default_gain() -> f32 {
+    1.0
+}
+
+fn default_meter_gain() -> f32 {
+    2.0
+}
+
+fn default_scope_gain() -> f32 {
+    DEFAULT_SCOPE_GAIN
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_title_style() -> String {
+    "raw".to_string()
+}
+
+fn default_use_trash() -> bool {
+    true
+}
+
+fn default_search_body() -> bool {
+    true
+}
+
+fn default_autoplay_on_load() -> bool {
+    true
+}
+
+fn default_replay_gain() -> bool {
+    true
+}
+
+fn default_follow_symlinks() -> bool {
+    true
+}
+
+fn default_monitor_frames() -> usize {
+    DEFAULT_MONITOR_FRAMES
+}
+
+fn default_sidecar_location() -> String {
+    "adjacent".to_string()
+}
+
+fn default_excerpt_tags() -> String {
+    "inherit".to_string()
+}
+
+fn default_waveform_image_width() -> u32 {
+    DEFAULT_WAVEFORM_IMAGE_SIZE.0
+}
+
+fn default_waveform_image_height() -> u32 {
+    DEFAULT_WAVEFORM_IMAGE_SIZE.1
+}
+
+fn default_min_selection_ms() -> u32 {
+    DEFAULT_MIN_SELECTION_MS
+}
+
+fn default_write_grace_secs() -> u32 {
+    DEFAULT_WRITE_GRACE_SECS
+}
+
+fn default_project_statuses() -> Vec<String> {
+    DEFAULT_PROJECT_STATUSES
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_status() -> String {
+    "active".to_string()
+}
+
 fn default_zimignore_content() -> String {
     r#"# ZIM Studio Default .zimignore
 # 
@@ -188,6 +663,7 @@ impl Default for Config {
 impl Config {
     pub fn new() -> Self {
         Self {
+            root_dir: None,
             default_artist: default_artist(),
             default_folders: default_folders(),
             default_gitignore: default_gitignore(),
@@ -195,9 +671,43 @@ impl Config {
             normalize_project_names: default_normalize_project_names(),
             daw_folders: default_daw_folders(),
             tag_mappings: default_tag_mappings(),
+            tag_regex: HashMap::new(),
+            default_gain: default_gain(),
+            meter_gain: default_meter_gain(),
+            log_level: default_log_level(),
+            log_file: None,
+            title_style: default_title_style(),
+            use_trash: default_use_trash(),
+            embed_notes: false,
+            search_body: default_search_body(),
+            autoplay_on_load: default_autoplay_on_load(),
+            replay_gain: default_replay_gain(),
+            skip_dirs: Vec::new(),
+            follow_symlinks: default_follow_symlinks(),
+            monitor_chunk_size: default_monitor_frames(),
+            audio_buffer_frames: 0,
+            sidecar_location: default_sidecar_location(),
+            excerpt_tags: default_excerpt_tags(),
+            waveform_image_width: default_waveform_image_width(),
+            waveform_image_height: default_waveform_image_height(),
+            min_selection_ms: default_min_selection_ms(),
+            block_short_selections: false,
+            absolute_sidecar_paths: false,
+            video_sidecars: false,
+            write_grace_secs: default_write_grace_secs(),
+            true_peak_meter: false,
+            scope_gain: default_scope_gain(),
+            scope_auto_gain: false,
+            project_statuses: default_project_statuses(),
+            default_status: default_status(),
         }
     }
 
+    /// Whether saved selections keep their source's tags
+    pub fn inherit_excerpt_tags(&self) -> bool {
+        self.excerpt_tags != "fresh"
+    }
+
     pub fn config_dir() -> Result<PathBuf, Box<dyn Error>> {
         // Check for XDG_CONFIG_HOME first (useful for testing)
         let config_dir = if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
@@ -218,6 +728,11 @@ impl Config {
         Ok(Self::config_dir()?.join("default.zimignore"))
     }
 
+    /// Ignore patterns applied to every project, beneath any `.zimignore` files
+    pub fn global_zimignore_path() -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Self::config_dir()?.join("global.zimignore"))
+    }
+
     pub fn load() -> Result<Self, Box<dyn Error>> {
         let config_path = Self::config_path()?;
 
@@ -278,20 +793,90 @@ impl Config {
     }
 
     pub fn set_value(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
-        match key {
-            "default_artist" => self.default_artist = value.to_string(),
-            "normalize_project_names" => {
-                self.normalize_project_names = value
-                    .parse::<bool>()
-                    .map_err(|_| "Value must be 'true' or 'false'")?;
+        let config_key = CONFIG_KEYS.iter().find(|k| k.name == key).ok_or_else(|| {
+            format!(
+                "Unknown configuration key: {key}. Valid keys: {}",
+                config_key_names().join(", ")
+            )
+        })?;
+        let value = config_key
+            .kind
+            .parse(value)
+            .map_err(|e| format!("Invalid value for {key}: {e}"))?;
+
+        match (key, value) {
+            ("root_dir", ConfigValue::Path(path)) => self.root_dir = path,
+            ("daw_folders", ConfigValue::List(folders)) => self.daw_folders = folders,
+            ("default_artist", ConfigValue::Text(artist)) => self.default_artist = artist,
+            ("normalize_project_names", ConfigValue::Bool(enabled)) => {
+                self.normalize_project_names = enabled
+            }
+            ("default_gain", ConfigValue::Number(gain)) => self.default_gain = gain,
+            ("meter_gain", ConfigValue::Number(gain)) => self.meter_gain = gain,
+            ("log_level", ConfigValue::Text(level)) => self.log_level = level,
+            ("log_file", ConfigValue::Path(path)) => self.log_file = path,
+            ("title_style", ConfigValue::Text(style)) => self.title_style = style,
+            ("use_trash", ConfigValue::Bool(enabled)) => self.use_trash = enabled,
+            ("embed_notes", ConfigValue::Bool(enabled)) => self.embed_notes = enabled,
+            ("search_body", ConfigValue::Bool(enabled)) => self.search_body = enabled,
+            ("autoplay_on_load", ConfigValue::Bool(enabled)) => self.autoplay_on_load = enabled,
+            ("replay_gain", ConfigValue::Bool(enabled)) => self.replay_gain = enabled,
+            ("follow_symlinks", ConfigValue::Bool(enabled)) => self.follow_symlinks = enabled,
+            ("audio_buffer_frames", ConfigValue::Integer(frames)) => {
+                self.audio_buffer_frames = frames
+            }
+            ("monitor_chunk_size", ConfigValue::Integer(frames)) => {
+                self.monitor_chunk_size = frames as usize
+            }
+            ("sidecar_location", ConfigValue::Text(location)) => self.sidecar_location = location,
+            ("excerpt_tags", ConfigValue::Text(mode)) => self.excerpt_tags = mode,
+            ("waveform_image_width", ConfigValue::Integer(width)) => {
+                self.waveform_image_width = width
+            }
+            ("waveform_image_height", ConfigValue::Integer(height)) => {
+                self.waveform_image_height = height
+            }
+            ("min_selection_ms", ConfigValue::Integer(ms)) => self.min_selection_ms = ms,
+            ("block_short_selections", ConfigValue::Bool(enabled)) => {
+                self.block_short_selections = enabled
+            }
+            ("absolute_sidecar_paths", ConfigValue::Bool(enabled)) => {
+                self.absolute_sidecar_paths = enabled
+            }
+            ("video_sidecars", ConfigValue::Bool(enabled)) => self.video_sidecars = enabled,
+            ("write_grace_secs", ConfigValue::Integer(secs)) => self.write_grace_secs = secs,
+            ("true_peak_meter", ConfigValue::Bool(enabled)) => self.true_peak_meter = enabled,
+            ("scope_gain", ConfigValue::Number(gain)) => self.scope_gain = gain,
+            ("scope_auto_gain", ConfigValue::Bool(enabled)) => self.scope_auto_gain = enabled,
+            ("project_statuses", ConfigValue::List(statuses)) => {
+                if !statuses.contains(&self.default_status) {
+                    return Err(format!(
+                        "Invalid value for {key}: the list must include the default_status \
+                         '{}' (change default_status first)",
+                        self.default_status
+                    )
+                    .into());
+                }
+                self.project_statuses = statuses
+            }
+            ("default_status", ConfigValue::Text(status)) => {
+                if !self.project_statuses.contains(&status) {
+                    return Err(format!(
+                        "Invalid value for {key}: '{status}' is not one of the project_statuses: {}",
+                        self.project_statuses.join(", ")
+                    )
+                    .into());
+                }
+                self.default_status = status
             }
-            _ => return Err(format!("Unknown configuration key: {key}").into()),
+            _ => return Err(format!("Configuration key {key} cannot be set").into()),
         }
         Ok(())
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use std::sync::Mutex;
@@ -386,6 +971,269 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_set_value_rejects_unknown_key_with_valid_keys() {
+        let mut config = Config::new();
+        let error = config.set_value("colour", "red").unwrap_err().to_string();
+        assert!(error.contains("Unknown configuration key: colour"));
+        for name in config_key_names() {
+            assert!(error.contains(name));
+        }
+    }
+
+    /// A key's value as it would be saved, or `None` when it isn't written
+    fn saved_value(config: &Config, name: &str) -> Option<toml::Value> {
+        let toml::Value::Table(fields) = toml::Value::try_from(config).unwrap() else {
+            panic!("config doesn't serialize to a table");
+        };
+        fields.get(name).cloned()
+    }
+
+    fn to_saved(value: ConfigValue) -> Option<toml::Value> {
+        Some(match value {
+            ConfigValue::Text(text) => toml::Value::String(text),
+            ConfigValue::Bool(flag) => toml::Value::Boolean(flag),
+            ConfigValue::Number(number) => toml::Value::Float(number.into()),
+            ConfigValue::Integer(number) => toml::Value::Integer(number.into()),
+            ConfigValue::Path(path) => toml::Value::String(path?),
+            ConfigValue::List(items) => {
+                toml::Value::Array(items.into_iter().map(toml::Value::String).collect())
+            }
+        })
+    }
+
+    #[test]
+    fn test_config_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+        let dir = temp_dir.path().to_string_lossy().to_string();
+
+        let defaults = Config::new();
+        // A config file without the key gets the same default
+        let loaded: Config = toml::from_str("default_artist = \"Someone\"").unwrap();
+
+        for config_key in CONFIG_KEYS {
+            let name = config_key.name;
+            let default = saved_value(&defaults, name);
+            if name != "default_artist" {
+                assert_eq!(saved_value(&loaded, name), default, "{name}");
+            }
+
+            // A value other than the default, and values that must be refused
+            let (value, invalid): (String, Vec<String>) = match config_key.kind {
+                ValueKind::Text => ("text".to_string(), vec![]),
+                ValueKind::Bool => {
+                    let flag = default == Some(toml::Value::Boolean(true));
+                    ((!flag).to_string(), vec!["maybe".to_string()])
+                }
+                ValueKind::Gain => (
+                    "0.5".to_string(),
+                    vec!["loud".into(), "2.5".into(), "-0.1".into()],
+                ),
+                ValueKind::Integer(min, max) => {
+                    let value = if default == Some(toml::Value::Integer(max.into())) {
+                        min
+                    } else {
+                        max
+                    };
+                    (
+                        value.to_string(),
+                        vec![
+                            (max + 1).to_string(),
+                            "-1".into(),
+                            "1.5".into(),
+                            "big".into(),
+                        ],
+                    )
+                }
+                ValueKind::Number(min, max) => (
+                    max.to_string(),
+                    vec![
+                        (min / 2.0).to_string(),
+                        (max * 2.0).to_string(),
+                        "loud".into(),
+                    ],
+                ),
+                ValueKind::Choice(choices) => {
+                    let choice = choices
+                        .iter()
+                        .find(|choice| default != Some(toml::Value::String(choice.to_string())))
+                        .unwrap();
+                    (choice.to_uppercase(), vec!["nonsense".to_string()])
+                }
+                ValueKind::OptionalPath => ("/var/log/zim.log".to_string(), vec![]),
+                ValueKind::OptionalDirectory => {
+                    (dir.clone(), vec![file.to_string_lossy().to_string()])
+                }
+                ValueKind::FolderList => ("live,logic".to_string(), vec!["live,../x".to_string()]),
+                ValueKind::WordList => (
+                    "active,released,demo".to_string(),
+                    vec!["".into(), "a,a".into(), "on hold".into()],
+                ),
+                ValueKind::ProjectStatus => {
+                    ("released".to_string(), vec!["unheard-of".to_string()])
+                }
+            };
+
+            let mut config = Config::new();
+            config.set_value(name, &value).unwrap();
+            let set = saved_value(&config, name);
+            assert_ne!(set, default, "{name}");
+            assert_eq!(
+                set,
+                to_saved(config_key.kind.parse(&value).unwrap()),
+                "{name}"
+            );
+            for invalid in invalid {
+                assert!(
+                    config.set_value(name, &invalid).is_err(),
+                    "{name} = {invalid}"
+                );
+                assert_eq!(saved_value(&config, name), set, "{name}");
+            }
+
+            // Keys that can be unset go back to their default
+            if matches!(
+                config_key.kind,
+                ValueKind::OptionalPath | ValueKind::OptionalDirectory
+            ) {
+                config.set_value(name, "").unwrap();
+                assert_eq!(saved_value(&config, name), None, "{name}");
+            }
+        }
+
+        let mut config = Config::new();
+        assert!(config.inherit_excerpt_tags());
+        config.set_value("excerpt_tags", "fresh").unwrap();
+        assert!(!config.inherit_excerpt_tags());
+    }
+
+    #[test]
+    fn test_value_kind_parse() {
+        assert_eq!(ValueKind::Bool.parse("TRUE"), Ok(ConfigValue::Bool(true)));
+        let error = ValueKind::Bool.parse("maybe").unwrap_err();
+        assert_eq!(error, "expected true or false, got 'maybe'");
+        assert!(ValueKind::Gain.parse("3").is_err());
+        assert_eq!(
+            ValueKind::Choice(TITLE_STYLES).parse("Spaces"),
+            Ok(ConfigValue::Text("spaces".to_string()))
+        );
+        assert_eq!(
+            ValueKind::OptionalPath.parse(""),
+            Ok(ConfigValue::Path(None))
+        );
+        assert_eq!(
+            ValueKind::FolderList.parse(" live, logic ,,"),
+            Ok(ConfigValue::List(vec![
+                "live".to_string(),
+                "logic".to_string()
+            ]))
+        );
+        assert_eq!(
+            ValueKind::Integer(128, 16384).describe(),
+            "a whole number from 128 to 16384"
+        );
+        assert_eq!(
+            ValueKind::Number(0.1, 32.0).describe(),
+            "a number from 0.1 to 32"
+        );
+    }
+
+    #[test]
+    fn test_parse_folder_names() {
+        assert_eq!(parse_folder_names(""), Ok(vec![]));
+        assert_eq!(
+            parse_folder_names("live,Logic Pro,reaper"),
+            Ok(vec![
+                "live".to_string(),
+                "Logic Pro".to_string(),
+                "reaper".to_string()
+            ])
+        );
+        for invalid in ["../live", "a/b", "a\\b", ".hidden", "..", "live,live"] {
+            assert!(parse_folder_names(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_set_root_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config::new();
+        assert!(config.root_dir.is_none());
+
+        let dir = temp_dir.path().to_string_lossy().to_string();
+        config.set_value("root_dir", &dir).unwrap();
+        assert_eq!(config.root_dir.as_deref(), Some(dir.as_str()));
+
+        // A directory that doesn't exist yet is allowed, a file is not
+        let missing = temp_dir.path().join("later").to_string_lossy().to_string();
+        config.set_value("root_dir", &missing).unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+        assert!(
+            config
+                .set_value("root_dir", &file.to_string_lossy())
+                .is_err()
+        );
+        assert_eq!(config.root_dir.as_deref(), Some(missing.as_str()));
+
+        config.set_value("root_dir", "").unwrap();
+        assert!(config.root_dir.is_none());
+    }
+
+    #[test]
+    fn test_skip_dirs_from_toml() {
+        assert!(Config::new().skip_dirs.is_empty());
+        let config: Config =
+            toml::from_str("skip_dirs = [\"Freeze Files\", \"Bounces-old\"]").unwrap();
+        assert_eq!(config.skip_dirs, ["Freeze Files", "Bounces-old"]);
+    }
+
+    #[test]
+    fn test_set_project_statuses() {
+        let mut config = Config::new();
+        assert_eq!(config.default_status, "active");
+        assert_eq!(
+            config.project_statuses,
+            ["active", "paused", "released", "archived"]
+        );
+
+        config
+            .set_value("project_statuses", "Demo, active, mixing,mastered")
+            .unwrap();
+        assert_eq!(
+            config.project_statuses,
+            ["demo", "active", "mixing", "mastered"]
+        );
+        config.set_value("default_status", "Demo").unwrap();
+        assert_eq!(config.default_status, "demo");
+
+        // Unknown defaults, and lists that would orphan the default, are refused
+        let error = config.set_value("default_status", "released").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value for default_status: 'released' is not one of the project_statuses: \
+             demo, active, mixing, mastered"
+        );
+        let error = config
+            .set_value("project_statuses", "active,released")
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("must include the default_status 'demo'")
+        );
+        assert!(config.set_value("project_statuses", "").is_err());
+        assert!(config.set_value("project_statuses", "a,a").is_err());
+        assert!(config.set_value("project_statuses", "on hold").is_err());
+        assert_eq!(config.default_status, "demo");
+
+        let config: Config = toml::from_str("default_artist = \"Someone\"").unwrap();
+        assert_eq!(config.default_status, "active");
+        assert_eq!(config.project_statuses.len(), 4);
+    }
+
     #[test]
     fn test_config_save_and_load() {
         let _guard = ENV_MUTEX.lock().unwrap();
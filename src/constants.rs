@@ -9,11 +9,38 @@ pub const SPINNER_CHARS: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "
 /// File extension for sidecar metadata files
 pub const SIDECAR_EXTENSION: &str = "md";
 
+/// Hidden directory holding sidecars when `sidecar_location` is `zim`. It
+/// mirrors the tree below the directory that contains it.
+pub const SIDECAR_DIR: &str = ".zim";
+
 /// Directories to skip during file system traversal
 pub const SKIP_DIRECTORIES: &[&str] = &["node_modules", ".git", "temp"];
 
 /// Supported audio file extensions
-pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "aiff", "mp3", "m4a"];
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac", "aif", "aiff", "mp3", "m4a"];
+
+/// Audio file extensions the player can decode, a subset of `AUDIO_EXTENSIONS`
+pub const PLAYABLE_EXTENSIONS: &[&str] = &["wav", "flac", "aif", "aiff"];
+
+/// Picture reference extensions `zim update` writes sidecars for when
+/// `video_sidecars` is set
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "m4v"];
 
 /// YAML frontmatter delimiter
 pub const YAML_DELIMITER: &str = "---\n";
+
+/// File holding project-level metadata at the root of each project
+pub const PROJECT_METADATA_FILE: &str = "README.md";
+
+/// Sidecar frontmatter field holding the audio duration in seconds
+pub const DURATION_FIELD: &str = "duration";
+
+/// Sidecar frontmatter field holding the playback gain in dB that brings the
+/// file to the replay gain reference loudness
+pub const REPLAY_GAIN_FIELD: &str = "replay_gain";
+
+/// Manifest of sidecar hashes written by `zim snapshot` at the project root
+pub const SNAPSHOT_FILE: &str = "zim-snapshot.yml";
+
+/// Files processed so far by an unfinished `zim update`, at the project root
+pub const UPDATE_CHECKPOINT_FILE: &str = ".zim-update-checkpoint";
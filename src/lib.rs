@@ -6,3 +6,8 @@ pub mod zimignore;
 
 #[cfg(feature = "player")]
 pub mod media;
+
+// Shared with the binary's tests, some of which use fixtures the library's don't
+#[cfg(test)]
+#[allow(dead_code)]
+mod test_support;
@@ -19,22 +19,45 @@ use clap::{CommandFactory, Parser, Subcommand, builder::PossibleValuesParser};
 use clap_complete::{Generator, Shell, generate};
 use std::error::Error;
 use std::io;
+use zim_studio::utils::duration::parse_duration;
+use zim_studio::utils::progress::Verbosity;
 
 mod cli;
 mod config;
 mod media;
 mod project;
+mod tag_vocabulary;
 mod templates;
 mod wav_metadata;
 
 #[cfg(feature = "player")]
 mod player;
 
+#[cfg(test)]
+mod test_support;
+
 #[derive(Parser)]
 #[command(name = "zim")]
 #[command(about = "Terminal-based audio project scaffold and metadata system")]
 #[command(version)]
 struct Cli {
+    /// Suppress progress output, printing only warnings and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// Print without colors (also set by the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print help including hidden options, such as the player's test signals
+    #[arg(long, global = true)]
+    help_all: bool,
+    /// Descend into symlinked directories when scanning, overriding the
+    /// `follow_symlinks` setting
+    #[arg(long, global = true, conflicts_with = "no_follow_symlinks")]
+    follow_symlinks: bool,
+    /// Skip symlinked directories when scanning, overriding the
+    /// `follow_symlinks` setting
+    #[arg(long, global = true)]
+    no_follow_symlinks: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -70,6 +93,12 @@ enum Commands {
         /// Interactively customize the .zimignore content
         #[arg(short, long)]
         interactive: bool,
+        /// DAW folders to create under project/ (comma-separated, overrides daw_folders)
+        #[arg(long, value_name = "DAW1,DAW2", value_delimiter = ',')]
+        daw: Option<Vec<String>>,
+        /// List the DAW folders new projects get, then exit
+        #[arg(long, conflicts_with_all = ["name", "daw"])]
+        list_daw: bool,
     },
     /// Update sidecar metadata files for media assets
     Update {
@@ -79,12 +108,53 @@ enum Commands {
         /// Extra tags to apply to newly created sidecar files (repeatable, deduplicated)
         #[arg(short = 't', long = "tag", action = clap::ArgAction::Append)]
         tags: Vec<String>,
+        /// Only process files matching this glob (repeatable, relative to the project root)
+        #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append)]
+        include: Vec<String>,
+        /// Skip files matching this glob (repeatable, relative to the project root)
+        #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append)]
+        exclude: Vec<String>,
+        /// Save embedded cover art next to each audio file and list it in the new sidecar
+        #[arg(long)]
+        extract_art: bool,
+        /// Create or refresh the sidecar for this one audio file without scanning the project
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["include", "exclude"])]
+        file: Option<String>,
+        /// Recompute the audio MD5 stored in tagged WAVs and report files whose audio changed
+        #[arg(long, conflicts_with_all = ["file", "tags", "extract_art"])]
+        refresh_hash: bool,
+        /// Only process audio modified within this long, e.g. 30m, 24h or 2d
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = parse_duration,
+            conflicts_with_all = ["file", "refresh_hash"]
+        )]
+        since: Option<std::time::Duration>,
+        /// Measure each file's loudness and store the playback gain that levels it in its sidecar
+        #[arg(long, conflicts_with_all = ["file", "tags", "extract_art", "refresh_hash", "since"])]
+        replaygain: bool,
+        /// Continue an interrupted update, skipping the files it already processed
+        #[arg(long, conflicts_with_all = ["file", "refresh_hash", "replaygain"])]
+        resume: bool,
+        /// Wait for files still being written (see write_grace_secs) and process them once they settle
+        #[arg(long, conflicts_with_all = ["file", "refresh_hash", "replaygain"])]
+        watch: bool,
     },
     /// Validate YAML frontmatter in all sidecar files
     Lint {
         /// Path to project (defaults to current directory)
         #[arg(default_value = ".")]
         path: String,
+        /// Only process files matching this glob (repeatable, relative to the project root)
+        #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append)]
+        include: Vec<String>,
+        /// Skip files matching this glob (repeatable, relative to the project root)
+        #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append)]
+        exclude: Vec<String>,
+        /// Rewrite mechanical frontmatter problems (tags, art, duration) before linting
+        #[arg(long)]
+        fix: bool,
     },
     /// Generate an index.yml file with consolidated track metadata
     Index {
@@ -92,6 +162,119 @@ enum Commands {
         #[arg(default_value = ".")]
         path: String,
     },
+    /// Record sidecar hashes, or check which sidecars changed since the last snapshot
+    Snapshot {
+        /// Path to project (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Report sidecars added, removed or modified since the snapshot
+        #[arg(long)]
+        check: bool,
+    },
+    /// Zip the project's sidecars, README and .zimignore for backup or sharing
+    Archive {
+        /// Path to project (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Zip file to write
+        #[arg(short, long, value_name = "FILE")]
+        output: String,
+        /// Also include the index.yml written by `zim index`
+        #[arg(long)]
+        include_index: bool,
+        /// Also include art files referenced by sidecars
+        #[arg(long)]
+        include_art: bool,
+    },
+    /// Check tagged WAVs against the audio MD5 stored in their metadata
+    Verify {
+        /// WAV file or directory to check (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+    },
+    /// Show the project tree with each audio file's duration and tag count
+    Tree {
+        /// Path to project (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Hide files that aren't audio and folders without any audio
+        #[arg(long)]
+        audio_only: bool,
+    },
+    /// Count how often each tag is used across the sidecars, most used first
+    Tags {
+        /// Path to search (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Only show tags starting with this text (case-insensitive)
+        #[arg(long, value_name = "TEXT")]
+        prefix: Option<String>,
+        /// Print the counts as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the source files that saved excerpts were cut from, flagging missing ones
+    Sources {
+        /// Path to search (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Print the sources as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a list of regions from an audio file as separate WAV files
+    #[cfg(feature = "player")]
+    Chop {
+        /// Source audio file (WAV or FLAC)
+        file: String,
+        /// Regions to export: lines of `in out [name]`, or a .cue sheet
+        #[arg(short, long, value_name = "FILE")]
+        regions: String,
+        /// Directory to write the excerpts to (created if missing)
+        #[arg(short, long, value_name = "DIR", default_value = ".")]
+        output: String,
+    },
+    /// Split a multichannel WAV into mono files, or downmix it to mono
+    #[cfg(feature = "player")]
+    Convert {
+        /// Source WAV file
+        file: String,
+        /// `split` writes one file per channel (foo_L.wav, foo_R.wav);
+        /// `mono` writes a single downmix (foo_mono.wav)
+        #[arg(long, value_parser = PossibleValuesParser::new(cli::convert::CHANNEL_MODES))]
+        channels: String,
+        /// Directory to write to (defaults to the source's directory)
+        #[arg(short, long, value_name = "DIR")]
+        output: Option<String>,
+    },
+    /// Measure integrated loudness and true peak across a folder and flag outliers
+    #[cfg(feature = "player")]
+    Loudness {
+        /// Audio file or directory to measure (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+        /// Platform target in LUFS to compare against (e.g. -14) instead of the median
+        #[arg(long, value_name = "LUFS", allow_hyphen_values = true)]
+        target: Option<f64>,
+        /// How far from the target or median a file may be, in LU
+        #[arg(long, value_name = "LU", default_value_t = 1.0)]
+        tolerance: f64,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move sidecars from beside their audio into a hidden .zim/ directory
+    MigrateSidecars {
+        /// Directory to migrate (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+    },
+    /// Rewrite sidecar file/path fields after moving or renaming a project
+    Reindex {
+        /// Path to project (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: String,
+    },
     /// Sync technical metadata in sidecar files with current audio file properties
     Sync {
         /// Path to project (defaults to current directory)
@@ -110,21 +293,93 @@ enum Commands {
     },
     /// Play audio files with integrated player (playlist or mixing mode)
     Play {
-        /// Audio file paths (playlist: unlimited files, mixing: up to 3 with --gains)
+        /// Audio file paths (playlist: unlimited files, mixing: up to 3 with --gains).
+        /// Use `-` to read newline-separated paths from stdin.
         files: Vec<String>,
-        /// Gain levels for each file (comma-separated, e.g., "0.8,1.2,0.6")
+        /// Read newline-separated file paths from stdin (same as passing `-`)
+        #[arg(long)]
+        stdin: bool,
+        /// Gain levels for each file (e.g., "0.8,1.2,0.6" or "0.8 1.2 0.6")
         #[arg(
             short,
             long,
             value_delimiter = ',',
             value_name = "GAIN1,GAIN2,GAIN3",
             help = "Gain levels for each file (0.0-2.0 range)",
-            long_help = "Comma-separated gain values for each file (0.0-2.0 range).\nExample: --gains 0.8,1.2,0.6\nDefaults to 1.0 for all files if not specified."
+            long_help = "Gain values for each file, in the same order (0.0-2.0 range).\nSeparate them with commas or spaces (quote the list when using spaces).\nExample: --gains 0.8,1.2,0.6 or --gains \"0.8 1.2 0.6\"\nDefaults to 1.0 for all files if not specified."
+        )]
+        gains: Option<Vec<String>>,
+        /// Master output gain applied to everything the player outputs
+        #[arg(
+            long,
+            value_name = "GAIN",
+            help = "Master output gain (0.0-2.0, default from config)"
+        )]
+        gain: Option<f32>,
+        /// Reference track to compare the mix against
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Reference track to A/B against the files ([r] switches)",
+            long_help = "A reference track (e.g. a commercial master) loaded alongside the files.\nIt is not mixed in: press [r] in the player to switch between the mix and the reference."
+        )]
+        reference: Option<String>,
+        /// Gain applied to the reference track
+        #[arg(
+            long,
+            value_name = "GAIN",
+            default_value_t = 1.0,
+            help = "Gain for the reference track (0.0-2.0)"
         )]
-        gains: Option<Vec<f32>>,
+        reference_gain: f32,
+        /// Where to start playing
+        #[arg(
+            long,
+            value_name = "TIME",
+            help = "Start playback at this time (e.g. 1:30, 90 or 1:02:03.5)",
+            long_help = "Seek to this time as soon as the file loads, as mm:ss, seconds or h:mm:ss,\nwith optional fractions (e.g. 1:30.250). Times past the end start at the end.\nIn playlist mode it applies to the first track."
+        )]
+        start: Option<String>,
+        /// Where to stop playing
+        #[arg(
+            long,
+            value_name = "TIME",
+            help = "Stop playback at this time, auditioning --start to --end",
+            long_help = "Mark --start (or the beginning) to this time and play just that region,\nstopping at the end of it. Add --loop to loop it instead."
+        )]
+        end: Option<String>,
+        /// Loop the --start/--end region, or the whole file
+        #[arg(
+            long = "loop",
+            help = "Loop the --start/--end region, or the whole file without them",
+            long_help = "Loop the --start/--end region instead of playing it once. Without
+--start or --end the whole file loops until stopped. In playlist mode
+it applies to the first track."
+        )]
+        looping: bool,
         /// Start interactive mode for browsing and playing
         #[arg(short, long)]
         interactive: bool,
+        /// Play a sine at this frequency instead of a file
+        #[arg(
+            long,
+            value_name = "HZ",
+            hide = true,
+            conflicts_with_all = ["files", "stdin", "gains", "reference", "interactive"],
+            help = "Play a test tone at this frequency instead of a file (1-20000 Hz)"
+        )]
+        tone: Option<f32>,
+        /// Play a synthesized test signal instead of a file
+        #[arg(
+            long,
+            value_name = "SIGNAL",
+            hide = true,
+            value_parser = PossibleValuesParser::new(["sine", "square", "white", "pink"]),
+            conflicts_with_all = ["files", "stdin", "gains", "reference", "interactive"],
+            help = "Play a test signal instead of a file",
+            long_help = "Play a synthesized signal at -12 dBFS instead of a file, to check the output\nand calibrate the meters. Tones default to 1 kHz; set another with --tone.\nIt plays for a minute; add --loop to keep it going."
+        )]
+        test: Option<String>,
     },
 }
 
@@ -141,6 +396,17 @@ enum AddAction {
         #[arg(short = 'r', long = "recursive")]
         recursive: bool,
     },
+    /// Stamp a template sidecar across takes, filling {{take}} from each filename
+    Stamp {
+        /// Markdown file with YAML frontmatter to merge into each sidecar
+        template: String,
+        /// Audio files (or their sidecars) to stamp
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// List the files and take numbers that would be stamped without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -188,13 +454,43 @@ enum ConfigAction {
     /// Set a configuration value
     Set {
         /// Configuration key
-        #[arg(value_parser = PossibleValuesParser::new(["root_dir", "default_artist", "normalize_project_names"]))]
+        #[arg(value_parser = PossibleValuesParser::new(config::config_key_names()))]
         key: String,
         /// Configuration value
         value: String,
     },
     /// Edit configuration file in your editor
     Edit,
+    /// Print the path of the configuration file
+    Path,
+    /// Print the configuration directory
+    Dir,
+}
+
+/// The parsed arguments when `--help-all` was given. Parsing ignores errors
+/// so the flag works without a subcommand or its required arguments.
+fn help_all_matches<I, T>(args: I) -> Option<clap::ArgMatches>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let matches = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+        .ok()?;
+    matches.get_flag("help_all").then_some(matches)
+}
+
+/// Print help with the hidden options shown, for `subcommand` or for `zim`
+/// itself
+fn print_help_all(subcommand: Option<&str>) -> io::Result<()> {
+    let reveal = |cmd: clap::Command| cmd.mut_args(|arg| arg.hide(false));
+    let mut cmd = reveal(Cli::command()).mut_subcommands(reveal);
+    cmd.build();
+    match subcommand.and_then(|name| cmd.find_subcommand_mut(name)) {
+        Some(subcommand) => subcommand.print_long_help(),
+        None => cmd.print_long_help(),
+    }
 }
 
 fn print_completions<G: Generator>(generator: G, cmd: &mut clap::Command) {
@@ -207,7 +503,17 @@ fn print_completions<G: Generator>(generator: G, cmd: &mut clap::Command) {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    if let Some(matches) = help_all_matches(std::env::args_os()) {
+        print_help_all(matches.subcommand_name())?;
+        return Ok(());
+    }
+
     let cli = Cli::parse();
+    zim_studio::utils::color::init(cli.no_color);
+    zim_studio::utils::parallel_scan::set_follow_symlinks_flag(
+        (cli.follow_symlinks || cli.no_follow_symlinks).then_some(cli.follow_symlinks),
+    );
+    let verbosity = Verbosity::from_quiet(cli.quiet);
 
     match cli.command {
         Commands::Init => {
@@ -223,6 +529,12 @@ fn main() -> Result<(), Box<dyn Error>> {
             ConfigAction::Edit => {
                 cli::config::handle_config_edit()?;
             }
+            ConfigAction::Path => {
+                cli::config::handle_config_path()?;
+            }
+            ConfigAction::Dir => {
+                cli::config::handle_config_dir()?;
+            }
         },
         Commands::Completions { shell } => {
             let mut cmd = Cli::command();
@@ -234,26 +546,129 @@ fn main() -> Result<(), Box<dyn Error>> {
             zimignore_template,
             no_zimignore,
             interactive,
+            daw,
+            list_daw,
         } => {
-            cli::new::handle_new(
-                name.as_deref(),
-                path.as_deref(),
-                zimignore_template.as_deref(),
-                no_zimignore,
-                interactive,
-            )?;
+            if list_daw {
+                cli::new::handle_list_daw()?;
+            } else {
+                cli::new::handle_new(
+                    name.as_deref(),
+                    path.as_deref(),
+                    zimignore_template.as_deref(),
+                    no_zimignore,
+                    interactive,
+                    daw,
+                )?;
+            }
         }
-        Commands::Update { path, tags } => {
-            cli::update::handle_update(&path, &tags)?;
+        Commands::Update {
+            path,
+            tags,
+            include,
+            exclude,
+            extract_art,
+            file,
+            refresh_hash,
+            since,
+            replaygain,
+            resume,
+            watch,
+        } => {
+            if replaygain {
+                #[cfg(feature = "player")]
+                cli::update::handle_replay_gain(&path, &include, &exclude, verbosity)?;
+                #[cfg(not(feature = "player"))]
+                return Err("--replaygain needs the 'player' feature to decode audio".into());
+            } else if refresh_hash {
+                cli::update::handle_refresh_hash(&path, &include, &exclude, verbosity)?;
+            } else if let Some(file) = file {
+                cli::update::handle_update_file(&file, &tags, extract_art, verbosity)?;
+            } else {
+                cli::update::handle_update(
+                    &path,
+                    &tags,
+                    &include,
+                    &exclude,
+                    extract_art,
+                    since,
+                    resume,
+                    watch,
+                    verbosity,
+                )?;
+            }
         }
-        Commands::Lint { path } => {
-            cli::lint::handle_lint(&path)?;
+        Commands::Lint {
+            path,
+            include,
+            exclude,
+            fix,
+        } => {
+            cli::lint::handle_lint(&path, &include, &exclude, fix, verbosity)?;
         }
         Commands::Index { path } => {
-            cli::index::handle_index(&path)?;
+            cli::index::handle_index(&path, verbosity)?;
+        }
+        Commands::Snapshot { path, check } => {
+            cli::snapshot::handle_snapshot(&path, check, verbosity)?;
+        }
+        Commands::Archive {
+            path,
+            output,
+            include_index,
+            include_art,
+        } => {
+            let options = cli::archive::ArchiveOptions {
+                include_index,
+                include_art,
+            };
+            cli::archive::handle_archive(&path, &output, options, verbosity)?;
+        }
+        Commands::Verify { path } => {
+            cli::verify::handle_verify(&path, verbosity)?;
+        }
+        Commands::Tree { path, audio_only } => {
+            cli::tree::handle_tree(&path, audio_only)?;
+        }
+        Commands::Tags { path, prefix, json } => {
+            cli::tags::handle_tags(&path, prefix.as_deref(), json, verbosity)?;
+        }
+        Commands::Sources { path, json } => {
+            cli::sources::handle_sources(&path, json, verbosity)?;
+        }
+        #[cfg(feature = "player")]
+        Commands::Chop {
+            file,
+            regions,
+            output,
+        } => {
+            cli::chop::handle_chop(&file, &regions, &output, verbosity)?;
+        }
+        #[cfg(feature = "player")]
+        Commands::Convert {
+            file,
+            channels,
+            output,
+        } => {
+            cli::convert::handle_convert(&file, &channels, output.as_deref(), verbosity)?;
+        }
+        #[cfg(feature = "player")]
+        Commands::Loudness {
+            path,
+            target,
+            tolerance,
+            json,
+        } => {
+            cli::loudness::handle_loudness(&path, target, tolerance, json, verbosity)?;
+        }
+        Commands::MigrateSidecars { path } => {
+            cli::migrate_sidecars::handle_migrate_sidecars(&path, verbosity)?;
+        }
+        Commands::Reindex { path } => {
+            cli::reindex::handle_reindex(&path, verbosity)?;
         }
         Commands::Sync { path } => {
-            cli::sync::handle_sync(&path)?;
+            cli::sync::handle_sync(&path, verbosity)?;
         }
         Commands::Add { action } => match action {
             AddAction::Tag {
@@ -261,7 +676,14 @@ fn main() -> Result<(), Box<dyn Error>> {
                 tags,
                 recursive,
             } => {
-                cli::add::handle_add_tag(&path, &tags, recursive)?;
+                cli::add::handle_add_tag(&path, &tags, recursive, verbosity)?;
+            }
+            AddAction::Stamp {
+                template,
+                files,
+                dry_run,
+            } => {
+                cli::add::handle_add_stamp(&template, &files, dry_run)?;
             }
         },
         Commands::Tag { action } => match action {
@@ -288,12 +710,83 @@ fn main() -> Result<(), Box<dyn Error>> {
         },
         Commands::Play {
             files,
+            stdin,
             gains,
+            gain,
+            reference,
+            reference_gain,
+            start,
+            end,
+            looping,
             interactive,
+            tone,
+            test,
         } => {
-            cli::play::handle_play(files, gains, interactive)?;
+            let reference = reference.map(|path| (path, reference_gain));
+            let files = if tone.is_some() || test.is_some() {
+                vec![cli::play::test_signal_file(test.as_deref(), tone)?]
+            } else if stdin || files.iter().any(|f| f == "-") {
+                cli::play::files_with_stdin(files)?
+            } else {
+                files
+            };
+            let region = cli::play::PlayRegion {
+                start,
+                end,
+                looping,
+            };
+            cli::play::handle_play(files, gains, gain, reference, region, interactive)?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_new_with_daw_list() {
+        let cli = Cli::try_parse_from(["zim", "new", "x", "--daw", "logic,renoise"]).unwrap();
+        match cli.command {
+            Commands::New { daw, .. } => {
+                assert_eq!(daw, Some(vec!["logic".to_string(), "renoise".to_string()]));
+            }
+            _ => panic!("expected the new command"),
+        }
+    }
+
+    #[test]
+    fn test_follow_symlinks_flags() {
+        let cli = Cli::try_parse_from(["zim", "update", "--no-follow-symlinks"]).unwrap();
+        assert!(!cli.follow_symlinks);
+        assert!(cli.no_follow_symlinks);
+
+        let both = ["zim", "update", "--follow-symlinks", "--no-follow-symlinks"];
+        assert!(Cli::try_parse_from(both).is_err());
+    }
+
+    #[test]
+    fn test_help_all_flag() {
+        let cli = Cli::try_parse_from(["zim", "update", "--help-all"]).unwrap();
+        assert!(cli.help_all);
+
+        // Works without a subcommand, or one missing its required arguments
+        let matches = help_all_matches(["zim", "--help-all"]).unwrap();
+        assert_eq!(matches.subcommand_name(), None);
+        let matches = help_all_matches(["zim", "play", "--help-all"]).unwrap();
+        assert_eq!(matches.subcommand_name(), Some("play"));
+        let matches = help_all_matches(["zim", "add", "tag", "--help-all"]).unwrap();
+        assert_eq!(matches.subcommand_name(), Some("add"));
+
+        assert!(help_all_matches(["zim", "play"]).is_none());
+        // After `--` it's a file name, not the flag
+        assert!(help_all_matches(["zim", "play", "--", "--help-all"]).is_none());
+    }
+}
@@ -0,0 +1,522 @@
+//! Embedded cover art: FLAC PICTURE blocks, ID3v2 APIC/PIC frames in MP3s,
+//! and the iTunes `covr` atom in M4A files.
+//!
+//! Only the metadata region of each file is read, so large files are cheap to
+//! scan. When a file carries several pictures the front cover is preferred.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// ID3v2/FLAC picture type for the front cover
+const FRONT_COVER: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedPicture {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl EmbeddedPicture {
+    /// File extension for the picture, from its MIME type or magic bytes
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type.to_lowercase().as_str() {
+            "image/jpeg" | "image/jpg" | "jpg" => return "jpg",
+            "image/png" | "png" => return "png",
+            "image/gif" => return "gif",
+            "image/bmp" => return "bmp",
+            "image/webp" => return "webp",
+            _ => {}
+        }
+        match self.data.as_slice() {
+            [0xFF, 0xD8, ..] => "jpg",
+            [0x89, b'P', b'N', b'G', ..] => "png",
+            [b'G', b'I', b'F', ..] => "gif",
+            [b'B', b'M', ..] => "bmp",
+            [
+                b'R',
+                b'I',
+                b'F',
+                b'F',
+                _,
+                _,
+                _,
+                _,
+                b'W',
+                b'E',
+                b'B',
+                b'P',
+                ..,
+            ] => "webp",
+            _ => "bin",
+        }
+    }
+}
+
+/// Read the embedded cover picture, if the format supports one and the file has it
+pub fn read_embedded_picture(
+    path: &Path,
+) -> Result<Option<EmbeddedPicture>, Box<dyn std::error::Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    match extension.as_deref() {
+        Some("flac") => read_flac_picture(path),
+        Some("mp3") => read_id3_picture(path),
+        Some("m4a") => read_mp4_cover(path),
+        _ => Ok(None),
+    }
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Keep the first picture found, unless a later one is the front cover
+fn keep_preferred(best: &mut Option<(u32, EmbeddedPicture)>, candidate: (u32, EmbeddedPicture)) {
+    let replace = match best {
+        None => true,
+        Some((picture_type, _)) => *picture_type != FRONT_COVER && candidate.0 == FRONT_COVER,
+    };
+    if replace {
+        *best = Some(candidate);
+    }
+}
+
+fn read_flac_picture(path: &Path) -> Result<Option<EmbeddedPicture>, Box<dyn std::error::Error>> {
+    let mut file = File::open(path)?;
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != b"fLaC" {
+        return Err("Not a valid FLAC file".into());
+    }
+
+    let mut best: Option<(u32, EmbeddedPicture)> = None;
+    loop {
+        let mut header = [0u8; 4];
+        file.read_exact(&mut header)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+
+        // Block type 6 is PICTURE; everything else is skipped
+        if block_type == 6 {
+            let mut block = vec![0u8; length];
+            file.read_exact(&mut block)?;
+            if let Some(candidate) = parse_flac_picture_block(&block) {
+                keep_preferred(&mut best, candidate);
+            }
+        } else {
+            file.seek(SeekFrom::Current(length as i64))?;
+        }
+
+        if is_last {
+            break;
+        }
+    }
+
+    Ok(best.map(|(_, picture)| picture))
+}
+
+/// Parse a METADATA_BLOCK_PICTURE body into its picture type and image
+fn parse_flac_picture_block(block: &[u8]) -> Option<(u32, EmbeddedPicture)> {
+    let picture_type = be_u32(block, 0)?;
+    let mime_length = be_u32(block, 4)? as usize;
+    let mime_type = String::from_utf8_lossy(block.get(8..8 + mime_length)?).to_string();
+    let mut offset = 8 + mime_length;
+    let description_length = be_u32(block, offset)? as usize;
+    // Skip the description, then width, height, colour depth and palette size
+    offset += 4 + description_length + 16;
+    let data_length = be_u32(block, offset)? as usize;
+    let data = block.get(offset + 4..offset + 4 + data_length)?.to_vec();
+    Some((picture_type, EmbeddedPicture { mime_type, data }))
+}
+
+fn read_id3_picture(path: &Path) -> Result<Option<EmbeddedPicture>, Box<dyn std::error::Error>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; 10];
+    if file.read_exact(&mut header).is_err() || &header[0..3] != b"ID3" {
+        return Ok(None);
+    }
+
+    // A corrupt size can't ask for more than the file holds
+    let remaining = file.metadata()?.len().saturating_sub(header.len() as u64);
+    let size = syncsafe(&header[6..10]).min(remaining as usize);
+    let mut tag = vec![0u8; size];
+    file.read_exact(&mut tag)?;
+    Ok(parse_id3_frames(header[3], header[5], &tag))
+}
+
+/// Decode a 28-bit "syncsafe" integer (7 bits per byte)
+fn syncsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize)
+}
+
+/// Find the best picture frame in the body of an ID3v2 tag
+fn parse_id3_frames(version: u8, flags: u8, tag: &[u8]) -> Option<EmbeddedPicture> {
+    let mut offset = 0;
+
+    // Skip the extended header; its size field differs between 2.3 and 2.4
+    if flags & 0x40 != 0 && version >= 3 {
+        offset = match version {
+            3 => be_u32(tag, 0)? as usize + 4,
+            _ => syncsafe(tag.get(0..4)?),
+        };
+    }
+
+    let (id_length, header_length) = if version == 2 { (3, 6) } else { (4, 10) };
+    let mut best: Option<(u32, EmbeddedPicture)> = None;
+
+    while offset + header_length <= tag.len() {
+        let id = &tag[offset..offset + id_length];
+        if id[0] == 0 {
+            break; // Padding
+        }
+        let size = match version {
+            2 => {
+                u32::from_be_bytes([0, tag[offset + 3], tag[offset + 4], tag[offset + 5]]) as usize
+            }
+            3 => be_u32(tag, offset + 4)? as usize,
+            _ => syncsafe(&tag[offset + 4..offset + 8]),
+        };
+        let body = tag.get(offset + header_length..offset + header_length + size)?;
+
+        let picture = match id {
+            b"APIC" => parse_apic(body),
+            b"PIC" => parse_pic(body),
+            _ => None,
+        };
+        if let Some(candidate) = picture {
+            keep_preferred(&mut best, candidate);
+        }
+
+        offset += header_length + size;
+    }
+
+    best.map(|(_, picture)| picture)
+}
+
+/// Offset just past a description string terminated for the given text encoding
+fn skip_terminated(body: &[u8], start: usize, encoding: u8) -> Option<usize> {
+    if encoding == 1 || encoding == 2 {
+        // UTF-16 strings end with an aligned double null
+        (start..body.len().saturating_sub(1))
+            .step_by(2)
+            .find(|&i| body[i] == 0 && body[i + 1] == 0)
+            .map(|i| i + 2)
+    } else {
+        body.get(start..)?
+            .iter()
+            .position(|&b| b == 0)
+            .map(|i| start + i + 1)
+    }
+}
+
+/// ID3v2.3/2.4 APIC: encoding, MIME type, picture type, description, data
+fn parse_apic(body: &[u8]) -> Option<(u32, EmbeddedPicture)> {
+    let encoding = *body.first()?;
+    let mime_end = 1 + body.get(1..)?.iter().position(|&b| b == 0)?;
+    let mime_type = String::from_utf8_lossy(&body[1..mime_end]).to_string();
+    let picture_type = *body.get(mime_end + 1)? as u32;
+    let data_start = skip_terminated(body, mime_end + 2, encoding)?;
+    Some((
+        picture_type,
+        EmbeddedPicture {
+            mime_type,
+            data: body.get(data_start..)?.to_vec(),
+        },
+    ))
+}
+
+/// ID3v2.2 PIC: encoding, three-letter image format, picture type, description, data
+fn parse_pic(body: &[u8]) -> Option<(u32, EmbeddedPicture)> {
+    let encoding = *body.first()?;
+    let format = String::from_utf8_lossy(body.get(1..4)?).to_string();
+    let picture_type = *body.get(4)? as u32;
+    let data_start = skip_terminated(body, 5, encoding)?;
+    Some((
+        picture_type,
+        EmbeddedPicture {
+            mime_type: format,
+            data: body.get(data_start..)?.to_vec(),
+        },
+    ))
+}
+
+fn read_mp4_cover(path: &Path) -> Result<Option<EmbeddedPicture>, Box<dyn std::error::Error>> {
+    let mut file = File::open(path)?;
+    let file_length = file.metadata()?.len();
+
+    // The moov atom may come before or after the media data, so walk the
+    // top-level atoms until it turns up
+    let mut position = 0u64;
+    while position.saturating_add(8) <= file_length {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut header_length = 8;
+        if size == 1 {
+            let mut extended = [0u8; 8];
+            file.read_exact(&mut extended)?;
+            size = u64::from_be_bytes(extended);
+            header_length = 16;
+        } else if size == 0 {
+            size = file_length - position;
+        }
+        if size < header_length {
+            return Err("Invalid MP4 atom size".into());
+        }
+
+        if &header[4..8] == b"moov" {
+            // The size comes from the file, so never allocate past its end
+            if size > file_length - position {
+                return Err("MP4 moov atom runs past the end of the file".into());
+            }
+            let mut moov = vec![0u8; (size - header_length) as usize];
+            file.read_exact(&mut moov)?;
+            return Ok(parse_mp4_cover(&moov));
+        }
+        match position.checked_add(size) {
+            Some(next) => position = next,
+            None => break,
+        }
+    }
+
+    Ok(None)
+}
+
+/// Find a child atom by type, returning its body
+fn find_atom<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 0;
+    while offset + 8 <= data.len() {
+        let size = be_u32(data, offset)? as usize;
+        if size < 8 {
+            return None;
+        }
+        if &data[offset + 4..offset + 8] == kind {
+            return data.get(offset + 8..offset + size);
+        }
+        offset += size;
+    }
+    None
+}
+
+/// moov/udta/meta/ilst/covr/data, where meta is a full box with a 4-byte
+/// version/flags prefix and data starts with a type indicator and locale
+fn parse_mp4_cover(moov: &[u8]) -> Option<EmbeddedPicture> {
+    let udta = find_atom(moov, b"udta")?;
+    let meta = find_atom(udta, b"meta")?;
+    let ilst = find_atom(meta.get(4..)?, b"ilst")?;
+    let covr = find_atom(ilst, b"covr")?;
+    let data = find_atom(covr, b"data")?;
+
+    let mime_type = match be_u32(data, 0)? & 0x00FF_FFFF {
+        13 => "image/jpeg",
+        14 => "image/png",
+        27 => "image/bmp",
+        _ => "",
+    };
+    Some(EmbeddedPicture {
+        mime_type: mime_type.to_string(),
+        data: data.get(8..)?.to_vec(),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 4, 5, 6];
+
+    pub(crate) fn flac_picture_block(
+        picture_type: u32,
+        mime: &str,
+        data: &[u8],
+        last: bool,
+    ) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend(picture_type.to_be_bytes());
+        body.extend((mime.len() as u32).to_be_bytes());
+        body.extend(mime.as_bytes());
+        body.extend(4u32.to_be_bytes());
+        body.extend(b"desc");
+        body.extend([0u8; 16]);
+        body.extend((data.len() as u32).to_be_bytes());
+        body.extend(data);
+
+        let mut block = vec![if last { 0x86 } else { 0x06 }];
+        block.extend(&(body.len() as u32).to_be_bytes()[1..]);
+        block.extend(body);
+        block
+    }
+
+    fn write_fixture(name: &str, bytes: &[u8]) -> (TempDir, std::path::PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(name);
+        fs::write(&path, bytes).unwrap();
+        (temp_dir, path)
+    }
+
+    #[test]
+    fn test_flac_prefers_front_cover() {
+        let mut flac = b"fLaC".to_vec();
+        // STREAMINFO (contents irrelevant here)
+        flac.extend([0x00, 0, 0, 34]);
+        flac.extend([0u8; 34]);
+        flac.extend(flac_picture_block(4, "image/png", PNG, false));
+        flac.extend(flac_picture_block(3, "image/jpeg", JPEG, true));
+        flac.extend([0xFF, 0xF8]); // Start of audio frames
+
+        let (_dir, path) = write_fixture("song.flac", &flac);
+        let picture = read_embedded_picture(&path).unwrap().unwrap();
+        assert_eq!(picture.data, JPEG);
+        assert_eq!(picture.extension(), "jpg");
+    }
+
+    #[test]
+    fn test_flac_without_picture() {
+        let mut flac = b"fLaC".to_vec();
+        flac.extend([0x80, 0, 0, 34]);
+        flac.extend([0u8; 34]);
+
+        let (_dir, path) = write_fixture("song.flac", &flac);
+        assert_eq!(read_embedded_picture(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_mp3_apic_frame() {
+        let mut apic = vec![3]; // UTF-8
+        apic.extend(b"image/png\0");
+        apic.push(3);
+        apic.extend(b"cover\0");
+        apic.extend(PNG);
+
+        let mut tag = b"TIT2".to_vec();
+        tag.extend(3u32.to_be_bytes());
+        tag.extend([0, 0, 0, b'h', b'i']);
+        tag.extend(b"APIC");
+        tag.extend((apic.len() as u32).to_be_bytes());
+        tag.extend([0, 0]);
+        tag.extend(apic);
+        tag.extend([0u8; 16]); // Padding
+
+        let mut mp3 = b"ID3\x03\x00\x00".to_vec();
+        let size = tag.len();
+        mp3.extend([
+            (size >> 21) as u8 & 0x7F,
+            (size >> 14) as u8 & 0x7F,
+            (size >> 7) as u8 & 0x7F,
+            size as u8 & 0x7F,
+        ]);
+        mp3.extend(tag);
+
+        let (_dir, path) = write_fixture("song.mp3", &mp3);
+        let picture = read_embedded_picture(&path).unwrap().unwrap();
+        assert_eq!(picture.mime_type, "image/png");
+        assert_eq!(picture.data, PNG);
+    }
+
+    #[test]
+    fn test_apic_utf16_description() {
+        let mut apic = vec![1];
+        apic.extend(b"image/jpeg\0");
+        apic.push(3);
+        apic.extend([0xFF, 0xFE, b'a', 0, 0, 0]);
+        apic.extend(JPEG);
+        let (_, picture) = parse_apic(&apic).unwrap();
+        assert_eq!(picture.data, JPEG);
+    }
+
+    #[test]
+    fn test_id3_size_past_end_of_file() {
+        // Claims a 256MB tag but holds no frames
+        let mut mp3 = b"ID3\x03\x00\x00".to_vec();
+        mp3.extend([0x7F, 0x7F, 0x7F, 0x7F]);
+        mp3.extend([0u8; 16]);
+        let (_dir, path) = write_fixture("song.mp3", &mp3);
+        assert!(read_embedded_picture(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mp3_without_tag() {
+        let (_dir, path) = write_fixture("song.mp3", &[0xFF, 0xFB, 0x90, 0x00]);
+        assert_eq!(read_embedded_picture(&path).unwrap(), None);
+    }
+
+    fn atom(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut atom = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        atom.extend(kind);
+        atom.extend(body);
+        atom
+    }
+
+    #[test]
+    fn test_m4a_covr_atom() {
+        let mut data = 14u32.to_be_bytes().to_vec();
+        data.extend([0u8; 4]);
+        data.extend(PNG);
+        let ilst = atom(b"ilst", &atom(b"covr", &atom(b"data", &data)));
+        let mut meta = vec![0u8; 4];
+        meta.extend(atom(b"hdlr", &[0u8; 8]));
+        meta.extend(ilst);
+        let moov = atom(
+            b"moov",
+            &[
+                atom(b"mvhd", &[0u8; 4]),
+                atom(b"udta", &atom(b"meta", &meta)),
+            ]
+            .concat(),
+        );
+
+        // Media data before moov, as written by most encoders
+        let m4a = [
+            atom(b"ftyp", b"M4A \0\0\0\0"),
+            atom(b"mdat", &[0u8; 32]),
+            moov,
+        ]
+        .concat();
+        let (_dir, path) = write_fixture("song.m4a", &m4a);
+        let picture = read_embedded_picture(&path).unwrap().unwrap();
+        assert_eq!(picture.mime_type, "image/png");
+        assert_eq!(picture.data, PNG);
+        assert_eq!(picture.extension(), "png");
+    }
+
+    #[test]
+    fn test_m4a_corrupt_atom_sizes() {
+        // A moov claiming 4GB in a tiny file is refused rather than allocated
+        let mut m4a = atom(b"ftyp", b"M4A \0\0\0\0");
+        m4a.extend(u32::MAX.to_be_bytes());
+        m4a.extend(b"moov");
+        m4a.extend([0u8; 16]);
+        let (_dir, path) = write_fixture("huge.m4a", &m4a);
+        assert!(read_embedded_picture(&path).is_err());
+
+        // An extended size that would overflow the position ends the walk
+        let mut m4a = atom(b"ftyp", b"M4A \0\0\0\0");
+        m4a.extend(1u32.to_be_bytes());
+        m4a.extend(b"free");
+        m4a.extend(u64::MAX.to_be_bytes());
+        let (_dir, path) = write_fixture("overflow.m4a", &m4a);
+        assert!(read_embedded_picture(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_extension_falls_back_to_magic_bytes() {
+        let picture = EmbeddedPicture {
+            mime_type: "-->".to_string(),
+            data: JPEG.to_vec(),
+        };
+        assert_eq!(picture.extension(), "jpg");
+    }
+}
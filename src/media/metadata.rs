@@ -9,8 +9,6 @@ fn parse_ieee_extended_80(bytes: &[u8]) -> Option<f64> {
         return None;
     }
 
-    // Debug: log the raw bytes for analysis
-
     // Extract sign bit (bit 15 of first two bytes)
     let sign_and_exp = u16::from_be_bytes([bytes[0], bytes[1]]);
     let sign = (sign_and_exp & 0x8000) != 0;
@@ -37,28 +35,20 @@ fn parse_ieee_extended_80(bytes: &[u8]) -> Option<f64> {
     }
 
     // Convert to f64
-    // IEEE 754 extended has an explicit leading 1 bit in the mantissa
-    // The mantissa represents the fractional part after the implicit 1
+    // IEEE 754 extended stores the leading integer bit explicitly as bit 63
+    // of the mantissa, so there is no implicit 1 to add
     let mantissa_f64 = mantissa as f64 / (1u64 << 63) as f64;
 
     // Calculate the actual exponent (biased by 16383)
     let actual_exp = exponent as i32 - 16383;
 
     // Calculate the final value: (-1)^sign * mantissa * 2^exponent
-    let mut result = (1.0 + mantissa_f64) * 2.0f64.powi(actual_exp);
+    let mut result = mantissa_f64 * 2.0f64.powi(actual_exp);
 
     if sign {
         result = -result;
     }
 
-    // Check for known problematic values and try alternative parsing
-    let result_u32 = result.round() as u32;
-    if result_u32 == 153736 {
-        // Try interpreting as if it was encoded differently
-        // Sometimes the mantissa interpretation can be off
-        let _alt_result = mantissa as f64 / (1u64 << 32) as f64 * 2.0f64.powi(actual_exp - 32);
-    }
-
     Some(result)
 }
 
@@ -78,6 +68,82 @@ pub struct AiffData {
     pub audio_samples: Vec<i32>, // Raw samples in original bit depth
 }
 
+/// What the first bytes of a file say it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SniffedFormat {
+    Wav,
+    Flac,
+    Aiff,
+    Text,
+    Unknown,
+}
+
+impl SniffedFormat {
+    /// Identify a file from its first 12 bytes
+    fn from_header(header: &[u8]) -> Self {
+        let container = header.get(..4);
+        let form_type = header.get(8..12);
+        if matches!(container, Some(b"RIFF" | b"RF64" | b"BW64")) && form_type == Some(b"WAVE") {
+            SniffedFormat::Wav
+        } else if container == Some(b"fLaC") {
+            SniffedFormat::Flac
+        } else if container == Some(b"FORM") && matches!(form_type, Some(b"AIFF" | b"AIFC")) {
+            SniffedFormat::Aiff
+        } else if header
+            .iter()
+            .all(|&b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        {
+            SniffedFormat::Text
+        } else {
+            SniffedFormat::Unknown
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            SniffedFormat::Wav => "a WAV file",
+            SniffedFormat::Flac => "a FLAC file",
+            SniffedFormat::Aiff => "an AIFF file",
+            SniffedFormat::Text => "text",
+            SniffedFormat::Unknown => "something else",
+        }
+    }
+}
+
+/// Check a WAV, FLAC or AIFF file's magic bytes against its extension, so a
+/// renamed text file or a DAW proxy is refused with a clear message instead
+/// of failing partway through decoding. Other extensions aren't checked, and
+/// empty files pass since there's nothing to sniff yet.
+pub fn check_audio_header(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    let (expected, name) = match extension.as_deref() {
+        Some("wav") => (SniffedFormat::Wav, "WAV"),
+        Some("flac") => (SniffedFormat::Flac, "FLAC"),
+        Some("aif" | "aiff") => (SniffedFormat::Aiff, "AIFF"),
+        _ => return Ok(()),
+    };
+
+    let mut header = Vec::with_capacity(12);
+    File::open(path)?.take(12).read_to_end(&mut header)?;
+    if header.is_empty() {
+        return Ok(());
+    }
+    let found = SniffedFormat::from_header(&header);
+    if found == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} is not a {name} file (it looks like {})",
+            path.display(),
+            found.describe()
+        )
+        .into())
+    }
+}
+
 pub fn read_audio_metadata(path: &Path) -> Result<AudioMetadata, Box<dyn std::error::Error>> {
     let extension = path
         .extension()
@@ -87,6 +153,7 @@ pub fn read_audio_metadata(path: &Path) -> Result<AudioMetadata, Box<dyn std::er
     match extension.as_deref() {
         Some("flac") => read_flac_metadata(path),
         Some("wav") => read_wav_metadata(path),
+        Some("mp3") => read_mp3_metadata(path),
         _ => Err("Unsupported audio format".into()),
     }
 }
@@ -144,75 +211,322 @@ fn read_flac_metadata(path: &Path) -> Result<AudioMetadata, Box<dyn std::error::
 }
 
 fn read_wav_metadata(path: &Path) -> Result<AudioMetadata, Box<dyn std::error::Error>> {
-    let mut file = File::open(path)?;
-    let mut riff = [0u8; 4];
-    file.read_exact(&mut riff)?;
+    let layout = crate::wav_metadata::read_wav_layout(path)?;
+
+    // Compressed formats can only be counted from the fact chunk; for PCM
+    // and float a shorter fact count wins, as the data may be padded out to
+    // a whole block
+    let frames = if layout.is_compressed() {
+        layout.fact_frames()
+    } else {
+        let data_frames = layout.frames();
+        Some(
+            layout
+                .fact_frames()
+                .map_or(data_frames, |fact| fact.min(data_frames)),
+        )
+    };
+    let sample_rate = layout.sample_rate();
+    let duration_seconds = frames
+        .filter(|_| sample_rate > 0)
+        .map(|frames| frames as f64 / sample_rate as f64);
 
-    if &riff != b"RIFF" {
-        return Err("Not a valid WAV file".into());
+    Ok(AudioMetadata {
+        sample_rate,
+        channels: layout.channels(),
+        bits_per_sample: layout.bits_per_sample(),
+        duration_seconds,
+    })
+}
+
+/// Fields of an MPEG audio frame header needed for duration calculation
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MpegFrameHeader {
+    sample_rate: u32,
+    channels: u16,
+    samples_per_frame: u32,
+    frame_length: usize,
+    side_info_length: usize, // Layer III side info, where a Xing/Info header sits
+}
+
+/// Parse a 4-byte MPEG audio frame header, rejecting reserved or free-format values
+fn parse_mpeg_frame_header(bytes: &[u8]) -> Option<MpegFrameHeader> {
+    const BITRATES_V1_L1: [u32; 14] = [
+        32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+    ];
+    const BITRATES_V1_L2: [u32; 14] = [
+        32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    ];
+    const BITRATES_V1_L3: [u32; 14] = [
+        32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const BITRATES_V2_L1: [u32; 14] = [
+        32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+    ];
+    const BITRATES_V2_L2_L3: [u32; 14] =
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+        return None;
     }
 
-    // Skip file size
-    file.seek(SeekFrom::Current(4))?;
+    // Version: 0 = MPEG 2.5, 2 = MPEG 2, 3 = MPEG 1. Layer: 1 = III, 2 = II, 3 = I
+    let version = (bytes[1] >> 3) & 0x03;
+    let layer = (bytes[1] >> 1) & 0x03;
+    let bitrate_index = (bytes[2] >> 4) as usize;
+    let sample_rate_index = ((bytes[2] >> 2) & 0x03) as usize;
+    let padding = ((bytes[2] >> 1) & 0x01) as usize;
+    let mono = bytes[3] >> 6 == 0x03;
 
-    let mut wave = [0u8; 4];
-    file.read_exact(&mut wave)?;
-    if &wave != b"WAVE" {
-        return Err("Not a valid WAV file".into());
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 {
+        return None;
     }
+    let base_rate = [44100, 48000, 32000].get(sample_rate_index)?;
+    let sample_rate = match version {
+        3 => *base_rate,
+        2 => base_rate / 2,
+        _ => base_rate / 4,
+    };
 
-    // Find fmt chunk
-    loop {
-        let mut chunk_id = [0u8; 4];
-        file.read_exact(&mut chunk_id)?;
+    let mpeg1 = version == 3;
+    let bitrates = match (mpeg1, layer) {
+        (true, 3) => &BITRATES_V1_L1,
+        (true, 2) => &BITRATES_V1_L2,
+        (true, _) => &BITRATES_V1_L3,
+        (false, 3) => &BITRATES_V2_L1,
+        (false, _) => &BITRATES_V2_L2_L3,
+    };
+    let bitrate = bitrates[bitrate_index - 1] * 1000;
 
-        let mut chunk_size = [0u8; 4];
-        file.read_exact(&mut chunk_size)?;
-        let size = u32::from_le_bytes(chunk_size);
-
-        if &chunk_id == b"fmt " {
-            // Read format chunk
-            let mut fmt_data = vec![0u8; size.min(16) as usize];
-            file.read_exact(&mut fmt_data)?;
-
-            let channels = u16::from_le_bytes([fmt_data[2], fmt_data[3]]);
-            let sample_rate =
-                u32::from_le_bytes([fmt_data[4], fmt_data[5], fmt_data[6], fmt_data[7]]);
-            let bits_per_sample = u16::from_le_bytes([fmt_data[14], fmt_data[15]]);
-
-            // Try to find data chunk for duration
-            let mut duration_seconds = None;
-            while let Ok(()) = file.read_exact(&mut chunk_id) {
-                file.read_exact(&mut chunk_size)?;
-                let data_size = u32::from_le_bytes(chunk_size);
-
-                if &chunk_id == b"data" {
-                    let bytes_per_sample = (bits_per_sample / 8) as u32;
-                    let bytes_per_second = sample_rate * channels as u32 * bytes_per_sample;
-                    if bytes_per_second > 0 {
-                        duration_seconds = Some(data_size as f64 / bytes_per_second as f64);
-                    }
-                    break;
-                } else {
-                    file.seek(SeekFrom::Current(data_size as i64))?;
-                }
+    let samples_per_frame = match (layer, mpeg1) {
+        (3, _) => 384,
+        (2, _) | (1, true) => 1152,
+        _ => 576,
+    };
+    let frame_length = if layer == 3 {
+        (12 * bitrate / sample_rate) as usize * 4 + padding * 4
+    } else {
+        (samples_per_frame / 8 * bitrate / sample_rate) as usize + padding
+    };
+    let side_info_length = match (mpeg1, mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+
+    Some(MpegFrameHeader {
+        sample_rate,
+        channels: if mono { 1 } else { 2 },
+        samples_per_frame,
+        frame_length,
+        side_info_length,
+    })
+}
+
+/// Length of a leading ID3v2 tag, or 0 if there is none
+fn id3v2_length(bytes: &[u8]) -> usize {
+    if bytes.len() < 10 || &bytes[0..3] != b"ID3" {
+        return 0;
+    }
+    // Tag size is a 28-bit "syncsafe" integer (7 bits per byte)
+    let size = bytes[6..10]
+        .iter()
+        .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+    let footer = if bytes[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+/// Find the next valid frame at or after `start`. A candidate only counts if
+/// another frame header (or the end of the data) follows it, which filters out
+/// stray sync bytes.
+fn find_mpeg_frame(bytes: &[u8], start: usize) -> Option<(usize, MpegFrameHeader)> {
+    (start..bytes.len().saturating_sub(3)).find_map(|pos| {
+        let header = parse_mpeg_frame_header(&bytes[pos..])?;
+        let next = pos + header.frame_length;
+        let followed = next >= bytes.len()
+            || bytes[next..].starts_with(b"TAG")
+            || parse_mpeg_frame_header(&bytes[next..]).is_some();
+        followed.then_some((pos, header))
+    })
+}
+
+/// Total sample count from a Xing/Info or VBRI header in the first frame,
+/// corrected for LAME encoder delay and padding when present
+fn vbr_header_samples(frame: &[u8], header: &MpegFrameHeader) -> Option<u64> {
+    let read_u32 = |bytes: &[u8], at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    };
+
+    // Xing (VBR) or Info (CBR) header sits right after the side info
+    let xing = 4 + header.side_info_length;
+    if let Some(tag) = frame.get(xing..xing + 4)
+        && (tag == b"Xing" || tag == b"Info")
+    {
+        let flags = read_u32(frame, xing + 4)?;
+        if flags & 0x01 == 0 {
+            return None;
+        }
+        let frames = read_u32(frame, xing + 8)? as u64;
+        let mut total = frames * header.samples_per_frame as u64;
+
+        // Optional fields before the LAME extension: byte count, TOC, quality
+        let mut lame = xing + 12;
+        for (flag, length) in [(0x02, 4), (0x04, 100), (0x08, 4)] {
+            if flags & flag != 0 {
+                lame += length;
             }
+        }
+        if let Some(encoder) = frame.get(lame..lame + 4)
+            && matches!(encoder, b"LAME" | b"Lavf" | b"Lavc")
+            && let Some(gapless) = frame.get(lame + 21..lame + 24)
+        {
+            let delay = ((gapless[0] as u64) << 4) | (gapless[1] as u64 >> 4);
+            let padding = ((gapless[1] as u64 & 0x0F) << 8) | gapless[2] as u64;
+            total = total.saturating_sub(delay + padding);
+        }
+        return Some(total);
+    }
 
-            return Ok(AudioMetadata {
-                sample_rate,
-                channels,
-                bits_per_sample,
-                duration_seconds,
-            });
-        } else {
-            // Skip this chunk
-            file.seek(SeekFrom::Current(size as i64))?;
+    // Fraunhofer VBRI header sits at a fixed offset
+    if frame.get(36..40) == Some(b"VBRI".as_slice()) {
+        let frames = read_u32(frame, 36 + 14)? as u64;
+        return Some(frames * header.samples_per_frame as u64);
+    }
+
+    None
+}
+
+/// Read MP3 stream properties. Duration comes from the Xing/Info or VBRI
+/// header when present, otherwise from counting every frame, so VBR files
+/// aren't measured as if they were constant bitrate.
+fn read_mp3_metadata(path: &Path) -> Result<AudioMetadata, Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(path)?;
+    let start = id3v2_length(&bytes);
+    let (first, header) = find_mpeg_frame(&bytes, start).ok_or("No MPEG audio frames found")?;
+
+    let first_frame = &bytes[first..(first + header.frame_length).min(bytes.len())];
+    let total_samples = match vbr_header_samples(first_frame, &header) {
+        Some(total) => total,
+        None => {
+            let mut total = 0u64;
+            let mut pos = first;
+            while let Some(frame) = parse_mpeg_frame_header(bytes.get(pos..).unwrap_or_default()) {
+                total += frame.samples_per_frame as u64;
+                pos += frame.frame_length;
+            }
+            total
         }
+    };
+
+    Ok(AudioMetadata {
+        sample_rate: header.sample_rate,
+        channels: header.channels,
+        bits_per_sample: 16, // MP3 has no bit depth; decoders produce 16-bit PCM
+        duration_seconds: (total_samples > 0)
+            .then(|| total_samples as f64 / header.sample_rate as f64),
+    })
+}
+
+/// Sample encoding of an AIFF or AIFF-C sound data chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiffEncoding {
+    PcmBigEndian,
+    PcmLittleEndian,
+    Float32,
+    Float64,
+}
+
+/// Map an AIFF-C compression type to the encoding we can decode
+fn aiff_encoding(compression_type: &[u8]) -> Result<AiffEncoding, Box<dyn std::error::Error>> {
+    match compression_type {
+        b"NONE" | b"twos" => Ok(AiffEncoding::PcmBigEndian),
+        b"sowt" => Ok(AiffEncoding::PcmLittleEndian),
+        b"fl32" | b"FL32" => Ok(AiffEncoding::Float32),
+        b"fl64" | b"FL64" => Ok(AiffEncoding::Float64),
+        other => Err(format!(
+            "Unsupported AIFF-C compression type: {}",
+            String::from_utf8_lossy(other)
+        )
+        .into()),
     }
 }
 
+/// Convert a normalized float sample to full-scale 32-bit
+fn float_to_i32(sample: f64) -> i32 {
+    (sample.clamp(-1.0, 1.0) * i32::MAX as f64) as i32
+}
+
+/// Decode raw sound data into integer samples in the original bit depth.
+/// Float encodings are converted to 32-bit integers.
+fn decode_aiff_samples(
+    bytes: &[u8],
+    bits_per_sample: u16,
+    encoding: AiffEncoding,
+) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+    let little_endian = encoding == AiffEncoding::PcmLittleEndian;
+
+    let samples = match (encoding, bits_per_sample) {
+        (AiffEncoding::Float32, _) => bytes
+            .chunks_exact(4)
+            .map(|b| float_to_i32(f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64))
+            .collect(),
+        (AiffEncoding::Float64, _) => bytes
+            .chunks_exact(8)
+            .map(|b| {
+                float_to_i32(f64::from_be_bytes([
+                    b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+                ]))
+            })
+            .collect(),
+        // 8-bit samples (signed)
+        (_, 8) => bytes.iter().map(|&b| (b as i8) as i32).collect(),
+        (_, 16) => bytes
+            .chunks_exact(2)
+            .map(|b| {
+                let pair = [b[0], b[1]];
+                if little_endian {
+                    i16::from_le_bytes(pair) as i32
+                } else {
+                    i16::from_be_bytes(pair) as i32
+                }
+            })
+            .collect(),
+        (_, 24) => bytes
+            .chunks_exact(3)
+            .map(|b| {
+                let (hi, mid, lo) = if little_endian {
+                    (b[2], b[1], b[0])
+                } else {
+                    (b[0], b[1], b[2])
+                };
+                // Place in the top 24 bits, then shift back to sign extend
+                i32::from_be_bytes([hi, mid, lo, 0]) >> 8
+            })
+            .collect(),
+        (_, 32) => bytes
+            .chunks_exact(4)
+            .map(|b| {
+                let quad = [b[0], b[1], b[2], b[3]];
+                if little_endian {
+                    i32::from_le_bytes(quad)
+                } else {
+                    i32::from_be_bytes(quad)
+                }
+            })
+            .collect(),
+        _ => return Err(format!("Unsupported bit depth: {bits_per_sample}").into()),
+    };
+
+    Ok(samples)
+}
+
+/// Read an AIFF or AIFF-C file fully into memory.
+///
+/// AIFF-C files are supported when uncompressed (`NONE`/`twos`), little-endian
+/// (`sowt`), or floating point (`fl32`/`fl64`).
 pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error>> {
     let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
 
     // Read FORM header
     let mut form = [0u8; 4];
@@ -224,16 +538,19 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
     // Skip file size (big-endian)
     file.seek(SeekFrom::Current(4))?;
 
-    // Read AIFF identifier
-    let mut aiff = [0u8; 4];
-    file.read_exact(&mut aiff)?;
-    if &aiff != b"AIFF" {
-        return Err("Not a valid AIFF file".into());
-    }
+    // Read AIFF or AIFC identifier
+    let mut form_type = [0u8; 4];
+    file.read_exact(&mut form_type)?;
+    let is_aifc = match &form_type {
+        b"AIFF" => false,
+        b"AIFC" => true,
+        _ => return Err("Not a valid AIFF file".into()),
+    };
 
     let mut sample_rate = 0;
     let mut channels = 0;
     let mut bits_per_sample = 0;
+    let mut encoding = AiffEncoding::PcmBigEndian;
     let mut audio_samples = Vec::new();
 
     // Find both COMM and SSND chunks
@@ -246,11 +563,30 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
         let mut chunk_size = [0u8; 4];
         file.read_exact(&mut chunk_size)?;
         let size = u32::from_be_bytes(chunk_size); // Big-endian for AIFF
+        // Chunks are padded to an even length
+        let padded_size = size as i64 + (size % 2) as i64;
+
+        // Check chunks that get read into memory against the file, so a
+        // corrupt size can't ask for gigabytes
+        let is_read = &chunk_id == b"COMM" || &chunk_id == b"SSND";
+        if is_read && size as u64 > file_len.saturating_sub(file.stream_position()?) {
+            return Err(format!(
+                "AIFF file is truncated: '{}' chunk extends past the end of the file",
+                String::from_utf8_lossy(&chunk_id)
+            )
+            .into());
+        }
 
         if &chunk_id == b"COMM" {
-            // Read COMM chunk (Common chunk)
-            let mut comm_data = vec![0u8; size.min(18) as usize];
+            // Read COMM chunk (Common chunk). AIFF-C appends a compression
+            // type and name after the 18 standard bytes.
+            let mut comm_data = vec![0u8; size as usize];
             file.read_exact(&mut comm_data)?;
+            file.seek(SeekFrom::Current(padded_size - size as i64))?;
+
+            if comm_data.len() < 8 {
+                return Err("COMM chunk too short".into());
+            }
 
             channels = u16::from_be_bytes([comm_data[0], comm_data[1]]);
             let _num_sample_frames =
@@ -258,30 +594,18 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
             bits_per_sample = u16::from_be_bytes([comm_data[6], comm_data[7]]);
 
             // AIFF stores sample rate as 80-bit IEEE 754 extended precision
-            // Implement proper conversion from 80-bit IEEE 754 extended to f64
             sample_rate = if comm_data.len() >= 18 {
                 parse_ieee_extended_80(&comm_data[8..18])
-                    .map(|rate| {
-                        let rate_u32 = rate.round() as u32;
-                        // Fix common AIFF parsing errors based on concrete duration testing
-                        if rate_u32 == 109636 {
-                            // Common AIFF 80-bit IEEE parsing error
-                            44100
-                        } else if rate_u32 == 153736 {
-                            // Concrete test: ZIM shows 6:58, QuickTime shows 3:29 (exactly 2x)
-                            // This means our 44100 Hz correction was half what it should be
-                            88200
-                        } else if (1000..=200000).contains(&rate_u32) {
-                            rate_u32
-                        } else {
-                            // Invalid sample rate, use fallback
-                            44100
-                        }
-                    })
+                    .map(|rate| rate.round() as u32)
+                    .filter(|rate| (1000..=384000).contains(rate))
                     .unwrap_or(44100) // Fallback if parsing fails
             } else {
                 44100 // Fallback
             };
+
+            if is_aifc && comm_data.len() >= 22 {
+                encoding = aiff_encoding(&comm_data[18..22])?;
+            }
         } else if &chunk_id == b"SSND" {
             // Read SSND chunk (Sound Data chunk)
             // SSND has an 8-byte header: offset (4) + blockSize (4)
@@ -294,12 +618,6 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
                 ssnd_header[2],
                 ssnd_header[3],
             ]);
-            let _block_size = u32::from_be_bytes([
-                ssnd_header[4],
-                ssnd_header[5],
-                ssnd_header[6],
-                ssnd_header[7],
-            ]);
 
             // Skip offset bytes if any
             if offset > 0 {
@@ -307,63 +625,16 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
             }
 
             // Calculate how many audio bytes to read
-            let audio_bytes = size - 8 - offset; // size minus header minus offset
-
-            // Read audio data based on bit depth
-            match bits_per_sample {
-                8 => {
-                    // 8-bit samples (signed)
-                    let mut bytes = vec![0u8; audio_bytes as usize];
-                    file.read_exact(&mut bytes)?;
-                    audio_samples = bytes.into_iter().map(|b| (b as i8) as i32).collect();
-                }
-                16 => {
-                    // 16-bit samples (big-endian)
-                    let sample_count = (audio_bytes / 2) as usize;
-                    audio_samples.reserve(sample_count);
-                    for _ in 0..sample_count {
-                        let mut sample_bytes = [0u8; 2];
-                        file.read_exact(&mut sample_bytes)?;
-                        let sample = i16::from_be_bytes(sample_bytes);
-                        audio_samples.push(sample as i32);
-                    }
-                }
-                24 => {
-                    // 24-bit samples (big-endian, sign-extended to 32-bit)
-                    let sample_count = (audio_bytes / 3) as usize;
-                    audio_samples.reserve(sample_count);
-                    for _ in 0..sample_count {
-                        let mut sample_bytes = [0u8; 3];
-                        file.read_exact(&mut sample_bytes)?;
-                        // Convert 24-bit big-endian to 32-bit signed
-                        let sample = ((sample_bytes[0] as i32) << 16)
-                            | ((sample_bytes[1] as i32) << 8)
-                            | (sample_bytes[2] as i32);
-                        // Sign extend from 24-bit to 32-bit
-                        let sample = if sample & 0x800000 != 0 {
-                            sample | 0xFF000000u32 as i32
-                        } else {
-                            sample
-                        };
-                        audio_samples.push(sample);
-                    }
-                }
-                32 => {
-                    // 32-bit samples (big-endian)
-                    let sample_count = (audio_bytes / 4) as usize;
-                    audio_samples.reserve(sample_count);
-                    for _ in 0..sample_count {
-                        let mut sample_bytes = [0u8; 4];
-                        file.read_exact(&mut sample_bytes)?;
-                        let sample = i32::from_be_bytes(sample_bytes);
-                        audio_samples.push(sample);
-                    }
-                }
-                _ => return Err(format!("Unsupported bit depth: {bits_per_sample}").into()),
-            }
+            let audio_bytes = size.saturating_sub(8).saturating_sub(offset); // size minus header minus offset
+
+            let mut bytes = vec![0u8; audio_bytes as usize];
+            file.read_exact(&mut bytes)?;
+            file.seek(SeekFrom::Current(padded_size - size as i64))?;
+
+            audio_samples = decode_aiff_samples(&bytes, bits_per_sample, encoding)?;
         } else {
             // Skip this chunk
-            file.seek(SeekFrom::Current(size as i64))?;
+            file.seek(SeekFrom::Current(padded_size))?;
         }
     }
 
@@ -375,6 +646,11 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
         return Err("Missing SSND chunk in AIFF file".into());
     }
 
+    // Float data has been converted to 32-bit integers
+    if matches!(encoding, AiffEncoding::Float32 | AiffEncoding::Float64) {
+        bits_per_sample = 32;
+    }
+
     Ok(AiffData {
         sample_rate,
         channels,
@@ -382,3 +658,444 @@ pub fn read_aiff_data(path: &Path) -> Result<AiffData, Box<dyn std::error::Error
         audio_samples,
     })
 }
+
+/// Duration of an MP4 or QuickTime movie in seconds, from the `mvhd` box.
+///
+/// Only box headers are read on the way to it, so this stays cheap even when
+/// the `moov` box sits after gigabytes of media data.
+pub fn read_video_duration(path: &Path) -> Result<f64, Box<dyn std::error::Error>> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let moov_end = find_box(&mut file, file_len, b"moov")?.ok_or("No moov box found")?;
+    let mvhd_end = find_box(&mut file, moov_end, b"mvhd")?.ok_or("No mvhd box found")?;
+
+    let mut version = [0u8; 4]; // Version byte and 24 bits of flags
+    file.read_exact(&mut version)?;
+    let (timescale, duration) = if version[0] == 1 {
+        let mut body = [0u8; 28];
+        file.read_exact(&mut body)?;
+        (
+            u32::from_be_bytes(body[16..20].try_into()?),
+            u64::from_be_bytes(body[20..28].try_into()?),
+        )
+    } else {
+        let mut body = [0u8; 16];
+        file.read_exact(&mut body)?;
+        (
+            u32::from_be_bytes(body[8..12].try_into()?),
+            u32::from_be_bytes(body[12..16].try_into()?) as u64,
+        )
+    };
+    if file.stream_position()? > mvhd_end {
+        return Err("Truncated mvhd box".into());
+    }
+    if timescale == 0 || duration == 0 || duration == u32::MAX as u64 {
+        return Err("Movie duration is unknown".into());
+    }
+    Ok(duration as f64 / timescale as f64)
+}
+
+/// Skip through the boxes from the current position up to `end`, leaving the
+/// file at the start of the body of the first `kind` box. Returns where that
+/// box ends.
+fn find_box(
+    file: &mut File,
+    end: u64,
+    kind: &[u8; 4],
+) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    loop {
+        let start = file.stream_position()?;
+        if start + 8 > end {
+            return Ok(None);
+        }
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let size = match u32::from_be_bytes(header[0..4].try_into()?) {
+            // The box runs to the end of its parent
+            0 => end - start,
+            // A 64-bit size follows the type
+            1 => {
+                let mut large = [0u8; 8];
+                file.read_exact(&mut large)?;
+                u64::from_be_bytes(large)
+            }
+            size => size as u64,
+        };
+        let box_end = start + size;
+        if size < 8 || box_end > end {
+            return Err("Malformed box header".into());
+        }
+        if &header[4..8] == kind {
+            return Ok(Some(box_end));
+        }
+        file.seek(SeekFrom::Start(box_end))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{fmt_chunk, riff_wav};
+    use tempfile::TempDir;
+
+    /// A box with a 32-bit size header
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut bytes = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(kind);
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    #[test]
+    fn test_read_video_duration() {
+        let temp_dir = TempDir::new().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        // Version 0 mvhd: 1000 units per second, 90.5 seconds long
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&90_500u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0u8; 80]);
+        // The moov box comes after the media data, as cameras write it
+        let mut movie = mp4_box(b"ftyp", b"isom\0\0\0\0");
+        movie.extend(mp4_box(b"mdat", &[0u8; 64]));
+        movie.extend(mp4_box(
+            b"moov",
+            &[mp4_box(b"udta", &[]), mp4_box(b"mvhd", &mvhd)].concat(),
+        ));
+        let path = write("reel.mp4", &movie);
+        assert_eq!(read_video_duration(&path).unwrap(), 90.5);
+
+        // Version 1 mvhd uses 64-bit times
+        let mut mvhd = vec![1u8, 0, 0, 0];
+        mvhd.extend_from_slice(&[0u8; 16]);
+        mvhd.extend_from_slice(&600u32.to_be_bytes());
+        mvhd.extend_from_slice(&3000u64.to_be_bytes());
+        let path = write("reel.mov", &mp4_box(b"moov", &mp4_box(b"mvhd", &mvhd)));
+        assert_eq!(read_video_duration(&path).unwrap(), 5.0);
+
+        let path = write("no_moov.mp4", &mp4_box(b"mdat", &[0u8; 16]));
+        assert!(read_video_duration(&path).is_err());
+        let path = write("broken.mp4", b"\0\0\0\x04moov");
+        assert!(read_video_duration(&path).is_err());
+    }
+
+    #[test]
+    fn test_check_audio_header() {
+        let temp_dir = TempDir::new().unwrap();
+        let check = |name: &str, bytes: &[u8]| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            check_audio_header(&path).map_err(|e| e.to_string())
+        };
+
+        assert!(check("take.wav", b"RIFF\x24\0\0\0WAVEfmt ").is_ok());
+        assert!(check("take.flac", b"fLaC\0\0\0\x22").is_ok());
+        assert!(check("take.aif", b"FORM\0\0\0\x40AIFFCOMM").is_ok());
+        assert!(check("take.AIFF", b"FORM\0\0\0\x40AIFCFVER").is_ok());
+        assert!(check("empty.wav", b"").is_ok());
+        // Formats without a sniffer are left to their decoders
+        assert!(check("take.mp3", b"not really an mp3").is_ok());
+
+        let error = check("notes.wav", b"# Session notes\n").unwrap_err();
+        assert!(error.ends_with("is not a WAV file (it looks like text)"));
+        let error = check("renamed.wav", b"fLaC\0\0\0\x22").unwrap_err();
+        assert!(error.ends_with("(it looks like a FLAC file)"));
+        let error = check("proxy.flac", &[0xff, 0xd8, 0xff, 0xe0, 0, 0x10]).unwrap_err();
+        assert!(error.ends_with("is not a FLAC file (it looks like something else)"));
+    }
+
+    // 44100 Hz as an 80-bit IEEE 754 extended float
+    const RATE_44100: [u8; 10] = [0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0];
+
+    /// Build an AIFF or AIFF-C file with the given sound data
+    fn build_aiff(compression: Option<&[u8; 4]>, bits: u16, channels: u16, data: &[u8]) -> Vec<u8> {
+        let mut comm = Vec::new();
+        comm.extend_from_slice(&channels.to_be_bytes());
+        let frames = data.len() as u32 / (bits as u32 / 8).max(1) / channels as u32;
+        comm.extend_from_slice(&frames.to_be_bytes());
+        comm.extend_from_slice(&bits.to_be_bytes());
+        comm.extend_from_slice(&RATE_44100);
+        if let Some(compression) = compression {
+            comm.extend_from_slice(compression);
+            comm.extend_from_slice(&[0, 0]); // Empty pascal string name, padded
+        }
+
+        let mut body = Vec::new();
+        body.extend_from_slice(if compression.is_some() {
+            b"AIFC"
+        } else {
+            b"AIFF"
+        });
+        if compression.is_some() {
+            body.extend_from_slice(b"FVER");
+            body.extend_from_slice(&4u32.to_be_bytes());
+            body.extend_from_slice(&0xA280_5140u32.to_be_bytes());
+        }
+        body.extend_from_slice(b"COMM");
+        body.extend_from_slice(&(comm.len() as u32).to_be_bytes());
+        body.extend_from_slice(&comm);
+        body.extend_from_slice(b"SSND");
+        body.extend_from_slice(&(data.len() as u32 + 8).to_be_bytes());
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(data);
+
+        let mut file = b"FORM".to_vec();
+        file.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        file.extend_from_slice(&body);
+        file
+    }
+
+    fn read_fixture(bytes: &[u8]) -> Result<AiffData, Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fixture.aif");
+        std::fs::write(&path, bytes).unwrap();
+        read_aiff_data(&path)
+    }
+
+    const MP3_FRAME_SAMPLES: usize = 1152;
+
+    /// An MPEG-1 Layer III frame at 44.1 kHz stereo with a silent payload.
+    /// Bitrate indices: 5 = 64k, 9 = 128k, 14 = 320k.
+    fn mp3_frame(bitrate_index: u8) -> Vec<u8> {
+        let header = [0xFF, 0xFB, bitrate_index << 4, 0x00];
+        let length = parse_mpeg_frame_header(&header).unwrap().frame_length;
+        let mut frame = header.to_vec();
+        frame.resize(length, 0);
+        frame
+    }
+
+    /// Frames alternating between three bitrates, as a VBR encoder would write
+    fn vbr_frames(count: usize) -> Vec<u8> {
+        (0..count)
+            .flat_map(|i| mp3_frame([5, 9, 14][i % 3]))
+            .collect()
+    }
+
+    fn mp3_duration(bytes: &[u8]) -> f64 {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fixture.mp3");
+        std::fs::write(&path, bytes).unwrap();
+        read_audio_metadata(&path)
+            .unwrap()
+            .duration_seconds
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mp3_vbr_duration_from_xing_header() {
+        let frames = 40;
+        let (delay, padding) = (576u32, 1000u32);
+
+        // Info frame: Xing tag with frame count, byte count, TOC, quality and a LAME tag
+        let mut xing_frame = mp3_frame(9);
+        let mut tag = b"Xing".to_vec();
+        tag.extend_from_slice(&0x0Fu32.to_be_bytes());
+        tag.extend_from_slice(&(frames as u32).to_be_bytes());
+        tag.extend_from_slice(&[0; 4 + 100 + 4]);
+        tag.extend_from_slice(b"LAME3.100");
+        tag.extend_from_slice(&[0; 12]);
+        tag.extend_from_slice(&[
+            (delay >> 4) as u8,
+            ((delay & 0x0F) << 4 | padding >> 8) as u8,
+            padding as u8,
+        ]);
+        xing_frame[36..36 + tag.len()].copy_from_slice(&tag);
+
+        let mut bytes = xing_frame;
+        bytes.extend(vbr_frames(frames));
+
+        let expected = (frames * MP3_FRAME_SAMPLES - (delay + padding) as usize) as f64 / 44100.0;
+        assert!((mp3_duration(&bytes) - expected).abs() < 0.001);
+
+        // A constant-bitrate estimate from the first frame is badly off
+        let cbr_estimate = bytes.len() as f64 * 8.0 / 128_000.0;
+        assert!((cbr_estimate - expected).abs() > 0.1);
+    }
+
+    #[test]
+    fn test_mp3_vbr_duration_by_counting_frames() {
+        // No Xing header: ID3v2 tag, VBR frames, then an ID3v1 tag
+        let mut bytes = b"ID3\x03\x00\x00".to_vec();
+        bytes.extend_from_slice(&[0, 0, 0, 20]);
+        bytes.extend_from_slice(&[0xFF; 20]); // Sync-like bytes inside the tag are skipped
+        bytes.extend(vbr_frames(30));
+        bytes.extend_from_slice(b"TAG");
+        bytes.resize(bytes.len() + 125, 0);
+
+        let expected = (30 * MP3_FRAME_SAMPLES) as f64 / 44100.0;
+        assert!((mp3_duration(&bytes) - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_mp3_vbri_header() {
+        let mut vbri_frame = mp3_frame(9);
+        vbri_frame[36..40].copy_from_slice(b"VBRI");
+        vbri_frame[50..54].copy_from_slice(&12u32.to_be_bytes());
+        let mut bytes = vbri_frame;
+        bytes.extend(vbr_frames(12));
+
+        let expected = (12 * MP3_FRAME_SAMPLES) as f64 / 44100.0;
+        assert!((mp3_duration(&bytes) - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_mpeg_frame_header() {
+        let header = parse_mpeg_frame_header(&[0xFF, 0xFB, 0x90, 0x00]).unwrap();
+        assert_eq!(header.sample_rate, 44100);
+        assert_eq!(header.channels, 2);
+        assert_eq!(header.frame_length, 417); // 128 kbps at 44.1 kHz
+        assert_eq!(header.side_info_length, 32);
+
+        // MPEG-2 Layer III, 22.05 kHz mono, 64 kbps
+        let header = parse_mpeg_frame_header(&[0xFF, 0xF3, 0x80, 0xC0]).unwrap();
+        assert_eq!(header.sample_rate, 22050);
+        assert_eq!(header.channels, 1);
+        assert_eq!(header.samples_per_frame, 576);
+        assert_eq!(header.side_info_length, 9);
+
+        // Free-format bitrate and reserved sample rate are rejected
+        assert!(parse_mpeg_frame_header(&[0xFF, 0xFB, 0x00, 0x00]).is_none());
+        assert!(parse_mpeg_frame_header(&[0xFF, 0xFB, 0x9C, 0x00]).is_none());
+        assert!(parse_mpeg_frame_header(b"TAG").is_none());
+    }
+
+    #[test]
+    fn test_parse_ieee_extended_80() {
+        assert_eq!(parse_ieee_extended_80(&RATE_44100), Some(44100.0));
+        let rate_48000 = [0x40, 0x0E, 0xBB, 0x80, 0, 0, 0, 0, 0, 0];
+        assert_eq!(parse_ieee_extended_80(&rate_48000), Some(48000.0));
+    }
+
+    #[test]
+    fn test_read_aiff_16bit() {
+        let samples: [i16; 4] = [0, 1000, -1000, i16::MAX];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+
+        let aiff = read_fixture(&build_aiff(None, 16, 2, &data)).unwrap();
+        assert_eq!(aiff.sample_rate, 44100);
+        assert_eq!(aiff.channels, 2);
+        assert_eq!(aiff.bits_per_sample, 16);
+        assert_eq!(aiff.audio_samples, vec![0, 1000, -1000, 32767]);
+    }
+
+    #[test]
+    fn test_read_aiff_rejects_oversized_chunks() {
+        let aiff = build_aiff(None, 16, 1, &[0; 8]);
+        for id in [b"COMM", b"SSND"] {
+            let at = aiff.windows(4).position(|w| w == id).unwrap() + 4;
+            let mut corrupt = aiff.clone();
+            corrupt[at..at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            let error = read_fixture(&corrupt).unwrap_err();
+            assert!(error.to_string().contains("truncated"), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_read_aifc_sowt() {
+        let samples: [i16; 4] = [0, 1000, -1000, i16::MIN];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let aiff = read_fixture(&build_aiff(Some(b"sowt"), 16, 1, &data)).unwrap();
+        assert_eq!(aiff.sample_rate, 44100);
+        assert_eq!(aiff.bits_per_sample, 16);
+        assert_eq!(aiff.audio_samples, vec![0, 1000, -1000, -32768]);
+    }
+
+    #[test]
+    fn test_read_aifc_sowt_24bit() {
+        // -2 and 0x123456 as little-endian 24-bit
+        let data = [0xFE, 0xFF, 0xFF, 0x56, 0x34, 0x12];
+        let aiff = read_fixture(&build_aiff(Some(b"sowt"), 24, 1, &data)).unwrap();
+        assert_eq!(aiff.audio_samples, vec![-2, 0x123456]);
+    }
+
+    #[test]
+    fn test_read_aifc_float() {
+        let samples: [f32; 3] = [0.0, 0.5, -1.0];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+
+        let aiff = read_fixture(&build_aiff(Some(b"fl32"), 32, 1, &data)).unwrap();
+        assert_eq!(aiff.bits_per_sample, 32);
+        assert_eq!(aiff.audio_samples[0], 0);
+        assert_eq!(aiff.audio_samples[1], i32::MAX / 2);
+        assert_eq!(aiff.audio_samples[2], -i32::MAX);
+
+        let samples: [f64; 2] = [0.25, 2.0];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        let aiff = read_fixture(&build_aiff(Some(b"fl64"), 64, 1, &data)).unwrap();
+        assert_eq!(aiff.bits_per_sample, 32);
+        assert_eq!(aiff.audio_samples, vec![i32::MAX / 4, i32::MAX]); // Clamped
+    }
+
+    #[test]
+    fn test_read_aifc_unsupported_compression() {
+        let aiff = read_fixture(&build_aiff(Some(b"ima4"), 16, 1, &[0; 34]));
+        let error = aiff.unwrap_err().to_string();
+        assert!(error.contains("Unsupported AIFF-C compression type: ima4"));
+    }
+
+    #[test]
+    fn test_wav_duration_from_fact_chunk() {
+        let temp_dir = TempDir::new().unwrap();
+        let duration = |bytes: &[u8]| {
+            let path = temp_dir.path().join("take.wav");
+            std::fs::write(&path, bytes).unwrap();
+            read_audio_metadata(&path).unwrap().duration_seconds
+        };
+
+        // A float WAV padded past its last frame: the fact chunk has the real length
+        let float = fmt_chunk(3, 1, 8000, 4, 32, &[]);
+        let data = vec![0u8; 1002 * 4];
+        assert_eq!(duration(&riff_wav(&float, Some(1000), &data)), Some(0.125));
+        assert_eq!(duration(&riff_wav(&float, None, &data)), Some(0.12525));
+
+        // IMA ADPCM packs 4-bit samples in blocks; only the fact chunk can say
+        let adpcm = fmt_chunk(0x11, 1, 8000, 256, 4, &505u16.to_le_bytes());
+        let data = vec![0u8; 32 * 256];
+        assert_eq!(duration(&riff_wav(&adpcm, Some(16000), &data)), Some(2.0));
+        assert_eq!(duration(&riff_wav(&adpcm, None, &data)), None);
+
+        // PCM in a 24-bit container with 20 valid bits is counted by block
+        let pcm = fmt_chunk(1, 2, 48000, 6, 20, &[]);
+        let data = vec![0u8; 48000 * 6];
+        let metadata = {
+            let path = temp_dir.path().join("take.wav");
+            std::fs::write(&path, riff_wav(&pcm, None, &data)).unwrap();
+            read_audio_metadata(&path).unwrap()
+        };
+        assert_eq!(metadata.duration_seconds, Some(1.0));
+        assert_eq!(metadata.bits_per_sample, 20);
+    }
+
+    #[test]
+    fn test_wav_with_overlong_data_chunk() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("recording.wav");
+        let pcm = fmt_chunk(1, 1, 8000, 2, 16, &[]);
+        let wav = riff_wav(&pcm, None, &vec![0u8; 8000 * 2]);
+        let data_size_at = wav.len() - 8000 * 2 - 4;
+
+        // Cut short partway through the data
+        std::fs::write(&path, &wav[..wav.len() - 4000]).unwrap();
+        let metadata = read_audio_metadata(&path).unwrap();
+        assert_eq!(metadata.sample_rate, 8000);
+        assert_eq!(metadata.channels, 1);
+        assert_eq!(metadata.duration_seconds, Some(0.75));
+
+        // Still recording: the sizes are streaming placeholders
+        let mut streaming = wav.clone();
+        streaming[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        streaming[data_size_at..data_size_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &streaming).unwrap();
+        assert_eq!(
+            read_audio_metadata(&path).unwrap().duration_seconds,
+            Some(1.0)
+        );
+    }
+}
@@ -1 +1,2 @@
+pub mod artwork;
 pub mod metadata;
@@ -5,24 +5,76 @@
 //! and save functionality. It handles the main event loop, keyboard input processing,
 //! and state transitions between different modes (playback, browsing, saving).
 
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
+use crossterm::event::{self, Event, KeyCode};
 use log::info;
-use ratatui::{Terminal, backend::CrosstermBackend};
-use std::{error::Error, io, path::PathBuf, time::Duration};
+use ratatui::{Terminal, backend::CrosstermBackend, layout::Rect};
+use std::{
+    error::Error,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use super::audio::AudioEngine;
-use super::browser::Browser;
-use super::save_dialog::SaveDialog;
+use super::PlayerOptions;
+use super::audio::{
+    AudioEngine, AudioInfo, DecodeCancel, PreparedSource, SamplePosition, SourceHandles,
+    prepare_source,
+};
+use super::browser::{Browser, folder_audio_files};
+use super::channel_mode::ChannelMode;
+use super::excerpt::save_excerpt;
+use super::logging::init_logging;
+use super::loudness::TruePeakMeter;
+use super::mark_prompt::{MarkPrompt, MarkTarget, format_timecode};
+use super::metadata_editor::MetadataEditor;
+use super::mixed_source::ReferenceTrack;
+use super::save_dialog::{SaveDialog, ShortSelection};
 use super::telemetry::{AudioTelemetry, TelemetryConfig};
+use super::terminal::{TerminalGuard, copy_to_clipboard};
+use super::test_signal::TestSignal;
 use super::timeline_waveform::{TimelineWaveform, WaveformProgress};
 use super::ui;
 use super::waveform::WaveformBuffer;
-use std::sync::mpsc;
-use zim_studio::utils::sidecar::{SidecarCloneMode, clone_sidecar, get_sidecar_path};
+use crate::tag_vocabulary::TagVocabulary;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+    mpsc,
+};
+use zim_studio::constants::PLAYABLE_EXTENSIONS;
+use zim_studio::utils::gain::{db_to_gain, gain_to_db};
+use zim_studio::utils::sidecar::{
+    SidecarCloneMode, SidecarLocation, clone_sidecar, get_sidecar_path, read_loop_region,
+    read_replay_gain, write_loop_region,
+};
+
+/// How long a browser selection must stay put before its preview is decoded,
+/// so holding an arrow key doesn't decode every file it passes
+const PREFETCH_DELAY: Duration = Duration::from_millis(60);
+
+/// Meter gain in true-level mode: LEDs show RMS as it is
+const TRUE_LEVEL_METER_GAIN: f32 = 1.0;
+
+/// Number of level readings kept for the meter trail
+pub const LEVEL_HISTORY_LEN: usize = 32;
+
+/// Spacing of meter trail readings, so the trail covers a few seconds
+const LEVEL_HISTORY_INTERVAL: Duration = Duration::from_millis(125);
+
+/// Master volume change per key press
+const VOLUME_STEP_DB: f32 = 1.0;
+
+/// Rows PageUp/PageDown scroll the help overlay
+const HELP_PAGE_ROWS: isize = 10;
+
+/// Quietest volume before stepping down mutes the output
+const MIN_VOLUME_DB: f32 = -60.0;
+
+/// A browser preview being decoded on a worker thread
+struct Prefetch {
+    path: String,
+    rx: mpsc::Receiver<Result<PreparedSource, String>>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
@@ -76,23 +128,61 @@ pub struct App {
     pub browser: Browser,
     pub playback_position: f32, // 0.0 to 1.0
     pub duration: Option<std::time::Duration>,
-    pub mark_in: Option<f32>,  // 0.0 to 1.0
-    pub mark_out: Option<f32>, // 0.0 to 1.0
-    edit_counter: u32,         // Track number of edits this session
+    pub mark_in: Option<f32>,             // 0.0 to 1.0
+    pub mark_out: Option<f32>,            // 0.0 to 1.0
+    pub sampler_loop: Option<(f32, f32)>, // Loop from the WAV smpl chunk, 0.0 to 1.0
+    edit_counter: u32,                    // Track number of edits this session
     pub save_dialog: Option<SaveDialog>,
-    pub is_looping: bool,                  // Whether we're looping the selection
+    pub metadata_editor: Option<MetadataEditor>, // Inline tags/description form
+    pub mark_prompt: Option<MarkPrompt>,         // Typed timecode for mark in/out
+    pub tag_vocabulary: Option<TagVocabulary>,   // Project tags, collected on first edit
+    pub show_help: bool,                         // Keybinding help overlay visible
+    pub help_scroll: usize,                      // Rows the help overlay is scrolled down
+    pub viewport: Rect,                          // Terminal area of the last drawn frame
+    pub master_gain: f32, // Output gain applied when the audio engine is created
+    pub meter_gain: f32,  // RMS gain for the level meters from config (cosmetic above 1.0)
+    pub true_level: bool, // Meters show unamplified RMS instead of using meter_gain
+    pub true_peak_meter: bool, // Peak readout oversamples 4x to catch inter-sample peaks
+    pub peak_level: f32,  // Highest sample or true peak since the file loaded (linear)
+    true_peak: Option<TruePeakMeter>, // Oversampling state while true_peak_meter is on
+    pub scope_gain: f32,  // Oscilloscope display amplification from config
+    pub scope_auto_gain: bool, // Oscilloscope fits the recent peak instead of scope_gain
+    pub embed_notes: bool, // Write sidecar notes into the INFO chunk of saved selections
+    pub inherit_excerpt_tags: bool, // Saved selections keep the source's tags
+    pub autoplay_on_load: bool, // Start playing as soon as a file is loaded
+    pub sidecar_location: SidecarLocation, // Where sidecars for saved files are written
+    pub replay_gain: bool, // Apply the replay gain stored in each file's sidecar
+    pub file_replay_gain: Option<f32>, // Replay gain in dB from the loaded file's sidecar
+    pub monitor_frames: usize, // Frames per oscilloscope/meter update
+    pub audio_buffer_frames: Option<u32>, // Output buffer requested when the engine opens
+    pub waveform_image_size: (u32, u32), // Width and height of waveform SVGs
+    pub min_selection: Duration, // Selections shorter than this are flagged
+    pub block_short_selections: bool, // Refuse to save a flagged selection
+    pub is_looping: bool, // Whether we're looping the selection
+    pub loop_count: Option<u32>, // Passes of the selection before stopping (None: forever)
+    pub loops_remaining: Option<u32>, // Passes left in the current loop, counting this one
+    pub reference_file: Option<String>, // Reference track loaded alongside the mix
+    pub solo_reference: bool, // Listening to the reference instead of the mix
+    pub channel_mode: ChannelMode, // Stereo monitoring: solo a channel or swap L/R
     pub show_timeline_while_playing: bool, // Toggle timeline view during playback (default: oscilloscope)
+    pub show_scope: bool, // Draw the oscilloscope area when the terminal is tall enough
+    pub show_samples: bool, // Show the position in sample frames beside the time
+    pub sample_position: Option<SamplePosition>, // Frame position from the audio engine
     pub waveform_display_mode: WaveformDisplayMode, // Line, Scatter, or Vectorscope
     pub view_mode: ViewMode,
     pub telemetry: AudioTelemetry,
-    previous_left_level: f32,           // For slew gate rate calculation
-    previous_right_level: f32,          // For slew gate rate calculation
-    pub editor_message: Option<String>, // Message to show when editor can't open
-    editor_message_timer: Option<std::time::Instant>, // When to clear the message
-    pub playlist: Option<Vec<String>>,  // Playlist of files to play sequentially
-    pub playlist_index: usize,          // Current position in playlist (0-based)
+    pub level_history: std::collections::VecDeque<f32>, // Recent peak levels, oldest first
+    level_history_at: Option<std::time::Instant>,       // When the last trail reading was taken
+    previous_left_level: f32,                           // For slew gate rate calculation
+    previous_right_level: f32,                          // For slew gate rate calculation
+    pub editor_message: Option<String>,                 // Message to show when editor can't open
+    editor_message_timer: Option<std::time::Instant>,   // When to clear the message
+    pub playlist: Option<Vec<String>>,                  // Playlist of files to play sequentially
+    pub playlist_index: usize,                          // Current position in playlist (0-based)
     playlist_total_duration: Option<Duration>, // Cached total duration of all playlist tracks
-    is_loading_track: bool,             // Guard against race conditions during track loading
+    is_loading_track: bool,                    // Guard against race conditions during track loading
+    prefetch: Option<Prefetch>,                // Browser preview decoding in the background
+    prefetch_generation: Arc<AtomicUsize>,     // Bumped to cancel stale prefetches
 }
 
 impl App {
@@ -116,13 +206,49 @@ impl App {
             duration: None,
             mark_in: None,
             mark_out: None,
+            sampler_loop: None,
             edit_counter: 0,
             save_dialog: None,
+            metadata_editor: None,
+            mark_prompt: None,
+            tag_vocabulary: None,
+            show_help: false,
+            help_scroll: 0,
+            viewport: Rect::default(),
+            master_gain: 1.0,
+            meter_gain: 2.0,
+            true_level: false,
+            true_peak_meter: false,
+            peak_level: 0.0,
+            true_peak: None,
+            scope_gain: crate::config::DEFAULT_SCOPE_GAIN,
+            scope_auto_gain: false,
+            embed_notes: false,
+            inherit_excerpt_tags: true,
+            autoplay_on_load: true,
+            sidecar_location: SidecarLocation::default(),
+            replay_gain: true,
+            file_replay_gain: None,
+            monitor_frames: crate::config::DEFAULT_MONITOR_FRAMES,
+            audio_buffer_frames: None,
+            waveform_image_size: crate::config::DEFAULT_WAVEFORM_IMAGE_SIZE,
+            min_selection: Duration::from_millis(crate::config::DEFAULT_MIN_SELECTION_MS as u64),
+            block_short_selections: false,
             is_looping: false,
+            loop_count: None,
+            loops_remaining: None,
+            reference_file: None,
+            solo_reference: false,
+            channel_mode: ChannelMode::default(),
             show_timeline_while_playing: false,
+            show_scope: true,
+            show_samples: false,
+            sample_position: None,
             waveform_display_mode: WaveformDisplayMode::default(),
             view_mode: ViewMode::Player,
             telemetry: AudioTelemetry::new(),
+            level_history: std::collections::VecDeque::with_capacity(LEVEL_HISTORY_LEN),
+            level_history_at: None,
             previous_left_level: 0.0,
             previous_right_level: 0.0,
             editor_message: None,
@@ -131,32 +257,95 @@ impl App {
             playlist_index: 0,
             playlist_total_duration: None,
             is_loading_track: false,
+            prefetch: None,
+            prefetch_generation: Arc::new(AtomicUsize::new(0)),
         }
     }
 
-    pub fn load_file(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
-        // Create audio engine if needed
+    /// Create the audio engine if needed
+    fn ensure_audio_engine(&mut self) -> Result<&mut AudioEngine, Box<dyn Error>> {
         if self.audio_engine.is_none() {
-            let (engine, samples_rx) = AudioEngine::new()?;
+            let (mut engine, samples_rx) = AudioEngine::new(self.audio_buffer_frames)?;
+            engine.set_master_gain(self.output_gain());
+            engine.set_monitor_frames(self.monitor_frames);
+            engine.channel_mode().set(self.channel_mode);
             self.audio_engine = Some(engine);
             self.samples_rx = Some(samples_rx);
         }
+        Ok(self
+            .audio_engine
+            .as_mut()
+            .expect("audio engine was just created"))
+    }
+
+    /// Load a file, playing it straight away if `autoplay_on_load` is set
+    pub fn load_file(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
+        self.load_file_with(path, self.autoplay_on_load)
+    }
+
+    fn load_file_with(&mut self, path: &str, play: bool) -> Result<(), Box<dyn Error>> {
+        let handles = self.ensure_audio_engine()?.source_handles();
+        let source = prepare_source(std::path::Path::new(path), handles)?;
+        self.load_prepared(path, source, play);
+        Ok(())
+    }
+
+    /// Sample rates of the loaded file and of the output device, which differ
+    /// when playback is resampled
+    pub fn sample_rates(&self) -> Option<(u32, u32)> {
+        let engine = self.audio_engine.as_ref()?;
+        Some((
+            engine.info.as_ref()?.sample_rate,
+            engine.output_sample_rate(),
+        ))
+    }
+
+    /// Format of what's loaded: sample rate, bit depth and channel count
+    pub fn audio_info(&self) -> Option<&AudioInfo> {
+        self.audio_engine.as_ref()?.info.as_ref()
+    }
+
+    /// Switch to a decoded file, playing it or leaving it paused at the
+    /// start, and reset per-file state
+    fn load_prepared(&mut self, path: &str, source: PreparedSource, play: bool) {
+        // Whatever was being prefetched is no longer wanted
+        self.cancel_prefetch();
+
+        // Marks and looping belong to the previous file; a saved loop or
+        // sampler loop for this one is restored below
+        self.clear_marks();
+        self.loop_count = None;
+        self.loops_remaining = None;
 
         // Determine if we need to spawn waveform calculation
         let mut should_spawn_waveform = false;
         let path_string = path.to_string();
 
+        self.file_replay_gain = std::fs::read_to_string(get_sidecar_path(
+            std::path::Path::new(path),
+            self.sidecar_location,
+        ))
+        .ok()
+        .and_then(|content| read_replay_gain(&content))
+        .map(|gain| gain as f32);
+        let output_gain = self.output_gain();
+
         // Load the file
         if let Some(engine) = &mut self.audio_engine {
-            engine.load_file(std::path::Path::new(path))?;
+            engine.load_prepared(std::path::Path::new(path), source);
 
             // Update channel info and duration
             if let Some(info) = &engine.info {
                 self.is_stereo = info.channels > 1;
+                log_resampling(info.sample_rate, engine.output_sample_rate());
             }
             self.duration = engine.duration;
 
             self.current_file = Some(path.to_string());
+            engine.set_master_gain(output_gain);
+            self.reference_file = None;
+            self.solo_reference = false;
+            self.level_history.clear();
 
             // Calculate timeline waveform for WAV files (async, non-blocking)
             let path_obj = std::path::Path::new(path);
@@ -174,19 +363,75 @@ impl App {
                 self.waveform_progress = None;
             }
 
-            // Start playback automatically when file is loaded
-            self.is_playing = true;
-            engine.play();
+            self.is_playing = play;
+            if play {
+                engine.play();
+            } else {
+                engine.pause();
+            }
+        }
+
+        self.reset_peak();
+        self.sampler_loop = read_sampler_loop(std::path::Path::new(path));
+        if !self.restore_sidecar_loop(std::path::Path::new(path))
+            && let Some((start, end)) = self.sampler_loop
+        {
+            self.mark_in = Some(start);
+            self.mark_out = Some(end);
+            info!("Using sampler loop from smpl chunk as marks");
         }
 
         // Spawn waveform calculation if needed (outside the engine borrow)
         if should_spawn_waveform {
             self.spawn_waveform_calculation(path_string);
         }
+    }
 
+    /// Decode a browser selection in the background. The result is picked up
+    /// by `poll_prefetch`; starting another prefetch cancels this one.
+    fn start_prefetch(&mut self, path: String) -> Result<(), Box<dyn Error>> {
+        let handles = self.ensure_audio_engine()?.source_handles();
+        let generation = self.prefetch_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let rx = spawn_prefetch_worker(
+            path.clone(),
+            handles,
+            generation,
+            self.prefetch_generation.clone(),
+        );
+        self.prefetch = Some(Prefetch { path, rx });
         Ok(())
     }
 
+    fn cancel_prefetch(&mut self) {
+        if self.prefetch.take().is_some() {
+            self.prefetch_generation.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Hand a finished prefetch to the audio engine (call this in the main loop)
+    pub fn poll_prefetch(&mut self) {
+        let Some(prefetch) = &self.prefetch else {
+            return;
+        };
+        let result = match prefetch.rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.prefetch = None;
+                return;
+            }
+        };
+        let Some(Prefetch { path, .. }) = self.prefetch.take() else {
+            return;
+        };
+
+        match result {
+            // Previews wait for space rather than playing as you scroll
+            Ok(source) => self.load_prepared(&path, source, false),
+            Err(e) => log::warn!("Failed to preview {path}: {e}"),
+        }
+    }
+
     /// Spawn background thread to calculate timeline waveform
     fn spawn_waveform_calculation(&mut self, path: String) {
         let (progress_tx, progress_rx) = mpsc::channel();
@@ -240,21 +485,24 @@ impl App {
         &mut self,
         paths: &[String],
         gains: Option<Vec<f32>>,
+        reference: Option<ReferenceTrack>,
     ) -> Result<(), Box<dyn Error>> {
-        // Create audio engine if needed
-        if self.audio_engine.is_none() {
-            let (engine, samples_rx) = AudioEngine::new()?;
-            self.audio_engine = Some(engine);
-            self.samples_rx = Some(samples_rx);
-        }
+        self.ensure_audio_engine()?;
+
+        // A mix has its own gains rather than any one file's replay gain
+        self.file_replay_gain = None;
+        self.apply_output_gain();
 
         // Load the files for mixing
         if let Some(engine) = &mut self.audio_engine {
-            engine.load_files(paths, gains)?;
+            self.reference_file = reference.as_ref().map(|r| r.path.clone());
+            self.solo_reference = false;
+            engine.load_files(paths, gains, reference)?;
 
             // Update channel info and duration
             if let Some(info) = &engine.info {
                 self.is_stereo = info.channels > 1;
+                log_resampling(info.sample_rate, engine.output_sample_rate());
             }
             self.duration = engine.duration;
 
@@ -266,10 +514,14 @@ impl App {
             };
             self.current_file = Some(display_name);
 
-            // Start playback automatically when files are loaded
-            self.is_playing = true;
-            engine.play();
+            self.is_playing = self.autoplay_on_load;
+            if self.autoplay_on_load {
+                engine.play();
+            } else {
+                engine.pause();
+            }
         }
+        self.reset_peak();
 
         Ok(())
     }
@@ -456,11 +708,15 @@ impl App {
                 self.is_playing = false;
             } else {
                 // If at 100%, restart from beginning
-                if self.playback_position >= 0.99 {
+                let from_start = self.playback_position >= 0.99;
+                if from_start {
                     let _ = engine.seek_relative(-self.duration.unwrap_or_default().as_secs_f32());
                 }
                 engine.play();
                 self.is_playing = true;
+                if from_start {
+                    self.restart_true_peak();
+                }
             }
         }
     }
@@ -469,6 +725,25 @@ impl App {
         self.process_audio_samples();
         self.update_playback_state();
         self.apply_level_decay();
+        self.record_level_history(std::time::Instant::now());
+    }
+
+    /// Add the louder channel to the meter trail, at most once per interval
+    fn record_level_history(&mut self, now: std::time::Instant) {
+        if !self.is_playing {
+            return;
+        }
+        if let Some(last) = self.level_history_at
+            && now.duration_since(last) < LEVEL_HISTORY_INTERVAL
+        {
+            return;
+        }
+        if self.level_history.len() == LEVEL_HISTORY_LEN {
+            self.level_history.pop_front();
+        }
+        self.level_history
+            .push_back(self.left_level.max(self.right_level));
+        self.level_history_at = Some(now);
     }
 
     fn process_audio_samples(&mut self) {
@@ -503,6 +778,7 @@ impl App {
         } else {
             self.calculate_mono_levels(samples);
         }
+        self.update_peak(samples);
 
         // Apply gentler decay for better visibility
         self.left_level = (self.left_level * 0.98).max(self.left_level * 0.8);
@@ -528,6 +804,167 @@ impl App {
         );
     }
 
+    /// Start a new peak hold, for a file with the current channel count
+    fn reset_peak(&mut self) {
+        self.peak_level = 0.0;
+        self.restart_true_peak();
+    }
+
+    /// Clear the true-peak oversampler after the position jumps (a seek or a
+    /// loop wrap), so the step between the old and new audio isn't read as
+    /// an inter-sample peak. The peak hold is kept.
+    fn restart_true_peak(&mut self) {
+        let channels = if self.is_stereo { 2 } else { 1 };
+        self.true_peak = self.true_peak_meter.then(|| TruePeakMeter::new(channels));
+    }
+
+    /// Raise the peak hold to the loudest monitored sample, or to the true
+    /// peak between samples when oversampling is on
+    fn update_peak(&mut self, samples: &[f32]) {
+        let peak = match &mut self.true_peak {
+            Some(meter) => {
+                meter.process(samples);
+                meter.peak() as f32
+            }
+            None => samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs())),
+        };
+        self.peak_level = self.peak_level.max(peak);
+    }
+
+    /// Gain the meters apply to RMS: 1.0 in true-level mode, else the configured gain
+    fn effective_meter_gain(&self) -> f32 {
+        if self.true_level {
+            TRUE_LEVEL_METER_GAIN
+        } else {
+            self.meter_gain
+        }
+    }
+
+    /// Take the settings the player was started with
+    pub fn apply_options(&mut self, options: &PlayerOptions) {
+        self.master_gain = options.master_gain;
+        self.set_meter_gain(options.meter_gain);
+        self.embed_notes = options.embed_notes;
+        self.inherit_excerpt_tags = options.inherit_excerpt_tags;
+        self.autoplay_on_load = options.autoplay_on_load;
+        self.sidecar_location = options.sidecar_location;
+        self.replay_gain = options.replay_gain;
+        self.browser.search_body = options.search_body;
+        self.browser.sidecar_location = options.sidecar_location;
+        self.monitor_frames = options.monitor_frames;
+        self.audio_buffer_frames = options.audio_buffer_frames;
+        self.waveform_image_size = options.waveform_image_size;
+        self.min_selection = options.min_selection;
+        self.block_short_selections = options.block_short_selections;
+        self.true_peak_meter = options.true_peak_meter;
+        self.scope_gain = options.scope_gain;
+        self.scope_auto_gain = options.scope_auto_gain;
+    }
+
+    pub fn set_meter_gain(&mut self, gain: f32) {
+        self.meter_gain = gain;
+        self.telemetry.set_meter_gain(self.effective_meter_gain());
+    }
+
+    /// Switch the meters between the configured gain and true level
+    pub fn toggle_true_level(&mut self) {
+        self.true_level = !self.true_level;
+        self.telemetry.set_meter_gain(self.effective_meter_gain());
+        self.editor_message = Some(if self.true_level {
+            "Meters: true level".to_string()
+        } else {
+            format!("Meters: gain {:.1}", self.meter_gain)
+        });
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    /// Show or hide the sample frame position for sample-accurate edits
+    pub fn toggle_sample_position(&mut self) {
+        self.show_samples = !self.show_samples;
+        self.editor_message = Some(if self.show_samples {
+            "Sample position on".to_string()
+        } else {
+            "Sample position off".to_string()
+        });
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    /// Hide or show the oscilloscope, the most expensive part of each redraw
+    pub fn toggle_scope(&mut self) {
+        self.show_scope = !self.show_scope;
+        self.editor_message = Some(if self.show_scope {
+            "Scope on".to_string()
+        } else {
+            "Scope off".to_string()
+        });
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    /// Jump to `seconds` into what's loaded, for `zim play --start`. Times
+    /// past the end are clamped to it, with a message saying so.
+    pub fn seek_to(&mut self, seconds: f64) {
+        let Some(duration) = self.duration else {
+            return;
+        };
+        let length = duration.as_secs_f64();
+        let target = seconds.clamp(0.0, length);
+        if target < seconds {
+            self.editor_message = Some(format!(
+                "Start {} is past the end ({})",
+                format_timecode(seconds),
+                format_timecode(length)
+            ));
+            self.editor_message_timer = Some(std::time::Instant::now());
+        }
+
+        if let Some(engine) = &mut self.audio_engine {
+            let current = length * engine.get_progress() as f64;
+            if let Err(e) = engine.seek_relative((target - current) as f32) {
+                log::error!("Could not seek to {target:.3}s: {e}");
+                return;
+            }
+        }
+        if length > 0.0 {
+            self.playback_position = (target / length) as f32;
+        }
+        self.restart_true_peak();
+    }
+
+    /// Set up `zim play --start/--end/--loop`: mark `start`..`end` seconds
+    /// (each defaulting to that end of the file) and play the region once,
+    /// stopping at mark out, or loop it. With only a start this just seeks,
+    /// and `--loop` on its own loops the whole file.
+    pub fn apply_play_region(&mut self, start: Option<f64>, end: Option<f64>, looping: bool) {
+        if end.is_none() && !looping {
+            if let Some(start) = start {
+                self.seek_to(start);
+            }
+            return;
+        }
+        let Some(length) = self.duration.map(|d| d.as_secs_f64()).filter(|l| *l > 0.0) else {
+            return;
+        };
+        let start = start.unwrap_or(0.0).clamp(0.0, length);
+        let end = end.unwrap_or(length).clamp(0.0, length);
+        if end <= start {
+            self.editor_message = Some(format!(
+                "Region {}–{} is empty",
+                format_timecode(start),
+                format_timecode(end)
+            ));
+            self.editor_message_timer = Some(std::time::Instant::now());
+            self.seek_to(start);
+            return;
+        }
+
+        self.mark_in = Some((start / length) as f32);
+        self.mark_out = Some((end / length) as f32);
+        self.loop_count = if looping { None } else { Some(1) };
+        self.loops_remaining = self.loop_count;
+        self.is_looping = true;
+        self.seek_to(start);
+    }
+
     fn calculate_stereo_levels(&mut self, samples: &[f32]) {
         let mut left_sum = 0.0;
         let mut right_sum = 0.0;
@@ -544,15 +981,15 @@ impl App {
             }
         }
 
-        // Amplify the RMS values to make LEDs more responsive
-        self.left_level = ((left_sum / left_count.max(1) as f32).sqrt() * 2.0).min(1.0);
-        self.right_level = ((right_sum / right_count.max(1) as f32).sqrt() * 2.0).min(1.0);
+        // Scale RMS by the meter gain (amplified by default to make LEDs more responsive)
+        let gain = self.effective_meter_gain();
+        self.left_level = ((left_sum / left_count.max(1) as f32).sqrt() * gain).min(1.0);
+        self.right_level = ((right_sum / right_count.max(1) as f32).sqrt() * gain).min(1.0);
     }
 
     fn calculate_mono_levels(&mut self, samples: &[f32]) {
         let sum: f32 = samples.iter().map(|s| s * s).sum();
-        // Mono - amplify for better visibility
-        let rms = ((sum / samples.len() as f32).sqrt() * 2.0).min(1.0);
+        let rms = ((sum / samples.len() as f32).sqrt() * self.effective_meter_gain()).min(1.0);
         self.left_level = rms;
         self.right_level = rms;
     }
@@ -563,6 +1000,7 @@ impl App {
 
         if let Some(engine) = &self.audio_engine {
             self.playback_position = engine.get_progress();
+            self.sample_position = engine.sample_position();
 
             // Check if we've reached the end (not looping)
             if !self.is_looping && self.is_playing && self.playback_position >= 1.0 {
@@ -592,8 +1030,14 @@ impl App {
                     if let Some(engine) = &self.audio_engine {
                         engine.pause();
                     }
+                } else if !self.is_playing {
+                    // A playlist that's playing carries on, whatever
+                    // autoplay_on_load says about loading files
+                    self.is_playing = true;
+                    if let Some(engine) = &self.audio_engine {
+                        engine.play();
+                    }
                 }
-                // Note: load_next_track() calls load_file() which starts playback automatically
             } else {
                 // No more tracks, stop playback
                 self.is_playing = false;
@@ -603,9 +1047,16 @@ impl App {
             }
         }
 
+        // A counted loop stops at mark out once its last pass is done
+        if need_loop_seek.is_some() && self.at_loop_end() && !self.count_loop_pass() {
+            need_loop_seek = None;
+            self.finish_counted_loop();
+        }
+
         // Apply loop seek if needed
         if let (Some(offset), Some(engine)) = (need_loop_seek, &mut self.audio_engine) {
             let _ = engine.seek_relative(offset);
+            self.restart_true_peak();
         }
     }
 
@@ -626,6 +1077,101 @@ impl App {
         None
     }
 
+    fn at_loop_end(&self) -> bool {
+        match (self.mark_in, self.mark_out) {
+            (Some(mark_in), Some(mark_out)) => self.playback_position >= mark_in.max(mark_out),
+            _ => false,
+        }
+    }
+
+    /// Record a finished pass of the loop, returning whether to go round again
+    fn count_loop_pass(&mut self) -> bool {
+        match self.loops_remaining {
+            None => true,
+            Some(remaining) if remaining > 1 => {
+                self.loops_remaining = Some(remaining - 1);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    fn finish_counted_loop(&mut self) {
+        self.is_looping = false;
+        self.is_playing = false;
+        self.loops_remaining = self.loop_count;
+        if let Some(engine) = &self.audio_engine {
+            engine.pause();
+        }
+        info!(
+            "Loop finished after {} passes",
+            self.loop_count.unwrap_or(0)
+        );
+    }
+
+    /// Step the master volume by `step_db` decibels. Below `MIN_VOLUME_DB`
+    /// the output is muted, and stepping up from mute starts there.
+    pub fn adjust_volume(&mut self, step_db: f32) {
+        let db = gain_to_db(self.master_gain);
+        let gain = if db == f32::NEG_INFINITY {
+            if step_db > 0.0 {
+                db_to_gain(MIN_VOLUME_DB)
+            } else {
+                0.0
+            }
+        } else if db + step_db < MIN_VOLUME_DB - 0.01 {
+            0.0
+        } else {
+            // Snap to whole steps so repeated presses land on round numbers
+            db_to_gain(((db + step_db) / VOLUME_STEP_DB).round() * VOLUME_STEP_DB)
+        };
+        self.master_gain = gain.min(crate::config::MAX_GAIN);
+        self.apply_output_gain();
+    }
+
+    /// Gain sent to the sink: the master volume, times the loaded file's
+    /// replay gain when that's switched on
+    fn output_gain(&self) -> f32 {
+        match self.file_replay_gain {
+            Some(db) if self.replay_gain => self.master_gain * db_to_gain(db),
+            _ => self.master_gain,
+        }
+    }
+
+    fn apply_output_gain(&self) {
+        if let Some(engine) = &self.audio_engine {
+            engine.set_master_gain(self.output_gain());
+        }
+    }
+
+    /// Switch between honoring and ignoring the replay gain stored in sidecars
+    pub fn toggle_replay_gain(&mut self) {
+        self.replay_gain = !self.replay_gain;
+        self.apply_output_gain();
+        self.editor_message = Some(match (self.replay_gain, self.file_replay_gain) {
+            (false, _) => "Replay gain: off".to_string(),
+            (true, Some(db)) => format!("Replay gain: {db:+.1} dB"),
+            (true, None) => "Replay gain: on (none stored for this file)".to_string(),
+        });
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    /// Cycle the number of loop passes: forever, 1, 2, 4
+    pub fn cycle_loop_count(&mut self) {
+        self.loop_count = match self.loop_count {
+            None => Some(1),
+            Some(1) => Some(2),
+            Some(2) => Some(4),
+            Some(_) => None,
+        };
+        self.loops_remaining = self.loop_count;
+        self.editor_message = Some(match self.loop_count {
+            Some(count) => format!("Loop {count}×, then stop at mark out"),
+            None => "Loop forever".to_string(),
+        });
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
     fn apply_level_decay(&mut self) {
         // Store previous levels for slew gate calculation
         self.previous_left_level = self.left_level;
@@ -669,6 +1215,84 @@ impl App {
         info!("Mark out set at {:.1}%", self.playback_position * 100.0);
     }
 
+    /// Prompt for a typed timecode to set a mark, starting from the existing
+    /// mark or the playhead
+    pub fn open_mark_prompt(&mut self, target: MarkTarget) {
+        let Some(duration) = self.duration.map(|d| d.as_secs_f64()) else {
+            self.editor_message = Some("No file loaded".to_string());
+            self.editor_message_timer = Some(std::time::Instant::now());
+            return;
+        };
+        let position = match target {
+            MarkTarget::In => self.mark_in,
+            MarkTarget::Out => self.mark_out,
+        }
+        .unwrap_or(self.playback_position);
+        self.mark_prompt = Some(MarkPrompt::new(target, position as f64 * duration));
+    }
+
+    /// Apply the typed timecode. An invalid one keeps the prompt open with
+    /// the reason shown.
+    pub fn confirm_mark_prompt(&mut self) {
+        let (Some(prompt), Some(duration)) = (self.mark_prompt.as_mut(), self.duration) else {
+            return;
+        };
+        let duration = duration.as_secs_f64();
+        let other_mark = match prompt.target {
+            MarkTarget::In => self.mark_out,
+            MarkTarget::Out => self.mark_in,
+        };
+        match prompt.resolve(duration, other_mark) {
+            Ok(position) => {
+                let target = prompt.target;
+                match target {
+                    MarkTarget::In => self.mark_in = Some(position),
+                    MarkTarget::Out => self.mark_out = Some(position),
+                }
+                let timecode = format_timecode(position as f64 * duration);
+                info!(
+                    "Mark {} set at {timecode} from typed timecode",
+                    target.name()
+                );
+                self.editor_message = Some(format!("Mark {} set at {timecode}", target.name()));
+                self.editor_message_timer = Some(std::time::Instant::now());
+                self.mark_prompt = None;
+            }
+            Err(e) => prompt.error = Some(e),
+        }
+    }
+
+    /// The marked selection as `mm:ss.mmm - mm:ss.mmm`, earliest first, as
+    /// you'd write it in an edit note
+    pub fn selection_timecodes(&self) -> Option<String> {
+        let duration = self.duration?.as_secs_f64();
+        let (mark_in, mark_out) = (self.mark_in?, self.mark_out?);
+        let (start, end) = (mark_in.min(mark_out), mark_in.max(mark_out));
+        Some(format!(
+            "{} - {}",
+            format_timecode(start as f64 * duration),
+            format_timecode(end as f64 * duration)
+        ))
+    }
+
+    /// Copy the selection's timecodes to the clipboard for sharing an edit
+    /// point, and show them in case the terminal can't copy
+    pub fn copy_selection_timecodes(&mut self) {
+        let message = match self.selection_timecodes() {
+            None if self.duration.is_none() => "No file loaded".to_string(),
+            None => "Set mark in and mark out to copy timecodes".to_string(),
+            Some(timecodes) => match copy_to_clipboard(&timecodes) {
+                Ok(()) => format!("Copied {timecodes}"),
+                Err(e) => {
+                    log::error!("Failed to copy timecodes: {e}");
+                    format!("{timecodes} (copy failed: {e})")
+                }
+            },
+        };
+        self.editor_message = Some(message);
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
     pub fn clear_marks(&mut self) {
         self.mark_in = None;
         self.mark_out = None;
@@ -676,6 +1300,227 @@ impl App {
         info!("Marks cleared");
     }
 
+    /// Show the keybinding help from the top
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+        self.help_scroll = 0;
+    }
+
+    /// Scroll the help overlay by `rows`, stopping once its last binding
+    /// is on screen in the last drawn frame
+    pub fn scroll_help(&mut self, rows: isize) {
+        let max = super::help_ui::max_help_scroll(self.viewport);
+        self.help_scroll = self.help_scroll.saturating_add_signed(rows).min(max);
+    }
+
+    /// Switch between the mix and the reference track when comparing
+    pub fn toggle_reference(&mut self) {
+        let Some(engine) = &self.audio_engine else {
+            return;
+        };
+        if !engine.has_reference() {
+            self.editor_message =
+                Some("No reference track - use 'zim play --reference'".to_string());
+            self.editor_message_timer = Some(std::time::Instant::now());
+            return;
+        }
+        self.solo_reference = engine.toggle_reference();
+        info!(
+            "Listening to {}",
+            if self.solo_reference {
+                "reference"
+            } else {
+                "mix"
+            }
+        );
+    }
+
+    /// Decode the current file again to hear a fresh render from a DAW. The
+    /// marks, playhead position and play/pause state carry over.
+    pub fn reload_current_file(&mut self) {
+        let message = match self.reload_from_disk() {
+            Ok(name) => format!("Reloaded {name}"),
+            Err(e) => {
+                log::error!("Failed to reload: {e}");
+                format!("Can't reload: {e}")
+            }
+        };
+        self.editor_message = Some(message);
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    fn reload_from_disk(&mut self) -> Result<String, Box<dyn Error>> {
+        let engine = self.audio_engine.as_ref().ok_or("No file loaded")?;
+        let path = engine
+            .single_file_path()
+            .ok_or("only a single file can be reloaded, not a mix")?
+            .to_string();
+        let name = std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        if !std::path::Path::new(&path).is_file() {
+            return Err(format!("{name} is no longer on disk").into());
+        }
+
+        let progress = engine.get_progress();
+        let marks = (self.mark_in, self.mark_out);
+        self.load_file_with(&path, self.is_playing)?;
+
+        // Keep the marks set by hand rather than any loop saved in the sidecar
+        (self.mark_in, self.mark_out) = marks;
+        if let (Some(engine), Some(duration)) = (&mut self.audio_engine, self.duration) {
+            engine.seek_relative(duration.as_secs_f32() * progress)?;
+        }
+        info!("Reloaded {path} at {:.1}%", progress * 100.0);
+        Ok(name)
+    }
+
+    /// Load the next (or previous) audio file in the current file's folder,
+    /// wrapping at the ends. Playback carries on, or stays paused.
+    pub fn step_in_folder(&mut self, forward: bool) {
+        let message = match self.load_folder_neighbor(forward) {
+            Ok(message) => message,
+            Err(e) => {
+                log::warn!("Can't step through the folder: {e}");
+                format!("Can't step through the folder: {e}")
+            }
+        };
+        self.editor_message = Some(message);
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    fn load_folder_neighbor(&mut self, forward: bool) -> Result<String, Box<dyn Error>> {
+        let path = self
+            .audio_engine
+            .as_ref()
+            .and_then(|engine| engine.single_file_path())
+            .ok_or("no single file loaded")?
+            .to_string();
+        let current = std::path::Path::new(&path);
+        if TestSignal::from_path(current).is_some() {
+            return Err("a test signal isn't in a folder".into());
+        }
+        let dir = current
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(std::path::Path::new("."));
+
+        let files = folder_audio_files(dir)?;
+        let index = folder_neighbor(&files, current, forward).ok_or("no audio files in it")?;
+        let next = &files[index];
+        if next.file_name() == current.file_name() {
+            return Err("no other audio files in it".into());
+        }
+        self.load_file_with(&next.to_string_lossy(), self.is_playing)?;
+        info!("Stepped to {}", next.display());
+        Ok(format!(
+            "{} ({}/{})",
+            next.file_name().unwrap_or_default().to_string_lossy(),
+            index + 1,
+            files.len()
+        ))
+    }
+
+    /// Switch to a monitoring mode, or back to normal stereo if it's already active
+    pub fn toggle_channel_mode(&mut self, mode: ChannelMode) {
+        if self.current_file.is_some() && !self.is_stereo {
+            self.editor_message = Some("Channel monitoring needs a stereo file".to_string());
+            self.editor_message_timer = Some(std::time::Instant::now());
+            return;
+        }
+
+        self.channel_mode = if self.channel_mode == mode {
+            ChannelMode::Stereo
+        } else {
+            mode
+        };
+        if let Some(engine) = &self.audio_engine {
+            engine.channel_mode().set(self.channel_mode);
+        }
+        info!("Channel monitoring: {}", self.channel_mode.label());
+    }
+
+    /// Step through the mono checks: L+R at -3 dB, at -6 dB, then stereo
+    pub fn cycle_mono_check(&mut self) {
+        let next = match self.channel_mode {
+            ChannelMode::Mono3dB => ChannelMode::Mono6dB,
+            ChannelMode::Mono6dB => ChannelMode::Stereo,
+            _ => ChannelMode::Mono3dB,
+        };
+        self.toggle_channel_mode(next);
+    }
+
+    /// Step through mid/side auditioning: mid only, side only, then stereo
+    pub fn cycle_mid_side(&mut self) {
+        let next = match self.channel_mode {
+            ChannelMode::Mid => ChannelMode::Side,
+            ChannelMode::Side => ChannelMode::Stereo,
+            _ => ChannelMode::Mid,
+        };
+        self.toggle_channel_mode(next);
+    }
+
+    /// Set marks from a loop region saved in the file's sidecar, if any.
+    /// Returns whether marks were set.
+    fn restore_sidecar_loop(&mut self, audio_path: &std::path::Path) -> bool {
+        let Some(duration) = self.duration.map(|d| d.as_secs_f64()) else {
+            return false;
+        };
+        let Some((start, end)) =
+            std::fs::read_to_string(get_sidecar_path(audio_path, self.sidecar_location))
+                .ok()
+                .and_then(|content| read_loop_region(&content))
+        else {
+            return false;
+        };
+
+        if duration > 0.0 && start < duration {
+            self.mark_in = Some((start / duration) as f32);
+            self.mark_out = Some((end / duration).min(1.0) as f32);
+            info!("Restored loop from sidecar: {start:.3}s - {end:.3}s");
+            return true;
+        }
+        false
+    }
+
+    /// Save the current marks to the sidecar as `loop_start`/`loop_end`
+    pub fn save_loop_to_sidecar(&mut self) {
+        let message = match self.write_loop_to_sidecar() {
+            Ok(message) => message,
+            Err(e) => {
+                log::error!("Failed to save loop: {e}");
+                format!("Failed to save loop: {e}")
+            }
+        };
+        self.editor_message = Some(message);
+        self.editor_message_timer = Some(std::time::Instant::now());
+    }
+
+    fn write_loop_to_sidecar(&self) -> Result<String, Box<dyn Error>> {
+        let current_file = self.current_file.as_ref().ok_or("No audio file loaded")?;
+        let (Some(mark_in), Some(mark_out)) = (self.mark_in, self.mark_out) else {
+            return Ok("Set mark in and mark out before saving a loop".to_string());
+        };
+        if mark_in >= mark_out {
+            return Ok("Mark in must come before mark out".to_string());
+        }
+        let duration = self.duration.ok_or("Unknown duration")?.as_secs_f64();
+
+        let sidecar_path =
+            get_sidecar_path(std::path::Path::new(current_file), self.sidecar_location);
+        if !sidecar_path.exists() {
+            return Ok("No sidecar file found - run 'zim update' first".to_string());
+        }
+
+        let start = mark_in as f64 * duration;
+        let end = mark_out as f64 * duration;
+        let content = std::fs::read_to_string(&sidecar_path)?;
+        std::fs::write(&sidecar_path, write_loop_region(&content, start, end)?)?;
+
+        Ok(format!("Loop saved: {start:.2}s - {end:.2}s"))
+    }
+
     pub fn toggle_loop(&mut self) {
         if self.mark_in.is_some() && self.mark_out.is_some() {
             self.is_looping = !self.is_looping;
@@ -688,6 +1533,8 @@ impl App {
                 }
             );
 
+            self.loops_remaining = self.loop_count;
+
             // If starting loop, jump to mark in position
             if self.is_looping
                 && let (Some(mark_in), Some(duration), Some(engine)) =
@@ -697,6 +1544,7 @@ impl App {
                 let current_seconds = duration.as_secs_f32() * self.playback_position;
                 let offset = start_seconds - current_seconds;
                 let _ = engine.seek_relative(offset);
+                self.restart_true_peak();
             }
         } else {
             info!("Cannot loop without both marks set");
@@ -716,6 +1564,12 @@ impl App {
         }
     }
 
+    /// The selection's length when it is shorter than `min_selection`
+    pub fn short_selection(&self) -> Option<Duration> {
+        self.get_selection_duration()
+            .filter(|selection| *selection < self.min_selection)
+    }
+
     pub fn open_save_dialog(&mut self) {
         if let Some(current_file) = &self.current_file {
             let path = std::path::Path::new(current_file);
@@ -744,11 +1598,15 @@ impl App {
                 format!("{base_name}.{extension}")
             };
 
-            self.save_dialog = Some(SaveDialog::new(
-                parent.to_path_buf(),
-                suggested_name,
-                has_selection,
-            ));
+            let mut dialog = SaveDialog::new(parent.to_path_buf(), suggested_name, has_selection);
+            if has_selection && let Some(selection) = self.short_selection() {
+                dialog.short_selection = Some(ShortSelection {
+                    length: selection,
+                    minimum: self.min_selection,
+                    blocked: self.block_short_selections,
+                });
+            }
+            self.save_dialog = Some(dialog);
 
             info!(
                 "Opened save dialog with filename: {}",
@@ -757,15 +1615,25 @@ impl App {
         }
     }
 
+    /// Save the selection, or the whole file, to `path`. Returns how many
+    /// samples of a saved selection were at full scale.
     pub fn save_audio(
         &self,
         path: std::path::PathBuf,
         save_selection: bool,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<usize, Box<dyn Error>> {
         if let Some(current_file) = &self.current_file {
             if save_selection && self.mark_in.is_some() && self.mark_out.is_some() {
                 // Save selection
                 self.save_selection(current_file, path)
+            } else if super::transcode::needs_transcode(std::path::Path::new(current_file), &path) {
+                // Save full file in the format its extension names
+                super::transcode::save_transcoded(
+                    std::path::Path::new(current_file),
+                    &path,
+                    self.sidecar_location,
+                )?;
+                Ok(0)
             } else {
                 // Save full file (copy audio + sidecar)
                 std::fs::copy(current_file, &path)?;
@@ -777,11 +1645,12 @@ impl App {
                     &path,
                     SidecarCloneMode::FullCopy,
                     None,
+                    self.sidecar_location,
                 ) {
                     log::warn!("Failed to create sidecar file: {e}");
                     // Don't fail the entire operation if sidecar creation fails
                 }
-                Ok(())
+                Ok(0)
             }
         } else {
             Err("No file loaded".into())
@@ -792,257 +1661,95 @@ impl App {
         &self,
         source_path: &str,
         dest_path: std::path::PathBuf,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<usize, Box<dyn Error>> {
         let (mark_in, mark_out) = match (self.mark_in, self.mark_out) {
             (Some(a), Some(b)) => (a.min(b), a.max(b)),
             _ => return Err("No selection marks set".into()),
         };
+        let duration_secs = self.duration.map(|d| d.as_secs_f64()).unwrap_or(0.0);
+
+        // Get tags from browser as fallback
+        let tags_fallback: Option<Vec<String>> =
+            if let Some((idx, _)) = self.browser.filtered_indices.get(self.browser.selected) {
+                self.browser
+                    .items
+                    .get(*idx)
+                    .map(|audio_file| audio_file.metadata.tags.clone())
+            } else {
+                None
+            };
 
-        // Determine SOURCE format from extension
-        let source_ext = std::path::Path::new(source_path)
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_default();
+        save_excerpt(
+            std::path::Path::new(source_path),
+            &dest_path,
+            mark_in as f64 * duration_secs,
+            mark_out as f64 * duration_secs,
+            duration_secs,
+            tags_fallback.as_deref(),
+            self.inherit_excerpt_tags,
+            self.embed_notes,
+            self.sidecar_location,
+        )
+    }
 
-        // Save the audio selection first
-        let audio_result = match source_ext.as_str() {
-            "wav" => self.save_wav_selection(source_path, dest_path.clone(), mark_in, mark_out),
-            "flac" => {
-                self.save_flac_to_wav_selection(source_path, dest_path.clone(), mark_in, mark_out)
+    pub fn open_sidecar_in_editor(&mut self) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        // Get the current file path
+        let current_file = match &self.current_file {
+            Some(file) => file,
+            None => {
+                self.editor_message = Some("No audio file loaded".to_string());
+                self.editor_message_timer = Some(std::time::Instant::now());
+                return Ok(None);
             }
-            _ => Err(format!("Unsupported source format: {source_ext}").into()),
         };
 
-        // If audio save succeeded, try to clone and modify the sidecar file
-        if audio_result.is_ok() {
-            // Convert normalized positions to actual time in seconds
-            let duration_secs = self.duration.map(|d| d.as_secs_f32()).unwrap_or(0.0);
-            let start_time = mark_in * duration_secs;
-            let end_time = mark_out * duration_secs;
-
-            // Get tags from browser as fallback
-            let tags_fallback: Option<Vec<String>> =
-                if let Some((idx, _)) = self.browser.filtered_indices.get(self.browser.selected) {
-                    self.browser
-                        .items
-                        .get(*idx)
-                        .map(|audio_file| audio_file.metadata.tags.clone())
-                } else {
-                    None
-                };
+        // Construct sidecar path using utility function
+        let sidecar_path =
+            get_sidecar_path(std::path::Path::new(current_file), self.sidecar_location);
 
-            if let Err(e) = clone_sidecar(
-                std::path::Path::new(source_path),
-                &dest_path,
-                SidecarCloneMode::Selection {
-                    start_time,
-                    end_time,
-                    duration: duration_secs,
-                },
-                tags_fallback.as_deref(),
-            ) {
-                log::warn!("Failed to create sidecar file: {e}");
-                // Don't fail the entire operation if sidecar creation fails
-            }
+        // Check if sidecar exists
+        if !sidecar_path.exists() {
+            self.editor_message =
+                Some("No sidecar file found - run 'zim update' first".to_string());
+            self.editor_message_timer = Some(std::time::Instant::now());
+            return Ok(None);
         }
 
-        audio_result
+        // Return the path to signal that we can open the editor
+        Ok(Some(sidecar_path))
     }
 
-    fn save_wav_selection(
-        &self,
-        source_path: &str,
-        dest_path: std::path::PathBuf,
-        start: f32,
-        end: f32,
-    ) -> Result<(), Box<dyn Error>> {
-        use hound::{WavReader, WavWriter};
-        use std::fs::File;
-        use std::io::BufReader;
-
-        // Open source file
-        let reader = WavReader::new(BufReader::new(File::open(source_path)?))?;
-        let spec = reader.spec();
-
-        // Calculate sample range
-        let (start_sample, samples_to_write) = self.calculate_sample_range(&reader, start, end);
-
-        // Create output file
-        let mut writer = WavWriter::create(&dest_path, spec)?;
-
-        // Read and write samples based on bit depth
-        self.copy_wav_samples(
-            reader,
-            &mut writer,
-            spec.bits_per_sample,
-            start_sample,
-            samples_to_write,
-        )?;
-
-        writer.finalize()?;
-        info!("Saved WAV selection to: {dest_path:?}");
-        Ok(())
-    }
+    /// Open the inline tags/description form for the current file's sidecar
+    pub fn open_metadata_editor(&mut self) {
+        let Some(sidecar_path) = self.open_sidecar_in_editor().ok().flatten() else {
+            return;
+        };
 
-    fn calculate_sample_range(
-        &self,
-        reader: &hound::WavReader<std::io::BufReader<std::fs::File>>,
-        start: f32,
-        end: f32,
-    ) -> (usize, usize) {
-        let total_samples = reader.len() as usize;
-        let start_sample = (start * total_samples as f32) as usize;
-        let end_sample = (end * total_samples as f32) as usize;
-        let samples_to_write = end_sample - start_sample;
-        (start_sample, samples_to_write)
-    }
-
-    fn copy_wav_samples<W: std::io::Write + std::io::Seek>(
-        &self,
-        mut reader: hound::WavReader<std::io::BufReader<std::fs::File>>,
-        writer: &mut hound::WavWriter<W>,
-        bits_per_sample: u16,
-        start_sample: usize,
-        samples_to_write: usize,
-    ) -> Result<(), Box<dyn Error>> {
-        match bits_per_sample {
-            16 => self.copy_samples::<i16, _>(&mut reader, writer, start_sample, samples_to_write),
-            24 | 32 => {
-                self.copy_samples::<i32, _>(&mut reader, writer, start_sample, samples_to_write)
-            }
-            8 => self.copy_samples::<i8, _>(&mut reader, writer, start_sample, samples_to_write),
-            _ => Err(format!("Unsupported bit depth: {bits_per_sample}").into()),
-        }
-    }
-
-    fn copy_samples<T, W>(
-        &self,
-        reader: &mut hound::WavReader<std::io::BufReader<std::fs::File>>,
-        writer: &mut hound::WavWriter<W>,
-        start_sample: usize,
-        samples_to_write: usize,
-    ) -> Result<(), Box<dyn Error>>
-    where
-        T: hound::Sample + std::fmt::Debug,
-        W: std::io::Write + std::io::Seek,
-    {
-        let samples: Vec<T> = reader
-            .samples::<T>()
-            .skip(start_sample)
-            .take(samples_to_write)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        for sample in samples {
-            writer.write_sample(sample)?;
-        }
-
-        Ok(())
-    }
-
-    fn save_flac_to_wav_selection(
-        &self,
-        source_path: &str,
-        dest_path: std::path::PathBuf,
-        start: f32,
-        end: f32,
-    ) -> Result<(), Box<dyn Error>> {
-        use claxon::FlacReader;
-        use hound::{WavSpec, WavWriter};
-
-        // Open FLAC file
-        let reader = FlacReader::open(source_path)?;
-        let info = reader.streaminfo();
-
-        // Calculate sample range
-        let total_samples = info.samples.unwrap_or(0) as usize;
-        let start_sample = (start * total_samples as f32) as usize;
-        let end_sample = (end * total_samples as f32) as usize;
-
-        // Create WAV spec from FLAC info
-        let spec = WavSpec {
-            channels: info.channels as u16,
-            sample_rate: info.sample_rate,
-            bits_per_sample: 16, // Convert to 16-bit for compatibility
-            sample_format: hound::SampleFormat::Int,
-        };
-
-        // Create output WAV file
-        let mut writer = WavWriter::create(&dest_path, spec)?;
-
-        // Read and convert samples
-        self.convert_flac_samples(
-            reader,
-            &mut writer,
-            info.bits_per_sample,
-            start_sample,
-            end_sample,
-        )?;
-
-        writer.finalize()?;
-        info!("Saved FLAC selection as WAV to: {dest_path:?}");
-        Ok(())
-    }
-
-    fn convert_flac_samples<W: std::io::Write + std::io::Seek>(
-        &self,
-        mut reader: claxon::FlacReader<std::fs::File>,
-        writer: &mut hound::WavWriter<W>,
-        bits_per_sample: u32,
-        start_sample: usize,
-        end_sample: usize,
-    ) -> Result<(), Box<dyn Error>> {
-        let mut sample_count = 0;
-
-        for sample in reader.samples() {
-            if sample_count >= start_sample && sample_count < end_sample {
-                let sample = sample?;
-                let sample_i16 = self.convert_sample_to_16bit(sample, bits_per_sample);
-                writer.write_sample(sample_i16)?;
-            }
-
-            sample_count += 1;
-            if sample_count >= end_sample {
-                break;
-            }
-        }
-
-        Ok(())
-    }
-
-    fn convert_sample_to_16bit(&self, sample: i32, bits_per_sample: u32) -> i16 {
-        match bits_per_sample {
-            16 => sample as i16,
-            24 => (sample >> 8) as i16,
-            32 => (sample >> 16) as i16,
-            _ => (sample >> (bits_per_sample - 16)) as i16,
-        }
-    }
+        // Collect project tags once per session, reusing the browser scan if present
+        let browser = &self.browser;
+        let vocabulary = self
+            .tag_vocabulary
+            .get_or_insert_with(|| {
+                if browser.items.is_empty() {
+                    TagVocabulary::scan(std::path::Path::new(".")).unwrap_or_default()
+                } else {
+                    TagVocabulary::from_tag_lists(
+                        browser
+                            .items
+                            .iter()
+                            .map(|item| item.metadata.tags.as_slice()),
+                    )
+                }
+            })
+            .clone();
 
-    pub fn open_sidecar_in_editor(&mut self) -> Result<Option<PathBuf>, Box<dyn Error>> {
-        // Get the current file path
-        let current_file = match &self.current_file {
-            Some(file) => file,
-            None => {
-                self.editor_message = Some("No audio file loaded".to_string());
+        match MetadataEditor::from_sidecar(sidecar_path) {
+            Ok(editor) => self.metadata_editor = Some(editor.with_vocabulary(vocabulary)),
+            Err(e) => {
+                self.editor_message = Some(format!("Cannot edit sidecar: {e}"));
                 self.editor_message_timer = Some(std::time::Instant::now());
-                return Ok(None);
             }
-        };
-
-        // Construct sidecar path using utility function
-        let sidecar_path = get_sidecar_path(std::path::Path::new(current_file));
-
-        // Check if sidecar exists
-        if !sidecar_path.exists() {
-            self.editor_message =
-                Some("No sidecar file found - run 'zim update' first".to_string());
-            self.editor_message_timer = Some(std::time::Instant::now());
-            return Ok(None);
         }
-
-        // Return the path to signal that we can open the editor
-        Ok(Some(sidecar_path))
     }
 
     pub fn launch_editor(&mut self, sidecar_path: PathBuf) -> Result<(), Box<dyn Error>> {
@@ -1108,20 +1815,20 @@ impl App {
 pub fn run_with_file(
     file_path: Option<&str>,
     _gains: Option<Vec<f32>>,
+    options: &PlayerOptions,
 ) -> Result<(), Box<dyn Error>> {
     // Initialize logging
     init_logging()?;
     info!("Starting ZIM Audio Player");
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Setup terminal; the guard restores it however this function exits
+    let mut guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and load file if provided
     let mut app = App::new();
+    app.apply_options(options);
 
     // Scan current directory for audio files
     info!("Scanning directory for audio files...");
@@ -1129,18 +1836,9 @@ pub fn run_with_file(
         log::error!("Could not scan directory: {e}");
     }
 
-    if let Some(path) = file_path
-        && let Err(e) = app.load_file(path)
-    {
-        // Clean up terminal before showing error
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-        return Err(e);
+    if let Some(path) = file_path {
+        app.load_file(path)?;
+        app.apply_play_region(options.start, options.end, options.loop_region);
     }
 
     loop {
@@ -1154,13 +1852,7 @@ pub fn run_with_file(
                         // Store terminal state
                         let terminal_state_result = (|| -> Result<(), Box<dyn Error>> {
                             // Temporarily restore terminal for editor
-                            disable_raw_mode()?;
-                            execute!(
-                                terminal.backend_mut(),
-                                LeaveAlternateScreen,
-                                DisableMouseCapture
-                            )?;
-                            terminal.show_cursor()?;
+                            guard.leave()?;
                             Ok(())
                         })();
 
@@ -1177,12 +1869,7 @@ pub fn run_with_file(
 
                             // Always attempt to restore terminal state, even if editor failed
                             let restore_result = (|| -> Result<(), Box<dyn Error>> {
-                                enable_raw_mode()?;
-                                execute!(
-                                    terminal.backend_mut(),
-                                    EnterAlternateScreen,
-                                    EnableMouseCapture
-                                )?;
+                                guard.resume()?;
                                 terminal.hide_cursor()?;
                                 terminal.clear()?;
                                 Ok(())
@@ -1212,13 +1899,7 @@ pub fn run_with_file(
             }
             Err(e) => {
                 // Restore terminal before showing error
-                disable_raw_mode()?;
-                execute!(
-                    terminal.backend_mut(),
-                    LeaveAlternateScreen,
-                    DisableMouseCapture
-                )?;
-                terminal.show_cursor()?;
+                guard.leave()?;
 
                 eprintln!("Error: {e}");
                 return Err(e);
@@ -1231,13 +1912,7 @@ pub fn run_with_file(
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    guard.leave()?;
 
     Ok(())
 }
@@ -1245,33 +1920,24 @@ pub fn run_with_file(
 pub fn run_with_files(
     file_paths: &[String],
     gains: Option<Vec<f32>>,
+    options: &PlayerOptions,
 ) -> Result<(), Box<dyn Error>> {
     // Initialize logging
     init_logging()?;
     info!("Starting ZIM Audio Player in mixing mode");
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Setup terminal; the guard restores it however this function exits
+    let mut guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and load files for mixing
     let mut app = App::new();
+    app.apply_options(options);
 
     // Load multiple files
-    if let Err(e) = app.load_files(file_paths, gains) {
-        // Clean up terminal before showing error
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-        return Err(e);
-    }
+    app.load_files(file_paths, gains, options.reference.clone())?;
+    app.apply_play_region(options.start, options.end, options.loop_region);
 
     loop {
         let res = run_app(&mut terminal, &mut app);
@@ -1284,13 +1950,7 @@ pub fn run_with_files(
                         // Store terminal state
                         let terminal_state_result = (|| -> Result<(), Box<dyn Error>> {
                             // Temporarily restore terminal for editor
-                            disable_raw_mode()?;
-                            execute!(
-                                terminal.backend_mut(),
-                                LeaveAlternateScreen,
-                                DisableMouseCapture
-                            )?;
-                            terminal.show_cursor()?;
+                            guard.leave()?;
                             Ok(())
                         })();
 
@@ -1307,12 +1967,7 @@ pub fn run_with_files(
 
                             // Always attempt to restore terminal state, even if editor failed
                             let restore_result = (|| -> Result<(), Box<dyn Error>> {
-                                enable_raw_mode()?;
-                                execute!(
-                                    terminal.backend_mut(),
-                                    EnterAlternateScreen,
-                                    EnableMouseCapture
-                                )?;
+                                guard.resume()?;
                                 terminal.hide_cursor()?;
                                 terminal.clear()?;
                                 Ok(())
@@ -1342,13 +1997,7 @@ pub fn run_with_files(
             }
             Err(e) => {
                 // Restore terminal before showing error
-                disable_raw_mode()?;
-                execute!(
-                    terminal.backend_mut(),
-                    LeaveAlternateScreen,
-                    DisableMouseCapture
-                )?;
-                terminal.show_cursor()?;
+                guard.leave()?;
 
                 eprintln!("Error: {e}");
                 return Err(e);
@@ -1361,18 +2010,15 @@ pub fn run_with_files(
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    guard.leave()?;
 
     Ok(())
 }
 
-pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
+pub fn run_with_playlist(
+    file_paths: &[String],
+    options: &PlayerOptions,
+) -> Result<(), Box<dyn Error>> {
     // Initialize logging
     init_logging()?;
     info!(
@@ -1380,15 +2026,14 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
         file_paths.len()
     );
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Setup terminal; the guard restores it however this function exits
+    let mut guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and set up playlist
     let mut app = App::new();
+    app.apply_options(options);
 
     // Scan current directory for audio files (for browser functionality)
     info!("Scanning directory for audio files...");
@@ -1401,19 +2046,10 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
     app.playlist_index = 0;
     app.playlist_total_duration = App::calculate_playlist_total_duration(file_paths);
 
-    // Load first track
-    if !file_paths.is_empty()
-        && let Err(e) = app.load_file(&file_paths[0])
-    {
-        // Clean up terminal before showing error
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-        return Err(e);
+    // Load first track; --start and --end only apply to it
+    if let Some(first) = file_paths.first() {
+        app.load_file(first)?;
+        app.apply_play_region(options.start, options.end, options.loop_region);
     }
 
     loop {
@@ -1427,13 +2063,7 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
                         // Store terminal state
                         let terminal_state_result = (|| -> Result<(), Box<dyn Error>> {
                             // Temporarily restore terminal for editor
-                            disable_raw_mode()?;
-                            execute!(
-                                terminal.backend_mut(),
-                                LeaveAlternateScreen,
-                                DisableMouseCapture
-                            )?;
-                            terminal.show_cursor()?;
+                            guard.leave()?;
                             Ok(())
                         })();
 
@@ -1450,12 +2080,7 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
 
                             // Always attempt to restore terminal state, even if editor failed
                             let restore_result = (|| -> Result<(), Box<dyn Error>> {
-                                enable_raw_mode()?;
-                                execute!(
-                                    terminal.backend_mut(),
-                                    EnterAlternateScreen,
-                                    EnableMouseCapture
-                                )?;
+                                guard.resume()?;
                                 terminal.hide_cursor()?;
                                 terminal.clear()?;
                                 Ok(())
@@ -1485,13 +2110,7 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
             }
             Err(e) => {
                 // Restore terminal before showing error
-                disable_raw_mode()?;
-                execute!(
-                    terminal.backend_mut(),
-                    LeaveAlternateScreen,
-                    DisableMouseCapture
-                )?;
-                terminal.show_cursor()?;
+                guard.leave()?;
 
                 eprintln!("Error: {e}");
                 return Err(e);
@@ -1504,13 +2123,7 @@ pub fn run_with_playlist(file_paths: &[String]) -> Result<(), Box<dyn Error>> {
     }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    guard.leave()?;
 
     Ok(())
 }
@@ -1529,6 +2142,9 @@ where
         // Poll for timeline waveform calculation updates
         app.poll_waveform_updates();
 
+        // Start a browser preview once it has decoded
+        app.poll_prefetch();
+
         // Clear editor message after 3 seconds
         if let Some(timer) = app.editor_message_timer
             && timer.elapsed() > Duration::from_secs(3)
@@ -1538,7 +2154,10 @@ where
             app.editor_message_timer.take();
         }
 
-        terminal.draw(|f| ui::draw(f, app))?;
+        terminal.draw(|f| {
+            app.viewport = f.area();
+            ui::draw(f, app);
+        })?;
 
         // Poll for events with a short timeout to allow continuous rendering
         if event::poll(Duration::from_millis(50))?
@@ -1554,8 +2173,43 @@ where
 }
 
 fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn Error>> {
-    if app.save_dialog.is_some() {
+    // Raw mode delivers Ctrl+C as a key rather than SIGINT, so quit here. The
+    // browser search box keeps it for clearing the query.
+    let searching = app.view_mode == ViewMode::Browser
+        && app.browser.focus == super::browser::BrowserFocus::Search;
+    if key.code == KeyCode::Char('c')
+        && key.modifiers.contains(event::KeyModifiers::CONTROL)
+        && !searching
+    {
+        app.should_quit = true;
+        return Ok(());
+    }
+
+    if app.show_help {
+        // Help overlay swallows all keys until dismissed
+        match key.code {
+            KeyCode::Char('?') | KeyCode::Esc => app.show_help = false,
+            KeyCode::Char('j') | KeyCode::Down => app.scroll_help(1),
+            KeyCode::Char('k') | KeyCode::Up => app.scroll_help(-1),
+            KeyCode::PageDown => app.scroll_help(HELP_PAGE_ROWS),
+            KeyCode::PageUp => app.scroll_help(-HELP_PAGE_ROWS),
+            _ => {}
+        }
+        Ok(())
+    } else if app.save_dialog.is_some() {
         handle_save_dialog_keys(app, key)
+    } else if app.metadata_editor.is_some() {
+        handle_metadata_editor_keys(app, key);
+        Ok(())
+    } else if let Some(prompt) = app.mark_prompt.as_mut() {
+        match key.code {
+            KeyCode::Esc => app.mark_prompt = None,
+            KeyCode::Enter => app.confirm_mark_prompt(),
+            KeyCode::Backspace => prompt.pop_char(),
+            KeyCode::Char(c) => prompt.push_char(c),
+            _ => {}
+        }
+        Ok(())
     } else {
         match app.view_mode {
             ViewMode::Player => handle_player_keys(app, key),
@@ -1564,11 +2218,24 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn E
     }
 }
 
+#[allow(clippy::collapsible_match)]
 fn handle_save_dialog_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn Error>> {
     use super::save_dialog::SaveDialogFocus;
 
     let save_dialog = app.save_dialog.as_mut().unwrap();
 
+    // The go-to and new folder prompts take every key until applied or cancelled
+    if save_dialog.is_prompting() {
+        match key.code {
+            KeyCode::Esc => save_dialog.cancel_entry(),
+            KeyCode::Enter => save_dialog.confirm_entry(),
+            KeyCode::Backspace => save_dialog.pop_char(),
+            KeyCode::Char(c) => save_dialog.push_char(c),
+            _ => {}
+        }
+        return Ok(());
+    }
+
     match key.code {
         KeyCode::Esc => {
             app.save_dialog = None;
@@ -1586,6 +2253,21 @@ fn handle_save_dialog_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Bo
                 save_dialog.navigate_down();
             }
         }
+        KeyCode::Char('l') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            save_dialog.toggle_sampler_loop();
+        }
+        KeyCode::Char('w') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            save_dialog.toggle_waveform_image();
+        }
+        KeyCode::Char('f') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            save_dialog.cycle_format();
+        }
+        KeyCode::Char('g') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            save_dialog.start_path_entry();
+        }
+        KeyCode::Char('n') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
+            save_dialog.start_new_folder();
+        }
         KeyCode::Enter => {
             if save_dialog.focus == SaveDialogFocus::DirectoryList {
                 save_dialog.enter_directory();
@@ -1604,18 +2286,104 @@ fn handle_save_dialog_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Bo
     Ok(())
 }
 
+fn handle_metadata_editor_keys(app: &mut App, key: event::KeyEvent) {
+    let editor = app.metadata_editor.as_mut().unwrap();
+
+    match key.code {
+        KeyCode::Esc => {
+            app.metadata_editor = None;
+        }
+        KeyCode::Tab => {
+            // Tab completes a suggested tag, otherwise switches fields
+            let completed = editor.accept_suggestion();
+            if !completed {
+                editor.toggle_focus();
+            }
+        }
+        KeyCode::BackTab => editor.toggle_focus(),
+        KeyCode::Down => editor.select_next_suggestion(),
+        KeyCode::Up => editor.select_previous_suggestion(),
+        KeyCode::Enter => {
+            let message = match editor.save() {
+                Ok(()) => {
+                    if let Some(vocabulary) = app.tag_vocabulary.as_mut() {
+                        vocabulary.record_change(&editor.original_tags, &editor.parsed_tags());
+                    }
+                    let audio_path = app.current_file.clone().unwrap_or_default();
+                    app.browser.refresh_item(std::path::Path::new(&audio_path));
+                    "Metadata saved".to_string()
+                }
+                Err(e) => {
+                    log::error!("Failed to save metadata: {e}");
+                    format!("Failed to save metadata: {e}")
+                }
+            };
+            app.editor_message = Some(message);
+            app.editor_message_timer = Some(std::time::Instant::now());
+            app.metadata_editor = None;
+        }
+        KeyCode::Backspace => editor.pop_char(),
+        KeyCode::Char(c) => editor.push_char(c),
+        _ => {}
+    }
+}
+
 fn execute_save(app: &mut App) -> Result<(), Box<dyn Error>> {
     if let Some(save_dialog) = &app.save_dialog {
+        // The dialog already says why; keep it open so the marks can be fixed
+        if save_dialog
+            .short_selection
+            .is_some_and(|short| short.blocked)
+        {
+            info!("Not saving a selection shorter than the minimum");
+            return Ok(());
+        }
         let save_path = save_dialog.get_full_path();
         let has_selection = save_dialog.has_selection;
+        let write_sampler_loop = save_dialog.write_sampler_loop;
+        let write_waveform_image = save_dialog.write_waveform_image;
         info!("Saving to: {save_path:?}");
 
         // Perform the save
-        if let Err(e) = app.save_audio(save_path, has_selection) {
-            log::error!("Failed to save audio: {e}");
-            return Err(e);
-        } else {
-            app.edit_counter += 1;
+        match app.save_audio(save_path.clone(), has_selection) {
+            Err(e) => {
+                log::error!("Failed to save audio: {e}");
+                return Err(e);
+            }
+            Ok(clipped) => {
+                app.edit_counter += 1;
+                // Full scale almost always means the source or a gain change
+                // clipped, so flag it without stopping the save
+                if clipped > 0 {
+                    log::warn!("Saved {save_path:?} with {clipped} clipped samples");
+                    app.editor_message = Some(format!(
+                        "Saved, but {clipped} samples hit full scale (clipping)"
+                    ));
+                    app.editor_message_timer = Some(std::time::Instant::now());
+                }
+            }
+        }
+
+        if has_selection && write_sampler_loop {
+            // The saved selection is the loop, so it repeats end to end in a sampler
+            if let Err(e) = write_whole_file_loop(&save_path) {
+                log::error!("Failed to write sampler loop: {e}");
+                app.editor_message = Some(format!("Saved, but failed to write loop: {e}"));
+                app.editor_message_timer = Some(std::time::Instant::now());
+            }
+        }
+
+        if has_selection && write_waveform_image {
+            let (width, height) = app.waveform_image_size;
+            match super::waveform_image::write_waveform_svg(&save_path, width, height) {
+                Ok(svg_path) => info!("Wrote waveform image: {svg_path:?}"),
+                Err(e) => {
+                    log::error!("Failed to write waveform image: {e}");
+                    app.editor_message =
+                        Some(format!("Saved, but failed to write waveform image: {e}"));
+                    app.editor_message_timer = Some(std::time::Instant::now());
+                }
+            }
         }
     }
 
@@ -1623,6 +2391,55 @@ fn execute_save(app: &mut App) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Note in the log when rodio will resample the source for the device
+fn log_resampling(source_rate: u32, output_rate: u32) {
+    if source_rate != output_rate {
+        info!("Resampling {source_rate} Hz to the output device's {output_rate} Hz");
+    }
+}
+
+/// Read the first sampler loop of a WAV file as normalized positions
+fn read_sampler_loop(path: &std::path::Path) -> Option<(f32, f32)> {
+    let is_wav = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return None;
+    }
+
+    let sample_loop = *crate::wav_metadata::read_sample_loops(path).ok()?.first()?;
+    let frames = hound::WavReader::open(path).ok()?.duration();
+    if frames == 0 || sample_loop.start >= frames {
+        return None;
+    }
+
+    // The loop end is the last frame played, so the region runs to the frame after it
+    let start = sample_loop.start as f32 / frames as f32;
+    let end = ((sample_loop.end as f32 + 1.0) / frames as f32).min(1.0);
+    info!(
+        "Found sampler loop: frames {} - {}",
+        sample_loop.start, sample_loop.end
+    );
+    Some((start, end))
+}
+
+/// Mark a saved WAV as one loop spanning the whole file
+fn write_whole_file_loop(path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    let frames = hound::WavReader::open(path)?.duration();
+    if frames == 0 {
+        return Err("Saved selection is empty".into());
+    }
+    crate::wav_metadata::write_sample_loop(
+        path,
+        crate::wav_metadata::SampleLoop {
+            start: 0,
+            end: frames - 1,
+        },
+    )
+}
+
+#[allow(clippy::collapsible_match)]
 fn handle_integrated_browser_keys(
     app: &mut App,
     key: event::KeyEvent,
@@ -1702,6 +2519,10 @@ fn handle_integrated_browser_keys(
                     // Show search box when / is pressed in file list
                     app.browser.show_search();
                 }
+                KeyCode::Char('?') => app.open_help(),
+                KeyCode::Char('w') => app.browser.show_waveform = !app.browser.show_waveform,
+                KeyCode::Char('f') => app.browser.show_full_path = !app.browser.show_full_path,
+                KeyCode::Char('g') => app.browser.cycle_grouping(),
                 KeyCode::Char('h') => {
                     // Seek backward
                     if app.current_file.is_some() {
@@ -1724,17 +2545,18 @@ fn handle_integrated_browser_keys(
                 }
                 KeyCode::Char(' ') => {
                     // Toggle play/pause (only in Files focus)
-                    // First ensure we have the selected file loaded
                     if let Some(path) = app.browser.get_selected_path() {
                         let path_str = path.to_string_lossy().to_string();
 
-                        // If no file is loaded or it's different from the selected one, load it
+                        // Take a prefetch that has just finished, otherwise
+                        // load the selection now so space never plays the
+                        // previous file
+                        app.poll_prefetch();
                         if app.current_file.as_ref() != Some(&path_str) {
-                            preview_selected_file(app)?;
+                            app.load_file_with(&path_str, true)?;
+                        } else {
+                            app.toggle_playback();
                         }
-
-                        // Now toggle playback
-                        app.toggle_playback();
                     }
                 }
                 KeyCode::Enter => {
@@ -1796,26 +2618,52 @@ fn preview_selected_file(app: &mut App) -> Result<(), Box<dyn Error>> {
 
     if let Some(path_str) = selected_path {
         // Only load if it's an audio file and different from current
-        if (path_str.ends_with(".wav")
-            || path_str.ends_with(".flac")
-            || path_str.ends_with(".aiff")
-            || path_str.ends_with(".aif"))
-            && app.current_file.as_ref() != Some(&path_str)
-        {
-            app.load_file(&path_str)?;
-            // Don't auto-play, let user control with space
-            app.is_playing = false;
-            if let Some(engine) = &app.audio_engine {
-                engine.pause();
+        let playable = Path::new(&path_str)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| PLAYABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if playable && app.current_file.as_ref() != Some(&path_str) {
+            // Decode in the background so scanning through large files stays responsive
+            if app.prefetch.as_ref().map(|p| &p.path) != Some(&path_str) {
+                app.start_prefetch(path_str)?;
             }
+        } else {
+            app.cancel_prefetch();
         }
     }
     Ok(())
 }
 
+/// Decode `path` on a worker thread, giving up as soon as `generation` is
+/// no longer current: before the selection has settled or partway through
+/// the decode
+fn spawn_prefetch_worker(
+    path: String,
+    handles: SourceHandles,
+    generation: usize,
+    current_generation: Arc<AtomicUsize>,
+) -> mpsc::Receiver<Result<PreparedSource, String>> {
+    let (tx, rx) = mpsc::channel();
+    let cancel = DecodeCancel::new(generation, current_generation);
+    std::thread::spawn(move || {
+        std::thread::sleep(PREFETCH_DELAY);
+        if cancel.is_cancelled() {
+            return;
+        }
+        let handles = handles.with_cancel(cancel.clone());
+        let result = prepare_source(std::path::Path::new(&path), handles);
+        if cancel.is_cancelled() {
+            return;
+        }
+        let _ = tx.send(result.map_err(|e| e.to_string()));
+    });
+    rx
+}
+
 fn handle_player_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn Error>> {
     match key.code {
         KeyCode::Char('q') => app.should_quit = true,
+        KeyCode::Char('?') => app.open_help(),
         KeyCode::Char(' ') => app.toggle_playback(),
         KeyCode::Char('b') => {
             app.view_mode = ViewMode::Browser;
@@ -1845,13 +2693,32 @@ fn handle_player_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn
         }
         KeyCode::Char('[') | KeyCode::Char('i') => app.set_mark_in(),
         KeyCode::Char(']') | KeyCode::Char('o') => app.set_mark_out(),
+        KeyCode::Char('I') => app.open_mark_prompt(MarkTarget::In),
+        KeyCode::Char('O') => app.open_mark_prompt(MarkTarget::Out),
         KeyCode::Char('x') => app.clear_marks(),
+        KeyCode::Char('y') => app.copy_selection_timecodes(),
+        KeyCode::Char('#') => app.toggle_sample_position(),
         KeyCode::Char('s') => app.open_save_dialog(),
+        KeyCode::Char('R') => app.reload_current_file(),
         KeyCode::Char('l') => app.toggle_loop(),
+        KeyCode::Char('L') => app.save_loop_to_sidecar(),
+        KeyCode::Char('c') => app.cycle_loop_count(),
+        KeyCode::Char('-') => app.adjust_volume(-VOLUME_STEP_DB),
+        KeyCode::Char('+' | '=') => app.adjust_volume(VOLUME_STEP_DB),
+        KeyCode::Char('r') => app.toggle_reference(),
+        KeyCode::Char(',') => app.toggle_channel_mode(ChannelMode::SoloLeft),
+        KeyCode::Char('.') => app.toggle_channel_mode(ChannelMode::SoloRight),
+        KeyCode::Char('X') => app.toggle_channel_mode(ChannelMode::Swap),
+        KeyCode::Char('M') => app.cycle_mono_check(),
+        KeyCode::Char('W') => app.cycle_mid_side(),
         KeyCode::Char('e') => {
             // Signal that we want to open editor
             return Err("EDITOR_REQUESTED".into());
         }
+        KeyCode::Char('E') => app.open_metadata_editor(),
+        KeyCode::Char('v') => app.toggle_true_level(),
+        KeyCode::Char('G') => app.toggle_replay_gain(),
+        KeyCode::Char('S') => app.toggle_scope(),
         KeyCode::Char('t') => {
             if app.telemetry.config().enabled {
                 app.disable_telemetry();
@@ -1877,6 +2744,8 @@ fn handle_player_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn
                 log::error!("Failed to load next track: {e}");
             }
         }
+        KeyCode::Char('N') => app.step_in_folder(true),
+        KeyCode::Char('P') => app.step_in_folder(false),
         KeyCode::Char('p') => {
             // Previous track in playlist
             if app.has_previous_track()
@@ -1890,9 +2759,37 @@ fn handle_player_keys(app: &mut App, key: event::KeyEvent) -> Result<(), Box<dyn
     Ok(())
 }
 
+/// Index of the file after (or before) `current` in a sorted folder listing,
+/// wrapping at the ends. A current file missing from the listing, deleted
+/// since it loaded say, steps to where it would have been.
+fn folder_neighbor(files: &[PathBuf], current: &std::path::Path, forward: bool) -> Option<usize> {
+    if files.is_empty() {
+        return None;
+    }
+    let len = files.len();
+    let name = current.file_name();
+    let index = match files.iter().position(|file| file.file_name() == name) {
+        Some(index) if forward => (index + 1) % len,
+        Some(index) => (index + len - 1) % len,
+        None => {
+            let after = files
+                .iter()
+                .position(|file| file.file_name() > name)
+                .unwrap_or(len);
+            if forward {
+                after % len
+            } else {
+                (after + len - 1) % len
+            }
+        }
+    };
+    Some(index)
+}
+
 fn seek_audio(app: &mut App, seconds: f32) {
     if let Some(engine) = &mut app.audio_engine {
         let _ = engine.seek_relative(seconds);
+        app.restart_true_peak();
     }
 }
 
@@ -1900,26 +2797,14 @@ fn seek_audio_percentage(app: &mut App, percentage: f32) {
     if let (Some(engine), Some(duration)) = (&mut app.audio_engine, app.duration) {
         let seconds = duration.as_secs_f32() * percentage;
         let _ = engine.seek_relative(seconds);
+        app.restart_true_peak();
     }
 }
 
-fn init_logging() -> Result<(), Box<dyn Error>> {
-    use simplelog::*;
-    use std::fs::File;
-
-    let log_file = "/tmp/zim-player.log";
-    CombinedLogger::init(vec![WriteLogger::new(
-        LevelFilter::Debug,
-        Config::default(),
-        File::create(log_file)?,
-    )])?;
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{pcm16, write_wav};
     use std::time::Duration;
 
     #[test]
@@ -1942,6 +2827,119 @@ mod tests {
         assert_eq!(app.view_mode, ViewMode::Player);
     }
 
+    #[test]
+    fn test_toggle_scope() {
+        let mut app = App::new();
+        assert!(app.show_scope);
+
+        app.toggle_scope();
+        assert!(!app.show_scope);
+        assert_eq!(app.editor_message.as_deref(), Some("Scope off"));
+        app.toggle_scope();
+        assert!(app.show_scope);
+    }
+
+    #[test]
+    fn test_folder_neighbor_wraps() {
+        let files: Vec<PathBuf> = ["a.wav", "b.wav", "c.wav"]
+            .iter()
+            .map(|name| PathBuf::from("takes").join(name))
+            .collect();
+        let step = |current: &str, forward: bool| {
+            folder_neighbor(&files, std::path::Path::new(current), forward)
+        };
+        assert_eq!(step("takes/a.wav", true), Some(1));
+        assert_eq!(step("takes/c.wav", true), Some(0));
+        assert_eq!(step("takes/a.wav", false), Some(2));
+        // Matched by name, however the path was written
+        assert_eq!(step("./takes/b.wav", false), Some(0));
+        // A file no longer in the listing steps from where it would be
+        assert_eq!(step("takes/bb.wav", true), Some(2));
+        assert_eq!(step("takes/bb.wav", false), Some(1));
+        assert_eq!(step("takes/z.wav", true), Some(0));
+        assert_eq!(
+            folder_neighbor(&[], std::path::Path::new("a.wav"), true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_step_in_folder_without_a_file() {
+        let mut app = App::new();
+        app.step_in_folder(true);
+        assert_eq!(
+            app.editor_message.as_deref(),
+            Some("Can't step through the folder: no single file loaded")
+        );
+        assert!(app.current_file.is_none());
+    }
+
+    #[test]
+    fn test_seek_to_clamps_to_the_end() {
+        let mut app = App::new();
+        app.seek_to(10.0);
+        assert_eq!(app.playback_position, 0.0);
+
+        app.duration = Some(Duration::from_secs(60));
+        app.seek_to(15.0);
+        assert_eq!(app.playback_position, 0.25);
+        assert!(app.editor_message.is_none());
+
+        app.seek_to(90.0);
+        assert_eq!(app.playback_position, 1.0);
+        assert_eq!(
+            app.editor_message.as_deref(),
+            Some("Start 01:30.000 is past the end (01:00.000)")
+        );
+    }
+
+    #[test]
+    fn test_apply_play_region() {
+        let mut app = App::new();
+        app.duration = Some(Duration::from_secs(60));
+
+        // A start on its own only seeks
+        app.apply_play_region(Some(30.0), None, false);
+        assert_eq!(app.playback_position, 0.5);
+        assert!(app.mark_in.is_none());
+        assert!(!app.is_looping);
+
+        // With an end the region plays once and stops at mark out
+        app.apply_play_region(Some(15.0), Some(30.0), false);
+        assert_eq!((app.mark_in, app.mark_out), (Some(0.25), Some(0.5)));
+        assert_eq!(app.playback_position, 0.25);
+        assert!(app.is_looping);
+        assert_eq!(app.loop_count, Some(1));
+
+        // Looping from a start runs to the end of the file, forever
+        app.apply_play_region(Some(45.0), None, true);
+        assert_eq!((app.mark_in, app.mark_out), (Some(0.75), Some(1.0)));
+        assert_eq!(app.loop_count, None);
+
+        // Looping without a region loops the whole file
+        app.apply_play_region(None, None, true);
+        assert_eq!((app.mark_in, app.mark_out), (Some(0.0), Some(1.0)));
+        assert_eq!(app.playback_position, 0.0);
+        assert!(app.is_looping);
+        assert_eq!(app.loop_count, None);
+
+        // An end past the file is clamped to its end
+        let mut app = App::new();
+        app.duration = Some(Duration::from_secs(60));
+        app.apply_play_region(Some(30.0), Some(90.0), false);
+        assert_eq!(app.mark_out, Some(1.0));
+
+        // A region wholly past the end clamps to nothing, so no marks are set
+        let mut app = App::new();
+        app.duration = Some(Duration::from_secs(60));
+        app.apply_play_region(Some(70.0), Some(65.0), false);
+        assert!(app.mark_in.is_none());
+        assert_eq!(
+            app.editor_message.as_deref(),
+            Some("Region 01:00.000–01:00.000 is empty")
+        );
+    }
+
     #[test]
     fn test_set_mark_in() {
         let mut app = App::new();
@@ -1976,6 +2974,39 @@ mod tests {
         assert!(!app.is_looping);
     }
 
+    #[test]
+    fn test_toggle_sample_position() {
+        let mut app = App::new();
+        assert!(!app.show_samples);
+        app.toggle_sample_position();
+        assert!(app.show_samples);
+        assert_eq!(app.editor_message.as_deref(), Some("Sample position on"));
+        app.toggle_sample_position();
+        assert!(!app.show_samples);
+    }
+
+    #[test]
+    fn test_selection_timecodes() {
+        let mut app = App::new();
+        app.copy_selection_timecodes();
+        assert_eq!(app.editor_message.as_deref(), Some("No file loaded"));
+
+        app.duration = Some(Duration::from_secs(100));
+        app.mark_in = Some(0.625);
+        assert!(app.selection_timecodes().is_none());
+        app.copy_selection_timecodes();
+        assert_eq!(
+            app.editor_message.as_deref(),
+            Some("Set mark in and mark out to copy timecodes")
+        );
+
+        app.mark_out = Some(0.25);
+        assert_eq!(
+            app.selection_timecodes().as_deref(),
+            Some("00:25.000 - 01:02.500")
+        );
+    }
+
     #[test]
     fn test_get_selection_duration_no_marks() {
         let app = App::new();
@@ -2045,6 +3076,40 @@ mod tests {
         assert!(!app.is_looping);
     }
 
+    #[test]
+    fn test_counted_loop_stops_at_mark_out() {
+        let mut app = App::new();
+        app.mark_in = Some(0.2);
+        app.mark_out = Some(0.8);
+        app.duration = Some(Duration::from_secs(10));
+
+        app.cycle_loop_count();
+        app.cycle_loop_count();
+        assert_eq!(app.loop_count, Some(2));
+
+        app.toggle_loop();
+        assert_eq!(app.loops_remaining, Some(2));
+        app.is_playing = true;
+        app.playback_position = 0.8;
+
+        // First pass ends: go round again
+        assert!(app.at_loop_end());
+        assert!(app.count_loop_pass());
+        assert_eq!(app.loops_remaining, Some(1));
+
+        // Second pass ends: stop instead of seeking back
+        assert!(!app.count_loop_pass());
+        app.finish_counted_loop();
+        assert!(!app.is_looping);
+        assert!(!app.is_playing);
+        assert_eq!(app.loops_remaining, Some(2));
+
+        app.cycle_loop_count();
+        app.cycle_loop_count();
+        assert_eq!(app.loop_count, None);
+        assert!(app.count_loop_pass());
+    }
+
     #[test]
     fn test_open_save_dialog_without_file() {
         let mut app = App::new();
@@ -2071,6 +3136,37 @@ mod tests {
         assert!(dialog.filename.contains("_edit"));
     }
 
+    #[test]
+    fn test_short_selection_warns_or_blocks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new();
+        app.current_file = Some(temp_dir.path().join("take.wav").display().to_string());
+        app.duration = Some(Duration::from_secs(10));
+        app.mark_in = Some(0.5);
+        app.mark_out = Some(0.5001);
+        assert!(app.short_selection().is_some());
+
+        app.open_save_dialog();
+        let short = app.save_dialog.as_ref().unwrap().short_selection.unwrap();
+        assert!(!short.blocked);
+        assert!(short.message().contains("1.0ms"));
+
+        // Blocking keeps the dialog open and writes nothing
+        app.block_short_selections = true;
+        app.open_save_dialog();
+        execute_save(&mut app).unwrap();
+        let dialog = app.save_dialog.as_ref().unwrap();
+        assert!(!dialog.get_full_path().exists());
+
+        // A long enough selection, or a zero minimum, isn't flagged
+        app.mark_out = Some(0.6);
+        assert!(app.short_selection().is_none());
+        app.mark_out = Some(0.5001);
+        app.min_selection = Duration::ZERO;
+        app.open_save_dialog();
+        assert!(app.save_dialog.as_ref().unwrap().short_selection.is_none());
+    }
+
     #[test]
     fn test_check_loop_boundaries_no_marks() {
         let app = App::new();
@@ -2101,20 +3197,6 @@ mod tests {
         assert!(seek.unwrap() < 0.0); // Should seek backwards
     }
 
-    #[test]
-    fn test_convert_sample_to_16bit() {
-        let app = App::new();
-
-        // Test 16-bit (no conversion)
-        assert_eq!(app.convert_sample_to_16bit(1000, 16), 1000);
-
-        // Test 24-bit conversion
-        assert_eq!(app.convert_sample_to_16bit(256000, 24), 1000);
-
-        // Test 32-bit conversion
-        assert_eq!(app.convert_sample_to_16bit(65536000, 32), 1000);
-    }
-
     #[test]
     fn test_apply_level_decay_playing() {
         let mut app = App::new();
@@ -2130,6 +3212,33 @@ mod tests {
         assert_eq!(app.right_level, 0.99);
     }
 
+    #[test]
+    fn test_level_history_scrolls() {
+        let mut app = App::new();
+        let start = std::time::Instant::now();
+
+        // Nothing is recorded while stopped
+        app.record_level_history(start);
+        assert!(app.level_history.is_empty());
+
+        app.is_playing = true;
+        app.left_level = 0.2;
+        app.right_level = 0.4;
+        app.record_level_history(start);
+        assert_eq!(app.level_history, [0.4]);
+
+        // Readings closer together than the interval are skipped
+        app.record_level_history(start + LEVEL_HISTORY_INTERVAL / 2);
+        assert_eq!(app.level_history.len(), 1);
+
+        for i in 1..=LEVEL_HISTORY_LEN as u32 {
+            app.left_level = 0.5;
+            app.record_level_history(start + LEVEL_HISTORY_INTERVAL * i);
+        }
+        assert_eq!(app.level_history.len(), LEVEL_HISTORY_LEN);
+        assert!(app.level_history.iter().all(|&level| level == 0.5));
+    }
+
     #[test]
     fn test_apply_level_decay_stopped() {
         let mut app = App::new();
@@ -2198,4 +3307,501 @@ mod tests {
         // Should show "1/1 [01:23:45 total]"
         assert_eq!(pos_str, "1/1 [01:23:45 total]");
     }
+
+    #[test]
+    fn test_help_overlay_toggle() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let mut app = App::new();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        handle_key_event(&mut app, key(KeyCode::Char('?'))).unwrap();
+        assert!(app.show_help);
+
+        // Other keys are ignored while help is open
+        handle_key_event(&mut app, key(KeyCode::Char('q'))).unwrap();
+        assert!(app.show_help);
+        assert!(!app.should_quit);
+
+        // Scrolling stops at the top
+        handle_key_event(&mut app, key(KeyCode::Char('k'))).unwrap();
+        assert_eq!(app.help_scroll, 0);
+
+        // ...and at the bottom of the last drawn frame
+        app.viewport = Rect::new(0, 0, 120, 24);
+        let max = super::super::help_ui::max_help_scroll(app.viewport);
+        assert!(max > 0);
+        for _ in 0..max + 5 {
+            handle_key_event(&mut app, key(KeyCode::Char('j'))).unwrap();
+        }
+        assert_eq!(app.help_scroll, max);
+
+        handle_key_event(&mut app, key(KeyCode::Esc)).unwrap();
+        assert!(!app.show_help);
+
+        handle_key_event(&mut app, key(KeyCode::Char('?'))).unwrap();
+        handle_key_event(&mut app, key(KeyCode::Char('?'))).unwrap();
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_adjust_volume_in_db_steps() {
+        let mut app = App::new();
+        app.adjust_volume(-VOLUME_STEP_DB);
+        assert!((gain_to_db(app.master_gain) + 1.0).abs() < 0.001);
+
+        // Tops out at the maximum gain
+        for _ in 0..20 {
+            app.adjust_volume(VOLUME_STEP_DB);
+        }
+        assert_eq!(app.master_gain, crate::config::MAX_GAIN);
+
+        // Steps below the floor mute, and stepping up from mute starts at the floor
+        app.master_gain = db_to_gain(MIN_VOLUME_DB);
+        app.adjust_volume(-VOLUME_STEP_DB);
+        assert_eq!(app.master_gain, 0.0);
+        app.adjust_volume(-VOLUME_STEP_DB);
+        assert_eq!(app.master_gain, 0.0);
+        app.adjust_volume(VOLUME_STEP_DB);
+        assert!((gain_to_db(app.master_gain) - MIN_VOLUME_DB).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_replay_gain_scales_output() {
+        let mut app = App::new();
+        app.master_gain = 0.5;
+        assert_eq!(app.output_gain(), 0.5);
+
+        app.file_replay_gain = Some(6.0);
+        assert!((app.output_gain() - 0.5 * db_to_gain(6.0)).abs() < 1e-6);
+        // Volume changes keep the file's replay gain on top
+        app.adjust_volume(-VOLUME_STEP_DB);
+        assert!((app.output_gain() - app.master_gain * db_to_gain(6.0)).abs() < 1e-6);
+
+        app.toggle_replay_gain();
+        assert!(!app.replay_gain);
+        assert_eq!(app.output_gain(), app.master_gain);
+        assert_eq!(app.editor_message.as_deref(), Some("Replay gain: off"));
+        app.toggle_replay_gain();
+        assert_eq!(app.editor_message.as_deref(), Some("Replay gain: +6.0 dB"));
+    }
+
+    #[test]
+    fn test_ctrl_c_quits() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        // Even from the help overlay
+        let mut app = App::new();
+        app.show_help = true;
+        handle_key_event(&mut app, ctrl_c).unwrap();
+        assert!(app.should_quit);
+
+        // The browser search box still uses it to clear the query
+        let mut app = App::new();
+        app.view_mode = ViewMode::Browser;
+        app.browser.show_search();
+        app.browser.push_char('k');
+        handle_key_event(&mut app, ctrl_c).unwrap();
+        assert!(!app.should_quit);
+        assert!(app.browser.search_query.is_empty());
+    }
+
+    #[test]
+    fn test_mark_from_typed_timecode() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let press = |app: &mut App, code: KeyCode| {
+            handle_key_event(app, KeyEvent::new(code, KeyModifiers::NONE)).unwrap();
+        };
+        let type_text = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                press(app, KeyCode::Char(c));
+            }
+        };
+
+        let mut app = App::new();
+        press(&mut app, KeyCode::Char('I'));
+        assert!(app.mark_prompt.is_none());
+
+        app.duration = Some(Duration::from_secs(120));
+        app.playback_position = 0.5;
+        press(&mut app, KeyCode::Char('O'));
+        assert_eq!(app.mark_prompt.as_ref().unwrap().entry, "01:00.000");
+        app.mark_prompt.as_mut().unwrap().entry.clear();
+        type_text(&mut app, "1:30");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.mark_prompt.is_none());
+        assert_eq!(app.mark_out, Some(0.75));
+
+        // Mark in after mark out is refused and the prompt stays open
+        press(&mut app, KeyCode::Char('I'));
+        app.mark_prompt.as_mut().unwrap().entry.clear();
+        type_text(&mut app, "1:45");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.mark_prompt.as_ref().unwrap().error.is_some());
+        assert_eq!(app.mark_in, None);
+
+        for _ in 0..4 {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_text(&mut app, "0:15");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.mark_in, Some(0.125));
+
+        press(&mut app, KeyCode::Char('I'));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.mark_prompt.is_none());
+        assert_eq!(app.mark_in, Some(0.125));
+    }
+
+    #[test]
+    fn test_browser_waveform_toggle() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let mut app = App::new();
+        app.view_mode = ViewMode::Browser;
+        assert!(app.browser.show_waveform);
+
+        let key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE);
+        handle_key_event(&mut app, key).unwrap();
+        assert!(!app.browser.show_waveform);
+        handle_key_event(&mut app, key).unwrap();
+        assert!(app.browser.show_waveform);
+    }
+
+    #[test]
+    fn test_save_and_restore_sidecar_loop() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let audio_path = temp_dir.path().join("take.wav");
+        let sidecar_path = temp_dir.path().join("take.wav.md");
+        std::fs::write(&sidecar_path, "---\nfile: take.wav\n---\n").unwrap();
+
+        let mut app = App::new();
+        app.current_file = Some(audio_path.to_string_lossy().to_string());
+        app.duration = Some(std::time::Duration::from_secs(10));
+        app.mark_in = Some(0.25);
+        app.mark_out = Some(0.5);
+        app.save_loop_to_sidecar();
+
+        let content = std::fs::read_to_string(&sidecar_path).unwrap();
+        assert_eq!(read_loop_region(&content), Some((2.5, 5.0)));
+
+        app.clear_marks();
+        assert!(app.restore_sidecar_loop(&audio_path));
+        assert_eq!(app.mark_in, Some(0.25));
+        assert_eq!(app.mark_out, Some(0.5));
+    }
+
+    #[test]
+    fn test_loading_a_file_resets_marks_and_looping() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let paths: Vec<String> = ["looped.wav", "plain.wav"]
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                write_wav(&path, pcm16(1, 8000), [0i16; 80000]);
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        std::fs::write(
+            temp_dir.path().join("looped.wav.md"),
+            "---\nloop_start: 2.5\nloop_end: 5.0\n---\n",
+        )
+        .unwrap();
+
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            Default::default(),
+        );
+        let load = |app: &mut App, path: &str| {
+            let source = prepare_source(std::path::Path::new(path), handles.clone()).unwrap();
+            app.load_prepared(path, source, false);
+        };
+
+        // Without an audio engine nothing sets the duration, so give the
+        // files' real length
+        let mut app = App::new();
+        app.duration = Some(std::time::Duration::from_secs(10));
+        load(&mut app, &paths[0]);
+        assert_eq!(app.mark_in, Some(0.25));
+        assert_eq!(app.mark_out, Some(0.5));
+        app.is_looping = true;
+        app.cycle_loop_count();
+        assert_eq!(app.loops_remaining, Some(1));
+
+        // The next file has no saved loop, so nothing carries over
+        load(&mut app, &paths[1]);
+        assert_eq!((app.mark_in, app.mark_out), (None, None));
+        assert!(!app.is_looping);
+        assert_eq!((app.loop_count, app.loops_remaining), (None, None));
+    }
+
+    #[test]
+    fn test_saved_loop_restored_on_load() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        // A one-second file with a whole-file sampler loop
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let audio_path = temp_dir.path().join("take.wav");
+        write_wav(&audio_path, pcm16(1, 8000), [0i16; 8000]);
+        write_whole_file_loop(&audio_path).unwrap();
+        let sidecar_path = temp_dir.path().join("take.wav.md");
+        std::fs::write(&sidecar_path, "---\nfile: take.wav\n---\n").unwrap();
+        let path = audio_path.to_string_lossy().to_string();
+
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            Default::default(),
+        );
+        let load = |app: &mut App| {
+            let source = prepare_source(&audio_path, handles.clone()).unwrap();
+            app.load_prepared(&path, source, false);
+        };
+
+        // Without an audio engine nothing sets these from the file
+        let mut app = App::new();
+        app.current_file = Some(path.clone());
+        app.duration = Some(std::time::Duration::from_secs(1));
+        load(&mut app);
+        assert_eq!((app.mark_in, app.mark_out), (Some(0.0), Some(1.0)));
+
+        // L saves the marks in seconds
+        app.mark_in = Some(0.25);
+        app.mark_out = Some(0.5);
+        let key = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::NONE);
+        handle_player_keys(&mut app, key).unwrap();
+        let content = std::fs::read_to_string(&sidecar_path).unwrap();
+        assert_eq!(read_loop_region(&content), Some((0.25, 0.5)));
+
+        // and the next load restores them ahead of the sampler loop
+        app.clear_marks();
+        load(&mut app);
+        assert_eq!((app.mark_in, app.mark_out), (Some(0.25), Some(0.5)));
+    }
+
+    #[test]
+    fn test_prefetch_worker_skips_stale_generation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav_path = temp_dir.path().join("preview.wav");
+        write_wav(&wav_path, pcm16(1, 44100), [0i16]);
+        let path = wav_path.to_string_lossy().to_string();
+
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            Default::default(),
+        );
+        let current = Arc::new(AtomicUsize::new(2));
+
+        // Superseded before it settled: nothing is decoded or sent
+        let stale = spawn_prefetch_worker(path.clone(), handles.clone(), 1, current.clone());
+        assert!(stale.recv().is_err());
+
+        let fresh = spawn_prefetch_worker(path, handles, 2, current);
+        assert!(matches!(fresh.recv(), Ok(Ok(PreparedSource::Wav(_)))));
+    }
+
+    #[test]
+    fn test_channel_mode_keys_toggle() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut app = App::new();
+        app.is_stereo = true;
+
+        handle_key_event(&mut app, key(',')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::SoloLeft);
+        handle_key_event(&mut app, key('X')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Swap);
+        handle_key_event(&mut app, key('X')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Stereo);
+
+        // Mono check cycles -3 dB, -6 dB, stereo, and replaces a solo
+        handle_key_event(&mut app, key(',')).unwrap();
+        handle_key_event(&mut app, key('M')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Mono3dB);
+        handle_key_event(&mut app, key('M')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Mono6dB);
+        handle_key_event(&mut app, key('M')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Stereo);
+
+        // Mid/side cycles mid, side, stereo
+        handle_key_event(&mut app, key('M')).unwrap();
+        handle_key_event(&mut app, key('W')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Mid);
+        handle_key_event(&mut app, key('W')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Side);
+        handle_key_event(&mut app, key('W')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Stereo);
+
+        // Mono files have no channels to solo
+        app.current_file = Some("mono.wav".to_string());
+        app.is_stereo = false;
+        handle_key_event(&mut app, key('.')).unwrap();
+        assert_eq!(app.channel_mode, ChannelMode::Stereo);
+        assert!(app.editor_message.is_some());
+    }
+
+    #[test]
+    fn test_meter_gain_and_true_level() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+        let mut app = App::new();
+        app.is_stereo = true;
+        // Constant 0.25 on the left, silence on the right: RMS 0.25
+        let samples = [0.25, 0.0, -0.25, 0.0];
+
+        app.calculate_stereo_levels(&samples);
+        assert_eq!(app.left_level, 0.5);
+        assert_eq!(app.right_level, 0.0);
+
+        app.set_meter_gain(1.5);
+        app.calculate_stereo_levels(&samples);
+        assert_eq!(app.left_level, 0.375);
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE),
+        )
+        .unwrap();
+        assert!(app.true_level);
+        app.calculate_stereo_levels(&samples);
+        assert_eq!(app.left_level, 0.25);
+
+        app.is_stereo = false;
+        app.calculate_mono_levels(&[0.5, -0.5]);
+        assert_eq!(app.left_level, 0.5);
+        assert_eq!(app.right_level, 0.5);
+    }
+
+    #[test]
+    fn test_peak_hold_sample_and_true_peak() {
+        // A quarter-rate sine sampled 45° off its crest: every sample is at
+        // 0.707 but the waveform between them reaches full scale
+        let chunk: Vec<f32> = (0..256)
+            .map(|i| {
+                (std::f32::consts::FRAC_PI_4 + (i / 2) as f32 * std::f32::consts::FRAC_PI_2).sin()
+            })
+            .collect();
+        let mut app = App::new();
+        app.is_stereo = true;
+        app.reset_peak();
+        for _ in 0..4 {
+            app.calculate_audio_levels(&chunk);
+        }
+        assert!((app.peak_level - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+
+        app.true_peak_meter = true;
+        app.reset_peak();
+        for _ in 0..4 {
+            app.calculate_audio_levels(&chunk);
+        }
+        assert!(app.peak_level > 0.95, "{}", app.peak_level);
+
+        // Quieter audio doesn't lower the hold; loading a file resets it
+        app.calculate_audio_levels(&[0.1, 0.1]);
+        assert!(app.peak_level > 0.95);
+        app.reset_peak();
+        assert_eq!(app.peak_level, 0.0);
+    }
+
+    #[test]
+    fn test_seek_restarts_true_peak() {
+        // Audio at +0.5 until a seek lands on audio at -0.5. Interpolating
+        // across the jump rings well past either level.
+        let before = vec![0.5f32; 256];
+        let after = vec![-0.5f32; 256];
+        let peak_across_jump = |seek: bool| {
+            let mut app = App::new();
+            app.is_stereo = true;
+            app.true_peak_meter = true;
+            app.duration = Some(Duration::from_secs(10));
+            app.reset_peak();
+            app.calculate_audio_levels(&before);
+            if seek {
+                app.seek_to(5.0);
+            }
+            app.calculate_audio_levels(&after);
+            app.peak_level
+        };
+        assert!(peak_across_jump(false) > 0.6);
+        // Only the ringing from the new audio's own onset remains
+        assert!(peak_across_jump(true) < 0.6);
+    }
+
+    #[test]
+    fn test_reload_without_file_shows_message() {
+        let mut app = App::new();
+        app.reload_current_file();
+        assert_eq!(
+            app.editor_message.as_deref(),
+            Some("Can't reload: No file loaded")
+        );
+        assert!(app.current_file.is_none());
+    }
+
+    #[test]
+    fn test_sampler_loop_roundtrip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav_path = temp_dir.path().join("loop.wav");
+        write_wav(&wav_path, pcm16(2, 44100), [0i16; 200]);
+        assert_eq!(read_sampler_loop(&wav_path), None);
+
+        write_whole_file_loop(&wav_path).unwrap();
+        assert_eq!(read_sampler_loop(&wav_path), Some((0.0, 1.0)));
+
+        crate::wav_metadata::write_sample_loop(
+            &wav_path,
+            crate::wav_metadata::SampleLoop { start: 25, end: 74 },
+        )
+        .unwrap();
+        assert_eq!(read_sampler_loop(&wav_path), Some((0.25, 0.75)));
+    }
+
+    #[test]
+    fn test_saved_selection_records_lineage() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let untagged = temp_dir.path().join("untagged.wav");
+        let source = temp_dir.path().join("master.wav");
+        let excerpt = temp_dir.path().join("master_edit.wav");
+        write_wav(&untagged, pcm16(1, 8000), 0..1000i16);
+        let mut original = crate::wav_metadata::ZimMetadata::new_original("album", &untagged);
+        original.audio_md5 = crate::wav_metadata::calculate_audio_md5(&untagged).unwrap();
+        crate::wav_metadata::write_metadata(&untagged, &source, &original).unwrap();
+
+        let mut app = App::new();
+        app.mark_in = Some(0.25);
+        app.mark_out = Some(0.75);
+        app.duration = Some(Duration::from_millis(125));
+        app.save_selection(source.to_str().unwrap(), excerpt.clone())
+            .unwrap();
+
+        let lineage = crate::wav_metadata::read_metadata(&excerpt)
+            .unwrap()
+            .unwrap();
+        assert_eq!(lineage.parent_uuid.as_deref(), Some(original.uuid.as_str()));
+        assert_ne!(lineage.uuid, original.uuid);
+        assert_eq!(lineage.generation, 1);
+        assert_eq!(lineage.transform.as_deref(), Some("excerpt"));
+        assert_eq!(
+            lineage.audio_md5,
+            crate::wav_metadata::calculate_audio_md5(&excerpt).unwrap()
+        );
+        assert_eq!(hound::WavReader::open(&excerpt).unwrap().duration(), 500);
+
+        // An excerpt of an untagged file gets no lineage
+        let untagged_excerpt = temp_dir.path().join("untagged_edit.wav");
+        app.save_selection(untagged.to_str().unwrap(), untagged_excerpt.clone())
+            .unwrap();
+        assert!(
+            crate::wav_metadata::read_metadata(&untagged_excerpt)
+                .unwrap()
+                .is_none()
+        );
+    }
 }
@@ -3,8 +3,10 @@
 //! This module provides the core audio functionality for the player, handling
 //! file loading, playback control, and real-time audio sample streaming for
 //! visualization. It supports multiple audio formats (WAV, FLAC) and provides
-//! progress tracking and seeking capabilities.
+//! progress tracking and seeking capabilities. Files are decoded into memory,
+//! except very large WAV and FLAC files, which are streamed.
 
+use rodio::cpal::BufferSize;
 use rodio::{OutputStream, OutputStreamBuilder, Sink, Source};
 use std::error::Error;
 use std::fs::File;
@@ -12,21 +14,38 @@ use std::io::{BufReader, Read};
 use std::path::Path;
 use std::sync::{
     Arc,
-    atomic::{AtomicUsize, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     mpsc,
 };
 use std::time::Duration;
 
+use super::channel_mode::SharedChannelMode;
+use super::mixed_source::ReferenceTrack;
+use super::streaming_source::{StreamingSource, should_stream};
+use super::test_signal::{SignalSource, TestSignal};
+use crate::config::DEFAULT_MONITOR_FRAMES;
+use crate::wav_metadata;
+
 // Type alias for the audio engine creation result
 type AudioEngineResult = Result<(AudioEngine, mpsc::Receiver<Vec<f32>>), Box<dyn Error>>;
 
 pub struct AudioInfo {
     pub channels: u16,
     pub sample_rate: u32,
+    pub bits_per_sample: Option<u16>, // None for mixes of several files and test signals
+}
+
+/// Playback position in sample frames (one sample per channel)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplePosition {
+    pub frame: u64,
+    pub total_frames: u64,
+    /// The total is estimated from the duration, as it is for mixes
+    pub approximate: bool,
 }
 
 pub struct AudioEngine {
-    _stream: OutputStream,
+    stream: OutputStream,
     sink: Sink,
     samples_tx: mpsc::Sender<Vec<f32>>,
     pub info: Option<AudioInfo>,
@@ -38,19 +57,23 @@ pub struct AudioEngine {
     // For mixed sources
     mixed_file_paths: Option<Vec<String>>,
     mixed_gains: Option<Vec<f32>>,
+    mixed_reference: Option<ReferenceTrack>,
+    solo_reference: Arc<AtomicBool>,
+    channel_mode: SharedChannelMode,
+    monitor_frames: usize,
 }
 
 impl AudioEngine {
-    pub fn new() -> AudioEngineResult {
-        // Create output stream using rodio 0.21 API
-        let stream = OutputStreamBuilder::open_default_stream()
-            .map_err(|e| Box::new(e) as Box<dyn Error>)?;
+    /// Open the output device, asking for `buffer_frames` frames of output
+    /// buffer when set and the device default otherwise
+    pub fn new(buffer_frames: Option<u32>) -> AudioEngineResult {
+        let stream = open_output_stream(buffer_frames)?;
         let sink = Sink::connect_new(stream.mixer());
         let (samples_tx, samples_rx) = mpsc::channel();
 
         Ok((
             Self {
-                _stream: stream,
+                stream,
                 sink,
                 samples_tx,
                 info: None,
@@ -61,12 +84,43 @@ impl AudioEngine {
                 cached_aiff_data: None,
                 mixed_file_paths: None,
                 mixed_gains: None,
+                mixed_reference: None,
+                solo_reference: Arc::new(AtomicBool::new(false)),
+                channel_mode: SharedChannelMode::default(),
+                monitor_frames: DEFAULT_MONITOR_FRAMES,
             },
             samples_rx,
         ))
     }
 
-    pub fn load_file(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+    /// Handles a source needs to report samples and progress to this engine
+    pub fn source_handles(&self) -> SourceHandles {
+        SourceHandles::new(
+            self.samples_tx.clone(),
+            self.samples_played.clone(),
+            self.channel_mode.clone(),
+        )
+        .with_monitor_frames(self.monitor_frames)
+    }
+
+    /// Sample rate the output device runs at. rodio resamples sources at any
+    /// other rate to this one.
+    pub fn output_sample_rate(&self) -> u32 {
+        self.stream.config().sample_rate()
+    }
+
+    /// Frames per visualization chunk for sources loaded from now on
+    pub fn set_monitor_frames(&mut self, frames: usize) {
+        self.monitor_frames = frames;
+    }
+
+    /// Stereo monitoring mode, applied to the playing source immediately
+    pub fn channel_mode(&self) -> &SharedChannelMode {
+        &self.channel_mode
+    }
+
+    /// Start playing a source decoded by [`prepare_source`]
+    pub fn load_prepared(&mut self, path: &Path, source: PreparedSource) {
         // Stop any currently playing audio
         self.sink.stop();
 
@@ -81,37 +135,20 @@ impl AudioEngine {
         // Store the file path for seeking
         self.current_file_path = Some(path.to_string_lossy().to_string());
 
-        // Open and decode the file
-        let file = BufReader::new(File::open(path)?);
-
-        // Try to decode based on extension
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_default();
-
-        match ext.as_str() {
-            "wav" => {
-                let decoder = hound::WavReader::new(file)?;
-                self.play_wav(decoder)?;
-            }
-            "flac" => {
-                self.play_flac(path)?;
-            }
-            "aif" | "aiff" => {
-                self.play_aiff(path)?;
-            }
-            _ => return Err(format!("Unsupported audio format: {ext}").into()),
+        match source {
+            PreparedSource::Wav(source) => self.play_wav(source),
+            PreparedSource::Flac(source) => self.play_flac(source),
+            PreparedSource::Aiff(source, aiff_data) => self.play_aiff(source, aiff_data),
+            PreparedSource::Stream(source) => self.play_stream(source),
+            PreparedSource::Signal(source) => self.play_signal(source),
         }
-
-        Ok(())
     }
 
     pub fn load_files(
         &mut self,
         paths: &[String],
         gains: Option<Vec<f32>>,
+        reference: Option<ReferenceTrack>,
     ) -> Result<(), Box<dyn Error>> {
         // Stop any currently playing audio
         self.sink.stop();
@@ -126,19 +163,23 @@ impl AudioEngine {
         // Store mixed file information for seeking
         self.mixed_file_paths = Some(paths.to_vec());
         self.mixed_gains = gains.clone();
+        self.mixed_reference = reference;
+        self.solo_reference.store(false, Ordering::Relaxed);
 
         // Create mixed source
         let mixed_source = crate::player::mixed_source::create_mixed_source_from_files(
             paths,
             gains,
-            self.samples_tx.clone(),
-            self.samples_played.clone(),
+            self.mixed_reference.as_ref(),
+            self.solo_reference.clone(),
+            self.source_handles(),
         )?;
 
         // Get info from mixed source
         self.info = Some(AudioInfo {
             channels: mixed_source.channels(),
             sample_rate: mixed_source.sample_rate(),
+            bits_per_sample: None,
         });
 
         self.duration = mixed_source.total_duration();
@@ -176,15 +217,17 @@ impl AudioEngine {
         let mixed_source = crate::player::mixed_source::create_mixed_source_from_files_with_seek(
             paths,
             gains,
+            self.mixed_reference.as_ref(),
+            self.solo_reference.clone(),
             start_sample,
-            self.samples_tx.clone(),
-            self.samples_played.clone(),
+            self.source_handles(),
         )?;
 
         // Get info from mixed source
         self.info = Some(AudioInfo {
             channels: mixed_source.channels(),
             sample_rate: mixed_source.sample_rate(),
+            bits_per_sample: None,
         });
 
         self.duration = mixed_source.total_duration();
@@ -212,27 +255,14 @@ impl AudioEngine {
         Ok(())
     }
 
-    fn play_wav(
-        &mut self,
-        reader: hound::WavReader<BufReader<File>>,
-    ) -> Result<(), Box<dyn Error>> {
-        let spec = reader.spec();
-
-        log::info!(
-            "WAV format: {:?}, sample format: {:?}",
-            spec,
-            spec.sample_format
-        );
-
+    fn play_wav(&mut self, source: WavSource) {
         // Store audio info
         self.info = Some(AudioInfo {
-            channels: spec.channels,
-            sample_rate: spec.sample_rate,
+            channels: source.channels,
+            sample_rate: source.sample_rate,
+            bits_per_sample: Some(source.bits_per_sample),
         });
 
-        // Create a monitoring source that sends samples to visualization
-        let source = WavSource::new(reader, self.samples_tx.clone(), self.samples_played.clone())?;
-
         // Get duration from source
         self.duration = source.total_duration();
         self.total_samples = source.current_samples.len();
@@ -243,32 +273,25 @@ impl AudioEngine {
             self.duration
         );
 
-        // Play through rodio
-        self.sink.append(source);
-
         log::info!(
             "Playing WAV: {} Hz, {} channels, {} bits",
-            spec.sample_rate,
-            spec.channels,
-            spec.bits_per_sample
+            source.sample_rate,
+            source.channels,
+            source.bits_per_sample
         );
 
-        Ok(())
+        // Play through rodio
+        self.sink.append(source);
     }
 
-    fn play_flac(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
-        let reader = claxon::FlacReader::open(path)?;
-        let info = reader.streaminfo();
-
+    fn play_flac(&mut self, source: FlacSource) {
         // Store audio info
         self.info = Some(AudioInfo {
-            channels: info.channels as u16,
-            sample_rate: info.sample_rate,
+            channels: source.channels as u16,
+            sample_rate: source.sample_rate,
+            bits_per_sample: Some(source.bits_per_sample as u16),
         });
 
-        // Create FLAC source
-        let source = FlacSource::new(reader, self.samples_tx.clone(), self.samples_played.clone())?;
-
         // Get duration from source
         self.duration = source.total_duration();
         self.total_samples = source.current_samples.len();
@@ -279,44 +302,24 @@ impl AudioEngine {
             self.duration
         );
 
-        // Play through rodio
-        self.sink.append(source);
-
         log::info!(
             "Playing FLAC: {} Hz, {} channels",
-            info.sample_rate,
-            info.channels
+            source.sample_rate,
+            source.channels
         );
 
-        Ok(())
+        // Play through rodio
+        self.sink.append(source);
     }
 
-    fn play_aiff(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
-        // Load full AIFF file for best seek performance and seamless playback
-        log::info!("Loading AIFF file: {}", path.display());
-        let aiff_data = crate::media::metadata::read_aiff_data(path)?;
-
-        log::info!(
-            "AIFF loaded: {} total samples",
-            aiff_data.audio_samples.len()
-        );
-
-        let source = AiffSource::from_data(
-            aiff_data.clone(),
-            self.samples_tx.clone(),
-            self.samples_played.clone(),
-        )?;
-
+    fn play_aiff(&mut self, source: AiffSource, aiff_data: crate::media::metadata::AiffData) {
         // Cache the full data for fast seeking
-        self.cached_aiff_data = Some(aiff_data.clone());
-
         self.info = Some(AudioInfo {
             sample_rate: source.sample_rate(),
             channels: source.channels(),
+            bits_per_sample: Some(aiff_data.bits_per_sample),
         });
-
-        // Store the file path for seeking
-        self.current_file_path = Some(path.to_string_lossy().to_string());
+        self.cached_aiff_data = Some(aiff_data);
 
         // Set duration and total samples from our parser
         self.duration = source.total_duration();
@@ -324,10 +327,9 @@ impl AudioEngine {
 
         let calculated_duration = source.total_duration();
         log::info!(
-            "AIFF loaded: {} Hz, {} channels, {} samples loaded (of {} total), calculated duration: {:?}",
+            "AIFF loaded: {} Hz, {} channels, {} samples, calculated duration: {:?}",
             source.sample_rate(),
             source.channels(),
-            aiff_data.audio_samples.len(),
             source.total_samples(),
             calculated_duration
         );
@@ -339,14 +341,59 @@ impl AudioEngine {
 
         // Play through rodio
         self.sink.append(source);
+    }
 
-        Ok(())
+    fn play_stream(&mut self, source: StreamingSource) {
+        self.info = Some(AudioInfo {
+            channels: source.channels(),
+            sample_rate: source.sample_rate(),
+            bits_per_sample: Some(source.bits_per_sample()),
+        });
+
+        self.duration = source.total_duration();
+        self.total_samples = source.total_samples();
+
+        log::info!(
+            "Streaming: {} Hz, {} channels, {} bits, {} total samples, duration: {:?}",
+            source.sample_rate(),
+            source.channels(),
+            source.bits_per_sample(),
+            self.total_samples,
+            self.duration
+        );
+
+        self.sink.append(source);
+    }
+
+    fn play_signal(&mut self, source: SignalSource) {
+        self.info = Some(AudioInfo {
+            channels: source.channels(),
+            sample_rate: source.sample_rate(),
+            bits_per_sample: None,
+        });
+        self.duration = source.total_duration();
+        self.total_samples = source.total_samples();
+
+        log::info!(
+            "Playing test signal: {} Hz, {} channels",
+            source.sample_rate(),
+            source.channels()
+        );
+
+        self.sink.append(source);
     }
 
     pub fn play(&self) {
         self.sink.play();
     }
 
+    /// Set the master output gain. The sink keeps its volume across
+    /// `stop()` and `append()`, so this only needs to be set once.
+    pub fn set_master_gain(&self, gain: f32) {
+        self.sink
+            .set_volume(gain.clamp(0.0, crate::config::MAX_GAIN));
+    }
+
     pub fn pause(&self) {
         self.sink.pause();
     }
@@ -362,13 +409,32 @@ impl AudioEngine {
         }
     }
 
+    /// Where playback is in sample frames, once something is loaded
+    pub fn sample_position(&self) -> Option<SamplePosition> {
+        let channels = self.info.as_ref()?.channels.max(1) as u64;
+        if self.total_samples == 0 {
+            return None;
+        }
+        let total_frames = self.total_samples as u64 / channels;
+        let played = self.samples_played.load(Ordering::Relaxed) as u64;
+        Some(SamplePosition {
+            frame: (played / channels).min(total_frames),
+            total_frames,
+            approximate: self.mixed_file_paths.is_some(),
+        })
+    }
+
     fn load_file_from_position(
         &mut self,
         path: &Path,
         start_sample: usize,
     ) -> Result<(), Box<dyn Error>> {
-        // Open and decode the file
-        let file = BufReader::new(File::open(path)?);
+        if let Some(signal) = TestSignal::from_path(path) {
+            let mut source = SignalSource::new(signal, self.source_handles());
+            source.skip_to(start_sample);
+            self.sink.append(source);
+            return Ok(());
+        }
 
         // Try to decode based on extension
         let ext = path
@@ -377,10 +443,16 @@ impl AudioEngine {
             .map(|e| e.to_lowercase())
             .unwrap_or_default();
 
+        if should_stream(path) {
+            let source = StreamingSource::open_at(path, start_sample, self.source_handles())?;
+            self.sink.append(source);
+            log::info!("Streaming from sample: {start_sample}");
+            return Ok(());
+        }
+
         match ext.as_str() {
             "wav" => {
-                let decoder = hound::WavReader::new(file)?;
-                self.play_wav_from_position(decoder, start_sample)?;
+                self.play_wav_from_position(path, start_sample)?;
             }
             "flac" => {
                 self.play_flac_from_position(path, start_sample)?;
@@ -396,14 +468,11 @@ impl AudioEngine {
 
     fn play_wav_from_position(
         &mut self,
-        reader: hound::WavReader<BufReader<File>>,
+        path: &Path,
         start_sample: usize,
     ) -> Result<(), Box<dyn Error>> {
-        let _spec = reader.spec();
-
         // Create a monitoring source that sends samples to visualization
-        let mut source =
-            WavSource::new(reader, self.samples_tx.clone(), self.samples_played.clone())?;
+        let mut source = WavSource::open(path, self.source_handles())?;
 
         // Skip to the start position
         source.skip_to(start_sample);
@@ -424,8 +493,7 @@ impl AudioEngine {
         let reader = claxon::FlacReader::open(path)?;
 
         // Create FLAC source
-        let mut source =
-            FlacSource::new(reader, self.samples_tx.clone(), self.samples_played.clone())?;
+        let mut source = FlacSource::new(reader, self.source_handles())?;
 
         // Skip to the start position
         source.skip_to(start_sample);
@@ -445,11 +513,7 @@ impl AudioEngine {
     ) -> Result<(), Box<dyn Error>> {
         // Use cached AIFF data for fast seeking
         if let Some(aiff_data) = &self.cached_aiff_data {
-            let mut source = AiffSource::from_data(
-                aiff_data.clone(),
-                self.samples_tx.clone(),
-                self.samples_played.clone(),
-            )?;
+            let mut source = AiffSource::from_data(aiff_data.clone(), self.source_handles())?;
 
             // Skip to the start position
             source.skip_to(start_sample);
@@ -464,6 +528,25 @@ impl AudioEngine {
         }
     }
 
+    /// The file playing on its own, or None while mixing
+    pub fn single_file_path(&self) -> Option<&str> {
+        self.current_file_path.as_deref()
+    }
+
+    /// Whether a reference track was loaded alongside the mix
+    pub fn has_reference(&self) -> bool {
+        self.current_file_path.is_none() && self.mixed_reference.is_some()
+    }
+
+    /// Switch playback between the mix and the reference. Returns true when
+    /// the reference is now playing.
+    pub fn toggle_reference(&self) -> bool {
+        if !self.has_reference() {
+            return false;
+        }
+        !self.solo_reference.fetch_xor(true, Ordering::Relaxed)
+    }
+
     pub fn seek_relative(&mut self, seconds: f32) -> Result<(), Box<dyn Error>> {
         // Seek forward or backward by seconds
         if let Some(info) = &self.info {
@@ -509,6 +592,237 @@ impl AudioEngine {
     }
 }
 
+/// Lets a background decode give up once a newer one has started: the
+/// decode belongs to `generation` and is stale when `current` moves on
+#[derive(Clone)]
+pub struct DecodeCancel {
+    generation: usize,
+    current: Arc<AtomicUsize>,
+}
+
+impl DecodeCancel {
+    pub fn new(generation: usize, current: Arc<AtomicUsize>) -> Self {
+        Self {
+            generation,
+            current,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.current.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+/// Channels a decoded source reports samples and progress through
+#[derive(Clone)]
+pub struct SourceHandles {
+    pub samples_tx: mpsc::Sender<Vec<f32>>,
+    pub samples_played: Arc<AtomicUsize>,
+    pub channel_mode: SharedChannelMode,
+    pub monitor_frames: usize, // Frames per chunk sent to the visualization
+    pub cancel: Option<DecodeCancel>, // Set for decodes that may be superseded
+}
+
+impl SourceHandles {
+    pub fn new(
+        samples_tx: mpsc::Sender<Vec<f32>>,
+        samples_played: Arc<AtomicUsize>,
+        channel_mode: SharedChannelMode,
+    ) -> Self {
+        Self {
+            samples_tx,
+            samples_played,
+            channel_mode,
+            monitor_frames: DEFAULT_MONITOR_FRAMES,
+            cancel: None,
+        }
+    }
+
+    pub fn with_monitor_frames(mut self, frames: usize) -> Self {
+        self.monitor_frames = frames;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: DecodeCancel) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(DecodeCancel::is_cancelled)
+    }
+
+    /// Fail if this decode has been superseded
+    fn check_cancelled(&self) -> Result<(), Box<dyn Error>> {
+        if self.is_cancelled() {
+            Err(DECODE_CANCELLED.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Samples per visualization chunk. The scope and meters read at most
+    /// two channels, so wider files send stereo-sized chunks.
+    pub fn monitor_chunk_len(&self, channels: u16) -> usize {
+        if channels > 1 {
+            self.monitor_frames * 2
+        } else {
+            self.monitor_frames
+        }
+    }
+}
+
+/// A decoded file, ready for [`AudioEngine::load_prepared`]. Decoding is the
+/// slow part of loading, so this can be built on a worker thread.
+pub enum PreparedSource {
+    Wav(WavSource),
+    Flac(FlacSource),
+    Aiff(AiffSource, crate::media::metadata::AiffData),
+    // Too large to decode up front
+    Stream(StreamingSource),
+    // Synthesized by `--tone` or `--test`
+    Signal(SignalSource),
+}
+
+/// Smallest WAV that can hold a header: RIFF header, fmt chunk and data chunk header
+const MIN_WAV_FILE_BYTES: u64 = 44;
+
+/// Samples decoded between checks for a cancelled decode
+const DECODE_CANCEL_CHECK_SAMPLES: usize = 1 << 16;
+pub const DECODE_CANCELLED: &str = "Decoding cancelled";
+
+/// Highest source sample rate accepted; anything above is a corrupt header
+const MAX_SOURCE_SAMPLE_RATE: u32 = 768_000;
+
+/// Reject empty, obviously truncated and mislabeled files before they reach
+/// a decoder, so partial downloads, interrupted renders and renamed files
+/// fail with a clear message
+fn check_audio_file(path: &Path, ext: &str) -> Result<(), Box<dyn Error>> {
+    let len = std::fs::metadata(path)?.len();
+    if len == 0 {
+        return Err(format!("{} is empty (0 bytes)", path.display()).into());
+    }
+    crate::media::metadata::check_audio_header(path)?;
+    if ext == "wav" && len < MIN_WAV_FILE_BYTES {
+        return Err(format!(
+            "{} is too short to be a valid WAV ({len} bytes)",
+            path.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Open the default output device at its default configuration, falling back
+/// to the other configurations it supports (such as a lower sample rate) and
+/// then to other devices. Sources at any other rate, a 192 kHz file on a
+/// 48 kHz interface say, are resampled to whatever rate the device runs at.
+///
+/// A `buffer_frames` request the device rejects is dropped with a warning
+/// rather than failing playback; the fallbacks use the device's buffer.
+fn open_output_stream(buffer_frames: Option<u32>) -> Result<OutputStream, Box<dyn Error>> {
+    if let Some(frames) = buffer_frames {
+        match OutputStreamBuilder::from_default_device().and_then(|builder| {
+            builder
+                .with_buffer_size(BufferSize::Fixed(frames))
+                .open_stream()
+        }) {
+            Ok(stream) => {
+                log_output_config(&stream);
+                return Ok(stream);
+            }
+            Err(e) => log::warn!(
+                "The output device rejected a {frames} frame buffer ({e}), using its default"
+            ),
+        }
+    }
+
+    let stream = OutputStreamBuilder::from_default_device()
+        .and_then(|builder| builder.open_stream_or_fallback())
+        .or_else(|e| {
+            log::warn!("Could not open the default output device ({e}), trying others");
+            OutputStreamBuilder::open_default_stream()
+        })
+        .map_err(|e| format!("Could not open an audio output device: {e}"))?;
+    log_output_config(&stream);
+    Ok(stream)
+}
+
+fn log_output_config(stream: &OutputStream) {
+    let config = stream.config();
+    let buffer = match config.buffer_size() {
+        BufferSize::Fixed(frames) => format!("{frames} frame buffer"),
+        BufferSize::Default => "default buffer".to_string(),
+    };
+    log::info!(
+        "Audio output: {} Hz, {} channels, {buffer}",
+        config.sample_rate(),
+        config.channel_count()
+    );
+}
+
+/// Refuse sample rates no file really has, which would stall or break the
+/// resampler rather than play
+fn check_sample_rate(path: &Path, sample_rate: u32) -> Result<(), Box<dyn Error>> {
+    if sample_rate == 0 || sample_rate > MAX_SOURCE_SAMPLE_RATE {
+        return Err(format!(
+            "{} has an unplayable sample rate ({sample_rate} Hz)",
+            path.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Decode an audio file into a source for the engine that owns `handles`
+pub fn prepare_source(
+    path: &Path,
+    handles: SourceHandles,
+) -> Result<PreparedSource, Box<dyn Error>> {
+    if let Some(signal) = TestSignal::from_path(path) {
+        return Ok(PreparedSource::Signal(SignalSource::new(signal, handles)));
+    }
+
+    // Try to decode based on extension
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    check_audio_file(path, &ext)?;
+    handles.check_cancelled()?;
+
+    if should_stream(path) {
+        return Ok(PreparedSource::Stream(StreamingSource::open(
+            path, handles,
+        )?));
+    }
+
+    match ext.as_str() {
+        "wav" => {
+            let source = WavSource::open(path, handles)?;
+            check_sample_rate(path, source.sample_rate)?;
+            Ok(PreparedSource::Wav(source))
+        }
+        "flac" => {
+            let reader = claxon::FlacReader::open(path)?;
+            check_sample_rate(path, reader.streaminfo().sample_rate)?;
+            let source = FlacSource::new(reader, handles)?;
+            Ok(PreparedSource::Flac(source))
+        }
+        "aif" | "aiff" => {
+            // Load full AIFF file for best seek performance and seamless playback
+            log::info!("Loading AIFF file: {}", path.display());
+            let aiff_data = crate::media::metadata::read_aiff_data(path)?;
+            check_sample_rate(path, aiff_data.sample_rate)?;
+            let source = AiffSource::from_data(aiff_data.clone(), handles)?;
+            Ok(PreparedSource::Aiff(source, aiff_data))
+        }
+        _ => Err(format!("Unsupported audio format: {ext}").into()),
+    }
+}
+
 // Custom source that monitors samples for visualization
 pub struct WavSource {
     samples_tx: mpsc::Sender<Vec<f32>>,
@@ -518,47 +832,151 @@ pub struct WavSource {
     current_samples: Vec<i32>, // Use i32 to handle up to 24-bit
     position: usize,
     monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
     samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
 }
 
 impl WavSource {
+    /// Decode a WAV file, falling back to a raw read for 64-bit float,
+    /// which hound doesn't support. Compressed formats are refused with a
+    /// message naming the format.
+    pub fn open(path: &Path, handles: SourceHandles) -> Result<Self, Box<dyn Error>> {
+        let layout = wav_metadata::read_wav_layout(path);
+        match hound::WavReader::new(BufReader::new(File::open(path)?)) {
+            Ok(reader) => {
+                log::info!("WAV format: {:?}", reader.spec());
+                let mut source = Self::new(reader, handles)?;
+                if let Ok(layout) = &layout {
+                    source.trim_to_fact(layout);
+                }
+                Ok(source)
+            }
+            Err(e) => match layout {
+                Ok(layout) if layout.is_float64() => {
+                    log::info!("WAV format: 64-bit float, {} channels", layout.channels());
+                    let mut source = Self::from_float64(path, &layout, handles)?;
+                    source.trim_to_fact(&layout);
+                    Ok(source)
+                }
+                Ok(layout) if layout.is_compressed() => Err(format!(
+                    "{} is a compressed WAV ({}), which can't be played; convert it to PCM first",
+                    path.display(),
+                    layout.format_name()
+                )
+                .into()),
+                _ => Err(e.into()),
+            },
+        }
+    }
+
+    /// Drop the padding after the last real frame of a float WAV, whose
+    /// fact chunk holds the true length, so the duration and progress bar
+    /// match the audio. Plain PCM has no padding and ignores the chunk.
+    fn trim_to_fact(&mut self, layout: &wav_metadata::WavLayout) {
+        if !layout.is_float() {
+            return;
+        }
+        if let Some(frames) = layout.fact_frames() {
+            let len = frames as usize * self.channels as usize;
+            if len < self.current_samples.len() {
+                log::info!(
+                    "Trimming {} padding samples after the fact chunk length",
+                    self.current_samples.len() - len
+                );
+                self.current_samples.truncate(len);
+            }
+        }
+    }
+
+    fn from_float64(
+        path: &Path,
+        layout: &wav_metadata::WavLayout,
+        handles: SourceHandles,
+    ) -> Result<Self, Box<dyn Error>> {
+        if layout.channels() == 0 || layout.sample_rate() == 0 {
+            return Err("Invalid WAV format: no channels or zero sample rate".into());
+        }
+        let samples = wav_metadata::read_float64_samples(path, layout)?
+            .into_iter()
+            .map(|s| float_to_i32(s as f32))
+            .collect();
+        let monitor_chunk = handles.monitor_chunk_len(layout.channels());
+
+        Ok(Self {
+            samples_tx: handles.samples_tx,
+            sample_rate: layout.sample_rate(),
+            channels: layout.channels(),
+            bits_per_sample: 32,
+            current_samples: samples,
+            position: 0,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
+        })
+    }
+
     pub fn new(
         mut reader: hound::WavReader<BufReader<File>>,
-        samples_tx: mpsc::Sender<Vec<f32>>,
-        samples_played: Arc<AtomicUsize>,
+        handles: SourceHandles,
     ) -> Result<Self, Box<dyn Error>> {
         let spec = reader.spec();
+        if spec.channels == 0 || spec.sample_rate == 0 {
+            return Err("Invalid WAV format: no channels or zero sample rate".into());
+        }
 
         // Read samples based on bit depth
         let samples = match spec.bits_per_sample {
+            32 if spec.sample_format == hound::SampleFormat::Float => {
+                let samples: Result<Vec<f32>, _> = collect_samples(reader.samples(), &handles);
+                samples
+                    .map_err(truncated_wav)?
+                    .into_iter()
+                    .map(float_to_i32)
+                    .collect()
+            }
             16 => {
-                let samples: Result<Vec<i16>, _> = reader.samples().collect();
-                samples?.into_iter().map(|s| s as i32).collect()
+                let samples: Result<Vec<i16>, _> = collect_samples(reader.samples(), &handles);
+                samples
+                    .map_err(truncated_wav)?
+                    .into_iter()
+                    .map(|s| s as i32)
+                    .collect()
             }
             24 => {
-                let samples: Result<Vec<i32>, _> = reader.samples().collect();
-                samples?
+                let samples: Result<Vec<i32>, _> = collect_samples(reader.samples(), &handles);
+                samples.map_err(truncated_wav)?
             }
             32 => {
-                let samples: Result<Vec<i32>, _> = reader.samples().collect();
-                samples?
+                let samples: Result<Vec<i32>, _> = collect_samples(reader.samples(), &handles);
+                samples.map_err(truncated_wav)?
             }
             8 => {
-                let samples: Result<Vec<i8>, _> = reader.samples().collect();
-                samples?.into_iter().map(|s| (s as i32) << 8).collect()
+                // hound has already removed the unsigned offset, so 0 is silence
+                let samples: Result<Vec<i8>, _> = collect_samples(reader.samples(), &handles);
+                samples
+                    .map_err(truncated_wav)?
+                    .into_iter()
+                    .map(|s| s as i32)
+                    .collect()
             }
             _ => return Err(format!("Unsupported bit depth: {}", spec.bits_per_sample).into()),
         };
+        handles.check_cancelled()?;
+        let monitor_chunk = handles.monitor_chunk_len(spec.channels);
 
         Ok(Self {
-            samples_tx,
+            samples_tx: handles.samples_tx,
             sample_rate: spec.sample_rate,
             channels: spec.channels,
             bits_per_sample: spec.bits_per_sample,
             current_samples: samples,
             position: 0,
-            monitor_buffer: Vec::with_capacity(1024),
-            samples_played,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
         })
     }
 
@@ -567,6 +985,31 @@ impl WavSource {
     }
 }
 
+/// Scale a float sample to the 32-bit integer range the source plays from
+pub(super) fn float_to_i32(sample: f32) -> i32 {
+    (sample.clamp(-1.0, 1.0) as f64 * 2147483647.0) as i32
+}
+
+/// Collect decoded samples, stopping early once the decode is cancelled.
+/// Callers check `handles.is_cancelled()` afterwards.
+fn collect_samples<T, E>(
+    samples: impl Iterator<Item = Result<T, E>>,
+    handles: &SourceHandles,
+) -> Result<Vec<T>, E> {
+    let mut collected = Vec::new();
+    for (index, sample) in samples.enumerate() {
+        if index % DECODE_CANCEL_CHECK_SAMPLES == 0 && handles.is_cancelled() {
+            break;
+        }
+        collected.push(sample?);
+    }
+    Ok(collected)
+}
+
+fn truncated_wav(e: hound::Error) -> Box<dyn Error> {
+    format!("WAV audio data is truncated or unreadable: {e}").into()
+}
+
 impl Iterator for WavSource {
     type Item = f32;
 
@@ -575,7 +1018,9 @@ impl Iterator for WavSource {
             return None;
         }
 
-        let sample = self.current_samples[self.position];
+        let sample = self
+            .channel_mode
+            .sample(self.position, self.channels, &self.current_samples);
         self.position += 1;
 
         // Update samples played counter
@@ -595,8 +1040,7 @@ impl Iterator for WavSource {
 
         // Send visualization data in chunks (keeping stereo interleaving)
         // For stereo: buffer will contain L,R,L,R,L,R...
-        let chunk_size = if self.channels > 1 { 2048 } else { 1024 };
-        if self.monitor_buffer.len() >= chunk_size {
+        if self.monitor_buffer.len() >= self.monitor_chunk {
             let _ = self.samples_tx.send(self.monitor_buffer.clone());
             self.monitor_buffer.clear();
         }
@@ -634,32 +1078,34 @@ pub struct FlacSource {
     current_samples: Vec<i32>,
     position: usize,
     monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
     samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
 }
 
 impl FlacSource {
     pub fn new<R: Read>(
         mut reader: claxon::FlacReader<R>,
-        samples_tx: mpsc::Sender<Vec<f32>>,
-        samples_played: Arc<AtomicUsize>,
+        handles: SourceHandles,
     ) -> Result<Self, Box<dyn Error>> {
         let info = reader.streaminfo();
 
         // Read all samples
-        let mut samples = Vec::new();
-        for sample in reader.samples() {
-            samples.push(sample?);
-        }
+        let samples = collect_samples(reader.samples(), &handles)?;
+        handles.check_cancelled()?;
+        let monitor_chunk = handles.monitor_chunk_len(info.channels as u16);
 
         Ok(Self {
-            samples_tx,
+            samples_tx: handles.samples_tx,
             sample_rate: info.sample_rate,
             channels: info.channels,
             bits_per_sample: info.bits_per_sample,
             current_samples: samples,
             position: 0,
-            monitor_buffer: Vec::with_capacity(1024),
-            samples_played,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
         })
     }
 
@@ -676,7 +1122,9 @@ impl Iterator for FlacSource {
             return None;
         }
 
-        let sample = self.current_samples[self.position];
+        let sample =
+            self.channel_mode
+                .sample(self.position, self.channels as u16, &self.current_samples);
         self.position += 1;
 
         // Update samples played counter
@@ -693,8 +1141,7 @@ impl Iterator for FlacSource {
         self.monitor_buffer.push(sample_f32);
 
         // Send visualization data in chunks (keeping stereo interleaving)
-        let chunk_size = if self.channels > 1 { 2048 } else { 1024 };
-        if self.monitor_buffer.len() >= chunk_size {
+        if self.monitor_buffer.len() >= self.monitor_chunk {
             let _ = self.samples_tx.send(self.monitor_buffer.clone());
             self.monitor_buffer.clear();
         }
@@ -732,24 +1179,28 @@ pub struct AiffSource {
     current_samples: Vec<i32>,
     position: usize,
     monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
     samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
 }
 
 impl AiffSource {
     pub fn from_data(
         aiff_data: crate::media::metadata::AiffData,
-        samples_tx: mpsc::Sender<Vec<f32>>,
-        samples_played: Arc<AtomicUsize>,
+        handles: SourceHandles,
     ) -> Result<Self, Box<dyn std::error::Error>> {
+        let monitor_chunk = handles.monitor_chunk_len(aiff_data.channels);
         Ok(Self {
-            samples_tx,
+            samples_tx: handles.samples_tx,
             sample_rate: aiff_data.sample_rate,
             channels: aiff_data.channels,
             bits_per_sample: aiff_data.bits_per_sample,
             current_samples: aiff_data.audio_samples,
             position: 0,
-            monitor_buffer: Vec::new(),
-            samples_played,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
         })
     }
 
@@ -770,7 +1221,9 @@ impl Iterator for AiffSource {
             return None;
         }
 
-        let sample = self.current_samples[self.position];
+        let sample = self
+            .channel_mode
+            .sample(self.position, self.channels, &self.current_samples);
         self.position += 1;
 
         // Update samples played counter
@@ -788,8 +1241,8 @@ impl Iterator for AiffSource {
         // Store normalized sample for visualization
         self.monitor_buffer.push(sample_f32);
 
-        // Send samples in chunks of 1024 for visualization
-        if self.monitor_buffer.len() >= 1024 {
+        // Send visualization data in chunks (keeping stereo interleaving)
+        if self.monitor_buffer.len() >= self.monitor_chunk {
             let _ = self.samples_tx.send(self.monitor_buffer.clone());
             self.monitor_buffer.clear();
         }
@@ -821,6 +1274,7 @@ impl rodio::Source for AiffSource {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{float32, fmt_chunk, pcm16, riff_wav, write_wav};
 
     fn is_ci_environment() -> bool {
         // Check common CI environment variables
@@ -846,13 +1300,216 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_prepare_source_without_engine() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("tone.wav");
+        write_wav(&path, pcm16(2, 8000), (0..16000).map(|i| (i % 100) as i16));
+
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+
+        match prepare_source(&path, handles.clone()).unwrap() {
+            PreparedSource::Wav(source) => {
+                assert_eq!(source.channels(), 2);
+                assert_eq!(source.total_duration(), Some(Duration::from_secs(1)));
+            }
+            _ => panic!("expected a WAV source"),
+        }
+
+        assert!(prepare_source(Path::new("take.mp3"), handles.clone()).is_err());
+        assert!(prepare_source(&temp_dir.path().join("missing.wav"), handles).is_err());
+    }
+
+    #[test]
+    fn test_cancelled_decode_stops_between_chunks() {
+        let current = Arc::new(AtomicUsize::new(1));
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        )
+        .with_cancel(DecodeCancel::new(1, current.clone()));
+
+        // Superseded partway through: decoding stops at the next chunk
+        let samples = (0..DECODE_CANCEL_CHECK_SAMPLES * 4).map(|i| {
+            if i == DECODE_CANCEL_CHECK_SAMPLES + 10 {
+                current.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok::<_, hound::Error>(i)
+        });
+        let decoded = collect_samples(samples, &handles).unwrap();
+        assert_eq!(decoded.len(), DECODE_CANCEL_CHECK_SAMPLES * 2);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("take.wav");
+        write_wav(&path, pcm16(1, 8000), [0i16]);
+        let error = prepare_source(&path, handles).err().unwrap();
+        assert_eq!(error.to_string(), DECODE_CANCELLED);
+    }
+
+    #[test]
+    fn test_wav_fact_chunk_sets_length() {
+        // Mono 8000 Hz with the given fmt tag, block align, bits and cbSize extra bytes
+        let wav = |tag: u16, align: u16, bits: u16, extra: &[u8], fact: u32, data: &[u8]| {
+            riff_wav(
+                &fmt_chunk(tag, 1, 8000, align, bits, extra),
+                Some(fact),
+                data,
+            )
+        };
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+
+        // Float samples padded to 1002 frames, of which 1000 are audio
+        let float = temp_dir.path().join("float.wav");
+        let data: Vec<u8> = (0..1002).flat_map(|_| 0.25f32.to_le_bytes()).collect();
+        std::fs::write(&float, wav(3, 4, 32, &[], 1000, &data)).unwrap();
+        match prepare_source(&float, handles.clone()).unwrap() {
+            PreparedSource::Wav(source) => {
+                assert_eq!(source.total_duration(), Some(Duration::from_millis(125)));
+                assert_eq!(source.count(), 1000);
+            }
+            _ => panic!("expected a WAV source"),
+        }
+        let metadata = crate::media::metadata::read_audio_metadata(&float).unwrap();
+        assert_eq!(metadata.duration_seconds, Some(0.125));
+
+        // Compressed audio can't be decoded, and the error says why
+        let adpcm = temp_dir.path().join("adpcm.wav");
+        std::fs::write(
+            &adpcm,
+            wav(0x11, 256, 4, &505u16.to_le_bytes(), 505, &[0; 256]),
+        )
+        .unwrap();
+        let error = prepare_source(&adpcm, handles).err().unwrap().to_string();
+        assert!(error.contains("compressed WAV (IMA ADPCM)"), "{error}");
+    }
+
+    #[test]
+    fn test_check_sample_rate() {
+        let path = Path::new("take.aif");
+        assert!(check_sample_rate(path, 44_100).is_ok());
+        assert!(check_sample_rate(path, 384_000).is_ok());
+        assert_eq!(
+            check_sample_rate(path, 0).unwrap_err().to_string(),
+            "take.aif has an unplayable sample rate (0 Hz)"
+        );
+        assert!(check_sample_rate(path, 3_000_000).is_err());
+    }
+
+    #[test]
+    fn test_prepare_source_rejects_empty_and_truncated_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+        let error_for = |name: &str, bytes: &[u8]| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            match prepare_source(&path, handles.clone()) {
+                Ok(_) => panic!("{name} should not load"),
+                Err(e) => e.to_string(),
+            }
+        };
+
+        assert!(error_for("empty.wav", &[]).contains("is empty"));
+        assert!(error_for("empty.flac", &[]).contains("is empty"));
+        assert!(error_for("stub.wav", b"RIFF\x04\0\0\0WAVE").contains("too short"));
+        assert!(error_for("notes.wav", b"# not audio at all").contains("looks like text"));
+
+        // A render cut off partway through its data chunk
+        let path = temp_dir.path().join("full.wav");
+        write_wav(&path, pcm16(2, 8000), 0..1000i16);
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(error_for("cut.wav", &bytes[..bytes.len() - 501]).contains("truncated"));
+    }
+
+    #[test]
+    fn test_wav_sample_formats_normalize() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+        let play = |path: &Path| -> Vec<f32> {
+            WavSource::open(path, handles.clone())
+                .unwrap()
+                .map(|s| (s * 1000.0).round() / 1000.0)
+                .collect()
+        };
+
+        // 8-bit is unsigned on disk: 128 is silence, 0 and 255 the extremes
+        let path = temp_dir.path().join("8bit.wav");
+        let spec = hound::WavSpec {
+            bits_per_sample: 8,
+            ..pcm16(1, 8000)
+        };
+        write_wav(&path, spec, [0i8, -128, 127, 64]);
+        assert_eq!(play(&path), [0.0, -1.0, 0.992, 0.5]);
+
+        let path = temp_dir.path().join("float32.wav");
+        write_wav(&path, float32(1, 8000), [0.0f32, -0.5, 0.25, 2.0]);
+        assert_eq!(play(&path), [0.0, -0.5, 0.25, 1.0]);
+
+        // 64-bit float, which hound can't read
+        let path = temp_dir.path().join("float64.wav");
+        let data: Vec<u8> = [0.75f64, -0.25]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        std::fs::write(
+            &path,
+            riff_wav(&fmt_chunk(3, 1, 8000, 8, 64, &[]), None, &data),
+        )
+        .unwrap();
+        assert_eq!(play(&path), [0.75, -0.25]);
+    }
+
+    #[test]
+    fn test_monitor_chunk_size() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("stereo.wav");
+        write_wav(&path, pcm16(2, 8000), [0i16; 20]);
+
+        let (samples_tx, samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+        assert_eq!(handles.monitor_chunk_len(1), DEFAULT_MONITOR_FRAMES);
+        assert_eq!(handles.monitor_chunk_len(2), DEFAULT_MONITOR_FRAMES * 2);
+
+        // Four stereo frames per chunk: 20 samples make two full chunks
+        let source = WavSource::open(&path, handles.with_monitor_frames(4)).unwrap();
+        assert_eq!(source.count(), 20);
+        let chunks: Vec<usize> = samples_rx.try_iter().map(|chunk| chunk.len()).collect();
+        assert_eq!(chunks, [8, 8]);
+    }
+
     #[test]
     fn test_new_audio_engine() {
         if skip_if_no_audio().is_err() {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
 
         // In CI or systems without audio, this might fail
         if result.is_err() {
@@ -877,7 +1534,7 @@ mod tests {
         }
 
         // Try to create engine, skip test if it fails (likely due to no audio device)
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
@@ -887,6 +1544,7 @@ mod tests {
 
         // Check initial progress
         assert_eq!(engine.get_progress(), 0.0);
+        assert!(engine.sample_position().is_none());
     }
 
     #[test]
@@ -895,14 +1553,14 @@ mod tests {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
         }
 
-        let (mut engine, _rx) = result.unwrap();
-        let result = engine.load_file(Path::new("/nonexistent/file.wav"));
+        let (engine, _rx) = result.unwrap();
+        let result = prepare_source(Path::new("/nonexistent/file.wav"), engine.source_handles());
 
         assert!(result.is_err());
     }
@@ -913,14 +1571,14 @@ mod tests {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
         }
 
-        let (mut engine, _rx) = result.unwrap();
-        let result = engine.load_file(Path::new("test.mp3"));
+        let (engine, _rx) = result.unwrap();
+        let result = prepare_source(Path::new("test.mp3"), engine.source_handles());
 
         assert!(result.is_err());
         // The actual error depends on whether the file exists or not
@@ -934,7 +1592,7 @@ mod tests {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
@@ -955,7 +1613,7 @@ mod tests {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
@@ -974,7 +1632,7 @@ mod tests {
             return;
         }
 
-        let result = AudioEngine::new();
+        let result = AudioEngine::new(None);
         if result.is_err() {
             eprintln!("Skipping test: AudioEngine creation failed (no audio device?)");
             return;
@@ -9,9 +9,12 @@
 use log::warn;
 use std::fs;
 use std::path::{Path, PathBuf};
+use zim_studio::constants::PLAYABLE_EXTENSIONS;
+use zim_studio::media::metadata::check_audio_header;
+use zim_studio::utils::parallel_scan::{SkipDirs, VisitedDirs, should_descend};
+use zim_studio::utils::sidecar::{SidecarLocation, get_sidecar_path, split_frontmatter};
 use zim_studio::zimignore::ZimIgnore;
 
-const SUPPORTED_AUDIO_EXTENSIONS: &[&str] = &["wav", "flac"];
 const DEFAULT_CONTEXT_SIZE: usize = 80;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,6 +23,41 @@ pub enum BrowserFocus {
     Files,
 }
 
+/// How the file list is grouped under header rows
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BrowserGrouping {
+    #[default]
+    Flat,
+    Project, // Sidecar `project` field
+    Folder,  // Parent folder relative to the scanned folder
+}
+
+impl BrowserGrouping {
+    fn next(self) -> Self {
+        match self {
+            BrowserGrouping::Flat => BrowserGrouping::Project,
+            BrowserGrouping::Project => BrowserGrouping::Folder,
+            BrowserGrouping::Folder => BrowserGrouping::Flat,
+        }
+    }
+
+    /// Short label for the file list title
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            BrowserGrouping::Flat => None,
+            BrowserGrouping::Project => Some("by project"),
+            BrowserGrouping::Folder => Some("by folder"),
+        }
+    }
+}
+
+/// A line of the file list: a group header, or a file with its position
+/// among the filtered items
+pub enum BrowserRow<'a> {
+    Header(String),
+    File(usize, &'a AudioFile),
+}
+
 #[derive(Debug, Clone)]
 enum SearchQuery {
     FullText(String),
@@ -38,7 +76,8 @@ pub struct FileMetadata {
     pub title: String,
     pub project: Option<String>,
     pub tags: Vec<String>,
-    pub content: String, // Full markdown content for searching
+    pub art: Vec<String>, // Paths of the sidecar's `art` entries
+    pub content: String,  // Full markdown content for searching
 }
 
 pub struct Browser {
@@ -48,7 +87,14 @@ pub struct Browser {
     pub search_query: String,
     pub focus: BrowserFocus,
     pub search_visible: bool, // Whether search box is shown
+    pub show_waveform: bool,  // Whether the preview includes the selected file's waveform
+    pub show_full_path: bool, // List paths relative to the scanned folder instead of names
+    pub grouping: BrowserGrouping,
+    pub search_body: bool, // Full-text search includes the sidecar's markdown body
+    pub sidecar_location: SidecarLocation, // Where new sidecars go; existing ones are found either way
+    root: PathBuf,                         // Folder the file list was scanned from
     zimignore: ZimIgnore,
+    skip_dirs: SkipDirs,
 }
 
 impl Browser {
@@ -60,18 +106,31 @@ impl Browser {
             search_query: String::new(),
             focus: BrowserFocus::Files, // Start with files focused
             search_visible: false,      // Start with search hidden
+            show_waveform: true,
+            show_full_path: false,
+            grouping: BrowserGrouping::default(),
+            search_body: true,
+            sidecar_location: SidecarLocation::default(),
+            root: PathBuf::new(),
             zimignore: ZimIgnore::new(),
+            skip_dirs: SkipDirs::default(),
         }
     }
 
     pub fn scan_directory(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         self.items.clear();
+        self.root = path.to_path_buf();
 
         // Load .zimignore patterns for this directory
         self.zimignore = ZimIgnore::load_for_directory(path);
+        self.skip_dirs = SkipDirs::from_config();
 
         // Recursively find all audio files
-        self.scan_directory_recursive(path)?;
+        let visited = VisitedDirs::default();
+        if self.skip_dirs.follow_symlinks() {
+            visited.first_visit(path);
+        }
+        self.scan_directory_recursive(path, &visited)?;
 
         // Sort by filename
         self.items
@@ -83,7 +142,11 @@ impl Browser {
         Ok(())
     }
 
-    fn scan_directory_recursive(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fn scan_directory_recursive(
+        &mut self,
+        path: &Path,
+        visited: &VisitedDirs,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Skip hidden directories (starting with .)
         if let Some(name) = path.file_name()
             && let Some(name_str) = name.to_str()
@@ -111,6 +174,11 @@ impl Browser {
                 if self.zimignore.is_ignored(relative_path, true) {
                     continue;
                 }
+                if let Some(name) = path.file_name()
+                    && self.skip_dirs.contains(&name.to_string_lossy())
+                {
+                    continue;
+                }
 
                 // Skip . and .. to avoid infinite recursion
                 if let Some(name) = path.file_name()
@@ -119,8 +187,11 @@ impl Browser {
                 {
                     continue;
                 }
+                if !should_descend(&path, &self.skip_dirs, visited) {
+                    continue;
+                }
                 // Recursively scan subdirectories
-                if let Err(e) = self.scan_directory_recursive(&path) {
+                if let Err(e) = self.scan_directory_recursive(&path, visited) {
                     warn!("Could not scan directory {path:?}: {e}");
                 }
             } else if path.is_file() {
@@ -153,15 +224,17 @@ impl Browser {
     }
 
     fn create_audio_file(&self, path: PathBuf) -> Result<AudioFile, Box<dyn std::error::Error>> {
+        // Leave out renamed non-audio files rather than failing when picked
+        check_audio_header(&path)?;
+
         let mut audio_file = AudioFile {
             audio_path: path.clone(),
             sidecar_path: None,
             metadata: FileMetadata::default(),
         };
 
-        // Look for sidecar .md file (append .md to full filename)
-        let mut sidecar = PathBuf::from(path.as_os_str());
-        sidecar.as_mut_os_string().push(".md");
+        // Look for the sidecar next to the file or under .zim/
+        let sidecar = get_sidecar_path(&path, self.sidecar_location);
 
         if sidecar.exists() {
             audio_file.sidecar_path = Some(sidecar.clone());
@@ -184,6 +257,16 @@ impl Browser {
         Ok(audio_file)
     }
 
+    /// Re-read the sidecar for one item after its metadata changed on disk
+    pub fn refresh_item(&mut self, audio_path: &Path) {
+        if let Some(index) = self.items.iter().position(|i| i.audio_path == audio_path)
+            && let Ok(audio_file) = self.create_audio_file(audio_path.to_path_buf())
+        {
+            self.items[index] = audio_file;
+            self.filter_items();
+        }
+    }
+
     pub fn push_char(&mut self, c: char) {
         log::debug!(
             "Before push_char: search_query = {:?}, char = {:?}",
@@ -222,6 +305,7 @@ impl Browser {
         self.filter_items();
     }
 
+    #[allow(clippy::unnecessary_sort_by)]
     fn filter_items(&mut self) {
         if self.search_query.is_empty() {
             // No search - show all items by index
@@ -235,7 +319,7 @@ impl Browser {
                 .iter()
                 .enumerate()
                 .filter_map(|(idx, item)| {
-                    score_item_with_query(item, &parsed_query)
+                    score_item_with_query(item, &parsed_query, self.search_body)
                         .map(|(_, score, context)| (idx, score, context))
                 })
                 .collect();
@@ -249,6 +333,18 @@ impl Browser {
                 .collect();
         }
 
+        // Keep each group together, in search order within the group
+        if self.grouping != BrowserGrouping::Flat {
+            let mut indices = std::mem::take(&mut self.filtered_indices);
+            indices.sort_by_cached_key(|(idx, _)| {
+                self.items
+                    .get(*idx)
+                    .map(|item| self.group_key(item))
+                    .unwrap_or_default()
+            });
+            self.filtered_indices = indices;
+        }
+
         // Reset selection if out of bounds
         if self.selected >= self.filtered_indices.len() {
             self.selected = 0;
@@ -271,6 +367,92 @@ impl Browser {
         }
     }
 
+    /// Cycle grouping (flat, by project, by folder), keeping the selected file selected
+    pub fn cycle_grouping(&mut self) {
+        let selected = self
+            .filtered_indices
+            .get(self.selected)
+            .map(|(idx, _)| *idx);
+        self.grouping = self.grouping.next();
+        self.filter_items();
+        if let Some(position) = selected.and_then(|selected| {
+            self.filtered_indices
+                .iter()
+                .position(|(idx, _)| *idx == selected)
+        }) {
+            self.selected = position;
+        }
+    }
+
+    /// Sort key for a file's group; files without a project sort last
+    fn group_key(&self, item: &AudioFile) -> (bool, String) {
+        match self.grouping {
+            BrowserGrouping::Flat => (false, String::new()),
+            BrowserGrouping::Project => match &item.metadata.project {
+                Some(project) => (false, project.clone()),
+                None => (true, String::new()),
+            },
+            BrowserGrouping::Folder => (false, self.folder_label(&item.audio_path)),
+        }
+    }
+
+    fn folder_label(&self, audio_path: &Path) -> String {
+        let folder = audio_path
+            .parent()
+            .map(|parent| parent.strip_prefix(&self.root).unwrap_or(parent))
+            .unwrap_or(Path::new(""));
+        if folder.as_os_str().is_empty() {
+            "./".to_string()
+        } else {
+            format!("{}/", folder.display())
+        }
+    }
+
+    fn group_label(&self, item: &AudioFile) -> String {
+        match self.grouping {
+            BrowserGrouping::Folder => self.folder_label(&item.audio_path),
+            _ => item
+                .metadata
+                .project
+                .clone()
+                .unwrap_or_else(|| "(no project)".to_string()),
+        }
+    }
+
+    /// The file list as displayed, with a header row starting each group
+    pub fn rows(&self) -> Vec<BrowserRow<'_>> {
+        let mut rows = Vec::new();
+        let mut current_group = None;
+        for (position, (item, _)) in self.get_filtered_items().into_iter().enumerate() {
+            if self.grouping != BrowserGrouping::Flat {
+                let label = self.group_label(item);
+                if current_group.as_ref() != Some(&label) {
+                    rows.push(BrowserRow::Header(label.clone()));
+                    current_group = Some(label);
+                }
+            }
+            rows.push(BrowserRow::File(position, item));
+        }
+        rows
+    }
+
+    /// How a file is named in the list: its file name, or its path relative
+    /// to the scanned folder when full paths are shown
+    pub fn display_name(&self, audio_path: &Path) -> String {
+        if self.show_full_path {
+            audio_path
+                .strip_prefix(&self.root)
+                .unwrap_or(audio_path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            audio_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Unknown".to_string())
+        }
+    }
+
     pub fn get_selected_path(&self) -> Option<&Path> {
         self.filtered_indices
             .get(self.selected)
@@ -286,10 +468,27 @@ impl Browser {
     }
 }
 
+/// The playable files directly in `dir`, filtered and sorted as the browser
+/// lists them, for stepping through a folder from the player
+pub fn folder_audio_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let zimignore = ZimIgnore::load_for_directory(dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && is_supported_audio_file(path)
+                && !zimignore.is_ignored(path, false)
+                && check_audio_header(path).is_ok()
+        })
+        .collect();
+    files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    Ok(files)
+}
+
 fn is_supported_audio_file(path: &Path) -> bool {
     path.extension()
         .and_then(|e| e.to_str())
-        .map(|e| SUPPORTED_AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .map(|e| PLAYABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
         .unwrap_or(false)
 }
 
@@ -301,7 +500,7 @@ fn parse_search_query(query: &str) -> SearchQuery {
 
         // Only recognize specific fields (allow both singular and plural for tags)
         match field.as_str() {
-            "title" | "tags" | "tag" => {
+            "title" | "tags" | "tag" | "art" => {
                 // Normalize "tag" to "tags" internally for consistency
                 let normalized_field = if field == "tag" {
                     "tags".to_string()
@@ -323,9 +522,10 @@ fn parse_search_query(query: &str) -> SearchQuery {
 fn score_item_with_query(
     item: &AudioFile,
     query: &SearchQuery,
+    search_body: bool,
 ) -> Option<(AudioFile, i64, Option<String>)> {
     match query {
-        SearchQuery::FullText(text) => score_item(item, text),
+        SearchQuery::FullText(text) => score_item(item, text, search_body),
         SearchQuery::FieldQuery { field, value } => score_field_query(item, field, value),
     }
 }
@@ -379,25 +579,50 @@ fn score_field_query(
                 }
             }
         }
+        "art" => {
+            // An empty value matches any item with artwork
+            let matching: Vec<&str> = item
+                .metadata
+                .art
+                .iter()
+                .filter(|path| path.to_lowercase().contains(value))
+                .map(String::as_str)
+                .collect();
+            if matching.is_empty() {
+                None
+            } else {
+                let context = Some(format!("Art: {}", matching.join(", ")));
+                Some((item.clone(), 100, context))
+            }
+        }
         _ => None,
     }
 }
 
-fn score_item(item: &AudioFile, query: &str) -> Option<(AudioFile, i64, Option<String>)> {
+/// Score a full-text match against the sidecar and filename. Without
+/// `search_body` only the sidecar's frontmatter fields are searched.
+fn score_item(
+    item: &AudioFile,
+    query: &str,
+    search_body: bool,
+) -> Option<(AudioFile, i64, Option<String>)> {
     let mut best_score = None;
     let mut context = None;
 
     // Search in metadata content using substring matching
-    if !item.metadata.content.is_empty() {
-        let content_lower = item.metadata.content.to_lowercase();
+    let searchable = if search_body {
+        item.metadata.content.as_str()
+    } else {
+        split_frontmatter(&item.metadata.content)
+            .map(|(frontmatter, _)| frontmatter)
+            .unwrap_or_default()
+    };
+    if !searchable.is_empty() {
+        let content_lower = searchable.to_lowercase();
 
         if let Some(pos) = content_lower.find(query) {
             // Found exact substring match
-            context = Some(extract_context(
-                &item.metadata.content,
-                pos,
-                DEFAULT_CONTEXT_SIZE,
-            ));
+            context = Some(extract_context(searchable, pos, DEFAULT_CONTEXT_SIZE));
             best_score = Some(100); // High score for exact matches
         }
     }
@@ -449,48 +674,12 @@ fn parse_sidecar_content(content: &str) -> FileMetadata {
     let mut metadata = FileMetadata::default();
 
     // Check if content has YAML frontmatter
-    if let Some(content_after_marker) = content.strip_prefix("---\n") {
-        // Find the end of frontmatter
-        if let Some(end_pos) = content_after_marker.find("\n---\n") {
-            let yaml_content = &content_after_marker[..end_pos];
-
-            // Parse YAML line by line (simple parser for our needs)
-            for line in yaml_content.lines() {
-                let line = line.trim();
-
-                if let Some(colon_pos) = line.find(':') {
-                    let key = line[..colon_pos].trim();
-                    let value = line[colon_pos + 1..].trim();
-
-                    match key {
-                        "title" => {
-                            // Remove quotes if present
-                            metadata.title = value.trim_matches('"').to_string();
-                        }
-                        "project" => {
-                            // Remove quotes if present
-                            let project_value = value.trim_matches('"');
-                            if project_value != "unknown" && !project_value.is_empty() {
-                                metadata.project = Some(project_value.to_string());
-                            }
-                        }
-                        "tags" => {
-                            // Parse array format: ["tag1", "tag2"] or []
-                            if value.starts_with('[') && value.ends_with(']') {
-                                let tags_str = &value[1..value.len() - 1];
-                                if !tags_str.is_empty() {
-                                    metadata.tags = tags_str
-                                        .split(',')
-                                        .map(|s| s.trim().trim_matches('"').to_string())
-                                        .filter(|s| !s.is_empty())
-                                        .collect();
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
+    if let Some((yaml_content, _)) = split_frontmatter(content) {
+        match serde_yaml::from_str::<serde_yaml::Mapping>(yaml_content) {
+            Ok(fields) => read_frontmatter_fields(&fields, &mut metadata),
+            // Hand-edited sidecars aren't always valid YAML, so fall back to
+            // picking out the fields line by line
+            Err(_) => scan_frontmatter_lines(yaml_content, &mut metadata),
         }
 
         // Also look for H1 title after frontmatter as fallback
@@ -517,6 +706,121 @@ fn parse_sidecar_content(content: &str) -> FileMetadata {
     metadata
 }
 
+/// Title, project, tags and art from parsed frontmatter, in inline
+/// (`[a, b]`) or block (`- a`) style
+fn read_frontmatter_fields(fields: &serde_yaml::Mapping, metadata: &mut FileMetadata) {
+    let text = |key: &str| fields.get(key).and_then(scalar_text);
+
+    if let Some(title) = text("title") {
+        metadata.title = title;
+    }
+    metadata.project = text("project").filter(|p| p != "unknown" && !p.is_empty());
+    metadata.tags = match fields.get("tags") {
+        Some(serde_yaml::Value::Sequence(tags)) => tags.iter().filter_map(scalar_text).collect(),
+        // A hand-written `tags: drums, live`
+        Some(serde_yaml::Value::String(tags)) => tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    if let Some(serde_yaml::Value::Sequence(art)) = fields.get("art") {
+        metadata.art = art
+            .iter()
+            .filter_map(|entry| match entry {
+                serde_yaml::Value::Mapping(entry) => entry.get("path").and_then(scalar_text),
+                entry => scalar_text(entry),
+            })
+            .filter(|path| !path.is_empty())
+            .collect();
+    }
+}
+
+/// A string, number or boolean as text
+fn scalar_text(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Line-by-line reading of frontmatter that isn't valid YAML. Only
+/// unindented keys are read, so the `description` of an art entry isn't
+/// taken for the file's.
+fn scan_frontmatter_lines(yaml_content: &str, metadata: &mut FileMetadata) {
+    let unquote = |value: &str| {
+        value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string()
+    };
+
+    // The block list ("- item" lines) that follows a bare "tags:" or "art:"
+    let mut block_list: Option<&str> = None;
+    for line in yaml_content.lines() {
+        let indented = line.starts_with([' ', '\t']);
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(list) = block_list {
+            if indented || trimmed.starts_with('-') {
+                match (list, trimmed.strip_prefix('-').map(str::trim)) {
+                    ("tags", Some(item)) => {
+                        let tag = unquote(item);
+                        if !tag.is_empty() {
+                            metadata.tags.push(tag);
+                        }
+                    }
+                    // Art entries are "- path: ..." mappings, with the path
+                    // anywhere among their keys
+                    ("art", item) => {
+                        let field = item.unwrap_or(trimmed);
+                        if let Some(path) = field.strip_prefix("path:") {
+                            metadata.art.push(unquote(path));
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            block_list = None;
+        }
+        if indented {
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "title" => metadata.title = unquote(value),
+            "project" => {
+                let project = unquote(value);
+                if project != "unknown" && !project.is_empty() {
+                    metadata.project = Some(project);
+                }
+            }
+            // Parse array format: ["tag1", "tag2"] or []
+            "tags" if value.starts_with('[') && value.ends_with(']') => {
+                metadata.tags = value[1..value.len() - 1]
+                    .split(',')
+                    .map(unquote)
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+            key @ ("tags" | "art") if value.is_empty() => block_list = Some(key),
+            _ => {}
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -535,6 +839,7 @@ mod tests {
                 title: "Test Title".to_string(),
                 project: Some("test-project".to_string()),
                 tags: vec!["tag1".to_string(), "tag2".to_string()],
+                art: Vec::new(),
                 content: "Test content for searching".to_string(),
             },
         }
@@ -549,6 +854,132 @@ mod tests {
         assert!(browser.search_query.is_empty());
     }
 
+    #[test]
+    fn test_display_name_toggles_relative_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("mixes/v2")).unwrap();
+        fs::write(root.join("mixes/v2/final.wav"), b"").unwrap();
+
+        let mut browser = create_test_browser();
+        browser.scan_directory(&root).unwrap();
+        let path = root.join("mixes/v2/final.wav");
+
+        assert_eq!(browser.display_name(&path), "final.wav");
+        browser.show_full_path = true;
+        assert_eq!(
+            browser.display_name(&path),
+            Path::new("mixes/v2/final.wav").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_scan_finds_aiff_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("library");
+        fs::create_dir_all(&root).unwrap();
+        for name in ["a.wav", "b.aif", "c.AIFF", "d.mp3", "e.txt"] {
+            fs::write(root.join(name), b"").unwrap();
+        }
+
+        let mut browser = create_test_browser();
+        browser.scan_directory(&root).unwrap();
+        let mut names: Vec<String> = browser
+            .items
+            .iter()
+            .map(|item| {
+                item.audio_path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, ["a.wav", "b.aif", "c.AIFF"]);
+    }
+
+    #[test]
+    fn test_folder_audio_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["c.flac", "a.wav", "b.aif", "notes.txt", "nested/d.wav"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        // Not audio despite the extension
+        fs::write(dir.join("fake.wav"), b"plain text, not a RIFF header").unwrap();
+
+        let names: Vec<String> = folder_audio_files(dir)
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["a.wav", "b.aif", "c.flac"]);
+    }
+
+    #[test]
+    fn test_grouping_inserts_headers() {
+        let mut browser = create_test_browser();
+        let mut loose = create_test_audio_file("/music/loose.wav");
+        loose.metadata.project = None;
+        let mut song_b = create_test_audio_file("/music/b/verse.wav");
+        song_b.metadata.project = Some("song-b".to_string());
+        let mut song_a = create_test_audio_file("/music/a/chorus.wav");
+        song_a.metadata.project = Some("song-a".to_string());
+        browser.items = vec![loose, song_b, song_a];
+        browser.root = PathBuf::from("/music");
+        browser.filter_items();
+        browser.selected = 1;
+
+        let labels = |browser: &Browser| -> Vec<String> {
+            browser
+                .rows()
+                .iter()
+                .map(|row| match row {
+                    BrowserRow::Header(label) => format!("# {label}"),
+                    BrowserRow::File(_, item) => browser.display_name(&item.audio_path),
+                })
+                .collect()
+        };
+        assert_eq!(labels(&browser), ["loose.wav", "verse.wav", "chorus.wav"]);
+
+        browser.cycle_grouping();
+        assert_eq!(
+            labels(&browser),
+            [
+                "# song-a",
+                "chorus.wav",
+                "# song-b",
+                "verse.wav",
+                "# (no project)",
+                "loose.wav"
+            ]
+        );
+        // The selection follows the file, not the position
+        assert_eq!(
+            browser.get_selected_path(),
+            Some(Path::new("/music/b/verse.wav"))
+        );
+
+        browser.cycle_grouping();
+        assert_eq!(browser.grouping, BrowserGrouping::Folder);
+        assert_eq!(
+            labels(&browser),
+            [
+                "# ./",
+                "loose.wav",
+                "# a/",
+                "chorus.wav",
+                "# b/",
+                "verse.wav"
+            ]
+        );
+
+        browser.cycle_grouping();
+        assert_eq!(browser.grouping, BrowserGrouping::Flat);
+    }
+
     #[test]
     fn test_is_supported_audio_file() {
         assert!(is_supported_audio_file(Path::new("test.wav")));
@@ -650,6 +1081,88 @@ Some description here.
         assert!(metadata.tags.contains(&"experimental".to_string()));
     }
 
+    #[test]
+    fn test_parse_sidecar_content_block_tags() {
+        let content = "---\ntitle: Pad\ntags:\n- ambient\n- '808'\nduration: 1.0\n---\n";
+        let metadata = parse_sidecar_content(content);
+        assert_eq!(metadata.title, "Pad");
+        assert_eq!(metadata.tags, vec!["ambient", "808"]);
+    }
+
+    #[test]
+    fn test_parse_sidecar_content_art() {
+        // Inline tags with block-style art, as zim update writes them
+        let content = r#"---
+title: "Cover"
+project: "unknown"
+tags: ["mix", "final"]
+art:
+  - path: "cover.jpg"
+    description: "Embedded cover art"
+    purpose: "cover_art"
+  - path: "back.png"
+    description: "Back cover"
+---
+"#;
+        let metadata = parse_sidecar_content(content);
+        assert_eq!(metadata.title, "Cover");
+        assert_eq!(metadata.project, None);
+        assert_eq!(metadata.tags, vec!["mix", "final"]);
+        assert_eq!(metadata.art, vec!["cover.jpg", "back.png"]);
+
+        // Block tags and inline art entries
+        let content = "---\ntags:\n  - drums\n  - live\nart: [{path: a.jpg}, b.png]\n---\n";
+        let metadata = parse_sidecar_content(content);
+        assert_eq!(metadata.tags, vec!["drums", "live"]);
+        assert_eq!(metadata.art, vec!["a.jpg", "b.png"]);
+    }
+
+    #[test]
+    fn test_parse_sidecar_content_invalid_yaml() {
+        // A Windows path is an invalid escape in a double-quoted string, so
+        // the fields are read line by line. Keys nested in art entries must
+        // not be taken for the file's own.
+        let content = r#"---
+title: "Take 3"
+source: "C:\Users\me\take.wav"
+tags:
+  - "vocal"
+  - 'lead'
+art:
+  - description: "Session photo"
+    title: "Not the title"
+    path: "photo.jpg"
+  - path: "sheet.png"
+project: "demo"
+---
+"#;
+        let metadata = parse_sidecar_content(content);
+        assert_eq!(metadata.title, "Take 3");
+        assert_eq!(metadata.project.as_deref(), Some("demo"));
+        assert_eq!(metadata.tags, vec!["vocal", "lead"]);
+        assert_eq!(metadata.art, vec!["photo.jpg", "sheet.png"]);
+
+        let content = "---\ntitle: \"x\\y\"\ntags: [\"a\", \"b\"]\nart: []\n---\n";
+        let metadata = parse_sidecar_content(content);
+        assert_eq!(metadata.tags, vec!["a", "b"]);
+        assert!(metadata.art.is_empty());
+    }
+
+    #[test]
+    fn test_art_field_query() {
+        let mut item = create_test_audio_file("song.wav");
+        assert!(score_field_query(&item, "art", "").is_none());
+
+        item.metadata.art = vec!["song.wav.cover.jpg".to_string()];
+        assert!(score_field_query(&item, "art", "").is_some());
+        assert!(score_field_query(&item, "art", "cover").is_some());
+        assert!(score_field_query(&item, "art", "png").is_none());
+        assert!(matches!(
+            parse_search_query("art: cover"),
+            SearchQuery::FieldQuery { ref field, .. } if field == "art"
+        ));
+    }
+
     #[test]
     fn test_parse_sidecar_content_markdown() {
         // Test old markdown format (fallback)
@@ -686,23 +1199,50 @@ Some description here.
         item.metadata.content = "This is some sample content with keywords".to_string();
 
         // Test content match
-        let result = score_item(&item, "sample");
+        let result = score_item(&item, "sample", true);
         assert!(result.is_some());
         let (_, score, context) = result.unwrap();
         assert_eq!(score, 100); // Content match gets high score
         assert!(context.is_some());
 
         // Test filename match
-        let result = score_item(&item, "test");
+        let result = score_item(&item, "test", true);
         assert!(result.is_some());
         let (_, score, _) = result.unwrap();
         assert_eq!(score, 50); // Filename match gets lower score
 
         // Test no match
-        let result = score_item(&item, "xyz");
+        let result = score_item(&item, "xyz", true);
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_score_item_frontmatter_only() {
+        let mut item = create_test_audio_file("take.wav");
+        item.metadata.content =
+            "---\ntitle: Bass Take\ntags: [bass]\n---\n# Notes\n\nTry with the 808 kick\n"
+                .to_string();
+
+        // Body text matches by default
+        assert!(score_item(&item, "808", true).is_some());
+        assert!(score_item(&item, "808", false).is_none());
+
+        // Frontmatter fields still match, with context from the frontmatter
+        let (_, score, context) = score_item(&item, "bass take", false).unwrap();
+        assert_eq!(score, 100);
+        assert!(context.unwrap().contains("Bass Take"));
+
+        // The filename is always searched
+        let (_, score, _) = score_item(&item, "take", false).unwrap();
+        assert_eq!(score, 100);
+        assert_eq!(score_item(&item, "tak", false).unwrap().1, 100);
+
+        // Without frontmatter there is nothing but the filename to match
+        item.metadata.content = "# Old notes about the 808".to_string();
+        assert!(score_item(&item, "808", false).is_none());
+        assert_eq!(score_item(&item, "take", false).unwrap().1, 50);
+    }
+
     #[test]
     fn test_filter_items_empty_query() {
         let mut browser = create_test_browser();
@@ -746,7 +1286,7 @@ Some description here.
         let audio_path = temp_dir.path().join("test.wav");
         let sidecar_path = temp_dir.path().join("test.wav.md");
 
-        fs::write(&audio_path, b"fake wav").unwrap();
+        fs::write(&audio_path, b"RIFF\0\0\0\0WAVE").unwrap();
         fs::write(
             &sidecar_path,
             r#"---
@@ -776,7 +1316,7 @@ Some content here.
         let temp_dir = TempDir::new().unwrap();
         let audio_path = temp_dir.path().join("test.flac");
 
-        fs::write(&audio_path, b"fake flac").unwrap();
+        fs::write(&audio_path, b"fLaC").unwrap();
 
         let browser = create_test_browser();
         let audio_file = browser.create_audio_file(audio_path).unwrap();
@@ -785,4 +1325,34 @@ Some content here.
         assert!(audio_file.metadata.tags.is_empty());
         assert!(audio_file.sidecar_path.is_none());
     }
+
+    #[test]
+    fn test_scan_skips_mislabeled_audio_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("take.wav"), b"RIFF\0\0\0\0WAVE").unwrap();
+        fs::write(root.join("notes.wav"), b"just some notes").unwrap();
+
+        let browser = create_test_browser();
+        match browser.create_audio_file(root.join("notes.wav")) {
+            Ok(_) => panic!("notes.wav should be refused"),
+            Err(e) => assert!(e.to_string().contains("looks like text")),
+        }
+
+        let mut browser = create_test_browser();
+        browser.scan_directory(&root).unwrap();
+        let names: Vec<String> = browser
+            .items
+            .iter()
+            .map(|item| {
+                item.audio_path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["take.wav"]);
+    }
 }
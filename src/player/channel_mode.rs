@@ -0,0 +1,253 @@
+//! Stereo monitoring modes: solo one channel, swap left and right, sum both
+//! to mono to check how a mix collapses, or audition the mid (L+R) or side
+//! (L-R) signal on its own to hear what is in the center and the width.
+//!
+//! The mode is applied in each source's `next()`, after decoding and before
+//! output, so it also reaches the meters and scope. It is shared through an
+//! atomic so changing it takes effect immediately without rebuilding the
+//! source. Files that aren't stereo play unchanged.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChannelMode {
+    #[default]
+    Stereo,
+    SoloLeft,  // Left channel in both ears
+    SoloRight, // Right channel in both ears
+    Swap,      // Left and right exchanged
+    Mono3dB,   // L+R in both ears at -3 dB, equal-power compensation
+    Mono6dB,   // L+R in both ears at -6 dB, so correlated material keeps its level
+    Mid,       // (L+R)/2 in both ears: the center of the mix
+    Side,      // (L-R)/2 in both ears: only what differs between the channels
+}
+
+impl ChannelMode {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ChannelMode::SoloLeft,
+            2 => ChannelMode::SoloRight,
+            3 => ChannelMode::Swap,
+            4 => ChannelMode::Mono3dB,
+            5 => ChannelMode::Mono6dB,
+            6 => ChannelMode::Mid,
+            7 => ChannelMode::Side,
+            _ => ChannelMode::Stereo,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            ChannelMode::Stereo => 0,
+            ChannelMode::SoloLeft => 1,
+            ChannelMode::SoloRight => 2,
+            ChannelMode::Swap => 3,
+            ChannelMode::Mono3dB => 4,
+            ChannelMode::Mono6dB => 5,
+            ChannelMode::Mid => 6,
+            ChannelMode::Side => 7,
+        }
+    }
+
+    /// The source channel that feeds output `channel` of a stereo frame.
+    /// Mono and mid/side modes read both channels, see [`ChannelMode::matrix`].
+    pub fn source_channel(self, channel: usize) -> usize {
+        match self {
+            ChannelMode::Stereo
+            | ChannelMode::Mono3dB
+            | ChannelMode::Mono6dB
+            | ChannelMode::Mid
+            | ChannelMode::Side => channel,
+            ChannelMode::SoloLeft => 0,
+            ChannelMode::SoloRight => 1,
+            ChannelMode::Swap => 1 - channel,
+        }
+    }
+
+    /// Short label for the player status line
+    pub fn label(self) -> &'static str {
+        match self {
+            ChannelMode::Stereo => "L R",
+            ChannelMode::SoloLeft => "L only",
+            ChannelMode::SoloRight => "R only",
+            ChannelMode::Swap => "R L (swapped)",
+            ChannelMode::Mono3dB => "MONO -3 dB",
+            ChannelMode::Mono6dB => "MONO -6 dB",
+            ChannelMode::Mid => "MID (L+R)",
+            ChannelMode::Side => "SIDE (L-R)",
+        }
+    }
+
+    /// Gains for left and right when both ears hear a blend of the two
+    /// channels, or None when each output reads a single source channel
+    pub fn matrix(self) -> Option<(f32, f32)> {
+        match self {
+            ChannelMode::Mono3dB => Some((
+                std::f32::consts::FRAC_1_SQRT_2,
+                std::f32::consts::FRAC_1_SQRT_2,
+            )),
+            ChannelMode::Mono6dB | ChannelMode::Mid => Some((0.5, 0.5)),
+            ChannelMode::Side => Some((0.5, -0.5)),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded sample type that can be blended across channels
+pub trait MonitorSample: Copy {
+    fn blend(left: Self, right: Self, gains: (f32, f32)) -> Self;
+}
+
+impl MonitorSample for i32 {
+    fn blend(left: Self, right: Self, (left_gain, right_gain): (f32, f32)) -> Self {
+        let sum = left as f64 * left_gain as f64 + right as f64 * right_gain as f64;
+        sum.clamp(i32::MIN as f64, i32::MAX as f64) as i32
+    }
+}
+
+impl MonitorSample for f32 {
+    fn blend(left: Self, right: Self, (left_gain, right_gain): (f32, f32)) -> Self {
+        left * left_gain + right * right_gain
+    }
+}
+
+/// Channel mode shared between the UI and the playing source
+#[derive(Debug, Clone, Default)]
+pub struct SharedChannelMode(Arc<AtomicU8>);
+
+impl SharedChannelMode {
+    pub fn get(&self) -> ChannelMode {
+        ChannelMode::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, mode: ChannelMode) {
+        self.0.store(mode.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Index of the sample to play at `position` of an interleaved buffer
+    /// holding `len` samples
+    pub fn remap(&self, position: usize, channels: u16, len: usize) -> usize {
+        if channels != 2 {
+            return position;
+        }
+        let channel = position % 2;
+        let index = position - channel + self.get().source_channel(channel);
+        // A trailing half frame has no partner sample
+        if index < len { index } else { position }
+    }
+
+    /// The sample to play at `position` of an interleaved buffer
+    pub fn sample<T: MonitorSample>(&self, position: usize, channels: u16, samples: &[T]) -> T {
+        if channels == 2
+            && let Some(gains) = self.get().matrix()
+        {
+            let left = position - position % 2;
+            if left + 1 < samples.len() {
+                return T::blend(samples[left], samples[left + 1], gains);
+            }
+            return samples[position];
+        }
+        samples[self.remap(position, channels, samples.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(mode: ChannelMode, samples: &[f32], channels: u16) -> Vec<f32> {
+        let shared = SharedChannelMode::default();
+        shared.set(mode);
+        (0..samples.len())
+            .map(|i| samples[shared.remap(i, channels, samples.len())])
+            .collect()
+    }
+
+    #[test]
+    fn test_remap_stereo_frames() {
+        let frames = [0.1, -0.1, 0.2, -0.2];
+        assert_eq!(play(ChannelMode::Stereo, &frames, 2), frames);
+        assert_eq!(
+            play(ChannelMode::SoloLeft, &frames, 2),
+            [0.1, 0.1, 0.2, 0.2]
+        );
+        assert_eq!(
+            play(ChannelMode::SoloRight, &frames, 2),
+            [-0.1, -0.1, -0.2, -0.2]
+        );
+        assert_eq!(play(ChannelMode::Swap, &frames, 2), [-0.1, 0.1, -0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_remap_leaves_other_layouts_alone() {
+        let mono = [0.1, 0.2, 0.3];
+        assert_eq!(play(ChannelMode::Swap, &mono, 1), mono);
+
+        // The unpaired last sample of a truncated stereo buffer plays as-is
+        assert_eq!(play(ChannelMode::SoloRight, &mono, 2), [0.2, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn test_mono_sum() {
+        let shared = SharedChannelMode::default();
+        shared.set(ChannelMode::Mono6dB);
+        let frames = [0.5f32, -0.5, 0.4, 0.2];
+        let summed: Vec<f32> = (0..4).map(|i| shared.sample(i, 2, &frames)).collect();
+        assert_eq!(summed, [0.0, 0.0, 0.3, 0.3]);
+
+        shared.set(ChannelMode::Mono3dB);
+        let frames = [16384i32, 16384];
+        assert_eq!(shared.sample(1, 2, &frames), 23170);
+
+        // Mono files and an unpaired trailing sample are left alone
+        assert_eq!(shared.sample(2, 1, &[1i32, 2, 3]), 3);
+        assert_eq!(shared.sample(2, 2, &[1i32, 2, 3]), 3);
+
+        // Summing full-scale 32-bit samples clamps instead of wrapping
+        assert_eq!(shared.sample(0, 2, &[i32::MAX, i32::MAX]), i32::MAX);
+    }
+
+    #[test]
+    fn test_mid_side() {
+        let shared = SharedChannelMode::default();
+        let frames = [0.5f32, 0.5, 0.75, -0.25];
+        let play = |mode| {
+            shared.set(mode);
+            (0..4)
+                .map(|i| shared.sample(i, 2, &frames))
+                .collect::<Vec<_>>()
+        };
+
+        // A centered signal has no side content at all
+        assert_eq!(play(ChannelMode::Mid), [0.5, 0.5, 0.25, 0.25]);
+        assert_eq!(play(ChannelMode::Side), [0.0, 0.0, 0.5, 0.5]);
+
+        // Out-of-phase integer samples cancel in the mid and add in the side
+        let frames = [1000i32, -1000];
+        shared.set(ChannelMode::Mid);
+        assert_eq!(shared.sample(0, 2, &frames), 0);
+        shared.set(ChannelMode::Side);
+        assert_eq!(shared.sample(1, 2, &frames), 1000);
+    }
+
+    #[test]
+    fn test_shared_mode_roundtrip() {
+        let shared = SharedChannelMode::default();
+        let source_side = shared.clone();
+        assert_eq!(source_side.get(), ChannelMode::Stereo);
+        for mode in [
+            ChannelMode::SoloLeft,
+            ChannelMode::SoloRight,
+            ChannelMode::Swap,
+            ChannelMode::Mono3dB,
+            ChannelMode::Mono6dB,
+            ChannelMode::Mid,
+            ChannelMode::Side,
+            ChannelMode::Stereo,
+        ] {
+            shared.set(mode);
+            assert_eq!(source_side.get(), mode);
+        }
+    }
+}
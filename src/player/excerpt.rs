@@ -0,0 +1,699 @@
+//! Writing a marked region of a file to a new WAV.
+//!
+//! Used by the player's save dialog and by `zim chop`. Excerpts keep the
+//! source's WAV format (FLAC sources become 16-bit WAV, AIFF sources keep
+//! their bit depth), carry its lineage, and get a copy of its sidecar with
+//! the selection recorded.
+
+use log::info;
+use std::error::Error;
+use std::path::Path;
+use zim_studio::utils::sidecar::{
+    SidecarCloneMode, SidecarLocation, clone_sidecar, get_sidecar_path, split_frontmatter,
+};
+
+/// Save `start`..`end` (in seconds) of `source` to `dest_path` with
+/// its lineage and sidecar, returning how many samples were written at full
+/// scale. Sidecar and notes failures are logged rather than returned, since
+/// the audio itself was saved.
+#[allow(clippy::too_many_arguments)]
+pub fn save_excerpt(
+    source: &Path,
+    dest_path: &Path,
+    start: f64,
+    end: f64,
+    duration: f64,
+    tags_fallback: Option<&[String]>,
+    inherit_tags: bool,
+    embed_notes: bool,
+    sidecar_location: SidecarLocation,
+) -> Result<usize, Box<dyn Error>> {
+    let clipped = write_excerpt_audio(source, dest_path, start, end)?;
+
+    // Carry the source's lineage into the excerpt before anything else
+    // touches its INFO chunk
+    if let Err(e) = tag_excerpt_lineage(source, dest_path) {
+        log::warn!("Failed to write lineage to {dest_path:?}: {e}");
+    }
+
+    if let Err(e) = clone_sidecar(
+        source,
+        dest_path,
+        SidecarCloneMode::Selection {
+            start_time: start as f32,
+            end_time: end as f32,
+            duration: duration as f32,
+            inherit_tags,
+        },
+        tags_fallback,
+        sidecar_location,
+    ) {
+        log::warn!("Failed to create sidecar file: {e}");
+    } else if embed_notes && let Err(e) = embed_sidecar_notes(dest_path, sidecar_location) {
+        log::warn!("Failed to embed notes in {dest_path:?}: {e}");
+    }
+    Ok(clipped)
+}
+
+/// Write just the audio of a selection, choosing the reader by extension.
+/// Returns the number of samples written at full scale.
+pub fn write_excerpt_audio(
+    source: &Path,
+    dest_path: &Path,
+    start: f64,
+    end: f64,
+) -> Result<usize, Box<dyn Error>> {
+    let source_ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    let existed = dest_path.exists();
+    let result = match source_ext.as_str() {
+        "wav" => save_wav_selection(source, dest_path, start, end),
+        "flac" => save_flac_to_wav_selection(source, dest_path, start, end),
+        "aif" | "aiff" => save_aiff_to_wav_selection(source, dest_path, start, end),
+        _ => Err(format!("Unsupported source format: {source_ext}").into()),
+    };
+    // Don't leave a truncated file behind
+    if result.is_err() && !existed {
+        let _ = std::fs::remove_file(dest_path);
+    }
+    result
+}
+
+/// A sample value that can be checked against the limits of its format
+trait FullScale {
+    /// Whether the sample sits at the edge of a `bits`-bit range, which
+    /// usually means the signal clipped before or while it was written
+    fn is_full_scale(&self, bits: u16) -> bool;
+}
+
+impl FullScale for f32 {
+    fn is_full_scale(&self, _bits: u16) -> bool {
+        self.abs() >= 1.0
+    }
+}
+
+macro_rules! impl_int_full_scale {
+    ($($int:ty),*) => {$(
+        impl FullScale for $int {
+            fn is_full_scale(&self, bits: u16) -> bool {
+                let max = (1i64 << (bits - 1)) - 1;
+                let value = *self as i64;
+                value >= max || value < -max
+            }
+        }
+    )*};
+}
+
+impl_int_full_scale!(i8, i16, i32);
+
+fn save_wav_selection(
+    source_path: &Path,
+    dest_path: &Path,
+    start: f64,
+    end: f64,
+) -> Result<usize, Box<dyn Error>> {
+    use hound::{WavReader, WavWriter};
+    use std::fs::File;
+    use std::io::BufReader;
+
+    // Open source file, copying formats hound can't decode as raw frames
+    let reader = match WavReader::new(BufReader::new(File::open(source_path)?)) {
+        Ok(reader) => reader,
+        Err(e) => {
+            let layout = match crate::wav_metadata::read_wav_layout(source_path) {
+                Ok(layout) if layout.is_float64() => layout,
+                _ => return Err(e.into()),
+            };
+            let (start_frame, frames) =
+                frame_range(start, end, layout.sample_rate(), layout.frames() as usize)?;
+            crate::wav_metadata::write_wav_excerpt(
+                source_path,
+                dest_path,
+                &layout,
+                start_frame as u64,
+                frames as u64,
+            )?;
+            info!("Saved WAV selection to: {dest_path:?}");
+            // Copied as raw bytes, so there are no decoded samples to check
+            return Ok(0);
+        }
+    };
+    let spec = reader.spec();
+
+    // Calculate sample range
+    let (start_sample, samples_to_write) = calculate_sample_range(&reader, start, end)?;
+
+    // Create output file
+    let mut writer = WavWriter::create(dest_path, spec)?;
+
+    // Read and write samples based on sample format
+    let clipped = copy_wav_samples(reader, &mut writer, spec, start_sample, samples_to_write)?;
+
+    writer.finalize()?;
+    info!("Saved WAV selection to: {dest_path:?}");
+    Ok(clipped)
+}
+
+/// The first frame and number of frames covered by `start`..`end` (in
+/// seconds) of a file `total_frames` long at `sample_rate`. Marks are clamped
+/// to the file, and a selection that is inverted, past the end or shorter
+/// than a frame is an error rather than an empty or truncated file.
+fn frame_range(
+    start: f64,
+    end: f64,
+    sample_rate: u32,
+    total_frames: usize,
+) -> Result<(usize, usize), String> {
+    if !start.is_finite() || !end.is_finite() {
+        return Err(format!("Invalid selection marks: {start} to {end}"));
+    }
+    if start >= end {
+        return Err(format!(
+            "Mark in ({start:.3}s) must come before mark out ({end:.3}s)"
+        ));
+    }
+    if total_frames == 0 || sample_rate == 0 {
+        return Err("The source has no audio to select from".to_string());
+    }
+    let to_frame =
+        |seconds: f64| ((seconds.max(0.0) * sample_rate as f64).round() as usize).min(total_frames);
+    let (start_frame, end_frame) = (to_frame(start), to_frame(end));
+    if start_frame >= total_frames {
+        return Err(format!(
+            "Selection starts past the end of the file ({total_frames} frames)"
+        ));
+    }
+    if end_frame <= start_frame {
+        return Err("Selection is shorter than one frame".to_string());
+    }
+    Ok((start_frame, end_frame - start_frame))
+}
+
+fn calculate_sample_range(
+    reader: &hound::WavReader<std::io::BufReader<std::fs::File>>,
+    start: f64,
+    end: f64,
+) -> Result<(usize, usize), Box<dyn Error>> {
+    // Work in whole frames so a stereo excerpt never starts on the right channel
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+    let (start_frame, frames) =
+        frame_range(start, end, spec.sample_rate, reader.duration() as usize)?;
+    Ok((start_frame * channels, frames * channels))
+}
+
+/// Fail when the source ran out before the selection did, which means its
+/// header claims more audio than the file holds
+fn check_complete(written: usize, expected: usize) -> Result<(), Box<dyn Error>> {
+    if written < expected {
+        return Err(format!(
+            "The source ended early: read {written} of {expected} samples in the selection"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn copy_wav_samples<W: std::io::Write + std::io::Seek>(
+    mut reader: hound::WavReader<std::io::BufReader<std::fs::File>>,
+    writer: &mut hound::WavWriter<W>,
+    spec: hound::WavSpec,
+    start_sample: usize,
+    samples_to_write: usize,
+) -> Result<usize, Box<dyn Error>> {
+    use hound::SampleFormat;
+
+    let bits = spec.bits_per_sample;
+    match (spec.sample_format, bits) {
+        (SampleFormat::Float, 32) => {
+            copy_samples::<f32, _>(&mut reader, writer, bits, start_sample, samples_to_write)
+        }
+        (SampleFormat::Int, 16) => {
+            copy_samples::<i16, _>(&mut reader, writer, bits, start_sample, samples_to_write)
+        }
+        (SampleFormat::Int, 24 | 32) => {
+            copy_samples::<i32, _>(&mut reader, writer, bits, start_sample, samples_to_write)
+        }
+        // hound reads and writes the unsigned 8-bit offset itself, so i8 values
+        // round-trip without a DC shift
+        (SampleFormat::Int, 8) => {
+            copy_samples::<i8, _>(&mut reader, writer, bits, start_sample, samples_to_write)
+        }
+        (format, bits) => Err(format!("Unsupported WAV format: {bits}-bit {format:?}").into()),
+    }
+}
+
+fn copy_samples<T, W>(
+    reader: &mut hound::WavReader<std::io::BufReader<std::fs::File>>,
+    writer: &mut hound::WavWriter<W>,
+    bits: u16,
+    start_sample: usize,
+    samples_to_write: usize,
+) -> Result<usize, Box<dyn Error>>
+where
+    T: hound::Sample + FullScale + std::fmt::Debug,
+    W: std::io::Write + std::io::Seek,
+{
+    let samples: Vec<T> = reader
+        .samples::<T>()
+        .skip(start_sample)
+        .take(samples_to_write)
+        .collect::<Result<Vec<_>, _>>()?;
+    check_complete(samples.len(), samples_to_write)?;
+
+    let mut clipped = 0;
+    for sample in samples {
+        if sample.is_full_scale(bits) {
+            clipped += 1;
+        }
+        writer.write_sample(sample)?;
+    }
+
+    Ok(clipped)
+}
+
+fn save_flac_to_wav_selection(
+    source_path: &Path,
+    dest_path: &Path,
+    start: f64,
+    end: f64,
+) -> Result<usize, Box<dyn Error>> {
+    use claxon::FlacReader;
+    use hound::{WavSpec, WavWriter};
+
+    // Open FLAC file
+    let reader = FlacReader::open(source_path)?;
+    let info = reader.streaminfo();
+
+    // Calculate sample range. STREAMINFO counts frames, but the reader
+    // yields interleaved samples.
+    let total_frames = info
+        .samples
+        .ok_or("The FLAC file doesn't record its length")? as usize;
+    let channels = info.channels as usize;
+    let (start_frame, frames) = frame_range(start, end, info.sample_rate, total_frames)?;
+    let start_sample = start_frame * channels;
+    let end_sample = (start_frame + frames) * channels;
+
+    // Create WAV spec from FLAC info
+    let spec = WavSpec {
+        channels: info.channels as u16,
+        sample_rate: info.sample_rate,
+        bits_per_sample: 16, // Convert to 16-bit for compatibility
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    // Create output WAV file
+    let mut writer = WavWriter::create(dest_path, spec)?;
+
+    // Read and convert samples
+    let clipped = convert_flac_samples(
+        reader,
+        &mut writer,
+        info.bits_per_sample,
+        start_sample,
+        end_sample,
+    )?;
+
+    writer.finalize()?;
+    info!("Saved FLAC selection as WAV to: {dest_path:?}");
+    Ok(clipped)
+}
+
+fn save_aiff_to_wav_selection(
+    source_path: &Path,
+    dest_path: &Path,
+    start: f64,
+    end: f64,
+) -> Result<usize, Box<dyn Error>> {
+    use hound::{WavSpec, WavWriter};
+
+    let aiff = crate::media::metadata::read_aiff_data(source_path)?;
+    let channels = aiff.channels.max(1) as usize;
+    let total_frames = aiff.audio_samples.len() / channels;
+    let (start_frame, frames) = frame_range(start, end, aiff.sample_rate, total_frames)?;
+    let samples = &aiff.audio_samples[start_frame * channels..(start_frame + frames) * channels];
+
+    // Float AIFF-C is decoded to 32-bit integers
+    let bits_per_sample = aiff.bits_per_sample.min(32);
+    let spec = WavSpec {
+        channels: aiff.channels,
+        sample_rate: aiff.sample_rate,
+        bits_per_sample,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = WavWriter::create(dest_path, spec)?;
+    let mut clipped = 0;
+    for &sample in samples {
+        if sample.is_full_scale(bits_per_sample) {
+            clipped += 1;
+        }
+        writer.write_sample(sample)?;
+    }
+
+    writer.finalize()?;
+    info!("Saved AIFF selection as WAV to: {dest_path:?}");
+    Ok(clipped)
+}
+
+fn convert_flac_samples<W: std::io::Write + std::io::Seek>(
+    mut reader: claxon::FlacReader<std::fs::File>,
+    writer: &mut hound::WavWriter<W>,
+    bits_per_sample: u32,
+    start_sample: usize,
+    end_sample: usize,
+) -> Result<usize, Box<dyn Error>> {
+    let mut sample_count = 0;
+    let mut written = 0;
+    let mut clipped = 0;
+
+    for sample in reader.samples() {
+        if sample_count >= start_sample && sample_count < end_sample {
+            let sample = sample?;
+            let sample_i16 = convert_sample_to_16bit(sample, bits_per_sample);
+            if sample_i16.is_full_scale(16) {
+                clipped += 1;
+            }
+            writer.write_sample(sample_i16)?;
+            written += 1;
+        }
+
+        sample_count += 1;
+        if sample_count >= end_sample {
+            break;
+        }
+    }
+
+    check_complete(written, end_sample - start_sample)?;
+    Ok(clipped)
+}
+
+fn convert_sample_to_16bit(sample: i32, bits_per_sample: u32) -> i16 {
+    match bits_per_sample {
+        16 => sample as i16,
+        24 => (sample >> 8) as i16,
+        32 => (sample >> 16) as i16,
+        bits if bits < 16 => (sample << (16 - bits)) as i16,
+        _ => (sample >> (bits_per_sample - 16)) as i16,
+    }
+}
+
+/// Embed lineage derived from a tagged source WAV into an excerpt saved from
+/// it: a new UUID with the source as parent, the next generation, and the
+/// excerpt's own audio hash. Untagged sources are left as they are.
+fn tag_excerpt_lineage(source_path: &Path, excerpt_path: &Path) -> Result<(), Box<dyn Error>> {
+    let is_wav = source_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return Ok(());
+    }
+    let Some(parent) = crate::wav_metadata::read_metadata(source_path)? else {
+        return Ok(());
+    };
+
+    let mut derived = parent.new_derived("excerpt");
+    derived.audio_md5 = crate::wav_metadata::calculate_audio_md5(excerpt_path)?;
+
+    // Write to a temp file then replace the excerpt
+    let temp_path = excerpt_path.with_extension("wav.tmp");
+    if let Err(e) = crate::wav_metadata::write_metadata(excerpt_path, &temp_path, &derived) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    std::fs::rename(&temp_path, excerpt_path)?;
+    Ok(())
+}
+
+/// Copy a saved selection's sidecar title, description and tags into the
+/// WAV's INFO chunk so other tools can show them
+fn embed_sidecar_notes(
+    wav_path: &Path,
+    sidecar_location: SidecarLocation,
+) -> Result<(), Box<dyn Error>> {
+    let content = std::fs::read_to_string(get_sidecar_path(wav_path, sidecar_location))?;
+    let (frontmatter, _) = split_frontmatter(&content).ok_or("Sidecar has no frontmatter")?;
+    let fields: serde_yaml::Mapping = serde_yaml::from_str(frontmatter)?;
+    let text = |key: &str| {
+        fields
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+    };
+    let notes = crate::wav_metadata::InfoNotes {
+        title: text("title"),
+        description: text("description"),
+        tags: fields
+            .get("tags")
+            .and_then(|v| v.as_sequence())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    if notes.is_empty() {
+        return Ok(());
+    }
+    crate::wav_metadata::write_info_notes(wav_path, &notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{float32, pcm16, write_wav};
+
+    #[test]
+    fn test_convert_sample_to_16bit() {
+        // Test 16-bit (no conversion)
+        assert_eq!(convert_sample_to_16bit(1000, 16), 1000);
+
+        // Test 24-bit conversion
+        assert_eq!(convert_sample_to_16bit(256000, 24), 1000);
+
+        // Test 32-bit conversion
+        assert_eq!(convert_sample_to_16bit(65536000, 32), 1000);
+    }
+
+    #[test]
+    fn test_frame_range() {
+        assert_eq!(frame_range(0.25, 0.75, 100, 100), Ok((25, 50)));
+        // Marks outside the file are clamped to it
+        assert_eq!(frame_range(-0.5, 2.0, 100, 100), Ok((0, 100)));
+        assert_eq!(frame_range(0.5, 1.5, 100, 100), Ok((50, 50)));
+
+        assert!(
+            frame_range(0.75, 0.25, 100, 100)
+                .unwrap_err()
+                .contains("before mark out")
+        );
+        assert!(frame_range(0.5, 0.5, 100, 100).is_err());
+        assert!(
+            frame_range(1.0, 1.5, 100, 100)
+                .unwrap_err()
+                .contains("past the end")
+        );
+        assert!(
+            frame_range(0.5, 0.501, 100, 100)
+                .unwrap_err()
+                .contains("one frame")
+        );
+        assert!(frame_range(0.0, 1.0, 100, 0).is_err());
+        assert!(frame_range(f64::NAN, 1.0, 100, 100).is_err());
+
+        // Cuts land on the exact frame deep into a long file
+        let frames = 48_000 * 600;
+        assert_eq!(
+            frame_range(599.99, 600.0, 48_000, frames),
+            Ok((frames - 480, 480))
+        );
+    }
+
+    #[test]
+    fn test_invalid_selection_writes_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("take.wav");
+        let excerpt = temp_dir.path().join("take_edit.wav");
+        write_wav(&source, pcm16(2, 8000), 0..200i16);
+
+        // Seconds at a fraction of the 100-frame file
+        let at = |fraction: f64| fraction * 100.0 / 8000.0;
+        let error = write_excerpt_audio(&source, &excerpt, at(0.8), at(0.2)).unwrap_err();
+        assert!(error.to_string().contains("before mark out"));
+        assert!(write_excerpt_audio(&source, &excerpt, at(1.2), at(1.5)).is_err());
+        assert!(!excerpt.exists());
+
+        // An out mark past the end stops at the last frame
+        write_excerpt_audio(&source, &excerpt, at(0.5), at(1.2)).unwrap();
+        let reader = hound::WavReader::open(&excerpt).unwrap();
+        assert_eq!(reader.duration(), 50);
+    }
+
+    #[test]
+    fn test_flac_selection_covers_all_channels() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav = temp_dir.path().join("pad.wav");
+        let flac = temp_dir.path().join("pad.flac");
+        let excerpt = temp_dir.path().join("pad_edit.wav");
+        let samples = (0..1000i16).flat_map(|frame| [frame, -frame]);
+        write_wav(&wav, pcm16(2, 8000), samples);
+        crate::player::transcode::save_transcoded(&wav, &flac, SidecarLocation::Adjacent).unwrap();
+
+        write_excerpt_audio(&flac, &excerpt, 500.0 / 8000.0, 1000.0 / 8000.0).unwrap();
+        let mut reader = hound::WavReader::open(&excerpt).unwrap();
+        let samples: Vec<i16> = reader.samples().map(|s| s.unwrap()).collect();
+        // The second half of the frames, both channels of each
+        assert_eq!(samples.len(), 1000);
+        assert_eq!(&samples[..4], &[500, -500, 501, -501]);
+        assert_eq!(&samples[998..], &[999, -999]);
+    }
+
+    #[test]
+    fn test_is_full_scale() {
+        assert!(i16::MAX.is_full_scale(16));
+        assert!(i16::MIN.is_full_scale(16));
+        assert!(!(i16::MAX - 1).is_full_scale(16));
+        assert!(8_388_607i32.is_full_scale(24));
+        assert!((-8_388_608i32).is_full_scale(24));
+        assert!(!8_388_606i32.is_full_scale(24));
+        assert!(127i8.is_full_scale(8));
+        assert!((-1.0f32).is_full_scale(32));
+        assert!(!0.999f32.is_full_scale(32));
+    }
+
+    #[test]
+    fn test_selection_counts_clipped_samples() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("hot.wav");
+        let excerpt = temp_dir.path().join("hot_edit.wav");
+        let samples = (0..100).map(|i| match i {
+            10 | 60 => i16::MAX,
+            70 => i16::MIN,
+            _ => 1000,
+        });
+        write_wav(&source, pcm16(1, 8000), samples);
+
+        let at = |frame: f64| frame / 8000.0;
+        assert_eq!(
+            write_excerpt_audio(&source, &excerpt, at(50.0), at(100.0)).unwrap(),
+            2
+        );
+        std::fs::remove_file(&excerpt).unwrap();
+        assert_eq!(
+            write_excerpt_audio(&source, &excerpt, at(20.0), at(50.0)).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_8bit_selection_roundtrip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("lofi.wav");
+        let excerpt = temp_dir.path().join("lofi_edit.wav");
+        let spec = hound::WavSpec {
+            bits_per_sample: 8,
+            ..pcm16(2, 8000)
+        };
+        let samples: Vec<i8> = (0..400).map(|i| (i % 256 - 128) as i8).collect();
+        write_wav(&source, spec, samples.iter().copied());
+
+        // On disk 8-bit is unsigned, so silence is stored as 128
+        let bytes = std::fs::read(&source).unwrap();
+        assert_eq!(bytes[44 + 128], 128);
+
+        save_wav_selection(&source, &excerpt, 50.0 / 8000.0, 150.0 / 8000.0).unwrap();
+
+        let mut reader = hound::WavReader::open(&excerpt).unwrap();
+        assert_eq!(reader.spec(), spec);
+        let saved: Vec<i8> = reader.samples().map(|s| s.unwrap()).collect();
+        assert_eq!(saved, &samples[100..300]);
+    }
+
+    #[test]
+    fn test_aiff_selection_saved_as_wav() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("take.aiff");
+        let excerpt = temp_dir.path().join("take_edit.wav");
+
+        // 16-bit stereo at 8000 Hz, big-endian
+        let samples: Vec<i16> = (0..200).map(|i| i * 100 - 10000).collect();
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        let mut comm = Vec::new();
+        comm.extend(2u16.to_be_bytes());
+        comm.extend(100u32.to_be_bytes());
+        comm.extend(16u16.to_be_bytes());
+        comm.extend([0x40, 0x0B, 0xFA, 0, 0, 0, 0, 0, 0, 0]);
+        let mut body = b"AIFF".to_vec();
+        body.extend(b"COMM");
+        body.extend((comm.len() as u32).to_be_bytes());
+        body.extend(&comm);
+        body.extend(b"SSND");
+        body.extend((data.len() as u32 + 8).to_be_bytes());
+        body.extend([0u8; 8]);
+        body.extend(&data);
+        let mut file = b"FORM".to_vec();
+        file.extend((body.len() as u32).to_be_bytes());
+        file.extend(body);
+        std::fs::write(&source, file).unwrap();
+
+        write_excerpt_audio(&source, &excerpt, 25.0 / 8000.0, 75.0 / 8000.0).unwrap();
+
+        let mut reader = hound::WavReader::open(&excerpt).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 8000);
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        let saved: Vec<i16> = reader.samples().map(|s| s.unwrap()).collect();
+        assert_eq!(saved, &samples[50..150]);
+    }
+
+    #[test]
+    fn test_float_selection_keeps_format() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("float.wav");
+        let excerpt = temp_dir.path().join("float_edit.wav");
+        let spec = float32(1, 48000);
+        let samples: Vec<f32> = (0..100).map(|i| i as f32 / 100.0 - 0.5).collect();
+        write_wav(&source, spec, samples.iter().copied());
+
+        save_wav_selection(&source, &excerpt, 50.0 / 48000.0, 100.0 / 48000.0).unwrap();
+
+        let mut reader = hound::WavReader::open(&excerpt).unwrap();
+        assert_eq!(reader.spec(), spec);
+        let saved: Vec<f32> = reader.samples().map(|s| s.unwrap()).collect();
+        assert_eq!(saved, &samples[50..]);
+    }
+
+    #[test]
+    fn test_embed_sidecar_notes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav_path = temp_dir.path().join("take_edit.wav");
+        write_wav(&wav_path, pcm16(1, 8000), [0i16; 100]);
+        let before = std::fs::metadata(&wav_path).unwrap().len();
+
+        // A sidecar with nothing to embed leaves the WAV alone
+        let sidecar = get_sidecar_path(&wav_path, SidecarLocation::Adjacent);
+        std::fs::write(&sidecar, "---\ntitle: \"\"\n---\n").unwrap();
+        embed_sidecar_notes(&wav_path, SidecarLocation::Adjacent).unwrap();
+        assert_eq!(std::fs::metadata(&wav_path).unwrap().len(), before);
+
+        std::fs::write(
+            &sidecar,
+            "---\ntitle: \"Take\"\ndescription: \"Second verse\"\ntags: [\"vocal\"]\n---\n# Notes\n",
+        )
+        .unwrap();
+        embed_sidecar_notes(&wav_path, SidecarLocation::Adjacent).unwrap();
+        let bytes = std::fs::read(&wav_path).unwrap();
+        let contains = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(b"INAM\x04\0\0\0Take"));
+        assert!(contains(b"Second verse"));
+        assert!(contains(b"IKEY\x05\0\0\0vocal"));
+        assert_eq!(hound::WavReader::open(&wav_path).unwrap().duration(), 100);
+    }
+}
@@ -0,0 +1,190 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::app::ViewMode;
+use super::keymap::{BROWSER_BINDINGS, KeyBinding, PLAYER_BINDINGS};
+
+const KEY_COLUMN_WIDTH: usize = 11;
+// Inner width needed to split the bindings into two columns
+const SIDE_BY_SIDE_WIDTH: u16 = 92;
+
+fn section_lines(title: &str, bindings: &[KeyBinding]) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        title.to_string(),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))];
+
+    lines.extend(bindings.iter().map(binding_line));
+    lines
+}
+
+fn binding_line(binding: &KeyBinding) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{:<KEY_COLUMN_WIDTH$}", binding.key),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw(binding.description),
+    ])
+}
+
+type Section = (&'static str, &'static [KeyBinding]);
+
+/// Every binding, grouped by view, with the current view's listed first
+fn sections(view_mode: ViewMode) -> [Section; 2] {
+    let player = ("Player", PLAYER_BINDINGS);
+    let browser = ("Browser", BROWSER_BINDINGS);
+    match view_mode {
+        ViewMode::Player => [player, browser],
+        ViewMode::Browser => [browser, player],
+    }
+}
+
+/// The sections sit side by side when there's room, otherwise one above
+/// the other with a blank line between
+fn side_by_side(inner_width: u16) -> bool {
+    inner_width >= SIDE_BY_SIDE_WIDTH
+}
+
+/// Rows of content: each section is a title plus its bindings
+fn content_rows(inner_width: u16) -> usize {
+    let [first, second] = sections(ViewMode::Player).map(|(_, bindings)| 1 + bindings.len());
+    if side_by_side(inner_width) {
+        first.max(second)
+    } else {
+        first + 1 + second
+    }
+}
+
+/// Where the overlay sits in `area`, and how many rows of content it holds
+fn overlay_layout(area: Rect) -> (Rect, usize) {
+    let modal_width = (SIDE_BY_SIDE_WIDTH + 2).min(area.width.saturating_sub(4));
+    let content_rows = content_rows(modal_width.saturating_sub(2));
+    // Borders and the controls line
+    let modal_height = (content_rows as u16 + 3).min(area.height.saturating_sub(2));
+
+    let modal_area = Rect {
+        x: area.x + (area.width - modal_width) / 2,
+        y: area.y + (area.height - modal_height) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+    (modal_area, content_rows)
+}
+
+/// How far the overlay can scroll in a terminal of `area` before its last
+/// binding reaches the bottom
+pub fn max_help_scroll(area: Rect) -> usize {
+    let (modal_area, content_rows) = overlay_layout(area);
+    let visible_rows = modal_area.height.saturating_sub(3) as usize;
+    content_rows.saturating_sub(visible_rows)
+}
+
+pub fn draw_help_overlay(f: &mut Frame, area: Rect, view_mode: ViewMode, scroll: usize) {
+    let (modal_area, _) = overlay_layout(area);
+    let scroll = scroll.min(max_help_scroll(area));
+
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White))
+        .title(" Keyboard Shortcuts ")
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(block, modal_area);
+
+    let inner_area = modal_area.inner(ratatui::layout::Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner_area);
+
+    let [(first_title, first), (second_title, second)] = sections(view_mode);
+    let offset = (scroll as u16, 0);
+    if side_by_side(inner_area.width) {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[0]);
+        f.render_widget(
+            Paragraph::new(section_lines(first_title, first)).scroll(offset),
+            columns[0],
+        );
+        f.render_widget(
+            Paragraph::new(section_lines(second_title, second)).scroll(offset),
+            columns[1],
+        );
+    } else {
+        let mut lines = section_lines(first_title, first);
+        lines.push(Line::from(""));
+        lines.extend(section_lines(second_title, second));
+        f.render_widget(Paragraph::new(lines).scroll(offset), chunks[0]);
+    }
+
+    let mut controls = Vec::new();
+    if max_help_scroll(area) > 0 {
+        controls.extend([
+            Span::styled("[j/k]", Style::default().fg(Color::Yellow)),
+            Span::raw(" scroll  "),
+        ]);
+    }
+    controls.extend([
+        Span::styled("[?]", Style::default().fg(Color::Yellow)),
+        Span::raw(" or "),
+        Span::styled("[Esc]", Style::default().fg(Color::Red)),
+        Span::raw(" close"),
+    ]);
+    let controls_widget = Paragraph::new(Line::from(controls)).alignment(Alignment::Center);
+    f.render_widget(controls_widget, chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_lists_every_binding() {
+        // The current view's bindings come first, but both views are listed
+        for view_mode in [ViewMode::Player, ViewMode::Browser] {
+            let listed: usize = sections(view_mode)
+                .iter()
+                .map(|(_, bindings)| bindings.len())
+                .sum();
+            assert_eq!(listed, PLAYER_BINDINGS.len() + BROWSER_BINDINGS.len());
+        }
+        assert_eq!(sections(ViewMode::Browser)[0].0, "Browser");
+    }
+
+    #[test]
+    fn test_help_scrolls_only_when_cut_off() {
+        let tall = Rect::new(0, 0, 120, 60);
+        assert_eq!(max_help_scroll(tall), 0);
+
+        // Side by side, the longer section sets the height
+        let short = Rect::new(0, 0, 120, 24);
+        let rows = 1 + PLAYER_BINDINGS.len();
+        assert_eq!(max_help_scroll(short), rows - (24 - 2 - 3));
+
+        // A narrow terminal stacks the sections
+        let narrow = Rect::new(0, 0, 60, 40);
+        assert_eq!(
+            max_help_scroll(narrow),
+            1 + PLAYER_BINDINGS.len() + 1 + 1 + BROWSER_BINDINGS.len() - (40 - 2 - 3)
+        );
+    }
+}
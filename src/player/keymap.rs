@@ -0,0 +1,217 @@
+//! Keybinding definitions shared by the control hints and the help overlay.
+//!
+//! Each binding carries the key shown in the UI, a short label for the hint
+//! rows, and a longer description for the `?` help overlay. Rendering code
+//! refers to these constants rather than literal strings so the hints and the
+//! help listing cannot drift apart.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+}
+
+const fn bind(key: &'static str, label: &'static str, description: &'static str) -> KeyBinding {
+    KeyBinding {
+        key,
+        label,
+        description,
+    }
+}
+
+// Player view
+pub const PLAY_PAUSE: KeyBinding = bind("space", "play", "Play / pause");
+pub const SEEK: KeyBinding = bind("←→", "seek", "Seek 5s (Shift: jump 20%)");
+pub const BROWSE: KeyBinding = bind("/", "browse", "Open file browser (also b)");
+pub const QUIT: KeyBinding = bind("q", "quit", "Quit (also Ctrl+C)");
+pub const MARK_IN: KeyBinding = bind("i", "in", "Set mark in (also [)");
+pub const MARK_OUT: KeyBinding = bind("o", "out", "Set mark out (also ])");
+pub const TYPED_MARK: KeyBinding = bind(
+    "I/O",
+    "type mark",
+    "Set mark in / out from a typed mm:ss.mmm timecode",
+);
+pub const CLEAR_MARKS: KeyBinding = bind("x", "clear", "Clear marks");
+pub const COPY_TIMECODES: KeyBinding = bind(
+    "y",
+    "copy tc",
+    "Copy the selection as mm:ss.mmm - mm:ss.mmm to the clipboard",
+);
+pub const LOOP: KeyBinding = bind("l", "loop", "Toggle looping of the selection");
+pub const LOOP_COUNT: KeyBinding = bind("c", "count", "Cycle loop passes: 1, 2, 4, forever");
+pub const SAVE_LOOP: KeyBinding = bind(
+    "L",
+    "keep loop",
+    "Save marks to the sidecar as the file's loop",
+);
+pub const REFERENCE: KeyBinding =
+    bind("r", "ref", "Switch between the mix and the reference track");
+pub const SOLO_LEFT: KeyBinding = bind(",", "solo L", "Solo left channel (again: stereo)");
+pub const SOLO_RIGHT: KeyBinding = bind(".", "solo R", "Solo right channel (again: stereo)");
+pub const SWAP_CHANNELS: KeyBinding = bind("X", "swap", "Swap left and right (again: stereo)");
+pub const MONO_CHECK: KeyBinding = bind(
+    "M",
+    "mono",
+    "Mono check: L+R at -3 dB, then -6 dB, then stereo",
+);
+pub const MID_SIDE: KeyBinding = bind(
+    "W",
+    "mid/side",
+    "Audition the mid (L+R), then the side (L-R), then stereo",
+);
+pub const SAMPLES: KeyBinding = bind(
+    "#",
+    "samples",
+    "Show the position and length in sample frames (~: estimated)",
+);
+pub const TIMELINE: KeyBinding = bind("w", "timeline", "Toggle timeline waveform while playing");
+pub const SCOPE: KeyBinding = bind(
+    "S",
+    "scope",
+    "Hide / show the oscilloscope to save CPU on slow terminals",
+);
+pub const DISPLAY_MODE: KeyBinding = bind("m", "mode", "Cycle display: line, scatter, vector");
+pub const SAVE: KeyBinding = bind("s", "save", "Save file or marked selection");
+pub const RELOAD: KeyBinding = bind(
+    "R",
+    "reload",
+    "Reload the file from disk, keeping marks and position",
+);
+pub const EDIT: KeyBinding = bind("e", "edit", "Edit sidecar in $EDITOR");
+pub const QUICK_EDIT: KeyBinding = bind("E", "tags", "Edit tags and description inline");
+pub const PREV_TRACK: KeyBinding = bind("p", "prev", "Previous track in playlist");
+pub const NEXT_TRACK: KeyBinding = bind("n", "next", "Next track in playlist");
+pub const FOLDER_STEP: KeyBinding = bind(
+    "N/P",
+    "folder",
+    "Next / previous audio file in this folder (wraps)",
+);
+pub const VOLUME: KeyBinding = bind("-/+", "vol", "Volume down / up 1 dB");
+pub const TRUE_LEVEL: KeyBinding = bind(
+    "v",
+    "true lvl",
+    "Toggle true-level meters (unamplified RMS)",
+);
+pub const REPLAY_GAIN: KeyBinding = bind(
+    "G",
+    "replay gain",
+    "Toggle the replay gain stored by zim update --replaygain",
+);
+pub const TELEMETRY: KeyBinding = bind("t", "telemetry", "Toggle audio telemetry logging");
+pub const HELP: KeyBinding = bind("?", "help", "Toggle this help");
+
+// Browser view
+pub const BROWSER_NAVIGATE: KeyBinding = bind("j/k or ↑↓", "Navigate", "Move selection");
+pub const BROWSER_SELECT: KeyBinding = bind("Enter", "Select", "Load file and return to player");
+pub const BROWSER_SEARCH: KeyBinding =
+    bind("/", "Search", "Search (try 'title:', 'tag:' or 'art:')");
+pub const BROWSER_BACK: KeyBinding = bind("Esc", "Back", "Hide search, or back to player");
+pub const BROWSER_PLAY: KeyBinding = bind("Space", "Play/Pause", "Play / pause selected file");
+pub const BROWSER_SEEK: KeyBinding = bind("h/l or ←→", "Seek", "Seek 5s (Shift: jump 20%)");
+pub const BROWSER_FOCUS: KeyBinding = bind("Tab", "Focus", "Switch between search and files");
+pub const BROWSER_CLEAR: KeyBinding = bind("Ctrl+K", "Clear", "Clear search text");
+pub const BROWSER_WAVEFORM: KeyBinding = bind("w", "Waveform", "Toggle waveform in the preview");
+pub const BROWSER_GROUP: KeyBinding =
+    bind("g", "Group", "Group files by project, by folder, or not");
+pub const BROWSER_FULL_PATH: KeyBinding =
+    bind("f", "Paths", "Toggle file names and relative paths");
+
+pub const PLAYER_BINDINGS: &[KeyBinding] = &[
+    PLAY_PAUSE,
+    SEEK,
+    BROWSE,
+    MARK_IN,
+    MARK_OUT,
+    TYPED_MARK,
+    CLEAR_MARKS,
+    COPY_TIMECODES,
+    LOOP,
+    LOOP_COUNT,
+    SAVE_LOOP,
+    REFERENCE,
+    SOLO_LEFT,
+    SOLO_RIGHT,
+    SWAP_CHANNELS,
+    MONO_CHECK,
+    MID_SIDE,
+    SAVE,
+    RELOAD,
+    EDIT,
+    QUICK_EDIT,
+    TIMELINE,
+    SAMPLES,
+    DISPLAY_MODE,
+    SCOPE,
+    PREV_TRACK,
+    NEXT_TRACK,
+    FOLDER_STEP,
+    VOLUME,
+    TRUE_LEVEL,
+    REPLAY_GAIN,
+    TELEMETRY,
+    HELP,
+    QUIT,
+];
+
+pub const BROWSER_BINDINGS: &[KeyBinding] = &[
+    BROWSER_NAVIGATE,
+    BROWSER_SELECT,
+    BROWSER_SEARCH,
+    BROWSER_BACK,
+    BROWSER_PLAY,
+    BROWSER_SEEK,
+    BROWSER_FOCUS,
+    BROWSER_CLEAR,
+    BROWSER_WAVEFORM,
+    BROWSER_FULL_PATH,
+    BROWSER_GROUP,
+    HELP,
+];
+
+/// Bindings shown in the browser's one-line hint, in display order
+pub const BROWSER_HINTS: &[KeyBinding] = &[
+    BROWSER_NAVIGATE,
+    BROWSER_SELECT,
+    BROWSER_SEARCH,
+    BROWSER_BACK,
+    BROWSER_PLAY,
+    BROWSER_SEEK,
+];
+
+/// Format bindings as a `key: Label | key: Label` hint line
+pub fn hint_line(bindings: &[KeyBinding]) -> String {
+    bindings
+        .iter()
+        .map(|b| format!("{}: {}", b.key, b.label))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_line() {
+        assert_eq!(
+            hint_line(BROWSER_HINTS),
+            "j/k or ↑↓: Navigate | Enter: Select | /: Search | Esc: Back | Space: Play/Pause | h/l or ←→: Seek"
+        );
+        assert_eq!(hint_line(&[]), "");
+    }
+
+    #[test]
+    fn test_player_keys_are_unique() {
+        let mut keys: Vec<&str> = PLAYER_BINDINGS.iter().map(|b| b.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), PLAYER_BINDINGS.len());
+    }
+
+    #[test]
+    fn test_help_listed_in_both_contexts() {
+        assert!(PLAYER_BINDINGS.contains(&HELP));
+        assert!(BROWSER_BINDINGS.contains(&HELP));
+    }
+}
@@ -0,0 +1,127 @@
+//! Log file setup for the player.
+//!
+//! The player writes diagnostic output (including telemetry snapshots) to a log
+//! file rather than the terminal. The level and location come from the
+//! `ZIM_LOG` and `ZIM_LOG_FILE` environment variables, falling back to the
+//! `log_level` and `log_file` configuration keys. A level of `off` disables file
+//! logging entirely.
+
+use crate::config::Config;
+use simplelog::LevelFilter;
+use std::error::Error;
+use std::path::PathBuf;
+
+const LOG_FILE_NAME: &str = "zim-player.log";
+
+#[derive(Debug, PartialEq)]
+pub struct LogSettings {
+    pub level: LevelFilter,
+    pub path: PathBuf,
+}
+
+/// Default log location. Unix keeps the historical `/tmp/zim-player.log`;
+/// other platforms use the system temp directory.
+fn default_log_path() -> PathBuf {
+    if cfg!(unix) {
+        PathBuf::from("/tmp").join(LOG_FILE_NAME)
+    } else {
+        std::env::temp_dir().join(LOG_FILE_NAME)
+    }
+}
+
+/// Resolve log settings, with environment values taking precedence over config.
+/// Returns `None` when logging is disabled.
+pub fn resolve_settings(
+    env_level: Option<&str>,
+    env_file: Option<&str>,
+    config: &Config,
+) -> Option<LogSettings> {
+    let level = env_level
+        .and_then(|l| l.parse::<LevelFilter>().ok())
+        .or_else(|| config.log_level.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::Info);
+
+    if level == LevelFilter::Off {
+        return None;
+    }
+
+    let path = env_file
+        .filter(|f| !f.is_empty())
+        .or(config.log_file.as_deref())
+        .map(|f| PathBuf::from(shellexpand::tilde(f).as_ref()))
+        .unwrap_or_else(default_log_path);
+
+    Some(LogSettings { level, path })
+}
+
+pub fn init_logging() -> Result<(), Box<dyn Error>> {
+    use simplelog::*;
+    use std::fs::File;
+
+    let config = crate::config::Config::load().unwrap_or_default();
+    let env_level = std::env::var("ZIM_LOG").ok();
+    let env_file = std::env::var("ZIM_LOG_FILE").ok();
+
+    let Some(settings) = resolve_settings(env_level.as_deref(), env_file.as_deref(), &config)
+    else {
+        return Ok(());
+    };
+
+    CombinedLogger::init(vec![WriteLogger::new(
+        settings.level,
+        Config::default(),
+        File::create(&settings.path)?,
+    )])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let config = Config::new();
+        let settings = resolve_settings(None, None, &config).unwrap();
+        assert_eq!(settings.level, LevelFilter::Info);
+        assert_eq!(settings.path, default_log_path());
+        assert!(settings.path.ends_with(LOG_FILE_NAME));
+    }
+
+    #[test]
+    fn test_config_values() {
+        let mut config = Config::new();
+        config.log_level = "warn".to_string();
+        config.log_file = Some("/var/log/zim.log".to_string());
+
+        let settings = resolve_settings(None, None, &config).unwrap();
+        assert_eq!(settings.level, LevelFilter::Warn);
+        assert_eq!(settings.path, PathBuf::from("/var/log/zim.log"));
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let mut config = Config::new();
+        config.log_level = "warn".to_string();
+        config.log_file = Some("/var/log/zim.log".to_string());
+
+        let settings = resolve_settings(Some("trace"), Some("/tmp/other.log"), &config).unwrap();
+        assert_eq!(settings.level, LevelFilter::Trace);
+        assert_eq!(settings.path, PathBuf::from("/tmp/other.log"));
+
+        // Unparseable env level falls back to config
+        let settings = resolve_settings(Some("loud"), None, &config).unwrap();
+        assert_eq!(settings.level, LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_off_disables_logging() {
+        let mut config = Config::new();
+        assert!(resolve_settings(Some("off"), None, &config).is_none());
+
+        config.log_level = "off".to_string();
+        assert!(resolve_settings(None, None, &config).is_none());
+        assert!(resolve_settings(Some("debug"), None, &config).is_some());
+    }
+}
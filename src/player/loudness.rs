@@ -0,0 +1,389 @@
+//! Integrated loudness and true peak, as defined by ITU-R BS.1770-4.
+//!
+//! Loudness is measured on K-weighted audio in 400ms blocks overlapping by
+//! 75%, gated first at -70 LUFS and then 10 LU below the level of the blocks
+//! that passed. True peak is the highest sample after 4x oversampling, which
+//! catches the inter-sample peaks a DAC can produce.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+/// Blocks quieter than this are silence and never count
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Blocks more than this far below the ungated level are ignored
+const RELATIVE_GATE_LU: f64 = 10.0;
+const BLOCK_SECONDS: f64 = 0.4;
+const BLOCK_STEP_SECONDS: f64 = 0.1;
+
+const OVERSAMPLING: usize = 4;
+/// Input samples on each side of an interpolated point
+const INTERPOLATION_HALF_TAPS: i64 = 6;
+const INTERPOLATION_TAPS: usize = 2 * INTERPOLATION_HALF_TAPS as usize;
+
+/// True peaks above this leave too little headroom for lossy encoding
+pub const TRUE_PEAK_CEILING: f64 = -1.0;
+
+/// Files measured at once. Each is decoded whole into memory, so measuring
+/// long masters on every core could run the machine out of memory.
+const MAX_PARALLEL_MEASUREMENTS: usize = 4;
+
+/// Loudness of one file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    /// Integrated loudness in LUFS; `None` for silence or audio shorter
+    /// than one 400ms block
+    pub integrated: Option<f64>,
+    /// Highest oversampled peak in dBTP; `None` for digital silence
+    pub true_peak: Option<f64>,
+}
+
+/// A thread pool for measuring files in parallel, no wider than
+/// `MAX_PARALLEL_MEASUREMENTS`
+pub fn measurement_pool() -> Result<rayon::ThreadPool, rayon::ThreadPoolBuildError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(rayon::current_num_threads().min(MAX_PARALLEL_MEASUREMENTS))
+        .build()
+}
+
+/// Measure interleaved samples (-1.0 to 1.0) with `channels` channels
+pub fn measure(samples: &[f32], sample_rate: u32, channels: u16) -> Loudness {
+    let channels = channels.max(1) as usize;
+    Loudness {
+        integrated: integrated_loudness(samples, sample_rate, channels),
+        true_peak: true_peak(samples, channels),
+    }
+}
+
+/// A second-order IIR section, run in direct form I
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2], // a1, a2 with a0 normalized to 1
+}
+
+impl Biquad {
+    fn run(&self, input: impl Iterator<Item = f64>) -> Vec<f64> {
+        let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+        input
+            .map(|x| {
+                let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2
+                    - self.a[0] * y1
+                    - self.a[1] * y2;
+                (x2, x1, y2, y1) = (x1, x, y1, y);
+                y
+            })
+            .collect()
+    }
+}
+
+/// The two K-weighting stages for `sample_rate`: a high shelf modelling the
+/// head, then a high pass. BS.1770 gives coefficients for 48kHz only; these
+/// are derived from the same analog prototypes so other rates match.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    let f0 = 1_681.974_450_955_533;
+    let gain_db = 3.999_843_853_973_347;
+    let q = 0.707_175_236_955_419_6;
+    let k = (PI * f0 / fs).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    let f0 = 38.135_470_876_024_44;
+    let q = 0.500_327_037_323_877_3;
+    let k = (PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    [shelf, high_pass]
+}
+
+/// How much each channel counts: surrounds are boosted by 1.5dB and the
+/// LFE of a 5.1 file is left out
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6, 3) => 0.0,
+        (6, 4 | 5) | (5, 3 | 4) => 1.41,
+        _ => 1.0,
+    }
+}
+
+fn loudness_of(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+fn integrated_loudness(samples: &[f32], sample_rate: u32, channels: usize) -> Option<f64> {
+    let frames = samples.len() / channels;
+    let block = (BLOCK_SECONDS * sample_rate as f64).round() as usize;
+    let step = (BLOCK_STEP_SECONDS * sample_rate as f64).round() as usize;
+    if block == 0 || step == 0 || frames < block {
+        return None;
+    }
+
+    // Weighted power of each block, summed across channels
+    let filters = k_weighting(sample_rate);
+    let block_count = (frames - block) / step + 1;
+    let mut block_power = vec![0.0; block_count];
+    for channel in 0..channels {
+        let weight = channel_weight(channel, channels);
+        if weight == 0.0 {
+            continue;
+        }
+        let input = samples
+            .iter()
+            .skip(channel)
+            .step_by(channels)
+            .map(|&s| s as f64);
+        let filtered = filters[1].run(filters[0].run(input).into_iter());
+        let squared: Vec<f64> = filtered.iter().map(|s| s * s).collect();
+
+        // A running sum makes each block's total a subtraction
+        let mut running = vec![0.0; squared.len() + 1];
+        for (index, value) in squared.iter().enumerate() {
+            running[index + 1] = running[index] + value;
+        }
+        for (index, power) in block_power.iter_mut().enumerate() {
+            let start = index * step;
+            *power += weight * (running[start + block] - running[start]) / block as f64;
+        }
+    }
+
+    let above_absolute: Vec<f64> = block_power
+        .into_iter()
+        .filter(|&power| power > 0.0 && loudness_of(power) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if above_absolute.is_empty() {
+        return None;
+    }
+    let mean = |powers: &[f64]| powers.iter().sum::<f64>() / powers.len() as f64;
+    let relative_gate = loudness_of(mean(&above_absolute)) - RELATIVE_GATE_LU;
+    let gated: Vec<f64> = above_absolute
+        .into_iter()
+        .filter(|&power| loudness_of(power) > relative_gate)
+        .collect();
+    Some(loudness_of(mean(&gated)))
+}
+
+/// Windowed-sinc coefficients for the points between input samples, one
+/// set per oversampled phase, each normalized to unity gain
+fn interpolation_phases() -> Vec<Vec<f64>> {
+    (1..OVERSAMPLING)
+        .map(|phase| {
+            let offset = phase as f64 / OVERSAMPLING as f64;
+            let taps: Vec<f64> = (-INTERPOLATION_HALF_TAPS + 1..=INTERPOLATION_HALF_TAPS)
+                .map(|tap| {
+                    let t = tap as f64 - offset;
+                    let sinc = if t == 0.0 {
+                        1.0
+                    } else {
+                        (PI * t).sin() / (PI * t)
+                    };
+                    let window = 0.5 * (1.0 + (PI * t / INTERPOLATION_HALF_TAPS as f64).cos());
+                    sinc * window
+                })
+                .collect();
+            let sum: f64 = taps.iter().sum();
+            taps.into_iter().map(|tap| tap / sum).collect()
+        })
+        .collect()
+}
+
+fn true_peak(samples: &[f32], channels: usize) -> Option<f64> {
+    let mut meter = TruePeakMeter::new(channels);
+    meter.process(samples);
+    meter.finish();
+    meter.peak_db()
+}
+
+/// True peak of interleaved audio that arrives in chunks, such as the
+/// player's monitored samples. Each point is interpolated once the samples
+/// after it have arrived, so the reading trails the input by a few samples.
+#[derive(Debug, Clone)]
+pub struct TruePeakMeter {
+    phases: Vec<Vec<f64>>,
+    /// The last `INTERPOLATION_TAPS` samples of each channel, oldest first
+    windows: Vec<VecDeque<f64>>,
+    /// Samples fed per channel, to skip points before the first sample
+    fed: Vec<usize>,
+    channels: usize,
+    /// Channel of the next sample, since a chunk can end mid-frame
+    next_channel: usize,
+    peak: f64,
+}
+
+impl TruePeakMeter {
+    pub fn new(channels: usize) -> Self {
+        let channels = channels.max(1);
+        Self {
+            phases: interpolation_phases(),
+            windows: vec![VecDeque::from(vec![0.0; INTERPOLATION_TAPS]); channels],
+            fed: vec![0; channels],
+            channels,
+            next_channel: 0,
+            peak: 0.0,
+        }
+    }
+
+    /// Add interleaved samples (-1.0 to 1.0)
+    pub fn process(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.push(self.next_channel, sample as f64);
+            self.next_channel = (self.next_channel + 1) % self.channels;
+        }
+    }
+
+    /// Interpolate the points after the last samples, as if silence followed
+    pub fn finish(&mut self) {
+        for channel in 0..self.channels {
+            for _ in 0..INTERPOLATION_HALF_TAPS {
+                self.push(channel, 0.0);
+            }
+        }
+    }
+
+    /// Highest peak so far as a linear level (1.0 is full scale)
+    pub fn peak(&self) -> f64 {
+        self.peak
+    }
+
+    /// Highest peak so far in dBTP; `None` while only silence has been seen
+    pub fn peak_db(&self) -> Option<f64> {
+        (self.peak > 0.0).then(|| 20.0 * self.peak.log10())
+    }
+
+    fn push(&mut self, channel: usize, sample: f64) {
+        self.peak = self.peak.max(sample.abs());
+        let window = &mut self.windows[channel];
+        window.pop_front();
+        window.push_back(sample);
+
+        // The window now ends HALF_TAPS samples after the one whose
+        // following points can be interpolated
+        self.fed[channel] += 1;
+        if self.fed[channel] <= INTERPOLATION_HALF_TAPS as usize {
+            return;
+        }
+        for taps in &self.phases {
+            let value: f64 = taps.iter().zip(window.iter()).map(|(c, s)| c * s).sum();
+            self.peak = self.peak.max(value.abs());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `seconds` of a sine in every channel, interleaved
+    fn sine(
+        frequency: f64,
+        amplitude: f64,
+        phase: f64,
+        sample_rate: u32,
+        channels: usize,
+        seconds: f64,
+    ) -> Vec<f32> {
+        let frames = (seconds * sample_rate as f64) as usize;
+        (0..frames)
+            .flat_map(|frame| {
+                let t = frame as f64 / sample_rate as f64;
+                let value = (amplitude * (2.0 * PI * frequency * t + phase).sin()) as f32;
+                std::iter::repeat_n(value, channels)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integrated_loudness_of_reference_sine() {
+        // A 1kHz sine at -6dBFS in both channels of a stereo file reads
+        // -6 LUFS, since K-weighting adds back the 0.691dB offset at 1kHz
+        for sample_rate in [44100, 48000, 96000] {
+            let samples = sine(1000.0, 0.5, 0.0, sample_rate, 2, 1.0);
+            let loudness = measure(&samples, sample_rate, 2);
+            let integrated = loudness.integrated.unwrap();
+            assert!(
+                (integrated - -6.02).abs() < 0.1,
+                "{sample_rate}Hz read {integrated}"
+            );
+        }
+
+        // The same tone in one channel is 3dB quieter
+        let mono = sine(1000.0, 0.5, 0.0, 48000, 1, 1.0);
+        let integrated = measure(&mono, 48000, 1).integrated.unwrap();
+        assert!((integrated - -9.03).abs() < 0.1, "mono read {integrated}");
+    }
+
+    #[test]
+    fn test_gating_ignores_silence() {
+        // Silence after a tone barely moves the integrated level; only the
+        // few blocks straddling the end of the tone count
+        let mut samples = sine(1000.0, 0.5, 0.0, 8000, 2, 5.0);
+        let tone_only = measure(&samples, 8000, 2).integrated.unwrap();
+        samples.extend(vec![0.0; 8000 * 2 * 10]);
+        let with_silence = measure(&samples, 8000, 2).integrated.unwrap();
+        assert!(
+            (tone_only - with_silence).abs() < 0.25,
+            "{tone_only} vs {with_silence}"
+        );
+
+        let silence = vec![0.0; 48000 * 2];
+        assert_eq!(
+            measure(&silence, 48000, 2),
+            Loudness {
+                integrated: None,
+                true_peak: None
+            }
+        );
+        // Shorter than one block
+        let blip = sine(1000.0, 0.5, 0.0, 48000, 2, 0.2);
+        assert_eq!(measure(&blip, 48000, 2).integrated, None);
+    }
+
+    #[test]
+    fn test_true_peak_finds_inter_sample_peaks() {
+        // A quarter-rate sine sampled 45° off its crest never has a sample
+        // above 0.707, but the waveform between them reaches full scale
+        let samples = sine(12000.0, 1.0, PI / 4.0, 48000, 1, 0.1);
+        let sample_peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(sample_peak < 0.71);
+
+        let true_peak = measure(&samples, 48000, 1).true_peak.unwrap();
+        assert!(true_peak > -0.5 && true_peak < 0.5, "read {true_peak}dBTP");
+    }
+
+    #[test]
+    fn test_true_peak_meter_in_chunks() {
+        // Fed in uneven chunks, as the player does, the meter reads the
+        // same as measuring the whole signal
+        let samples = sine(12000.0, 1.0, PI / 4.0, 48000, 2, 0.1);
+        let whole = measure(&samples, 48000, 2).true_peak.unwrap();
+        let mut meter = TruePeakMeter::new(2);
+        for chunk in samples.chunks(333) {
+            meter.process(chunk);
+        }
+        meter.finish();
+        assert_eq!(meter.peak_db(), Some(whole));
+
+        assert_eq!(TruePeakMeter::new(2).peak_db(), None);
+    }
+
+    #[test]
+    fn test_channel_weight() {
+        assert_eq!(channel_weight(0, 2), 1.0);
+        assert_eq!(channel_weight(3, 6), 0.0);
+        assert_eq!(channel_weight(5, 6), 1.41);
+        assert_eq!(channel_weight(2, 6), 1.0);
+    }
+}
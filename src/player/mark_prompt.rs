@@ -0,0 +1,179 @@
+//! Prompt for setting mark in or out from a typed timecode.
+//!
+//! Scrubbing to an edit point is slow when the exact time is already written
+//! down in a cue sheet. This prompt takes `mm:ss.mmm` (or `h:mm:ss.mmm`, or
+//! plain seconds) and converts it to the player's normalized position.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarkTarget {
+    In,
+    Out,
+}
+
+impl MarkTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarkTarget::In => "in",
+            MarkTarget::Out => "out",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarkPrompt {
+    pub target: MarkTarget,
+    pub entry: String,
+    pub error: Option<String>, // Why the last entry couldn't be applied
+}
+
+impl MarkPrompt {
+    /// Open the prompt with `seconds` filled in as a starting point
+    pub fn new(target: MarkTarget, seconds: f64) -> Self {
+        Self {
+            target,
+            entry: format_timecode(seconds),
+            error: None,
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if c.is_ascii_digit() || c == ':' || c == '.' {
+            self.entry.push(c);
+            self.error = None;
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        self.entry.pop();
+        self.error = None;
+    }
+
+    /// The entry as a 0.0-1.0 position in a file of `duration` seconds.
+    /// `other_mark` is the opposite mark, which the result must not cross.
+    pub fn resolve(&self, duration: f64, other_mark: Option<f32>) -> Result<f32, String> {
+        let seconds = parse_timecode(&self.entry)?;
+        if seconds > duration {
+            return Err(format!("Past the end ({})", format_timecode(duration)));
+        }
+
+        let position = if duration > 0.0 {
+            (seconds / duration) as f32
+        } else {
+            0.0
+        };
+        match (self.target, other_mark) {
+            (MarkTarget::In, Some(out)) if position >= out => Err(format!(
+                "Must be before mark out ({})",
+                format_timecode(out as f64 * duration)
+            )),
+            (MarkTarget::Out, Some(mark_in)) if position <= mark_in => Err(format!(
+                "Must be after mark in ({})",
+                format_timecode(mark_in as f64 * duration)
+            )),
+            _ => Ok(position),
+        }
+    }
+}
+
+/// Parse `ss.mmm`, `mm:ss.mmm` or `h:mm:ss.mmm` into seconds
+pub fn parse_timecode(text: &str) -> Result<f64, String> {
+    let invalid = || format!("'{text}' is not a time like 01:23.456");
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.len() > 3 || parts.iter().any(|part| part.is_empty()) {
+        return Err(invalid());
+    }
+
+    let (seconds, whole) = parts.split_last().ok_or_else(invalid)?;
+    let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+    if !seconds.is_finite() || seconds < 0.0 || (!whole.is_empty() && seconds >= 60.0) {
+        return Err(invalid());
+    }
+
+    let mut total = 0.0;
+    for (index, unit) in whole.iter().enumerate() {
+        let value: u32 = unit.parse().map_err(|_| invalid())?;
+        // Minutes are capped when hours come first
+        if index > 0 && value >= 60 {
+            return Err(invalid());
+        }
+        total = total * 60.0 + value as f64;
+    }
+    Ok(total * 60.0 + seconds)
+}
+
+/// Seconds as `mm:ss.mmm`, the form the prompt accepts
+pub fn format_timecode(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timecode() {
+        assert_eq!(parse_timecode("01:23.456"), Ok(83.456));
+        assert_eq!(parse_timecode("1:05"), Ok(65.0));
+        assert_eq!(parse_timecode("12.5"), Ok(12.5));
+        assert_eq!(parse_timecode("90"), Ok(90.0));
+        assert_eq!(parse_timecode("1:02:03.5"), Ok(3723.5));
+        assert_eq!(parse_timecode(" 00:00.000 "), Ok(0.0));
+
+        assert!(parse_timecode("").is_err());
+        assert!(parse_timecode("1:60").is_err());
+        assert!(parse_timecode("1:60:00").is_err());
+        assert!(parse_timecode("1::2").is_err());
+        assert!(parse_timecode("1:2:3:4").is_err());
+        assert!(parse_timecode("1.5:00").is_err());
+        assert!(parse_timecode("-3").is_err());
+    }
+
+    #[test]
+    fn test_format_timecode_round_trips() {
+        assert_eq!(format_timecode(83.456), "01:23.456");
+        assert_eq!(format_timecode(0.0), "00:00.000");
+        assert_eq!(format_timecode(3723.5), "62:03.500");
+        assert_eq!(format_timecode(59.9996), "01:00.000");
+        assert_eq!(parse_timecode(&format_timecode(12.345)), Ok(12.345));
+    }
+
+    #[test]
+    fn test_resolve_checks_bounds() {
+        let mut prompt = MarkPrompt::new(MarkTarget::In, 0.0);
+        prompt.entry = "00:30.000".to_string();
+        assert_eq!(prompt.resolve(120.0, None), Ok(0.25));
+        assert!(
+            prompt
+                .resolve(20.0, None)
+                .unwrap_err()
+                .contains("Past the end")
+        );
+        assert!(prompt.resolve(120.0, Some(0.25)).is_err());
+        assert_eq!(prompt.resolve(120.0, Some(0.5)), Ok(0.25));
+
+        let mut prompt = MarkPrompt::new(MarkTarget::Out, 0.0);
+        prompt.entry = "00:30.000".to_string();
+        assert!(prompt.resolve(120.0, Some(0.5)).is_err());
+        assert_eq!(prompt.resolve(120.0, Some(0.1)), Ok(0.25));
+        // The very end is a valid mark out
+        prompt.entry = "02:00".to_string();
+        assert_eq!(prompt.resolve(120.0, None), Ok(1.0));
+    }
+
+    #[test]
+    fn test_push_char_ignores_non_timecode_keys() {
+        let mut prompt = MarkPrompt::new(MarkTarget::In, 65.25);
+        assert_eq!(prompt.entry, "01:05.250");
+        prompt.entry.clear();
+        for c in "1a:0 5.x2".chars() {
+            prompt.push_char(c);
+        }
+        assert_eq!(prompt.entry, "1:05.2");
+    }
+}
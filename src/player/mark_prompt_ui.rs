@@ -0,0 +1,65 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::mark_prompt::{MarkPrompt, MarkTarget};
+
+const PROMPT_LABEL: &str = "Time: ";
+
+pub fn draw_mark_prompt(f: &mut Frame, area: Rect, prompt: &MarkPrompt) {
+    // Create a centered modal
+    let modal_width = 50.min(area.width.saturating_sub(4));
+    let modal_height = 5.min(area.height.saturating_sub(2));
+
+    let modal_area = Rect {
+        x: (area.width - modal_width) / 2,
+        y: (area.height - modal_height) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    // Clear the background
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White))
+        .title(match prompt.target {
+            MarkTarget::In => " Set Mark In ",
+            MarkTarget::Out => " Set Mark Out ",
+        })
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut entry_line = vec![
+        Span::styled(PROMPT_LABEL, Style::default().fg(Color::Yellow)),
+        Span::styled(prompt.entry.as_str(), Style::default().fg(Color::White)),
+    ];
+    if let Some(error) = &prompt.error {
+        entry_line.push(Span::styled(
+            format!("  {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let lines = vec![
+        Line::from(entry_line),
+        Line::from(""),
+        Line::from(Span::styled(
+            "mm:ss.mmm  Enter: set  Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    f.render_widget(Paragraph::new(lines).block(block), modal_area);
+
+    let cursor_x = modal_area.x + 1 + (PROMPT_LABEL.len() + prompt.entry.chars().count()) as u16;
+    if cursor_x < modal_area.x + modal_area.width.saturating_sub(1) {
+        f.set_cursor_position((cursor_x, modal_area.y + 1));
+    }
+}
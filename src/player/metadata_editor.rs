@@ -0,0 +1,335 @@
+//! Inline editor for a sidecar's tags and description.
+//!
+//! This module provides a small form for quick tagging during review without
+//! leaving the player. Only the `tags` and `description` frontmatter fields are
+//! rewritten; every other field and the markdown body are preserved. While
+//! typing a tag, existing project tags are offered as completions.
+
+use crate::tag_vocabulary::TagVocabulary;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use zim_studio::utils::sidecar::{split_frontmatter, update_frontmatter_fields};
+
+#[derive(Debug, Clone)]
+pub struct MetadataEditor {
+    pub sidecar_path: PathBuf,
+    pub tags: String, // Comma-separated while editing
+    pub description: String,
+    pub focus: MetadataEditorFocus,
+    pub original_tags: Vec<String>, // Tags as loaded, for vocabulary updates
+    pub vocabulary: TagVocabulary,
+    pub suggestion_index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetadataEditorFocus {
+    Tags,
+    Description,
+}
+
+impl MetadataEditor {
+    /// Load the current tags and description from a sidecar file
+    pub fn from_sidecar(sidecar_path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(&sidecar_path)?;
+        let (yaml_content, _) =
+            split_frontmatter(&content).ok_or("Sidecar file has no YAML frontmatter")?;
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_content)?;
+
+        let tags = yaml
+            .get("tags")
+            .and_then(|v| v.as_sequence())
+            .map(|seq| {
+                seq.iter()
+                    .filter_map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let description = yaml
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut editor = Self::new(sidecar_path, &tags, &description);
+        editor.original_tags = editor.parsed_tags();
+        Ok(editor)
+    }
+
+    pub fn new(sidecar_path: PathBuf, tags: &str, description: &str) -> Self {
+        Self {
+            sidecar_path,
+            tags: tags.to_string(),
+            description: description.to_string(),
+            focus: MetadataEditorFocus::Tags,
+            original_tags: Vec::new(),
+            vocabulary: TagVocabulary::default(),
+            suggestion_index: 0,
+        }
+    }
+
+    /// Attach the project tags used for autocomplete
+    pub fn with_vocabulary(mut self, vocabulary: TagVocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            MetadataEditorFocus::Tags => MetadataEditorFocus::Description,
+            MetadataEditorFocus::Description => MetadataEditorFocus::Tags,
+        };
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.suggestion_index = 0;
+        match self.focus {
+            MetadataEditorFocus::Tags => self.tags.push(c),
+            MetadataEditorFocus::Description => self.description.push(c),
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        self.suggestion_index = 0;
+        match self.focus {
+            MetadataEditorFocus::Tags => self.tags.pop(),
+            MetadataEditorFocus::Description => self.description.pop(),
+        };
+    }
+
+    /// Split the tags field on commas, dropping blanks and duplicates
+    pub fn parsed_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.split(',').map(str::trim) {
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+
+    /// The partial tag being typed (text after the last comma)
+    fn tag_fragment(&self) -> &str {
+        let start = self.tags.rfind(',').map(|i| i + 1).unwrap_or(0);
+        self.tags[start..].trim_start()
+    }
+
+    /// Existing tags completing the partial tag, most used first
+    pub fn suggestions(&self) -> Vec<&(String, usize)> {
+        let fragment = self.tag_fragment();
+        if self.focus != MetadataEditorFocus::Tags || fragment.is_empty() {
+            return Vec::new();
+        }
+
+        // Don't suggest tags that are already complete in the field
+        let completed: Vec<String> = self
+            .tags
+            .rsplit_once(',')
+            .map(|(done, _)| done.split(',').map(|t| t.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        self.vocabulary
+            .suggestions(fragment, &completed)
+            .into_iter()
+            .filter(|(tag, _)| tag != fragment)
+            .collect()
+    }
+
+    pub fn select_next_suggestion(&mut self) {
+        let count = self.suggestions().len();
+        if count > 0 {
+            self.suggestion_index = (self.suggestion_index + 1) % count;
+        }
+    }
+
+    pub fn select_previous_suggestion(&mut self) {
+        let count = self.suggestions().len();
+        if count > 0 {
+            self.suggestion_index = (self.suggestion_index + count - 1) % count;
+        }
+    }
+
+    /// Replace the partial tag with the highlighted suggestion.
+    /// Returns false when there is nothing to complete.
+    pub fn accept_suggestion(&mut self) -> bool {
+        let Some((tag, _)) = self.suggestions().get(self.suggestion_index).copied() else {
+            return false;
+        };
+        let tag = tag.clone();
+
+        let start = self.tags.len() - self.tag_fragment().len();
+        self.tags.truncate(start);
+        self.tags.push_str(&tag);
+        self.tags.push_str(", ");
+        self.suggestion_index = 0;
+        true
+    }
+
+    /// Write tags and description back to the sidecar
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let content = fs::read_to_string(&self.sidecar_path)?;
+        let tags = self
+            .parsed_tags()
+            .into_iter()
+            .map(serde_yaml::Value::String)
+            .collect();
+
+        let new_content = update_frontmatter_fields(
+            &content,
+            vec![
+                ("tags", serde_yaml::Value::Sequence(tags)),
+                (
+                    "description",
+                    serde_yaml::Value::String(self.description.trim().to_string()),
+                ),
+            ],
+        )?;
+
+        fs::write(&self.sidecar_path, new_content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SIDECAR: &str = r#"---
+file: "kick.wav"
+path: "samples"
+title: "kick"
+description: "a sample"
+duration: 1.50
+tags: ["drums", "808"]
+art: []
+---
+
+# Notes
+
+Punchy.
+"#;
+
+    #[test]
+    fn test_from_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("kick.wav.md");
+        fs::write(&path, SIDECAR).unwrap();
+
+        let editor = MetadataEditor::from_sidecar(path).unwrap();
+        assert_eq!(editor.tags, "drums, 808");
+        assert_eq!(editor.description, "a sample");
+        assert_eq!(editor.focus, MetadataEditorFocus::Tags);
+        assert_eq!(editor.original_tags, vec!["drums", "808"]);
+    }
+
+    fn editor_with_vocabulary(tags: &str) -> MetadataEditor {
+        let mut vocabulary = TagVocabulary::default();
+        vocabulary.record_change(&[], &["drums".to_string(), "drone".to_string()]);
+        vocabulary.record_change(&[], &["drums".to_string(), "808".to_string()]);
+        MetadataEditor::new(PathBuf::from("x.wav.md"), tags, "").with_vocabulary(vocabulary)
+    }
+
+    #[test]
+    fn test_suggestions_complete_fragment() {
+        let editor = editor_with_vocabulary("808, dr");
+        let tags: Vec<&str> = editor
+            .suggestions()
+            .iter()
+            .map(|(t, _)| t.as_str())
+            .collect();
+        assert_eq!(tags, vec!["drums", "drone"]);
+
+        // Nothing to complete without a fragment or outside the tags field
+        assert!(editor_with_vocabulary("808, ").suggestions().is_empty());
+        let mut editor = editor_with_vocabulary("dr");
+        editor.toggle_focus();
+        assert!(editor.suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_suggestions_skip_completed_tags() {
+        let editor = editor_with_vocabulary("drums, d");
+        let tags: Vec<&str> = editor
+            .suggestions()
+            .iter()
+            .map(|(t, _)| t.as_str())
+            .collect();
+        assert_eq!(tags, vec!["drone"]);
+    }
+
+    #[test]
+    fn test_accept_suggestion() {
+        let mut editor = editor_with_vocabulary("808,  dr");
+        editor.select_next_suggestion();
+        assert!(editor.accept_suggestion());
+        assert_eq!(editor.tags, "808,  drone, ");
+        assert_eq!(editor.suggestion_index, 0);
+
+        // Wraps around when cycling backwards
+        let mut editor = editor_with_vocabulary("dr");
+        editor.select_previous_suggestion();
+        assert!(editor.accept_suggestion());
+        assert_eq!(editor.tags, "drone, ");
+
+        let mut editor = editor_with_vocabulary("xyz");
+        assert!(!editor.accept_suggestion());
+        assert_eq!(editor.tags, "xyz");
+    }
+
+    #[test]
+    fn test_from_sidecar_without_frontmatter() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("kick.wav.md");
+        fs::write(&path, "# Just notes").unwrap();
+
+        assert!(MetadataEditor::from_sidecar(path).is_err());
+    }
+
+    #[test]
+    fn test_editing_follows_focus() {
+        let mut editor = MetadataEditor::new(PathBuf::from("x.wav.md"), "", "");
+
+        editor.push_char('a');
+        editor.toggle_focus();
+        editor.push_char('b');
+        editor.push_char('c');
+        editor.pop_char();
+
+        assert_eq!(editor.tags, "a");
+        assert_eq!(editor.description, "b");
+    }
+
+    #[test]
+    fn test_parsed_tags() {
+        let mut editor = MetadataEditor::new(PathBuf::from("x.wav.md"), " drums, ,808 ,drums,", "");
+        assert_eq!(editor.parsed_tags(), vec!["drums", "808"]);
+
+        editor.tags.clear();
+        assert!(editor.parsed_tags().is_empty());
+    }
+
+    #[test]
+    fn test_save_preserves_other_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("kick.wav.md");
+        fs::write(&path, SIDECAR).unwrap();
+
+        let mut editor = MetadataEditor::from_sidecar(path.clone()).unwrap();
+        editor.tags = "drums, kick".to_string();
+        editor.description = "an edited sample ".to_string();
+        editor.save().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let (yaml, body) = split_frontmatter(&content).unwrap();
+        let yaml: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(yaml["description"].as_str(), Some("an edited sample"));
+        assert_eq!(yaml["tags"][0].as_str(), Some("drums"));
+        assert_eq!(yaml["tags"][1].as_str(), Some("kick"));
+        assert_eq!(yaml["file"].as_str(), Some("kick.wav"));
+        assert_eq!(yaml["duration"].as_f64(), Some(1.5));
+        assert_eq!(body, "\n# Notes\n\nPunchy.\n");
+    }
+}
@@ -0,0 +1,134 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::metadata_editor::{MetadataEditor, MetadataEditorFocus};
+
+fn field_block(title: &'static str, focused: bool) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        })
+        .title(title)
+}
+
+pub fn draw_metadata_editor(f: &mut Frame, area: Rect, editor: &MetadataEditor) {
+    // Create a centered modal
+    let modal_width = 60.min(area.width.saturating_sub(4));
+    let modal_height = 12.min(area.height.saturating_sub(4));
+
+    let modal_area = Rect {
+        x: (area.width - modal_width) / 2,
+        y: (area.height - modal_height) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    // Clear the background
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White))
+        .title(" Edit Metadata ")
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(block, modal_area);
+
+    let inner_area = modal_area.inner(ratatui::layout::Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Tags field
+            Constraint::Length(3), // Description field
+            Constraint::Min(0),    // Tag suggestions
+            Constraint::Length(1), // Controls
+        ])
+        .split(inner_area);
+
+    let fields = [
+        (
+            " Tags (comma-separated) ",
+            editor.tags.as_str(),
+            MetadataEditorFocus::Tags,
+            chunks[0],
+        ),
+        (
+            " Description ",
+            editor.description.as_str(),
+            MetadataEditorFocus::Description,
+            chunks[1],
+        ),
+    ];
+
+    for (title, value, focus, chunk) in fields {
+        let focused = editor.focus == focus;
+        let widget = Paragraph::new(value)
+            .style(Style::default().fg(Color::White))
+            .block(field_block(title, focused));
+        f.render_widget(widget, chunk);
+
+        // Show cursor in the focused field
+        if focused {
+            let cursor_x = chunk.x + 1 + value.chars().count() as u16;
+            let cursor_y = chunk.y + 1;
+            if cursor_x < chunk.x + chunk.width.saturating_sub(1) {
+                f.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+    }
+
+    // Existing project tags matching the tag being typed
+    let suggestions = editor.suggestions();
+    if !suggestions.is_empty() {
+        let mut spans = vec![Span::styled(
+            "Suggestions: ",
+            Style::default().fg(Color::DarkGray),
+        )];
+        for (i, (tag, count)) in suggestions.iter().enumerate() {
+            let style = if i == editor.suggestion_index {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            spans.push(Span::styled(format!("{tag} ({count})"), style));
+            spans.push(Span::raw("  "));
+        }
+        let suggestions_widget = Paragraph::new(Line::from(spans)).wrap(Wrap { trim: true });
+        f.render_widget(suggestions_widget, chunks[2]);
+    }
+
+    // Controls
+    let switch_label = if suggestions.is_empty() {
+        " switch  "
+    } else {
+        " complete  "
+    };
+    let controls = vec![
+        Span::styled("[Tab]", Style::default().fg(Color::Yellow)),
+        Span::raw(switch_label),
+        Span::styled("[Enter]", Style::default().fg(Color::Green)),
+        Span::raw(" save  "),
+        Span::styled("[Esc]", Style::default().fg(Color::Red)),
+        Span::raw(" cancel"),
+    ];
+    let controls_widget = Paragraph::new(Line::from(controls)).alignment(Alignment::Center);
+    f.render_widget(controls_widget, chunks[3]);
+}
@@ -2,37 +2,55 @@
 //!
 //! This module provides real-time mixing of up to 3 audio files, with per-file
 //! gain control. All files are pre-mixed into memory for fast, high-quality seeking.
+//!
+//! A reference track (e.g. a commercial master) can be loaded alongside the mix.
+//! It is kept out of the sum and held in its own buffer, so playback can switch
+//! between the mix and the reference instantly for A/B comparison.
 
+use super::audio::SourceHandles;
+use super::channel_mode::SharedChannelMode;
 use rodio::Source;
 use std::sync::{
     Arc,
-    atomic::{AtomicUsize, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     mpsc,
 };
 use std::time::Duration;
 
+/// A track to compare the mix against, played solo instead of being mixed in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceTrack {
+    pub path: String,
+    pub gain: f32,
+}
+
 /// Helper to create a mixed source from file paths with monitoring
 pub fn create_mixed_source_from_files(
     file_paths: &[String],
     gains: Option<Vec<f32>>,
-    samples_tx: mpsc::Sender<Vec<f32>>,
-    samples_played: Arc<AtomicUsize>,
+    reference: Option<&ReferenceTrack>,
+    solo_reference: Arc<AtomicBool>,
+    handles: SourceHandles,
 ) -> Result<Box<dyn Source<Item = f32> + Send>, Box<dyn std::error::Error>> {
-    create_mixed_source_from_files_with_seek(file_paths, gains, 0, samples_tx, samples_played)
+    create_mixed_source_from_files_with_seek(
+        file_paths,
+        gains,
+        reference,
+        solo_reference,
+        0,
+        handles,
+    )
 }
 
 /// Helper to create a mixed source from file paths with seek support
 pub fn create_mixed_source_from_files_with_seek(
     file_paths: &[String],
     gains: Option<Vec<f32>>,
+    reference: Option<&ReferenceTrack>,
+    solo_reference: Arc<AtomicBool>,
     start_sample: usize,
-    samples_tx: mpsc::Sender<Vec<f32>>,
-    samples_played: Arc<AtomicUsize>,
+    handles: SourceHandles,
 ) -> Result<Box<dyn Source<Item = f32> + Send>, Box<dyn std::error::Error>> {
-    use std::fs::File;
-    use std::io::BufReader;
-    use std::path::Path;
-
     if file_paths.is_empty() {
         return Err("No files provided".into());
     }
@@ -59,46 +77,14 @@ pub fn create_mixed_source_from_files_with_seek(
     let mut channels = 2u16;
 
     for (i, path_str) in file_paths.iter().enumerate() {
-        let path = Path::new(path_str);
-
-        if !path.exists() {
-            return Err(format!("File not found: {path_str}").into());
-        }
-
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_default();
-
-        let (file_samples, file_sr, file_ch) = match ext.as_str() {
-            "wav" => {
-                let file = BufReader::new(File::open(path)?);
-                let decoder = hound::WavReader::new(file)?;
-                load_wav_samples(decoder)?
-            }
-            "flac" => {
-                let reader = claxon::FlacReader::open(path)?;
-                load_flac_samples(reader)?
-            }
-            "aif" | "aiff" => {
-                let aiff_data = crate::media::metadata::read_aiff_data(path)?;
-                load_aiff_samples(aiff_data)?
-            }
-            _ => return Err(format!("Unsupported audio format: {ext}").into()),
-        };
+        let (file_samples, file_sr, file_ch) = load_samples(path_str)?;
 
         // Use properties from first file as reference
         if i == 0 {
             sample_rate = file_sr;
             channels = file_ch;
         } else {
-            // Warn about mismatched properties
-            if file_sr != sample_rate || file_ch != channels {
-                log::warn!(
-                    "File {path_str} properties mismatch: {file_sr}Hz/{file_ch}ch vs {sample_rate}Hz/{channels}ch"
-                );
-            }
+            check_matches_mix(path_str, (file_sr, file_ch), (sample_rate, channels))?;
         }
 
         all_samples.push(file_samples);
@@ -106,21 +92,23 @@ pub fn create_mixed_source_from_files_with_seek(
     }
 
     // Pre-mix all samples into a single buffer
-    let max_length = all_samples.iter().map(|s| s.len()).max().unwrap_or(0);
-    let mut mixed_samples = vec![0.0f32; max_length];
-
-    for (file_samples, gain) in all_samples.iter().zip(gains.iter()) {
-        for (i, &sample) in file_samples.iter().enumerate() {
-            if i < mixed_samples.len() {
-                mixed_samples[i] += sample * gain;
-            }
+    let mut mixed_samples = premix(&all_samples, &gains);
+
+    // Load the reference on its own, padding whichever buffer is shorter so
+    // switching never changes the playback position
+    let reference_samples = match reference {
+        Some(reference) => {
+            let (file_samples, file_sr, file_ch) = load_samples(&reference.path)?;
+            check_matches_mix(&reference.path, (file_sr, file_ch), (sample_rate, channels))?;
+            let mut reference_samples = premix(&[file_samples], &[reference.gain]);
+            let length = mixed_samples.len().max(reference_samples.len());
+            mixed_samples.resize(length, 0.0);
+            reference_samples.resize(length, 0.0);
+            log::info!("Loaded reference track {}", reference.path);
+            Some(reference_samples)
         }
-    }
-
-    // Clamp the mixed result to prevent clipping
-    for sample in &mut mixed_samples {
-        *sample = sample.clamp(-1.0, 1.0);
-    }
+        None => None,
+    };
 
     log::info!(
         "Pre-mixed {} samples at {}Hz/{}ch ({}MB in memory)",
@@ -131,20 +119,94 @@ pub fn create_mixed_source_from_files_with_seek(
     );
 
     // Update the samples played counter to reflect the seek position
-    samples_played.store(start_sample, Ordering::Relaxed);
+    handles
+        .samples_played
+        .store(start_sample, Ordering::Relaxed);
 
     let pre_mixed_source = PreMixedSource::new(
         mixed_samples,
+        reference_samples,
+        solo_reference,
         sample_rate,
         channels,
         start_sample,
-        samples_tx,
-        samples_played,
+        handles,
     );
 
     Ok(Box::new(pre_mixed_source))
 }
 
+/// Refuse a file whose sample rate or channel count differs from the mix.
+/// Samples are summed and switched frame for frame, so a mismatch would play
+/// at the wrong speed or with its channels scrambled.
+pub fn check_matches_mix(
+    path: &str,
+    (sample_rate, channels): (u32, u16),
+    (mix_rate, mix_channels): (u32, u16),
+) -> Result<(), String> {
+    if sample_rate != mix_rate || channels != mix_channels {
+        return Err(format!(
+            "{path} is {sample_rate}Hz/{channels}ch but the mix is {mix_rate}Hz/{mix_channels}ch; \
+             convert it to match before mixing or comparing"
+        ));
+    }
+    Ok(())
+}
+
+/// Sum the inputs with their gains, clamping the result to prevent clipping
+fn premix(all_samples: &[Vec<f32>], gains: &[f32]) -> Vec<f32> {
+    let max_length = all_samples.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut mixed_samples = vec![0.0f32; max_length];
+
+    for (file_samples, gain) in all_samples.iter().zip(gains.iter()) {
+        for (mixed, &sample) in mixed_samples.iter_mut().zip(file_samples.iter()) {
+            *mixed += sample * gain;
+        }
+    }
+
+    for sample in &mut mixed_samples {
+        *sample = sample.clamp(-1.0, 1.0);
+    }
+
+    mixed_samples
+}
+
+/// Decode any supported audio file into interleaved f32 samples
+pub fn load_samples(path_str: &str) -> Result<(Vec<f32>, u32, u16), Box<dyn std::error::Error>> {
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::Path;
+
+    let path = Path::new(path_str);
+
+    if !path.exists() {
+        return Err(format!("File not found: {path_str}").into());
+    }
+
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "wav" => {
+            let file = BufReader::new(File::open(path)?);
+            let decoder = hound::WavReader::new(file)?;
+            load_wav_samples(decoder)
+        }
+        "flac" => {
+            let reader = claxon::FlacReader::open(path)?;
+            load_flac_samples(reader)
+        }
+        "aif" | "aiff" => {
+            let aiff_data = crate::media::metadata::read_aiff_data(path)?;
+            load_aiff_samples(aiff_data)
+        }
+        _ => Err(format!("Unsupported audio format: {ext}").into()),
+    }
+}
+
 /// Load WAV file samples into memory
 fn load_wav_samples(
     mut reader: hound::WavReader<std::io::BufReader<std::fs::File>>,
@@ -219,32 +281,42 @@ fn load_aiff_samples(
 /// Pre-mixed source that holds all mixed audio in memory for fast seeking
 struct PreMixedSource {
     mixed_samples: Vec<f32>,
+    reference_samples: Option<Vec<f32>>, // Same length as the mix when present
+    solo_reference: Arc<AtomicBool>,     // Play the reference instead of the mix
     sample_rate: u32,
     channels: u16,
     position: usize,
     samples_tx: mpsc::Sender<Vec<f32>>,
     samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
     monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
 }
 
 impl PreMixedSource {
     fn new(
         mixed_samples: Vec<f32>,
+        reference_samples: Option<Vec<f32>>,
+        solo_reference: Arc<AtomicBool>,
         sample_rate: u32,
         channels: u16,
         start_position: usize,
-        samples_tx: mpsc::Sender<Vec<f32>>,
-        samples_played: Arc<AtomicUsize>,
+        handles: SourceHandles,
     ) -> Self {
         let position = start_position.min(mixed_samples.len());
+        let monitor_chunk = handles.monitor_chunk_len(channels);
         Self {
             mixed_samples,
+            reference_samples,
+            solo_reference,
             sample_rate,
             channels,
             position,
-            samples_tx,
-            samples_played,
-            monitor_buffer: Vec::with_capacity(2048),
+            samples_tx: handles.samples_tx,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
         }
     }
 }
@@ -257,7 +329,13 @@ impl Iterator for PreMixedSource {
             return None;
         }
 
-        let sample = self.mixed_samples[self.position];
+        let samples = match &self.reference_samples {
+            Some(reference) if self.solo_reference.load(Ordering::Relaxed) => reference,
+            _ => &self.mixed_samples,
+        };
+        let sample = self
+            .channel_mode
+            .sample(self.position, self.channels, samples);
         self.position += 1;
 
         // Update samples played counter
@@ -267,8 +345,7 @@ impl Iterator for PreMixedSource {
         self.monitor_buffer.push(sample);
 
         // Send visualization data in chunks (keeping stereo interleaving)
-        let chunk_size = if self.channels > 1 { 2048 } else { 1024 };
-        if self.monitor_buffer.len() >= chunk_size {
+        if self.monitor_buffer.len() >= self.monitor_chunk {
             let _ = self.samples_tx.send(self.monitor_buffer.clone());
             self.monitor_buffer.clear();
         }
@@ -296,3 +373,76 @@ impl Source for PreMixedSource {
         Some(Duration::from_secs_f64(duration_secs))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::channel_mode::ChannelMode;
+
+    #[test]
+    fn test_check_matches_mix() {
+        assert!(check_matches_mix("a.wav", (44100, 2), (44100, 2)).is_ok());
+        let error = check_matches_mix("ref.wav", (48000, 2), (44100, 2)).unwrap_err();
+        assert!(error.contains("ref.wav is 48000Hz/2ch but the mix is 44100Hz/2ch"));
+        assert!(check_matches_mix("mono.wav", (44100, 1), (44100, 2)).is_err());
+    }
+
+    #[test]
+    fn test_premix_applies_gains_and_clamps() {
+        let mixed = premix(&[vec![0.5, 0.5, 0.2], vec![0.5, -0.25]], &[1.0, 2.0]);
+        assert_eq!(mixed, vec![1.0, 0.0, 0.2]);
+    }
+
+    #[test]
+    fn test_solo_reference_switches_buffers() {
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let solo_reference = Arc::new(AtomicBool::new(false));
+        let mut source = PreMixedSource::new(
+            vec![0.1, 0.2, 0.3, 0.4],
+            Some(vec![0.5, 0.6, 0.7, 0.8]),
+            solo_reference.clone(),
+            44100,
+            1,
+            0,
+            SourceHandles::new(
+                samples_tx,
+                Arc::new(AtomicUsize::new(0)),
+                SharedChannelMode::default(),
+            ),
+        );
+
+        assert_eq!(source.next(), Some(0.1));
+        solo_reference.store(true, Ordering::Relaxed);
+        assert_eq!(source.next(), Some(0.6));
+        assert_eq!(source.next(), Some(0.7));
+        solo_reference.store(false, Ordering::Relaxed);
+        assert_eq!(source.next(), Some(0.4));
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn test_channel_mode_applies_to_mix() {
+        let (samples_tx, _samples_rx) = mpsc::channel();
+        let channel_mode = SharedChannelMode::default();
+        let mut source = PreMixedSource::new(
+            vec![0.1, -0.1, 0.2, -0.2],
+            None,
+            Arc::new(AtomicBool::new(false)),
+            44100,
+            2,
+            0,
+            SourceHandles::new(
+                samples_tx,
+                Arc::new(AtomicUsize::new(0)),
+                channel_mode.clone(),
+            ),
+        );
+
+        channel_mode.set(ChannelMode::Swap);
+        assert_eq!(source.next(), Some(-0.1));
+        assert_eq!(source.next(), Some(0.1));
+        channel_mode.set(ChannelMode::SoloLeft);
+        assert_eq!(source.next(), Some(0.2));
+        assert_eq!(source.next(), Some(0.2));
+    }
+}
@@ -9,32 +9,106 @@
 pub mod app;
 pub mod audio;
 pub mod browser;
+pub mod channel_mode;
+pub mod excerpt;
+pub mod help_ui;
+pub mod keymap;
+pub mod logging;
+pub mod loudness;
+pub mod mark_prompt;
+pub mod mark_prompt_ui;
+pub mod metadata_editor;
+pub mod metadata_editor_ui;
 pub mod mixed_source;
 pub mod save_dialog;
 pub mod save_dialog_ui;
+pub mod streaming_source;
 pub mod telemetry;
+pub mod terminal;
+pub mod test_signal;
 pub mod timeline_waveform;
+pub mod transcode;
 pub mod ui;
 pub mod waveform;
+pub mod waveform_image;
 
+use mixed_source::ReferenceTrack;
 use std::error::Error;
 
+/// Startup settings for a player session
+#[derive(Debug, Clone)]
+pub struct PlayerOptions {
+    pub master_gain: f32,           // Output gain applied to the sink (0.0-2.0)
+    pub meter_gain: f32,            // RMS gain for the level meters (1.0 is true level)
+    pub embed_notes: bool,          // Write sidecar notes into saved WAV selections
+    pub inherit_excerpt_tags: bool, // Saved selections keep the source's tags
+    pub autoplay_on_load: bool,     // Start playing as soon as a file is loaded
+    pub replay_gain: bool,          // Apply the replay gain stored in sidecars
+    pub search_body: bool,          // Full-text search matches sidecar markdown bodies
+    pub sidecar_location: zim_studio::utils::sidecar::SidecarLocation, // Where new sidecars go
+    pub monitor_frames: usize,      // Frames per visualization update
+    pub audio_buffer_frames: Option<u32>, // Output buffer to request; None for the device default
+    pub waveform_image_size: (u32, u32), // Width and height of waveform SVGs
+    pub min_selection: std::time::Duration, // Shorter selections are flagged
+    pub block_short_selections: bool, // Refuse to save flagged selections
+    pub true_peak_meter: bool,      // Peak readout in dBTP from 4x oversampling
+    pub scope_gain: f32,            // Oscilloscope display amplification
+    pub scope_auto_gain: bool,      // Fit the oscilloscope to the recent peak instead
+    pub reference: Option<ReferenceTrack>, // Track to A/B against the mix
+    pub start: Option<f64>,         // Seconds to seek to after loading
+    pub end: Option<f64>,           // Seconds to stop at, marking start..end
+    pub loop_region: bool,          // Loop start..end instead of playing it once
+}
+
+impl Default for PlayerOptions {
+    fn default() -> Self {
+        Self {
+            master_gain: 1.0,
+            meter_gain: 2.0,
+            embed_notes: false,
+            inherit_excerpt_tags: true,
+            autoplay_on_load: true,
+            replay_gain: true,
+            search_body: true,
+            sidecar_location: Default::default(),
+            monitor_frames: crate::config::DEFAULT_MONITOR_FRAMES,
+            audio_buffer_frames: None,
+            waveform_image_size: crate::config::DEFAULT_WAVEFORM_IMAGE_SIZE,
+            min_selection: std::time::Duration::from_millis(
+                crate::config::DEFAULT_MIN_SELECTION_MS as u64,
+            ),
+            block_short_selections: false,
+            true_peak_meter: false,
+            scope_gain: crate::config::DEFAULT_SCOPE_GAIN,
+            scope_auto_gain: false,
+            reference: None,
+            start: None,
+            end: None,
+            loop_region: false,
+        }
+    }
+}
+
 pub fn run(
     files: Vec<String>,
     gains: Option<Vec<f32>>,
     _interactive: bool,
+    options: PlayerOptions,
 ) -> Result<(), Box<dyn Error>> {
     // Always launch TUI for now, but load file(s) if provided
     if files.is_empty() {
-        app::run_with_file(None, None)
+        app::run_with_file(None, None, &options)
+    } else if options.reference.is_some() {
+        // Mix plus a reference track to compare against
+        app::run_with_files(&files, gains, &options)
     } else if files.len() == 1 {
         // Single file playback
-        app::run_with_file(Some(&files[0]), None)
+        app::run_with_file(Some(&files[0]), None, &options)
     } else if gains.is_some() {
         // Multiple files with gains specified - mixing mode (simultaneous playback)
-        app::run_with_files(&files, gains)
+        app::run_with_files(&files, gains, &options)
     } else {
         // Multiple files without gains - playlist mode (sequential playback)
-        app::run_with_playlist(&files)
+        app::run_with_playlist(&files, &options)
     }
 }
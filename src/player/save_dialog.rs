@@ -4,10 +4,14 @@
 //! for navigating directories and editing filenames. It tracks whether the user is
 //! saving a selection or the full file, and automatically generates appropriate
 //! filenames for edits (e.g., "original_edit.wav", "original_edit_2.wav").
+//! A destination can also be typed as a path, and new folders created in place.
+//! A full file can be saved in another format by changing its extension.
 
+use super::transcode::TRANSCODE_FORMATS;
 use ratatui::widgets::ListState;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct SaveDialog {
@@ -17,17 +21,59 @@ pub struct SaveDialog {
     pub selected_index: usize,
     pub focus: SaveDialogFocus,
     pub has_selection: bool, // Whether we're saving a selection or full file
+    pub write_sampler_loop: bool, // Mark a saved selection as a loop in a smpl chunk
+    pub write_waveform_image: bool, // Write a waveform SVG beside a saved selection
+    pub source_extension: String, // Extension of the file being saved, for a plain copy
     pub list_state: ListState, // For proper scrolling in directory list
+    pub entry: String,       // Text typed at the go-to or new folder prompt
+    pub error: Option<String>, // Why the last prompt couldn't be applied
+    pub short_selection: Option<ShortSelection>, // Set when the selection is suspiciously short
+}
+
+/// A selection shorter than the configured minimum, likely marked by accident
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShortSelection {
+    pub length: Duration,
+    pub minimum: Duration,
+    pub blocked: bool, // Saving is refused rather than only warned about
+}
+
+impl ShortSelection {
+    /// Warning shown in the save dialog
+    pub fn message(&self) -> String {
+        let length = format_short_duration(self.length);
+        let minimum = format_short_duration(self.minimum);
+        if self.blocked {
+            format!("⚠ Selection is {length}, under the {minimum} minimum: not saving")
+        } else {
+            format!("⚠ Selection is only {length} (minimum {minimum})")
+        }
+    }
+}
+
+/// Milliseconds below a second, so a few samples don't read as "0.0s"
+pub fn format_short_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SaveDialogFocus {
     DirectoryList,
     FilenameField,
+    PathEntry, // Typing a destination folder or file path
+    NewFolder, // Typing the name of a folder to create here
 }
 
 impl SaveDialog {
     pub fn new(initial_path: PathBuf, suggested_filename: String, has_selection: bool) -> Self {
+        let source_extension = Path::new(&suggested_filename)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
         let mut dialog = Self {
             current_path: initial_path.clone(),
             filename: suggested_filename,
@@ -35,7 +81,13 @@ impl SaveDialog {
             selected_index: 0,
             focus: SaveDialogFocus::DirectoryList,
             has_selection,
+            write_sampler_loop: false,
+            write_waveform_image: false,
+            source_extension,
             list_state: ListState::default(),
+            entry: String::new(),
+            error: None,
+            short_selection: None,
         };
 
         // Load directories for initial path
@@ -106,22 +158,184 @@ impl SaveDialog {
         }
     }
 
+    pub fn go_to_parent(&mut self) {
+        if let Some(parent) = self.current_path.parent() {
+            self.current_path = parent.to_path_buf();
+            self.refresh_directories();
+        }
+    }
+
     pub fn toggle_focus(&mut self) {
         self.focus = match self.focus {
             SaveDialogFocus::DirectoryList => SaveDialogFocus::FilenameField,
-            SaveDialogFocus::FilenameField => SaveDialogFocus::DirectoryList,
+            _ => SaveDialogFocus::DirectoryList,
         };
     }
 
+    /// Whether a go-to or new folder prompt is taking the keyboard
+    pub fn is_prompting(&self) -> bool {
+        matches!(
+            self.focus,
+            SaveDialogFocus::PathEntry | SaveDialogFocus::NewFolder
+        )
+    }
+
+    /// Prompt for a destination, starting from the current folder
+    pub fn start_path_entry(&mut self) {
+        self.entry = format!("{}/", self.current_path.display());
+        self.error = None;
+        self.focus = SaveDialogFocus::PathEntry;
+    }
+
+    pub fn start_new_folder(&mut self) {
+        self.entry.clear();
+        self.error = None;
+        self.focus = SaveDialogFocus::NewFolder;
+    }
+
+    pub fn cancel_entry(&mut self) {
+        self.entry.clear();
+        self.focus = SaveDialogFocus::DirectoryList;
+    }
+
+    /// Apply the prompt. On failure the prompt stays open with `error` set.
+    pub fn confirm_entry(&mut self) {
+        let result = match self.focus {
+            SaveDialogFocus::PathEntry => self.go_to_entered_path(),
+            SaveDialogFocus::NewFolder => self.create_folder(),
+            _ => return,
+        };
+        match result {
+            Ok(()) => {
+                self.error = None;
+                self.cancel_entry();
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Go to a typed folder. A path to a file in an existing folder goes
+    /// there and takes the file name as well.
+    fn go_to_entered_path(&mut self) -> Result<(), String> {
+        let path = self.resolve_entry();
+        if path.is_dir() {
+            self.current_path = path;
+        } else if let (Some(parent), Some(name)) = (path.parent(), path.file_name())
+            && parent.is_dir()
+        {
+            self.current_path = parent.to_path_buf();
+            self.filename = name.to_string_lossy().to_string();
+        } else {
+            return Err(format!("No such folder: {}", path.display()));
+        }
+        self.refresh_directories();
+        Ok(())
+    }
+
+    /// Expand `~` and resolve relative paths against the current folder
+    fn resolve_entry(&self) -> PathBuf {
+        let entry = self.entry.trim();
+        let expanded = match entry.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                let home = std::env::var("HOME").unwrap_or_default();
+                PathBuf::from(format!("{home}{rest}"))
+            }
+            _ => PathBuf::from(entry),
+        };
+        if expanded.is_absolute() {
+            expanded
+        } else {
+            self.current_path.join(expanded)
+        }
+    }
+
+    /// Create a folder in the current one and move into it
+    fn create_folder(&mut self) -> Result<(), String> {
+        let name = self.entry.trim();
+        if name.is_empty()
+            || name == "."
+            || name == ".."
+            || Path::new(name).components().count() != 1
+        {
+            return Err(format!("Not a folder name: {name}"));
+        }
+        let folder = self.current_path.join(name);
+        fs::create_dir(&folder).map_err(|e| format!("Could not create {name}: {e}"))?;
+        self.current_path = folder;
+        self.refresh_directories();
+        Ok(())
+    }
+
+    /// Toggle writing the selection as a sampler loop (selections only)
+    pub fn toggle_sampler_loop(&mut self) {
+        if self.has_selection {
+            self.write_sampler_loop = !self.write_sampler_loop;
+        }
+    }
+
+    /// Toggle writing a waveform SVG beside the saved selection (selections only)
+    pub fn toggle_waveform_image(&mut self) {
+        if self.has_selection {
+            self.write_waveform_image = !self.write_waveform_image;
+        }
+    }
+
+    /// Formats a full-file save can be written in: a copy in the source's
+    /// own format first, then each format it can be transcoded to
+    pub fn formats(&self) -> Vec<String> {
+        let mut formats = vec![self.source_extension.clone()];
+        for format in TRANSCODE_FORMATS {
+            if !formats.iter().any(|f| f == format) {
+                formats.push(format.to_string());
+            }
+        }
+        formats
+    }
+
+    /// Extension of the file name, which picks the format it's saved in
+    pub fn format(&self) -> String {
+        Path::new(&self.filename)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+
+    /// Switch the file name to the next format (full-file saves only, since
+    /// selections are always WAV)
+    pub fn cycle_format(&mut self) {
+        if self.has_selection {
+            return;
+        }
+        let formats = self.formats();
+        let next = match formats.iter().position(|f| *f == self.format()) {
+            Some(index) => &formats[(index + 1) % formats.len()],
+            None => &formats[0],
+        };
+        self.filename = Path::new(&self.filename)
+            .with_extension(next)
+            .to_string_lossy()
+            .into_owned();
+    }
+
     pub fn push_char(&mut self, c: char) {
-        if self.focus == SaveDialogFocus::FilenameField {
-            self.filename.push(c);
+        match self.focus {
+            SaveDialogFocus::FilenameField => self.filename.push(c),
+            SaveDialogFocus::PathEntry | SaveDialogFocus::NewFolder => self.entry.push(c),
+            SaveDialogFocus::DirectoryList => {}
         }
     }
 
+    /// Delete a character from the focused field; in the directory list,
+    /// go up to the parent folder
     pub fn pop_char(&mut self) {
-        if self.focus == SaveDialogFocus::FilenameField {
-            self.filename.pop();
+        match self.focus {
+            SaveDialogFocus::FilenameField => {
+                self.filename.pop();
+            }
+            SaveDialogFocus::PathEntry | SaveDialogFocus::NewFolder => {
+                self.entry.pop();
+            }
+            SaveDialogFocus::DirectoryList => self.go_to_parent(),
         }
     }
 
@@ -131,6 +345,7 @@ impl SaveDialog {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
@@ -146,6 +361,26 @@ mod tests {
         assert_eq!(dialog.selected_index, 0);
     }
 
+    #[test]
+    fn test_cycle_format() {
+        let mut dialog = SaveDialog::new(PathBuf::from("/music"), "master.wav".to_string(), false);
+        assert_eq!(dialog.formats(), ["wav", "flac"]);
+        dialog.cycle_format();
+        assert_eq!(dialog.filename, "master.flac");
+        dialog.cycle_format();
+        assert_eq!(dialog.filename, "master.wav");
+
+        let mut dialog = SaveDialog::new(PathBuf::from("/music"), "take.aiff".to_string(), false);
+        assert_eq!(dialog.formats(), ["aiff", "wav", "flac"]);
+        dialog.cycle_format();
+        assert_eq!(dialog.filename, "take.wav");
+
+        // Selections are always written as WAV
+        let mut dialog = SaveDialog::new(PathBuf::from("/music"), "a_edit.wav".to_string(), true);
+        dialog.cycle_format();
+        assert_eq!(dialog.filename, "a_edit.wav");
+    }
+
     #[test]
     fn test_get_full_path() {
         let dialog = SaveDialog::new(
@@ -291,6 +526,97 @@ mod tests {
         assert_eq!(dialog.filename, "file.");
     }
 
+    #[test]
+    fn test_backspace_in_directory_list_goes_up() {
+        let mut dialog = SaveDialog::new(
+            PathBuf::from("/parent/child"),
+            "file.wav".to_string(),
+            false,
+        );
+        dialog.pop_char();
+        assert_eq!(dialog.current_path, PathBuf::from("/parent"));
+        assert_eq!(dialog.filename, "file.wav");
+    }
+
+    #[test]
+    fn test_path_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let exports = temp_dir.path().join("exports");
+        fs::create_dir(&exports).unwrap();
+        let mut dialog =
+            SaveDialog::new(temp_dir.path().to_path_buf(), "take.wav".to_string(), true);
+
+        // Relative to the current folder
+        dialog.start_path_entry();
+        assert!(dialog.is_prompting());
+        dialog.entry = "exports".to_string();
+        dialog.confirm_entry();
+        assert_eq!(dialog.current_path, exports);
+        assert_eq!(dialog.focus, SaveDialogFocus::DirectoryList);
+
+        // A full destination sets the folder and the file name
+        dialog.start_path_entry();
+        dialog.entry = format!("{}/chorus.wav", temp_dir.path().display());
+        dialog.confirm_entry();
+        assert_eq!(dialog.get_full_path(), temp_dir.path().join("chorus.wav"));
+
+        // A missing folder keeps the prompt open
+        dialog.start_path_entry();
+        dialog.entry = "missing/deeper/take.wav".to_string();
+        dialog.confirm_entry();
+        assert!(dialog.error.as_deref().unwrap().contains("No such folder"));
+        assert_eq!(dialog.focus, SaveDialogFocus::PathEntry);
+        dialog.cancel_entry();
+        assert_eq!(dialog.current_path, temp_dir.path());
+    }
+
+    #[test]
+    fn test_new_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut dialog =
+            SaveDialog::new(temp_dir.path().to_path_buf(), "take.wav".to_string(), true);
+
+        dialog.start_new_folder();
+        for c in "bounces".chars() {
+            dialog.push_char(c);
+        }
+        dialog.confirm_entry();
+        assert!(temp_dir.path().join("bounces").is_dir());
+        assert_eq!(dialog.current_path, temp_dir.path().join("bounces"));
+        assert!(!dialog.is_prompting());
+
+        dialog.start_new_folder();
+        dialog.entry = "a/b".to_string();
+        dialog.confirm_entry();
+        assert!(dialog.error.is_some());
+        assert!(dialog.is_prompting());
+    }
+
+    #[test]
+    fn test_toggle_sampler_loop() {
+        let mut dialog = SaveDialog::new(PathBuf::from("/test"), "loop.wav".to_string(), true);
+        assert!(!dialog.write_sampler_loop);
+        dialog.toggle_sampler_loop();
+        assert!(dialog.write_sampler_loop);
+
+        // Full-file copies keep the source's own chunks
+        let mut dialog = SaveDialog::new(PathBuf::from("/test"), "file.wav".to_string(), false);
+        dialog.toggle_sampler_loop();
+        assert!(!dialog.write_sampler_loop);
+    }
+
+    #[test]
+    fn test_toggle_waveform_image() {
+        let mut dialog = SaveDialog::new(PathBuf::from("/test"), "take.wav".to_string(), true);
+        assert!(!dialog.write_waveform_image);
+        dialog.toggle_waveform_image();
+        assert!(dialog.write_waveform_image);
+
+        let mut dialog = SaveDialog::new(PathBuf::from("/test"), "file.wav".to_string(), false);
+        dialog.toggle_waveform_image();
+        assert!(!dialog.write_waveform_image);
+    }
+
     #[test]
     fn test_focus_enum() {
         assert_eq!(
@@ -48,19 +48,45 @@ pub fn draw_save_dialog(f: &mut Frame, area: Rect, dialog: &SaveDialog) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(2), // Current path
-            Constraint::Min(5),    // Directory list
-            Constraint::Length(3), // Filename field
-            Constraint::Length(2), // Controls
+            Constraint::Length(2),                                           // Current path
+            Constraint::Min(5),                                              // Directory list
+            Constraint::Length(3),                                           // Filename field
+            Constraint::Length(3 + dialog.short_selection.is_some() as u16), // Controls
         ])
         .split(inner_area);
 
-    // Current path
-    let path_display = format!("📁 {}", dialog.current_path.display());
-    let path_widget = Paragraph::new(path_display)
-        .style(Style::default().fg(Color::Blue))
-        .block(Block::default().borders(Borders::BOTTOM));
+    // Current path, or the go-to / new folder prompt in its place
+    let prompt = match dialog.focus {
+        SaveDialogFocus::PathEntry => Some("Go to: "),
+        SaveDialogFocus::NewFolder => Some("New folder: "),
+        _ => None,
+    };
+    let path_line = if let Some(prompt) = prompt {
+        let mut spans = vec![
+            Span::styled(prompt, Style::default().fg(Color::Yellow)),
+            Span::styled(dialog.entry.as_str(), Style::default().fg(Color::White)),
+        ];
+        if let Some(error) = &dialog.error {
+            spans.push(Span::styled(
+                format!("  {error}"),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        Line::from(spans)
+    } else {
+        Line::from(Span::styled(
+            format!("📁 {}", dialog.current_path.display()),
+            Style::default().fg(Color::Blue),
+        ))
+    };
+    let path_widget = Paragraph::new(path_line).block(Block::default().borders(Borders::BOTTOM));
     f.render_widget(path_widget, chunks[0]);
+    if let Some(prompt) = prompt {
+        let cursor_x = chunks[0].x + (prompt.len() + dialog.entry.chars().count()) as u16;
+        if cursor_x < chunks[0].x + chunks[0].width {
+            f.set_cursor_position((cursor_x, chunks[0].y));
+        }
+    }
 
     // Directory list
     let dirs: Vec<ListItem> = dialog
@@ -112,14 +138,62 @@ pub fn draw_save_dialog(f: &mut Frame, area: Rect, dialog: &SaveDialog) {
     }
 
     // Controls
-    let controls = vec![
+    let navigation = Line::from(vec![
+        Span::styled("[⌫]", Style::default().fg(Color::Yellow)),
+        Span::raw(" up  "),
+        Span::styled("[^G]", Style::default().fg(Color::Yellow)),
+        Span::raw(" go to path  "),
+        Span::styled("[^N]", Style::default().fg(Color::Yellow)),
+        Span::raw(" new folder"),
+    ]);
+    let controls = Line::from(vec![
         Span::styled("[Tab]", Style::default().fg(Color::Yellow)),
         Span::raw(" switch  "),
         Span::styled("[Enter]", Style::default().fg(Color::Green)),
         Span::raw(" save  "),
         Span::styled("[Esc]", Style::default().fg(Color::Red)),
         Span::raw(" cancel"),
-    ];
-    let controls_widget = Paragraph::new(Line::from(controls)).alignment(Alignment::Center);
+    ]);
+    let mut lines = Vec::new();
+    if let Some(short) = &dialog.short_selection {
+        let color = if short.blocked {
+            Color::Red
+        } else {
+            Color::Yellow
+        };
+        lines.push(Line::from(Span::styled(
+            short.message(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(navigation);
+    // Extras written alongside a saved selection
+    if dialog.has_selection {
+        let checkbox = |checked: bool| if checked { "[x]" } else { "[ ]" };
+        lines.push(Line::from(vec![
+            Span::styled("[^L]", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" {} loop  ", checkbox(dialog.write_sampler_loop))),
+            Span::styled("[^W]", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(
+                " {} waveform svg",
+                checkbox(dialog.write_waveform_image)
+            )),
+        ]));
+    }
+    // A full file is copied, or transcoded when its extension changes
+    if !dialog.has_selection {
+        let format = dialog.format();
+        let how = if format == dialog.source_extension {
+            "copy"
+        } else {
+            "transcode"
+        };
+        lines.push(Line::from(vec![
+            Span::styled("[^F]", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" format: {} ({how})", format.to_uppercase())),
+        ]));
+    }
+    lines.push(controls);
+    let controls_widget = Paragraph::new(lines).alignment(Alignment::Center);
     f.render_widget(controls_widget, chunks[3]);
 }
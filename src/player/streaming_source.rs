@@ -0,0 +1,509 @@
+//! Streaming playback for files too large to decode into memory.
+//!
+//! The in-memory sources decode a whole file up front, which is what makes
+//! seeking instant, but a two-hour 24-bit stereo WAV would need gigabytes.
+//! Above [`STREAMING_THRESHOLD_BYTES`], WAV and FLAC files are instead decoded
+//! a window at a time as playback consumes them. Seeking re-opens the file at
+//! the target: WAV seeks the reader directly, while FLAC decodes forward from
+//! the start and discards blocks until it reaches the target frame. Integer
+//! and 32-bit float WAVs stream; 64-bit float and compressed WAVs stay on the
+//! in-memory path, which reads or rejects them.
+
+use super::audio::{SourceHandles, float_to_i32};
+use super::channel_mode::SharedChannelMode;
+use crate::wav_metadata;
+use rodio::Source;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+    mpsc,
+};
+use std::time::Duration;
+
+/// Files larger than this are streamed rather than decoded into memory
+pub const STREAMING_THRESHOLD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Frames decoded per WAV window (FLAC windows are one block)
+const WINDOW_FRAMES: usize = 16384;
+
+/// Whether `path` is a format that can stream and is big enough to need to
+pub fn should_stream(path: &Path) -> bool {
+    let streamable = match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("wav") => wav_streams(path),
+        Some("flac") => true,
+        _ => false,
+    };
+    streamable
+        && fs::metadata(path)
+            .map(|m| m.len() > STREAMING_THRESHOLD_BYTES)
+            .unwrap_or(false)
+}
+
+/// Whether hound can read the WAV's samples: integer PCM or 32-bit float
+fn wav_streams(path: &Path) -> bool {
+    wav_metadata::read_wav_layout(path)
+        .is_ok_and(|layout| !layout.is_compressed() && !layout.is_float64())
+}
+
+enum Decoder {
+    Wav {
+        reader: hound::WavReader<BufReader<File>>,
+        float: bool,
+    },
+    Flac {
+        reader: claxon::FlacReader<File>,
+        block_buffer: Vec<i32>,
+        // Frames still to discard after a seek
+        skip_frames: usize,
+    },
+}
+
+pub struct StreamingSource {
+    decoder: Decoder,
+    samples_tx: mpsc::Sender<Vec<f32>>,
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+    total_samples: usize,
+    window: Vec<i32>,
+    window_position: usize,
+    monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
+    samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
+}
+
+impl StreamingSource {
+    pub fn open(path: &Path, handles: SourceHandles) -> Result<Self, Box<dyn Error>> {
+        Self::open_at(path, 0, handles)
+    }
+
+    /// Open `path` positioned at interleaved sample `start_sample`, rounded
+    /// down to a whole frame
+    pub fn open_at(
+        path: &Path,
+        start_sample: usize,
+        handles: SourceHandles,
+    ) -> Result<Self, Box<dyn Error>> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        let (decoder, sample_rate, channels, bits_per_sample, total_samples) = match ext.as_str() {
+            "wav" => {
+                let mut reader = hound::WavReader::new(BufReader::new(File::open(path)?))?;
+                let spec = reader.spec();
+                let float = spec.sample_format == hound::SampleFormat::Float;
+                if float && spec.bits_per_sample != 32 {
+                    return Err("Streaming supports 32-bit float WAV files only".into());
+                }
+                let total_samples = reader.len() as usize;
+                let start_frame = start_sample / spec.channels.max(1) as usize;
+                reader.seek(start_frame as u32)?;
+                (
+                    Decoder::Wav { reader, float },
+                    spec.sample_rate,
+                    spec.channels,
+                    // Float samples are scaled to the 32-bit integer range
+                    if float { 32 } else { spec.bits_per_sample },
+                    total_samples,
+                )
+            }
+            "flac" => {
+                let reader = claxon::FlacReader::open(path)?;
+                let info = reader.streaminfo();
+                let channels = info.channels as u16;
+                let total_samples = info.samples.unwrap_or(0) as usize * info.channels as usize;
+                (
+                    Decoder::Flac {
+                        reader,
+                        block_buffer: Vec::new(),
+                        skip_frames: start_sample / channels.max(1) as usize,
+                    },
+                    info.sample_rate,
+                    channels,
+                    info.bits_per_sample as u16,
+                    total_samples,
+                )
+            }
+            _ => return Err(format!("Streaming not supported for: {ext}").into()),
+        };
+        let monitor_chunk = handles.monitor_chunk_len(channels);
+
+        log::info!(
+            "Streaming {}: {} Hz, {} channels, {} bits, from sample {}",
+            path.display(),
+            sample_rate,
+            channels,
+            bits_per_sample,
+            start_sample
+        );
+
+        Ok(Self {
+            decoder,
+            samples_tx: handles.samples_tx,
+            sample_rate,
+            channels,
+            bits_per_sample,
+            total_samples,
+            window: Vec::new(),
+            window_position: 0,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
+        })
+    }
+
+    /// Interleaved sample count of the whole file
+    pub fn total_samples(&self) -> usize {
+        self.total_samples
+    }
+
+    pub fn bits_per_sample(&self) -> u16 {
+        self.bits_per_sample
+    }
+
+    /// Decode the next window. Returns false at the end of the file.
+    fn fill_window(&mut self) -> bool {
+        self.window.clear();
+        self.window_position = 0;
+
+        match &mut self.decoder {
+            Decoder::Wav { reader, float } => {
+                let count = WINDOW_FRAMES * self.channels as usize;
+                let samples: Box<dyn Iterator<Item = Result<i32, hound::Error>>> = if *float {
+                    Box::new(
+                        reader
+                            .samples::<f32>()
+                            .take(count)
+                            .map(|s| s.map(float_to_i32)),
+                    )
+                } else {
+                    Box::new(reader.samples::<i32>().take(count))
+                };
+                for sample in samples {
+                    match sample {
+                        Ok(sample) => self.window.push(sample),
+                        Err(e) => {
+                            log::warn!("WAV stream ended early: {e}");
+                            break;
+                        }
+                    }
+                }
+            }
+            Decoder::Flac {
+                reader,
+                block_buffer,
+                skip_frames,
+            } => loop {
+                let buffer = std::mem::take(block_buffer);
+                let block = match reader.blocks().read_next_or_eof(buffer) {
+                    Ok(Some(block)) => block,
+                    Ok(None) => break,
+                    Err(e) => {
+                        log::warn!("FLAC stream ended early: {e}");
+                        break;
+                    }
+                };
+
+                let duration = block.duration() as usize;
+                if *skip_frames >= duration {
+                    *skip_frames -= duration;
+                    *block_buffer = block.into_buffer();
+                    continue;
+                }
+
+                for frame in *skip_frames..duration {
+                    for channel in 0..block.channels() {
+                        self.window.push(block.sample(channel, frame as u32));
+                    }
+                }
+                *skip_frames = 0;
+                *block_buffer = block.into_buffer();
+                break;
+            },
+        }
+
+        !self.window.is_empty()
+    }
+}
+
+impl Iterator for StreamingSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window_position >= self.window.len() && !self.fill_window() {
+            return None;
+        }
+
+        let sample = self
+            .channel_mode
+            .sample(self.window_position, self.channels, &self.window);
+        self.window_position += 1;
+
+        // Update samples played counter
+        self.samples_played.fetch_add(1, Ordering::Relaxed);
+
+        let sample_f32 = match self.bits_per_sample {
+            8 => sample as f32 / 128.0,
+            16 => sample as f32 / 32768.0,
+            24 => sample as f32 / 8388608.0,
+            _ => sample as f32 / 2147483648.0,
+        };
+
+        // Send visualization data in chunks (keeping stereo interleaving)
+        self.monitor_buffer.push(sample_f32);
+        if self.monitor_buffer.len() >= self.monitor_chunk {
+            let _ = self.samples_tx.send(self.monitor_buffer.clone());
+            self.monitor_buffer.clear();
+        }
+
+        Some(sample_f32)
+    }
+}
+
+impl Source for StreamingSource {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        // A FLAC stream may not record its length
+        if self.total_samples == 0 {
+            return None;
+        }
+        let duration_secs =
+            self.total_samples as f64 / (self.sample_rate as f64 * self.channels as f64);
+        Some(Duration::from_secs_f64(duration_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{float32, pcm16, write_wav};
+    use tempfile::TempDir;
+
+    fn handles() -> (SourceHandles, mpsc::Receiver<Vec<f32>>) {
+        let (samples_tx, samples_rx) = mpsc::channel();
+        let handles = SourceHandles::new(
+            samples_tx,
+            Arc::new(AtomicUsize::new(0)),
+            SharedChannelMode::default(),
+        );
+        (handles, samples_rx)
+    }
+
+    /// Sample `i` of a test ramp, distinct enough to check ordering
+    fn ramp(i: usize) -> i16 {
+        (i % 20000) as i16 - 10000
+    }
+
+    fn write_ramp(path: &Path, frames: usize) {
+        write_wav(path, pcm16(2, 8000), (0..frames * 2).map(ramp));
+    }
+
+    fn crc8(bytes: &[u8]) -> u8 {
+        bytes.iter().fold(0u8, |mut crc, &b| {
+            crc ^= b;
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ 0x07
+                } else {
+                    crc << 1
+                };
+            }
+            crc
+        })
+    }
+
+    fn crc16(bytes: &[u8]) -> u16 {
+        bytes.iter().fold(0u16, |mut crc, &b| {
+            crc ^= (b as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 {
+                    (crc << 1) ^ 0x8005
+                } else {
+                    crc << 1
+                };
+            }
+            crc
+        })
+    }
+
+    /// A stereo 16-bit FLAC of verbatim frames, `block` frames per block
+    fn write_flac(path: &Path, frames: usize, block: usize) {
+        let mut flac = b"fLaC".to_vec();
+        let mut streaminfo = Vec::new();
+        streaminfo.extend((block as u16).to_be_bytes());
+        streaminfo.extend((block as u16).to_be_bytes());
+        streaminfo.extend([0u8; 6]);
+        // 20-bit rate, 3-bit channels-1, 5-bit bps-1, 36-bit total samples
+        let packed: u64 = (8000u64 << 44) | (1 << 41) | (15 << 36) | frames as u64;
+        streaminfo.extend(packed.to_be_bytes());
+        streaminfo.extend([0u8; 16]);
+        flac.push(0x80);
+        flac.extend(&(streaminfo.len() as u32).to_be_bytes()[1..]);
+        flac.extend(streaminfo);
+
+        for (number, start) in (0..frames).step_by(block).enumerate() {
+            let length = block.min(frames - start);
+            // Sync, 8-bit blocksize at end of header, rate from streaminfo,
+            // independent stereo, 16-bit, single-byte frame number
+            let mut frame = vec![0xFF, 0xF8, 0x60, 0x18, number as u8, (length - 1) as u8];
+            frame.push(crc8(&frame));
+            for channel in 0..2 {
+                frame.push(0x02); // Verbatim subframe
+                for i in start..start + length {
+                    frame.extend(ramp(i * 2 + channel).to_be_bytes());
+                }
+            }
+            frame.extend(crc16(&frame).to_be_bytes());
+            flac.extend(frame);
+        }
+        fs::write(path, flac).unwrap();
+    }
+
+    fn expected(from: usize, frames: usize) -> Vec<f32> {
+        (from..frames * 2)
+            .map(|i| ramp(i) as f32 / 32768.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_wav_streams_in_windows() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("long.wav");
+        let frames = WINDOW_FRAMES * 2 + 100;
+        write_ramp(&path, frames);
+
+        let (handles, samples_rx) = handles();
+        let played = handles.samples_played.clone();
+        let source = StreamingSource::open(&path, handles).unwrap();
+        assert_eq!(source.total_samples(), frames * 2);
+        assert!(source.window.is_empty());
+
+        let samples: Vec<f32> = source.collect();
+        assert_eq!(samples, expected(0, frames));
+        assert_eq!(played.load(Ordering::Relaxed), frames * 2);
+        assert!(samples_rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_wav_open_at_seeks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("long.wav");
+        write_ramp(&path, 5000);
+
+        // An odd sample offset rounds down to the start of its frame
+        let source = StreamingSource::open_at(&path, 6001, handles().0).unwrap();
+        assert_eq!(source.collect::<Vec<_>>(), expected(6000, 5000));
+    }
+
+    #[test]
+    fn test_flac_streams_and_seeks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("long.flac");
+        write_flac(&path, 1000, 192);
+
+        let source = StreamingSource::open(&path, handles().0).unwrap();
+        assert_eq!(source.total_samples(), 2000);
+        assert_eq!(source.total_duration(), Some(Duration::from_millis(125)));
+        assert_eq!(source.collect::<Vec<_>>(), expected(0, 1000));
+
+        // Frame 500 falls inside the third block
+        let source = StreamingSource::open_at(&path, 1000, handles().0).unwrap();
+        assert_eq!(source.collect::<Vec<_>>(), expected(1000, 1000));
+
+        // STREAMINFO may leave the total at 0 for unknown
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[21] &= 0xF0;
+        bytes[22..26].fill(0);
+        fs::write(&path, bytes).unwrap();
+        let source = StreamingSource::open(&path, handles().0).unwrap();
+        assert_eq!(source.total_duration(), None);
+        assert_eq!(source.collect::<Vec<_>>(), expected(0, 1000));
+    }
+
+    /// A WAV header for `data_len` bytes of audio in the given format
+    fn wav_header(format_tag: u16, bits: u16, data_len: u32) -> Vec<u8> {
+        let block_align = 2 * bits / 8;
+        let mut wav = b"RIFF".to_vec();
+        wav.extend((36 + data_len).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(16u32.to_le_bytes());
+        wav.extend(format_tag.to_le_bytes());
+        wav.extend(2u16.to_le_bytes());
+        wav.extend(8000u32.to_le_bytes());
+        wav.extend((8000 * block_align as u32).to_le_bytes());
+        wav.extend(block_align.to_le_bytes());
+        wav.extend(bits.to_le_bytes());
+        wav.extend(b"data");
+        wav.extend(data_len.to_le_bytes());
+        wav
+    }
+
+    #[test]
+    fn test_float_wav_streams() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("render.wav");
+        write_wav(&path, float32(2, 8000), [0.0f32, -0.5, 0.25, 2.0]);
+
+        let samples: Vec<f32> = StreamingSource::open(&path, handles().0).unwrap().collect();
+        assert_eq!(samples, [0.0, -0.5, 0.25, 1.0]);
+
+        // Large float renders stream, except 64-bit float which hound can't read
+        let size = STREAMING_THRESHOLD_BYTES as u32;
+        for (name, bits, streams) in [("float32.wav", 32, true), ("float64.wav", 64, false)] {
+            let path = temp_dir.path().join(name);
+            let mut file = File::create(&path).unwrap();
+            std::io::Write::write_all(&mut file, &wav_header(3, bits, size)).unwrap();
+            file.set_len(44 + size as u64).unwrap();
+            assert_eq!(should_stream(&path), streams, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_should_stream_needs_large_supported_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("small.wav");
+        write_ramp(&path, 10);
+        assert!(!should_stream(&path));
+        assert!(!should_stream(&temp_dir.path().join("missing.wav")));
+
+        // Sparse files report a large size without using the disk
+        let big = temp_dir.path().join("big.flac");
+        File::create(&big)
+            .unwrap()
+            .set_len(STREAMING_THRESHOLD_BYTES + 1)
+            .unwrap();
+        assert!(should_stream(&big));
+
+        let aiff = temp_dir.path().join("big.aiff");
+        File::create(&aiff)
+            .unwrap()
+            .set_len(STREAMING_THRESHOLD_BYTES + 1)
+            .unwrap();
+        assert!(!should_stream(&aiff));
+    }
+}
@@ -99,6 +99,7 @@ pub struct AudioTelemetry {
     snapshots: VecDeque<TelemetrySnapshot>,
     last_capture: Instant,
     start_time: Instant,
+    meter_gain: f32, // RMS gain the player's meters are using
 }
 
 impl AudioTelemetry {
@@ -116,9 +117,15 @@ impl AudioTelemetry {
             snapshots: VecDeque::with_capacity(buffer_size),
             last_capture: now,
             start_time: now,
+            meter_gain: 2.0,
         }
     }
 
+    /// Record the gain the meters apply, so scaled RMS matches the display
+    pub fn set_meter_gain(&mut self, gain: f32) {
+        self.meter_gain = gain;
+    }
+
     /// Update configuration at runtime
     pub fn update_config(&mut self, config: TelemetryConfig) {
         self.config = config;
@@ -257,7 +264,7 @@ impl AudioTelemetry {
             } else {
                 0.0
             };
-            let scaled_rms = raw_rms * 2.0; // Based on current scaling in code
+            let scaled_rms = raw_rms * self.meter_gain;
             let final_level = scaled_rms.min(1.0);
 
             AudioFormatMetrics {
@@ -383,6 +390,7 @@ impl AudioTelemetry {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
 
@@ -0,0 +1,91 @@
+//! Terminal setup and teardown for the player.
+//!
+//! The player runs in raw mode on the alternate screen with mouse capture.
+//! [`TerminalGuard`] undoes all of that when it is dropped, so an early
+//! return or a panic in the event loop still hands back a usable shell. A
+//! panic hook restores the terminal before the panic message is printed,
+//! otherwise the message would be drawn on the alternate screen and lost.
+//!
+//! To check by hand, run `zim play` and press Ctrl+C: the player quits like
+//! `q` and the shell echoes input with a visible cursor. For the panic path,
+//! temporarily add a `panic!()` to `run_app` and check the message is
+//! readable in the restored shell.
+
+use crossterm::{
+    clipboard::CopyToClipboard,
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::error::Error;
+use std::io;
+use std::sync::Once;
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Raw mode, alternate screen and mouse capture for as long as it lives
+pub struct TerminalGuard {
+    active: bool,
+}
+
+impl TerminalGuard {
+    pub fn enter() -> Result<Self, Box<dyn Error>> {
+        install_panic_hook();
+        let mut guard = Self { active: false };
+        guard.resume()?;
+        Ok(guard)
+    }
+
+    /// Hand the terminal back to the shell, e.g. while an editor runs
+    pub fn leave(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.active {
+            self.active = false;
+            restore_terminal()?;
+        }
+        Ok(())
+    }
+
+    /// Take the terminal over again after [`TerminalGuard::leave`]
+    pub fn resume(&mut self) -> Result<(), Box<dyn Error>> {
+        enable_raw_mode()?;
+        self.active = true;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(())
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = restore_terminal();
+        }
+    }
+}
+
+/// Put `text` on the system clipboard with an OSC 52 escape, which the
+/// terminal handles, so it also works over SSH. Terminals that don't support
+/// it (or have it turned off) silently ignore the escape.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    execute!(io::stdout(), CopyToClipboard::to_clipboard_from(text))
+}
+
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal();
+            default_hook(info);
+        }));
+    });
+}
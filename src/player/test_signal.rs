@@ -0,0 +1,365 @@
+//! Built-in test signals for checking the output and calibrating the meters.
+//!
+//! `zim play --tone <Hz>` and `zim play --test <kind>` play a synthesized
+//! sine, square, white or pink noise signal instead of a file. It goes
+//! through the same monitoring path as decoded audio, so the scope, meters
+//! and channel modes all respond to it. The player opens it through a
+//! pseudo-path (see [`TestSignal::path`]) so seeking and looping work the
+//! same way as for files.
+
+use super::audio::SourceHandles;
+use super::channel_mode::SharedChannelMode;
+use rodio::Source;
+use std::error::Error;
+use std::path::Path;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+    mpsc,
+};
+use std::time::Duration;
+
+/// Prefix of the pseudo-path the player loads a test signal from
+pub const TEST_SIGNAL_PREFIX: &str = "test-signal:";
+
+/// Tone frequency when only `--test sine` or `--test square` is given
+pub const DEFAULT_TONE_HZ: f32 = 1000.0;
+
+/// How long a test signal plays; `--loop` keeps it going
+pub const TEST_SIGNAL_SECONDS: u64 = 60;
+
+const SAMPLE_RATE: u32 = 48_000;
+const CHANNELS: u16 = 2;
+
+/// Peak level of every signal: -12 dBFS
+const LEVEL: f32 = 0.251_188_64;
+
+/// Tone frequencies accepted, up to just below Nyquist at 48 kHz
+const TONE_RANGE: (f32, f32) = (1.0, 20_000.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignalKind {
+    Sine,
+    Square,
+    White, // White noise
+    Pink,  // Pink noise, -3 dB per octave
+}
+
+impl SignalKind {
+    /// Names accepted by `--test`
+    pub const NAMES: &[&str] = &["sine", "square", "white", "pink"];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sine" => Some(SignalKind::Sine),
+            "square" => Some(SignalKind::Square),
+            "white" => Some(SignalKind::White),
+            "pink" => Some(SignalKind::Pink),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SignalKind::Sine => "sine",
+            SignalKind::Square => "square",
+            SignalKind::White => "white",
+            SignalKind::Pink => "pink",
+        }
+    }
+
+    fn is_noise(self) -> bool {
+        matches!(self, SignalKind::White | SignalKind::Pink)
+    }
+}
+
+/// A test signal: its kind and, for tones, the frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestSignal {
+    pub kind: SignalKind,
+    pub frequency: f32,
+}
+
+impl TestSignal {
+    /// The signal for `--test <kind>` and `--tone <Hz>`. A frequency alone
+    /// means a sine; a tone kind alone plays at [`DEFAULT_TONE_HZ`].
+    pub fn new(kind: Option<&str>, frequency: Option<f32>) -> Result<Self, Box<dyn Error>> {
+        let kind = match kind {
+            Some(name) => SignalKind::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown test signal '{name}' (expected one of: {})",
+                    SignalKind::NAMES.join(", ")
+                )
+            })?,
+            None => SignalKind::Sine,
+        };
+        if kind.is_noise() && frequency.is_some() {
+            return Err(format!("--tone doesn't apply to {} noise", kind.name()).into());
+        }
+        let frequency = frequency.unwrap_or(DEFAULT_TONE_HZ);
+        if !(TONE_RANGE.0..=TONE_RANGE.1).contains(&frequency) {
+            return Err(format!(
+                "Tone frequency must be between {} and {} Hz",
+                TONE_RANGE.0, TONE_RANGE.1
+            )
+            .into());
+        }
+        Ok(Self { kind, frequency })
+    }
+
+    /// The pseudo-path the player loads this signal from, such as
+    /// `test-signal:sine@440` or `test-signal:pink`
+    pub fn path(&self) -> String {
+        if self.kind.is_noise() {
+            format!("{TEST_SIGNAL_PREFIX}{}", self.kind.name())
+        } else {
+            format!(
+                "{TEST_SIGNAL_PREFIX}{}@{}",
+                self.kind.name(),
+                self.frequency
+            )
+        }
+    }
+
+    /// The signal a pseudo-path names, or None for real files
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let spec = path.to_str()?.strip_prefix(TEST_SIGNAL_PREFIX)?;
+        let (name, frequency) = match spec.split_once('@') {
+            Some((name, frequency)) => (name, Some(frequency.parse().ok()?)),
+            None => (spec, None),
+        };
+        Self::new(Some(name), frequency).ok()
+    }
+}
+
+/// Endless source of the samples of a [`TestSignal`], cut off after
+/// [`TEST_SIGNAL_SECONDS`]. Both channels carry the same signal.
+pub struct SignalSource {
+    signal: TestSignal,
+    position: usize,
+    total_samples: usize,
+    frame: [f32; 2],
+    noise_state: u32,
+    pink_state: [f32; 7],
+    samples_tx: mpsc::Sender<Vec<f32>>,
+    monitor_buffer: Vec<f32>,
+    monitor_chunk: usize,
+    samples_played: Arc<AtomicUsize>,
+    channel_mode: SharedChannelMode,
+}
+
+impl SignalSource {
+    pub fn new(signal: TestSignal, handles: SourceHandles) -> Self {
+        let monitor_chunk = handles.monitor_chunk_len(CHANNELS);
+        Self {
+            signal,
+            position: 0,
+            total_samples: (TEST_SIGNAL_SECONDS * SAMPLE_RATE as u64) as usize * CHANNELS as usize,
+            frame: [0.0; 2],
+            noise_state: 0x9E37_79B9,
+            pink_state: [0.0; 7],
+            samples_tx: handles.samples_tx,
+            monitor_buffer: Vec::with_capacity(monitor_chunk),
+            monitor_chunk,
+            samples_played: handles.samples_played,
+            channel_mode: handles.channel_mode,
+        }
+    }
+
+    pub fn total_samples(&self) -> usize {
+        self.total_samples
+    }
+
+    /// Continue from an interleaved sample position, as after a seek
+    pub fn skip_to(&mut self, sample_position: usize) {
+        let frame_start = sample_position - sample_position % CHANNELS as usize;
+        self.position = frame_start.min(self.total_samples);
+    }
+
+    /// Uniform noise between -1.0 and 1.0 (xorshift32)
+    fn white(&mut self) -> f32 {
+        let mut x = self.noise_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.noise_state = x;
+        x as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    /// White noise through Paul Kellet's pinking filter
+    fn pink(&mut self) -> f32 {
+        let white = self.white();
+        let b = &mut self.pink_state;
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.153852;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+        b[6] = white * 0.115926;
+        // The filter's output peaks around +-4.5
+        (pink * 0.22).clamp(-1.0, 1.0)
+    }
+
+    /// The value of the signal for the frame starting at `position`
+    fn next_frame(&mut self) -> f32 {
+        let frame = self.position / CHANNELS as usize;
+        // Phase from the frame index, so a seek lands where it should
+        let phase = (frame as f64 * self.signal.frequency as f64 / SAMPLE_RATE as f64).fract();
+        let value = match self.signal.kind {
+            SignalKind::Sine => (phase * std::f64::consts::TAU).sin() as f32,
+            SignalKind::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            SignalKind::White => self.white(),
+            SignalKind::Pink => self.pink(),
+        };
+        value * LEVEL
+    }
+}
+
+impl Iterator for SignalSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.total_samples {
+            return None;
+        }
+
+        let channel = self.position % CHANNELS as usize;
+        if channel == 0 {
+            let value = self.next_frame();
+            self.frame = [value, value];
+        }
+        let sample = self.channel_mode.sample(channel, CHANNELS, &self.frame);
+        self.position += 1;
+        self.samples_played.fetch_add(1, Ordering::Relaxed);
+
+        self.monitor_buffer.push(sample);
+        if self.monitor_buffer.len() >= self.monitor_chunk {
+            let _ = self.samples_tx.send(self.monitor_buffer.clone());
+            self.monitor_buffer.clear();
+        }
+
+        Some(sample)
+    }
+}
+
+impl Source for SignalSource {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        CHANNELS
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs(TEST_SIGNAL_SECONDS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::channel_mode::ChannelMode;
+
+    fn source(kind: Option<&str>, frequency: Option<f32>) -> SignalSource {
+        let (tx, _rx) = mpsc::channel();
+        let handles = SourceHandles::new(tx, Arc::new(AtomicUsize::new(0)), Default::default());
+        SignalSource::new(TestSignal::new(kind, frequency).unwrap(), handles)
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn test_signal_arguments() {
+        let tone = TestSignal::new(None, Some(440.0)).unwrap();
+        assert_eq!(tone.kind, SignalKind::Sine);
+        assert_eq!(tone.path(), "test-signal:sine@440");
+        assert_eq!(TestSignal::from_path(Path::new(&tone.path())), Some(tone));
+
+        let pink = TestSignal::new(Some("pink"), None).unwrap();
+        assert_eq!(pink.path(), "test-signal:pink");
+        assert_eq!(
+            TestSignal::from_path(Path::new("test-signal:pink")),
+            Some(pink)
+        );
+        let square = TestSignal::new(Some("square"), None).unwrap();
+        assert_eq!(square.frequency, DEFAULT_TONE_HZ);
+
+        assert!(TestSignal::new(Some("saw"), None).is_err());
+        assert!(TestSignal::new(Some("white"), Some(440.0)).is_err());
+        assert!(TestSignal::new(None, Some(0.0)).is_err());
+        assert!(TestSignal::new(None, Some(30_000.0)).is_err());
+        assert_eq!(TestSignal::from_path(Path::new("mixes/sine.wav")), None);
+    }
+
+    #[test]
+    fn test_sine_and_square_levels() {
+        // 1 kHz at 48 kHz: exactly 48 frames per cycle
+        let sine: Vec<f32> = source(None, Some(1000.0)).take(9600).collect();
+        assert!((peak(&sine) - LEVEL).abs() < 1e-4);
+        assert_eq!(sine[0], sine[1], "both channels carry the signal");
+        let rms = (sine.iter().map(|s| s * s).sum::<f32>() / sine.len() as f32).sqrt();
+        assert!((rms - LEVEL * std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+
+        let square: Vec<f32> = source(Some("square"), Some(1000.0)).take(96).collect();
+        assert!(square[..48].iter().all(|&s| s == LEVEL));
+        assert!(square[48..].iter().all(|&s| s == -LEVEL));
+    }
+
+    #[test]
+    fn test_noise_is_bounded_and_pink_is_darker() {
+        let white: Vec<f32> = source(Some("white"), None).take(96_000).collect();
+        let pink: Vec<f32> = source(Some("pink"), None).take(96_000).collect();
+        assert!(peak(&white) <= LEVEL && peak(&pink) <= LEVEL);
+        let mean = white.iter().sum::<f32>() / white.len() as f32;
+        assert!(mean.abs() < 0.01, "{mean}");
+
+        // Pink noise has less energy at high frequencies, so consecutive
+        // frames differ less relative to its overall level
+        let roughness = |noise: &[f32]| {
+            let frames: Vec<f32> = noise.iter().step_by(2).copied().collect();
+            let diff: f32 = frames.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+            let power: f32 = frames.iter().map(|s| s * s).sum();
+            diff / power
+        };
+        assert!(roughness(&pink) < roughness(&white) / 2.0);
+    }
+
+    #[test]
+    fn test_seek_and_length() {
+        let mut full = source(None, Some(440.0));
+        assert_eq!(full.total_samples(), 60 * 48_000 * 2);
+        let expected: Vec<f32> = full.by_ref().skip(1000).take(10).collect();
+
+        let mut seeked = source(None, Some(440.0));
+        seeked.skip_to(1000);
+        assert_eq!(seeked.by_ref().take(10).collect::<Vec<_>>(), expected);
+
+        seeked.skip_to(usize::MAX);
+        assert_eq!(seeked.next(), None);
+    }
+
+    #[test]
+    fn test_side_monitoring_cancels_the_signal() {
+        let (tx, _rx) = mpsc::channel();
+        let handles = SourceHandles::new(tx, Arc::new(AtomicUsize::new(0)), Default::default());
+        handles.channel_mode.set(ChannelMode::Side);
+        let signal = TestSignal::new(None, Some(1000.0)).unwrap();
+        let source = SignalSource::new(signal, handles);
+        assert!(source.take(960).all(|s| s == 0.0));
+    }
+}
@@ -0,0 +1,243 @@
+//! Writing a whole file in another format.
+//!
+//! The save dialog uses this when a full-file save names a different
+//! extension than the source, such as a WAV master archived as FLAC. Both
+//! output formats are lossless, so 16- and 24-bit audio comes out sample for
+//! sample; 8-bit sources are written at 16 bits and deeper ones at 24.
+
+use super::mixed_source::load_samples;
+use log::{info, warn};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use zim_studio::utils::sidecar::{
+    SidecarCloneMode, SidecarLocation, clone_sidecar, get_sidecar_path, update_frontmatter_fields,
+};
+
+/// Formats a full file can be transcoded to, by extension
+pub const TRANSCODE_FORMATS: &[&str] = &["wav", "flac"];
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Whether saving `source` as `dest` means re-encoding rather than copying
+pub fn needs_transcode(source: &Path, dest: &Path) -> bool {
+    let dest_ext = extension_of(dest);
+    dest_ext != extension_of(source) && TRANSCODE_FORMATS.contains(&dest_ext.as_str())
+}
+
+/// Write `source` to `dest` in the format named by `dest`'s extension, with
+/// a copy of the source's sidecar. A sidecar failure is logged rather than
+/// returned, since the audio itself was saved.
+pub fn save_transcoded(
+    source: &Path,
+    dest: &Path,
+    sidecar_location: SidecarLocation,
+) -> Result<(), Box<dyn Error>> {
+    transcode_audio(source, dest)?;
+    info!("Transcoded {source:?} to {dest:?}");
+
+    if let Err(e) = clone_transcoded_sidecar(source, dest, sidecar_location) {
+        warn!("Failed to create sidecar file: {e}");
+    }
+    Ok(())
+}
+
+/// Bit depth to write for a source of `source_bits`
+fn output_bits(source_bits: u16) -> u16 {
+    if source_bits <= 16 { 16 } else { 24 }
+}
+
+/// Scale a -1.0 to 1.0 sample back to a `bits`-bit integer. Decoding divides
+/// by the same power of two, so samples at this depth or below round-trip
+/// exactly.
+fn quantize(sample: f32, bits: u16) -> i32 {
+    let scale = (1i64 << (bits - 1)) as f64;
+    (sample as f64 * scale).round().clamp(-scale, scale - 1.0) as i32
+}
+
+fn transcode_audio(source: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let source_bits = crate::media::metadata::read_audio_metadata(source)?.bits_per_sample;
+    let bits = output_bits(source_bits);
+    let (samples, sample_rate, channels) = load_samples(&source.to_string_lossy())?;
+    let samples: Vec<i32> = samples.iter().map(|&s| quantize(s, bits)).collect();
+
+    match extension_of(dest).as_str() {
+        "wav" => write_wav(dest, &samples, sample_rate, channels, bits),
+        "flac" => write_flac(dest, &samples, sample_rate, channels, bits),
+        other => Err(format!("Can't transcode to {other}").into()),
+    }
+}
+
+fn write_wav(
+    dest: &Path,
+    samples: &[i32],
+    sample_rate: u32,
+    channels: u16,
+    bits: u16,
+) -> Result<(), Box<dyn Error>> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: bits,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(dest, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+fn write_flac(
+    dest: &Path,
+    samples: &[i32],
+    sample_rate: u32,
+    channels: u16,
+    bits: u16,
+) -> Result<(), Box<dyn Error>> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| format!("Invalid FLAC encoder settings: {e}"))?;
+    let source = flacenc::source::MemSource::from_samples(
+        samples,
+        channels as usize,
+        bits as usize,
+        sample_rate as usize,
+    );
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| format!("FLAC encoding failed: {e}"))?;
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| format!("FLAC encoding failed: {e}"))?;
+    fs::write(dest, sink.as_slice())?;
+    Ok(())
+}
+
+/// Copy the source's sidecar to the new file and note which file it was
+/// transcoded from. Sources without a sidecar are left without one.
+fn clone_transcoded_sidecar(
+    source: &Path,
+    dest: &Path,
+    sidecar_location: SidecarLocation,
+) -> Result<(), Box<dyn Error>> {
+    clone_sidecar(
+        source,
+        dest,
+        SidecarCloneMode::FullCopy,
+        None,
+        sidecar_location,
+    )?;
+    let sidecar = get_sidecar_path(dest, sidecar_location);
+    if !sidecar.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&sidecar)?;
+    let updated = update_frontmatter_fields(
+        &content,
+        vec![(
+            "source_file",
+            serde_yaml::Value::String(source.to_string_lossy().into_owned()),
+        )],
+    )?;
+    fs::write(&sidecar, updated)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+    use tempfile::TempDir;
+
+    fn write_source(path: &Path, bits: u16, samples: &[i32]) {
+        let spec = hound::WavSpec {
+            bits_per_sample: bits,
+            ..pcm16(2, 44100)
+        };
+        write_wav(path, spec, samples.iter().copied());
+    }
+
+    #[test]
+    fn test_needs_transcode() {
+        let wav = Path::new("master.wav");
+        assert!(needs_transcode(wav, Path::new("master.flac")));
+        assert!(needs_transcode(wav, Path::new("master.FLAC")));
+        assert!(!needs_transcode(wav, Path::new("copy.WAV")));
+        // Not a format we can write, so it stays a byte copy
+        assert!(!needs_transcode(wav, Path::new("master.mp3")));
+        assert!(needs_transcode(
+            Path::new("take.aiff"),
+            Path::new("take.wav")
+        ));
+    }
+
+    #[test]
+    fn test_quantize() {
+        assert_eq!(quantize(0.5, 16), 16384);
+        assert_eq!(quantize(-1.0, 16), -32768);
+        assert_eq!(quantize(1.5, 16), 32767);
+        assert_eq!(quantize(-1.0, 24), -8388608);
+        assert_eq!(output_bits(8), 16);
+        assert_eq!(output_bits(32), 24);
+    }
+
+    #[test]
+    fn test_wav_to_flac_is_lossless() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("master.wav");
+        let samples: Vec<i32> = (0..8192)
+            .map(|i| ((i as f64 / 20.0).sin() * 4_000_000.0) as i32 + (i % 7))
+            .collect();
+        write_source(&source, 24, &samples);
+        fs::write(
+            temp_dir.path().join("master.wav.md"),
+            "---\nfile: master.wav\ntags: [\"master\"]\n---\n# Notes\n",
+        )
+        .unwrap();
+
+        let dest = temp_dir.path().join("master.flac");
+        save_transcoded(&source, &dest, SidecarLocation::Adjacent).unwrap();
+
+        let mut reader = claxon::FlacReader::open(&dest).unwrap();
+        assert_eq!(reader.streaminfo().bits_per_sample, 24);
+        assert_eq!(reader.streaminfo().channels, 2);
+        assert_eq!(reader.streaminfo().sample_rate, 44100);
+        let decoded: Vec<i32> = reader.samples().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, samples);
+        assert!(fs::metadata(&dest).unwrap().len() < fs::metadata(&source).unwrap().len());
+
+        let sidecar = fs::read_to_string(temp_dir.path().join("master.flac.md")).unwrap();
+        assert!(sidecar.contains("file: master.flac"));
+        assert!(sidecar.contains("master"));
+        assert!(sidecar.contains("source_file:"));
+    }
+
+    #[test]
+    fn test_flac_back_to_wav() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("take.wav");
+        let samples: Vec<i32> = (0..4096).map(|i| (i * 7 % 65536) - 32768).collect();
+        write_source(&source, 16, &samples);
+        let flac = temp_dir.path().join("take.flac");
+        save_transcoded(&source, &flac, SidecarLocation::Adjacent).unwrap();
+
+        let wav = temp_dir.path().join("take_again.wav");
+        save_transcoded(&flac, &wav, SidecarLocation::Adjacent).unwrap();
+        let mut reader = hound::WavReader::open(&wav).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        let decoded: Vec<i32> = reader.samples::<i32>().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, samples);
+        // No source sidecar, so none for the copies
+        assert!(!temp_dir.path().join("take_again.wav.md").exists());
+    }
+}
@@ -17,11 +17,29 @@ use ratatui::{
     },
 };
 
-use super::app::{App, ViewMode, WaveformDisplayMode};
+use super::app::{App, LEVEL_HISTORY_LEN, ViewMode, WaveformDisplayMode};
+use super::audio::{AudioInfo, SamplePosition};
+use super::channel_mode::ChannelMode;
+use super::help_ui::draw_help_overlay;
+use super::keymap;
+use super::loudness::TRUE_PEAK_CEILING;
+use super::mark_prompt_ui::draw_mark_prompt;
+use super::metadata_editor_ui::draw_metadata_editor;
+use super::save_dialog::format_short_duration;
 use super::save_dialog_ui::draw_save_dialog;
+use crate::config::SCOPE_GAIN_RANGE;
+use zim_studio::utils::gain::format_volume;
 
 // UI Constants
 const MIN_HEIGHT_FOR_OSCILLOSCOPE: u16 = 20;
+const PREVIEW_WAVEFORM_HEIGHT: u16 = 6; // Including borders
+// Browser layout: narrower terminals stack the preview under the file list,
+// and drop it when there isn't room for both
+const MIN_WIDTH_FOR_SIDE_PREVIEW: u16 = 80;
+const MIN_HEIGHT_FOR_STACKED_PREVIEW: u16 = 16;
+const MAX_BROWSER_LIST_WIDTH: u16 = 60;
+// Rows kept above the selection before the file list scrolls
+const BROWSER_SCROLL_LEAD: usize = 10;
 const LED_LEVEL_THRESHOLDS: [(f32, &str); 3] = [
     (0.3, "●"),  // Full circle
     (0.05, "◐"), // Half filled
@@ -32,11 +50,14 @@ const LED_LEVEL_THRESHOLDS: [(f32, &str); 3] = [
 const LED_CLIPPING_LEVEL: f32 = 0.9;
 const LED_HIGH_LEVEL: f32 = 0.3;
 const LED_LOW_LEVEL: f32 = 0.05;
+const LEVEL_TRAIL_BARS: [&str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
 
 // Grid constants for oscilloscope
 const GRID_COLOR: Color = Color::Rgb(0, 60, 30);
 const GRID_VERTICAL_STEP: usize = 10;
 const GRID_HORIZONTAL_LINES: [f64; 7] = [-0.75, -0.5, -0.25, 0.0, 0.25, 0.5, 0.75];
+// Level the recent peak is scaled to with scope_auto_gain, inside the clipping edge
+const SCOPE_AUTO_FIT: f32 = 0.9;
 
 // Helper functions
 fn format_time(seconds: u64) -> String {
@@ -79,11 +100,23 @@ pub fn draw(f: &mut Frame, app: &App) {
     if let Some(ref save_dialog) = app.save_dialog {
         draw_save_dialog(f, size, save_dialog);
     }
+
+    if let Some(ref editor) = app.metadata_editor {
+        draw_metadata_editor(f, size, editor);
+    }
+
+    if let Some(ref prompt) = app.mark_prompt {
+        draw_mark_prompt(f, size, prompt);
+    }
+
+    if app.show_help {
+        draw_help_overlay(f, size, app.view_mode, app.help_scroll);
+    }
 }
 
 fn draw_main_ui(f: &mut Frame, app: &App) {
     let size = f.area();
-    let show_oscilloscope = size.height > MIN_HEIGHT_FOR_OSCILLOSCOPE;
+    let show_oscilloscope = app.show_scope && size.height > MIN_HEIGHT_FOR_OSCILLOSCOPE;
 
     let constraints = if show_oscilloscope {
         vec![
@@ -92,6 +125,7 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
             Constraint::Length(3), // Progress bar
             Constraint::Min(7),    // Waveform area
             Constraint::Length(4), // Controls (increased for 2 rows)
+            Constraint::Length(1), // Now playing status
         ]
     } else {
         vec![
@@ -99,6 +133,7 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
             Constraint::Length(3), // File info + LEDs
             Constraint::Length(3), // Progress bar
             Constraint::Length(4), // Controls (increased for 2 rows)
+            Constraint::Length(1), // Now playing status
         ]
     };
 
@@ -160,20 +195,43 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
     } else {
         Color::Green
     };
-    let controls_row1 = vec![
-        create_control_button("space", Style::default().fg(play_color)),
+    let mut controls_row1 = vec![
+        create_control_button(keymap::PLAY_PAUSE.key, Style::default().fg(play_color)),
         Span::raw(if app.is_playing {
             " pause  "
         } else {
             " play  "
         }),
-        create_control_button("←→", Style::default().fg(Color::Magenta)),
-        Span::raw(" seek  "),
-        create_control_button("/", Style::default().fg(Color::Blue)),
-        Span::raw(" browse  "),
-        create_control_button("q", Style::default().fg(Color::Red)),
-        Span::raw(" quit"),
     ];
+    controls_row1.extend(create_control(
+        keymap::SEEK.key,
+        keymap::SEEK.label,
+        Style::default().fg(Color::Magenta),
+    ));
+    controls_row1.extend(create_control(
+        keymap::BROWSE.key,
+        keymap::BROWSE.label,
+        Style::default().fg(Color::Blue),
+    ));
+    controls_row1.push(create_control_button(
+        keymap::VOLUME.key,
+        Style::default().fg(Color::Green),
+    ));
+    controls_row1.push(Span::raw(format!(
+        " {} {}  ",
+        keymap::VOLUME.label,
+        format_volume(app.master_gain)
+    )));
+    controls_row1.extend(create_control(
+        keymap::HELP.key,
+        keymap::HELP.label,
+        Style::default().fg(Color::Cyan),
+    ));
+    controls_row1.push(create_control_button(
+        keymap::QUIT.key,
+        Style::default().fg(Color::Red),
+    ));
+    controls_row1.push(Span::raw(format!(" {}", keymap::QUIT.label)));
 
     // Second row of controls
     let loop_style = if app.is_looping {
@@ -183,24 +241,33 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
     };
 
     let mut controls_row2 = Vec::new();
-    controls_row2.extend(create_control("i", "in", Style::default().fg(Color::Green)));
     controls_row2.extend(create_control(
-        "o",
-        "out",
+        keymap::MARK_IN.key,
+        keymap::MARK_IN.label,
+        Style::default().fg(Color::Green),
+    ));
+    controls_row2.extend(create_control(
+        keymap::MARK_OUT.key,
+        keymap::MARK_OUT.label,
         Style::default().fg(Color::Green),
     ));
     controls_row2.extend(create_control(
-        "x",
-        "clear",
+        keymap::CLEAR_MARKS.key,
+        keymap::CLEAR_MARKS.label,
         Style::default().fg(Color::Yellow),
     ));
 
     // Loop control has dynamic label
-    controls_row2.push(create_control_button("l", loop_style));
+    controls_row2.push(create_control_button(keymap::LOOP.key, loop_style));
+    let passes = match (app.is_looping, app.loops_remaining, app.loop_count) {
+        (true, Some(remaining), Some(count)) => format!(" {remaining}/{count}"),
+        (false, _, Some(count)) => format!(" {count}×"),
+        _ => String::new(),
+    };
     controls_row2.push(Span::raw(if app.is_looping {
-        " loop ●  "
+        format!(" {} ●{passes}  ", keymap::LOOP.label)
     } else {
-        " loop  "
+        format!(" {}{passes}  ", keymap::LOOP.label)
     }));
 
     // Waveform view toggle (only when playing and timeline waveform available)
@@ -210,56 +277,56 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
         } else {
             Style::default().fg(Color::DarkGray)
         };
-        controls_row2.push(create_control_button("w", waveform_style));
+        controls_row2.push(create_control_button(keymap::TIMELINE.key, waveform_style));
         controls_row2.push(Span::raw(if app.show_timeline_while_playing {
-            " timeline ●  "
+            format!(" {} ●  ", keymap::TIMELINE.label)
         } else {
-            " timeline  "
+            format!(" {}  ", keymap::TIMELINE.label)
         }));
     }
 
     // Waveform display mode indicator (cycles: line → scatter → vector)
     let mode_style = Style::default().fg(Color::Yellow);
-    controls_row2.push(create_control_button("m", mode_style));
+    controls_row2.push(create_control_button(keymap::DISPLAY_MODE.key, mode_style));
     controls_row2.push(Span::raw(format!(
         " {}  ",
         app.waveform_display_mode.label()
     )));
 
     controls_row2.extend(create_control(
-        "s",
-        "save",
+        keymap::SAVE.key,
+        keymap::SAVE.label,
         Style::default().fg(Color::Cyan),
     ));
     controls_row2.extend(create_control(
-        "e",
-        "edit",
+        keymap::EDIT.key,
+        keymap::EDIT.label,
         Style::default().fg(Color::Magenta),
     ));
 
     // Add playlist controls if playlist is active
     if app.playlist.is_some() {
         controls_row2.extend(create_control(
-            "p",
-            "prev",
+            keymap::PREV_TRACK.key,
+            keymap::PREV_TRACK.label,
             Style::default().fg(Color::Blue),
         ));
         controls_row2.extend(create_control(
-            "n",
-            "next",
+            keymap::NEXT_TRACK.key,
+            keymap::NEXT_TRACK.label,
             Style::default().fg(Color::Blue),
         ));
     }
 
     // Telemetry control has dynamic label
     controls_row2.push(create_control_button(
-        "t",
+        keymap::TELEMETRY.key,
         Style::default().fg(Color::Yellow),
     ));
     controls_row2.push(Span::raw(if app.telemetry.config().enabled {
-        " telemetry ●"
+        format!(" {} ●", keymap::TELEMETRY.label)
     } else {
-        " telemetry"
+        format!(" {}", keymap::TELEMETRY.label)
     }));
 
     let controls_widget1 = Paragraph::new(Line::from(controls_row1)).alignment(Alignment::Center);
@@ -271,6 +338,99 @@ fn draw_main_ui(f: &mut Frame, app: &App) {
 
     f.render_widget(controls_widget1, control_chunks[0]);
     f.render_widget(controls_widget2, control_chunks[1]);
+
+    draw_status_bar(f, chunks[controls_idx + 1], app);
+}
+
+/// One line with everything about the loaded file at a glance: name,
+/// format, time and loop/selection state
+fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
+    let Some(file) = &app.current_file else {
+        return;
+    };
+    let filename = std::path::Path::new(file)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(file);
+    let dim = Style::default().fg(Color::DarkGray);
+    let separator = || Span::styled(" │ ", dim);
+
+    let mut spans = vec![
+        Span::styled(
+            if app.is_playing { "▶ " } else { "⏸ " },
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::styled(filename.to_string(), Style::default().fg(Color::White)),
+    ];
+    if let Some(info) = app.audio_info() {
+        spans.push(separator());
+        spans.push(Span::raw(format_audio_info(info)));
+    }
+    if let Some(duration) = app.duration {
+        let total = duration.as_secs_f64();
+        spans.push(separator());
+        spans.push(Span::raw(format!(
+            "{} / {}",
+            format_time((total * app.playback_position as f64) as u64),
+            format_duration(duration)
+        )));
+        if let Some(selection) = selection_status(app.mark_in, app.mark_out, total) {
+            spans.push(separator());
+            match app.short_selection() {
+                Some(length) => spans.push(Span::styled(
+                    format!(
+                        "{selection} ⚠ {} is too short",
+                        format_short_duration(length)
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )),
+                None => spans.push(Span::styled(selection, Style::default().fg(Color::Green))),
+            }
+        }
+    }
+    if app.is_looping {
+        spans.push(separator());
+        spans.push(Span::styled("loop ●", Style::default().fg(Color::Magenta)));
+    }
+
+    let status = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Black));
+    f.render_widget(status, area);
+}
+
+/// Format like "48 kHz/24-bit stereo"; mixes have no single bit depth
+fn format_audio_info(info: &AudioInfo) -> String {
+    let channels = match info.channels {
+        1 => "mono".to_string(),
+        2 => "stereo".to_string(),
+        n => format!("{n}ch"),
+    };
+    match info.bits_per_sample {
+        Some(bits) => format!(
+            "{}/{bits}-bit {channels}",
+            format_sample_rate(info.sample_rate)
+        ),
+        None => format!("{} {channels}", format_sample_rate(info.sample_rate)),
+    }
+}
+
+/// The marks as times in a file of `total` seconds, with the selection
+/// length once both are set
+fn selection_status(mark_in: Option<f32>, mark_out: Option<f32>, total: f64) -> Option<String> {
+    let time = |mark: f32| format_time((total * mark as f64) as u64);
+    match (mark_in, mark_out) {
+        (Some(mark_in), Some(mark_out)) => {
+            let (start, end) = (mark_in.min(mark_out), mark_in.max(mark_out));
+            Some(format!(
+                "sel {}–{} ({:.1}s)",
+                time(start),
+                time(end),
+                total * (end - start) as f64
+            ))
+        }
+        (Some(mark_in), None) => Some(format!("in {}", time(mark_in))),
+        (None, Some(mark_out)) => Some(format!("out {}", time(mark_out))),
+        (None, None) => None,
+    }
 }
 
 fn draw_file_info_with_leds(f: &mut Frame, area: Rect, app: &App) {
@@ -304,29 +464,105 @@ fn draw_file_info_with_leds(f: &mut Frame, area: Rect, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Min(20),    // File info
-            Constraint::Length(12), // LED indicators
+            Constraint::Min(20),                              // File info
+            Constraint::Length(LEVEL_HISTORY_LEN as u16 + 1), // Level trail
+            Constraint::Length(24),                           // Peak readout and LEDs
         ])
         .split(area);
 
     // File info - simplified since title now shows filename and position
-    let file_info = if app.current_file.is_some() {
-        "Ready".to_string()
+    let file_info = if let Some(reference) = &app.reference_file {
+        compare_line(app.solo_reference, reference)
+    } else if app.current_file.is_some() {
+        Line::from("Ready")
     } else {
-        "No file selected - Pass a file path to play".to_string()
+        Line::from("No file selected - Pass a file path to play")
+    };
+    let file_info = with_channel_mode(file_info, app.channel_mode);
+    let file_info = match app.sample_rates() {
+        Some((source, output)) => with_sample_rates(file_info, source, output),
+        None => file_info,
     };
 
     let file_widget = Paragraph::new(file_info).style(Style::default().fg(Color::White));
     f.render_widget(file_widget, chunks[0]);
 
-    // LED indicators
-    draw_leds(f, chunks[1], app);
+    // Recent levels scrolling right to left, then the LED indicators
+    draw_level_trail(f, chunks[1], app);
+    draw_leds(f, chunks[2], app);
 
     // Bottom border
     let border = Block::default().borders(Borders::BOTTOM);
     f.render_widget(border, area);
 }
 
+/// Append the monitoring mode to a status line when it isn't plain stereo
+fn with_channel_mode(mut line: Line<'static>, mode: ChannelMode) -> Line<'static> {
+    if mode != ChannelMode::Stereo {
+        line.spans.push(Span::raw("  "));
+        line.spans.push(Span::styled(
+            format!(" {} ", mode.label()),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    line
+}
+
+/// Append the file's sample rate, and the device rate when playback is
+/// resampled, so it is clear when you aren't hearing the native file
+fn with_sample_rates(mut line: Line<'static>, source: u32, output: u32) -> Line<'static> {
+    let dim = Style::default().fg(Color::DarkGray);
+    line.spans.push(Span::styled(
+        format!("  {}", format_sample_rate(source)),
+        dim,
+    ));
+    if source != output {
+        line.spans.push(Span::styled(
+            format!(" → {} resampled", format_sample_rate(output)),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    line
+}
+
+/// A sample rate in kHz without trailing zeros, e.g. "44.1 kHz" or "48 kHz"
+fn format_sample_rate(rate: u32) -> String {
+    let khz = format!("{:.3}", rate as f64 / 1000.0);
+    format!("{} kHz", khz.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Label showing whether the mix or the reference is audible
+fn compare_line(solo_reference: bool, reference: &str) -> Line<'static> {
+    let reference_name = std::path::Path::new(reference)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(reference)
+        .to_string();
+    let active = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let inactive = Style::default().fg(Color::DarkGray);
+    let (mix_style, reference_style) = if solo_reference {
+        (inactive, active)
+    } else {
+        (active, inactive)
+    };
+
+    Line::from(vec![
+        Span::styled(" MIX ", mix_style),
+        Span::raw(" "),
+        Span::styled(format!(" REF: {reference_name} "), reference_style),
+        Span::styled(
+            format!("  [{}] switch", keymap::REFERENCE.key),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
 fn draw_leds(f: &mut Frame, area: Rect, app: &App) {
     let led_text = if app.current_file.is_some() {
         let l_char = get_led_char(app.left_level);
@@ -334,12 +570,24 @@ fn draw_leds(f: &mut Frame, area: Rect, app: &App) {
         let l_color = get_led_color(app.left_level, true);
         let r_color = get_led_color(app.right_level, false);
 
-        vec![
+        let mut spans = Vec::new();
+        if let Some((readout, over)) = peak_readout(app.peak_level, app.true_peak_meter) {
+            let color = if over { Color::Red } else { Color::DarkGray };
+            spans.push(Span::styled(
+                format!("{readout} "),
+                Style::default().fg(color),
+            ));
+        }
+        if app.true_level {
+            spans.push(Span::styled("true ", Style::default().fg(Color::DarkGray)));
+        }
+        spans.extend([
             Span::raw("L"),
             Span::styled(l_char, Style::default().fg(l_color)),
             Span::raw(" R"),
             Span::styled(r_char, Style::default().fg(r_color)),
-        ]
+        ]);
+        spans
     } else {
         vec![
             Span::raw("L"),
@@ -353,6 +601,45 @@ fn draw_leds(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(led_widget, area);
 }
 
+/// The peak hold as "-3.2 dBFS", or "-0.8 dBTP" from the true-peak meter,
+/// and whether it is over the limit: full scale for samples, the delivery
+/// ceiling for true peak. None until there has been any signal.
+fn peak_readout(level: f32, true_peak: bool) -> Option<(String, bool)> {
+    if level <= 0.0 {
+        return None;
+    }
+    let db = 20.0 * (level as f64).log10();
+    let (unit, over) = if true_peak {
+        ("dBTP", db > TRUE_PEAK_CEILING)
+    } else {
+        ("dBFS", level >= 1.0)
+    };
+    Some((format!("pk {db:.1} {unit}"), over))
+}
+
+fn draw_level_trail(f: &mut Frame, area: Rect, app: &App) {
+    if app.current_file.is_none() {
+        return;
+    }
+    let bars: Vec<Span> = app
+        .level_history
+        .iter()
+        .map(|&level| {
+            Span::styled(
+                level_trail_bar(level),
+                Style::default().fg(get_led_color(level, true)),
+            )
+        })
+        .collect();
+    let trail = Paragraph::new(Line::from(bars)).alignment(Alignment::Right);
+    f.render_widget(trail, area);
+}
+
+fn level_trail_bar(level: f32) -> &'static str {
+    let index = (level.clamp(0.0, 1.0) * (LEVEL_TRAIL_BARS.len() - 1) as f32).round() as usize;
+    LEVEL_TRAIL_BARS[index]
+}
+
 fn get_led_char(level: f32) -> &'static str {
     for (threshold, symbol) in LED_LEVEL_THRESHOLDS.iter() {
         if level >= *threshold {
@@ -511,6 +798,16 @@ fn draw_vectorscope_grid(ctx: &mut Context, _area: Rect) {
     // doesn't have circle primitive, so we skip for now
 }
 
+/// Oscilloscope gain that brings `peak` to `SCOPE_AUTO_FIT`, within the
+/// range `scope_gain` accepts so near-silence isn't blown up to full scale
+fn auto_scope_gain(peak: f32) -> f32 {
+    let (min, max) = SCOPE_GAIN_RANGE;
+    if peak <= 0.0 {
+        return max;
+    }
+    (SCOPE_AUTO_FIT / peak).clamp(min, max)
+}
+
 fn draw_grid(ctx: &mut Context, area: Rect) {
     // Vertical grid lines
     for x in (0..area.width).step_by(GRID_VERTICAL_STEP) {
@@ -554,6 +851,16 @@ fn draw_waveform_chart(f: &mut Frame, area: Rect, app: &App) {
     };
 
     let has_signal = peaks.iter().any(|(min, max)| *min != 0.0 || *max != 0.0);
+    let gain = if !app.scope_auto_gain {
+        app.scope_gain
+    } else if use_timeline {
+        let peak = peaks.iter().fold(0.0f32, |peak, (min, max)| {
+            peak.max(min.abs()).max(max.abs())
+        });
+        auto_scope_gain(peak)
+    } else {
+        auto_scope_gain(app.waveform_buffer.recent_peak())
+    };
 
     // Choose colors based on mode
     let (upper_color, lower_color) = if use_timeline {
@@ -571,7 +878,7 @@ fn draw_waveform_chart(f: &mut Frame, area: Rect, app: &App) {
             .enumerate()
             .map(|(i, (_min, max))| {
                 let x = i as f64;
-                let y = (*max * 1.5).clamp(-0.95, 0.95) as f64;
+                let y = (*max * gain).clamp(-0.95, 0.95) as f64;
                 (x, y)
             })
             .collect();
@@ -581,7 +888,7 @@ fn draw_waveform_chart(f: &mut Frame, area: Rect, app: &App) {
             .enumerate()
             .map(|(i, (min, _max))| {
                 let x = i as f64;
-                let y = (*min * 1.5).clamp(-0.95, 0.95) as f64;
+                let y = (*min * gain).clamp(-0.95, 0.95) as f64;
                 (x, y)
             })
             .collect();
@@ -658,6 +965,15 @@ fn draw_waveform_chart(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(chart, area);
 }
 
+/// Frame position and length, the length marked `~` when it's an estimate
+fn format_sample_position(position: SamplePosition) -> String {
+    let approximate = if position.approximate { "~" } else { "" };
+    format!(
+        "smp {} / {approximate}{}",
+        position.frame, position.total_frames
+    )
+}
+
 fn draw_progress_bar(f: &mut Frame, area: Rect, app: &App) {
     let progress = app.playback_position;
 
@@ -675,17 +991,25 @@ fn draw_progress_bar(f: &mut Frame, area: Rect, app: &App) {
             time_str.push_str(&format!(" [{sel_secs:.1}s]"));
         }
 
+        if app.show_samples
+            && let Some(position) = app.sample_position
+        {
+            time_str.push_str(&format!("  {}", format_sample_position(position)));
+        }
+
         time_str
     } else {
         "00:00 / 00:00".to_string()
     };
 
-    // Create layout for time and progress
+    // Create layout for time and progress; the time display grows to fit
+    // sample positions
+    let time_width = (time_info.chars().count() as u16 + 4).max(20);
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Min(10),    // Progress bar
-            Constraint::Length(20), // Time display (increased for selection info)
+            Constraint::Min(10),            // Progress bar
+            Constraint::Length(time_width), // Time display
         ])
         .split(area);
 
@@ -729,6 +1053,23 @@ fn draw_progress_with_marks(f: &mut Frame, area: Rect, app: &App) {
     });
     let bar_width = inner_area.width;
 
+    // Draw sampler loop points from the WAV smpl chunk, under the marks
+    if let Some((loop_start, loop_end)) = app.sampler_loop {
+        for position in [loop_start, loop_end] {
+            let loop_x = inner_area.x + (position * bar_width as f32) as u16;
+            if loop_x < inner_area.x + bar_width {
+                let marker = Paragraph::new("╎").style(Style::default().fg(Color::Yellow));
+                let marker_area = Rect {
+                    x: loop_x,
+                    y: inner_area.y,
+                    width: 1,
+                    height: 1,
+                };
+                f.render_widget(marker, marker_area);
+            }
+        }
+    }
+
     // Draw mark in
     if let Some(mark_in) = app.mark_in {
         let mark_x = inner_area.x + (mark_in * bar_width as f32) as u16;
@@ -803,7 +1144,7 @@ fn draw_integrated_browser(f: &mut Frame, app: &App) {
         .split(size);
 
     // Draw file browser with preview
-    draw_browser_content(f, chunks[0], &app.browser);
+    draw_browser_content(f, chunks[0], app);
 
     // Draw mini player
     draw_mini_player(f, chunks[1], app);
@@ -867,24 +1208,33 @@ fn draw_floating_search_bar(f: &mut Frame, area: Rect, browser: &super::browser:
     f.render_widget(search, search_area);
 }
 
-fn draw_browser_content(f: &mut Frame, area: Rect, browser: &super::browser::Browser) {
-    use super::browser::BrowserFocus;
+fn draw_browser_content(f: &mut Frame, area: Rect, app: &App) {
+    use super::browser::{BrowserFocus, BrowserGrouping, BrowserRow};
 
-    // Split horizontally for file list and preview
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(40), // File list
-            Constraint::Percentage(60), // Preview
-        ])
-        .split(area);
+    let browser = &app.browser;
+
+    let (list_area, preview_area) = browser_layout(area);
 
     // File list
     let filtered_items = browser.get_filtered_items();
-    let files: Vec<Line> = filtered_items
+    let rows = browser.rows();
+    let selected_row = rows
         .iter()
-        .enumerate()
-        .map(|(i, (item, _))| {
+        .position(|row| matches!(row, BrowserRow::File(i, _) if *i == browser.selected))
+        .unwrap_or(0);
+    let files: Vec<Line> = rows
+        .iter()
+        .map(|row| {
+            let (i, item) = match row {
+                BrowserRow::Header(label) => {
+                    return Line::from(label.clone()).style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+                BrowserRow::File(i, item) => (*i, item),
+            };
             let style = if i == browser.selected {
                 Style::default().bg(Color::Blue).fg(Color::White)
             } else {
@@ -892,27 +1242,33 @@ fn draw_browser_content(f: &mut Frame, area: Rect, browser: &super::browser::Bro
             };
 
             let prefix = if i == browser.selected { "> " } else { "  " };
-            let filename = item
-                .audio_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown");
-
-            // Include project name if available
-            let display_text = if let Some(ref project) = item.metadata.project {
-                format!("{prefix}{filename} [{project}]")
+            let indent = if browser.grouping == BrowserGrouping::Flat {
+                ""
             } else {
-                format!("{prefix}{filename}")
+                "  "
+            };
+            let filename = browser.display_name(&item.audio_path);
+
+            // Include project name if available and not already the group header
+            let display_text = match &item.metadata.project {
+                Some(project) if browser.grouping != BrowserGrouping::Project => {
+                    format!("{indent}{prefix}{filename} [{project}]")
+                }
+                _ => format!("{indent}{prefix}{filename}"),
             };
 
             Line::from(display_text).style(style)
         })
         .collect();
 
-    let title = if browser.search_visible && browser.focus == BrowserFocus::Search {
-        "Files - Press Esc to return"
+    let hint = if browser.search_visible && browser.focus == BrowserFocus::Search {
+        "Press Esc to return"
     } else {
-        "Files - j/k to navigate, Enter to select, / to search"
+        "j/k to navigate, Enter to select, / to search"
+    };
+    let title = match browser.grouping.label() {
+        Some(grouping) => format!("Files {grouping} - {hint}"),
+        None => format!("Files - {hint}"),
     };
 
     let border_style = if browser.focus == BrowserFocus::Files {
@@ -921,6 +1277,8 @@ fn draw_browser_content(f: &mut Frame, area: Rect, browser: &super::browser::Bro
         Style::default().fg(Color::DarkGray)
     };
 
+    // Keep the selection on screen when the list is short, as when stacked
+    let scroll_lead = BROWSER_SCROLL_LEAD.min(list_area.height.saturating_sub(3) as usize);
     let file_list = Paragraph::new(files)
         .block(
             Block::default()
@@ -928,14 +1286,45 @@ fn draw_browser_content(f: &mut Frame, area: Rect, browser: &super::browser::Bro
                 .title(title)
                 .border_style(border_style),
         )
-        .scroll((browser.selected.saturating_sub(10) as u16, 0));
+        .scroll((selected_row.saturating_sub(scroll_lead) as u16, 0));
+
+    f.render_widget(file_list, list_area);
 
-    f.render_widget(file_list, chunks[0]);
+    let Some(preview_area) = preview_area else {
+        return;
+    };
+
+    // Preview, with the selected file's waveform along the bottom when available.
+    // Selecting a file loads it, so the player's timeline waveform is reused.
+    let selected_is_loaded = browser.selected < filtered_items.len()
+        && app.current_file.as_deref()
+            == Some(
+                filtered_items[browser.selected]
+                    .0
+                    .audio_path
+                    .to_string_lossy()
+                    .as_ref(),
+            );
+    let show_waveform = browser.show_waveform
+        && selected_is_loaded
+        && (app.timeline_waveform.is_some() || app.waveform_progress.is_some())
+        && preview_area.height >= PREVIEW_WAVEFORM_HEIGHT * 2;
+
+    let (preview_area, waveform_area) = if show_waveform {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(PREVIEW_WAVEFORM_HEIGHT),
+            ])
+            .split(preview_area);
+        (split[0], Some(split[1]))
+    } else {
+        (preview_area, None)
+    };
 
-    // Preview
     // Calculate how much text we can actually display in the preview area
     // Account for borders (2 lines) and wrapping
-    let preview_area = chunks[1];
     let usable_width = preview_area.width.saturating_sub(2) as usize; // Subtract border width
     let usable_height = preview_area.height.saturating_sub(2) as usize; // Subtract border height
     let max_chars = usable_width * usable_height; // Maximum characters that can fit
@@ -976,7 +1365,112 @@ fn draw_browser_content(f: &mut Frame, area: Rect, browser: &super::browser::Bro
         .block(Block::default().borders(Borders::ALL).title(preview_title))
         .wrap(ratatui::widgets::Wrap { trim: true });
 
-    f.render_widget(preview, chunks[1]);
+    f.render_widget(preview, preview_area);
+
+    if let Some(waveform_area) = waveform_area {
+        draw_preview_waveform(f, waveform_area, app);
+    }
+}
+
+/// Areas for the browser's file list and preview. Wide terminals put them
+/// side by side, 40/60 with the list capped at `MAX_BROWSER_LIST_WIDTH`;
+/// narrow ones stack the list over the preview, or show only the list when
+/// the terminal is also short.
+fn browser_layout(area: Rect) -> (Rect, Option<Rect>) {
+    if area.width < MIN_WIDTH_FOR_SIDE_PREVIEW {
+        if area.height < MIN_HEIGHT_FOR_STACKED_PREVIEW {
+            return (area, None);
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50), // File list
+                Constraint::Percentage(50), // Preview
+            ])
+            .split(area);
+        return (chunks[0], Some(chunks[1]));
+    }
+
+    let list_width = (area.width.saturating_mul(2) / 5).min(MAX_BROWSER_LIST_WIDTH);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(list_width), // File list
+            Constraint::Min(0),             // Preview
+        ])
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Draw a compact timeline waveform of the loaded file inside the browser preview
+fn draw_preview_waveform(f: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title("Waveform");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if let Some(ref progress) = app.waveform_progress {
+        let text = Paragraph::new(format!(
+            "Calculating waveform... {:.0}%",
+            progress.percentage
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+        f.render_widget(text, inner);
+        return;
+    }
+
+    let Some(ref timeline) = app.timeline_waveform else {
+        return;
+    };
+
+    let peaks = timeline.get_display_peaks(inner.width as usize);
+    let upper: Vec<(f64, f64)> = peaks
+        .iter()
+        .enumerate()
+        .map(|(i, (_min, max))| (i as f64, max.clamp(-1.0, 1.0) as f64))
+        .collect();
+    let lower: Vec<(f64, f64)> = peaks
+        .iter()
+        .enumerate()
+        .map(|(i, (min, _max))| (i as f64, min.clamp(-1.0, 1.0) as f64))
+        .collect();
+    let position_x = (app.playback_position * inner.width as f32) as f64;
+    let position = [(position_x, -1.0), (position_x, 1.0)];
+
+    let datasets = vec![
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Rgb(100, 150, 255)))
+            .data(&upper),
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Rgb(60, 100, 200)))
+            .data(&lower),
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Rgb(255, 200, 0)))
+            .data(&position),
+    ];
+
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, inner.width as f64])
+                .labels::<Vec<Span>>(vec![]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([-1.0, 1.0])
+                .labels::<Vec<Span>>(vec![]),
+        );
+
+    f.render_widget(chart, inner);
 }
 
 fn draw_mini_player(f: &mut Frame, area: Rect, app: &App) {
@@ -1051,17 +1545,15 @@ fn draw_mini_player(f: &mut Frame, area: Rect, app: &App) {
 fn draw_browser_help(f: &mut Frame, area: Rect, browser: &super::browser::Browser) {
     use super::browser::BrowserFocus;
 
-    let help_text = if browser.search_visible {
-        match browser.focus {
-            BrowserFocus::Search => {
-                "Type to search | Try: 'title: my song' or 'tag: ambient' | Enter/Esc: Hide search | ←→: Seek"
-            }
-            BrowserFocus::Files => {
-                "j/k or ↑↓: Navigate | Enter: Select | /: Search | Esc: Back | Space: Play/Pause | h/l or ←→: Seek"
-            }
-        }
+    let help_text = if browser.search_visible && browser.focus == BrowserFocus::Search {
+        "Type to search | Try: 'title: my song' or 'tag: ambient' | Enter/Esc: Hide search | ←→: Seek"
+            .to_string()
     } else {
-        "j/k or ↑↓: Navigate | Enter: Select | /: Search | Esc: Back | Space: Play/Pause | h/l or ←→: Seek"
+        format!(
+            "{} | {}: Help",
+            keymap::hint_line(keymap::BROWSER_HINTS),
+            keymap::HELP.key
+        )
     };
 
     let help_style = Style::default().fg(Color::DarkGray);
@@ -1076,6 +1568,42 @@ fn draw_browser_help(f: &mut Frame, area: Rect, browser: &super::browser::Browse
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_sample_position() {
+        let mut position = SamplePosition {
+            frame: 529_200,
+            total_frames: 7_938_000,
+            approximate: false,
+        };
+        assert_eq!(format_sample_position(position), "smp 529200 / 7938000");
+        position.approximate = true;
+        assert_eq!(format_sample_position(position), "smp 529200 / ~7938000");
+    }
+
+    #[test]
+    fn test_browser_layout() {
+        // Side by side, 40/60
+        let (list, preview) = browser_layout(Rect::new(0, 0, 100, 30));
+        let preview = preview.unwrap();
+        assert_eq!((list.width, preview.width), (40, 60));
+        assert_eq!(preview.x, 40);
+
+        // Very wide: the list stops growing and the preview takes the rest
+        let (list, preview) = browser_layout(Rect::new(0, 0, 300, 30));
+        assert_eq!(list.width, MAX_BROWSER_LIST_WIDTH);
+        assert_eq!(preview.unwrap().width, 300 - MAX_BROWSER_LIST_WIDTH);
+
+        // Narrow: the preview goes under the list
+        let (list, preview) = browser_layout(Rect::new(0, 0, 60, 30));
+        let preview = preview.unwrap();
+        assert_eq!((list.width, preview.width), (60, 60));
+        assert_eq!((list.height, preview.height, preview.y), (15, 15, 15));
+
+        // Narrow and short: only the list
+        let area = Rect::new(0, 0, 60, 12);
+        assert_eq!(browser_layout(area), (area, None));
+    }
+
     #[test]
     fn test_format_time() {
         assert_eq!(format_time(0), "00:00");
@@ -1093,6 +1621,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_sample_rate() {
+        assert_eq!(format_sample_rate(44100), "44.1 kHz");
+        assert_eq!(format_sample_rate(48000), "48 kHz");
+        assert_eq!(format_sample_rate(22050), "22.05 kHz");
+        assert_eq!(format_sample_rate(11025), "11.025 kHz");
+        assert_eq!(format_sample_rate(192000), "192 kHz");
+    }
+
+    #[test]
+    fn test_format_audio_info() {
+        let info = AudioInfo {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: Some(24),
+        };
+        assert_eq!(format_audio_info(&info), "48 kHz/24-bit stereo");
+        let mix = AudioInfo {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: None,
+        };
+        assert_eq!(format_audio_info(&mix), "44.1 kHz mono");
+        let surround = AudioInfo {
+            channels: 6,
+            sample_rate: 96000,
+            bits_per_sample: Some(32),
+        };
+        assert_eq!(format_audio_info(&surround), "96 kHz/32-bit 6ch");
+    }
+
+    #[test]
+    fn test_selection_status() {
+        assert_eq!(selection_status(None, None, 120.0), None);
+        assert_eq!(
+            selection_status(Some(0.25), None, 120.0).as_deref(),
+            Some("in 00:30")
+        );
+        assert_eq!(
+            selection_status(None, Some(0.5), 120.0).as_deref(),
+            Some("out 01:00")
+        );
+        // Marks set out of order still read start to end
+        assert_eq!(
+            selection_status(Some(0.5), Some(0.25), 120.0).as_deref(),
+            Some("sel 00:30–01:00 (30.0s)")
+        );
+    }
+
+    #[test]
+    fn test_with_sample_rates() {
+        let text = |line: Line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect::<String>()
+        };
+        assert_eq!(
+            text(with_sample_rates(Line::from("Ready"), 48000, 48000)),
+            "Ready  48 kHz"
+        );
+        assert_eq!(
+            text(with_sample_rates(Line::from("Ready"), 96000, 48000)),
+            "Ready  96 kHz → 48 kHz resampled"
+        );
+    }
+
     #[test]
     fn test_get_led_char() {
         assert_eq!(get_led_char(0.0), "○");
@@ -1103,6 +1698,14 @@ mod tests {
         assert_eq!(get_led_char(1.0), "●");
     }
 
+    #[test]
+    fn test_level_trail_bar() {
+        assert_eq!(level_trail_bar(0.0), "▁");
+        assert_eq!(level_trail_bar(0.5), "▅");
+        assert_eq!(level_trail_bar(1.0), "█");
+        assert_eq!(level_trail_bar(1.5), "█");
+    }
+
     #[test]
     fn test_get_led_color_left_channel() {
         // Test left channel colors
@@ -1121,6 +1724,39 @@ mod tests {
         assert_eq!(get_led_color(0.95, false), Color::Rgb(255, 100, 100));
     }
 
+    #[test]
+    fn test_auto_scope_gain() {
+        assert_eq!(auto_scope_gain(0.9), 1.0);
+        // A hot master is scaled down, a quiet recording up
+        assert_eq!(auto_scope_gain(1.8), 0.5);
+        assert!((auto_scope_gain(0.03) - 30.0).abs() < 1e-3);
+        // Near-silence stops at the largest gain scope_gain allows
+        assert_eq!(auto_scope_gain(0.001), SCOPE_GAIN_RANGE.1);
+        assert_eq!(auto_scope_gain(0.0), SCOPE_GAIN_RANGE.1);
+    }
+
+    #[test]
+    fn test_peak_readout() {
+        assert_eq!(peak_readout(0.0, false), None);
+        assert_eq!(
+            peak_readout(0.5, false),
+            Some(("pk -6.0 dBFS".to_string(), false))
+        );
+        assert_eq!(
+            peak_readout(1.0, false),
+            Some(("pk 0.0 dBFS".to_string(), true))
+        );
+        // Under full scale but over the -1 dBTP delivery ceiling
+        assert_eq!(
+            peak_readout(0.95, true),
+            Some(("pk -0.4 dBTP".to_string(), true))
+        );
+        assert_eq!(
+            peak_readout(0.5, true),
+            Some(("pk -6.0 dBTP".to_string(), false))
+        );
+    }
+
     #[test]
     fn test_led_clipping_color() {
         // Both channels should show red when clipping
@@ -96,6 +96,11 @@ impl WaveformBuffer {
             .collect()
     }
 
+    /// Highest absolute level among the buffered oscilloscope samples
+    pub fn recent_peak(&self) -> f32 {
+        self.samples.iter().fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
     /// Get min/max pairs for peak-to-peak display without trigger stabilization.
     /// This provides better waveform visualization by showing the envelope.
     /// Use `get_triggered_display_peaks` for a stable oscilloscope view.
@@ -208,6 +213,18 @@ mod tests {
         assert_eq!(samples, vec![2.0, 3.0, 4.0, 5.0, 6.0]);
     }
 
+    #[test]
+    fn test_recent_peak() {
+        let mut buffer = WaveformBuffer::new(3);
+        assert_eq!(buffer.recent_peak(), 0.0);
+        buffer.push_samples(&[0.2, -0.8, 0.1]);
+        assert_eq!(buffer.recent_peak(), 0.8);
+
+        // Only what's still buffered counts
+        buffer.push_samples(&[0.1, 0.3, -0.05]);
+        assert_eq!(buffer.recent_peak(), 0.3);
+    }
+
     #[test]
     fn test_find_trigger_offset_rising_edge() {
         let mut buffer = WaveformBuffer::new(100);
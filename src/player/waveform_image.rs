@@ -0,0 +1,106 @@
+//! Waveform thumbnails of saved selections.
+//!
+//! Renders the min/max peaks of a WAV file to a small SVG for documentation
+//! or a library catalog, written next to the audio as `<name>.svg`. The peaks
+//! come from the same downsampling as the player's timeline waveform.
+
+use super::timeline_waveform::TimelineWaveform;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const BACKGROUND_COLOR: &str = "#101010";
+const CENTER_LINE_COLOR: &str = "#003c1e";
+const WAVEFORM_COLOR: &str = "#00c060";
+
+/// Render `wav_path`'s waveform to `<stem>.svg` beside it and return the path
+pub fn write_waveform_svg(
+    wav_path: &Path,
+    width: u32,
+    height: u32,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let waveform = TimelineWaveform::from_wav_file_with_progress(wav_path, width as usize, None)?;
+    let svg = render_svg(&waveform.get_display_peaks(width as usize), width, height);
+    let svg_path = wav_path.with_extension("svg");
+    std::fs::write(&svg_path, svg)?;
+    Ok(svg_path)
+}
+
+/// An SVG of min/max peak pairs (each -1.0 to 1.0) spread across `width`.
+/// The outline traces the maxima left to right and the minima back, so the
+/// whole waveform is a single filled path.
+pub fn render_svg(peaks: &[(f32, f32)], width: u32, height: u32) -> String {
+    let mid = height as f32 / 2.0;
+    let y = |sample: f32| mid - sample.clamp(-1.0, 1.0) * mid;
+    let step = width as f32 / peaks.len().max(1) as f32;
+    let x = |index: usize| (index as f32 + 0.5) * step;
+
+    let mut outline = String::new();
+    for (index, (_, max)) in peaks.iter().enumerate() {
+        let command = if index == 0 { 'M' } else { 'L' };
+        let _ = write!(outline, "{command}{:.1},{:.1} ", x(index), y(*max));
+    }
+    for (index, (min, _)) in peaks.iter().enumerate().rev() {
+        let _ = write!(outline, "L{:.1},{:.1} ", x(index), y(*min));
+    }
+    outline.push('Z');
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+    let _ = writeln!(
+        svg,
+        "  <rect width=\"{width}\" height=\"{height}\" fill=\"{BACKGROUND_COLOR}\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "  <line x1=\"0\" y1=\"{mid:.1}\" x2=\"{width}\" y2=\"{mid:.1}\" stroke=\"{CENTER_LINE_COLOR}\"/>"
+    );
+    if !peaks.is_empty() {
+        // The stroke keeps silent stretches visible as a thin line
+        let _ = writeln!(
+            svg,
+            "  <path d=\"{outline}\" fill=\"{WAVEFORM_COLOR}\" stroke=\"{WAVEFORM_COLOR}\" stroke-width=\"1\"/>"
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{pcm16, write_wav};
+
+    #[test]
+    fn test_render_svg_traces_peaks() {
+        let svg = render_svg(&[(-0.5, 0.5), (-1.0, 1.0)], 100, 40);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\""));
+        assert!(svg.contains("viewBox=\"0 0 100 40\""));
+        // Maxima left to right, then minima right to left
+        assert!(svg.contains("d=\"M25.0,10.0 L75.0,0.0 L75.0,40.0 L25.0,30.0 Z\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        // No audio still gives a valid image with just the center line
+        let empty = render_svg(&[], 100, 40);
+        assert!(!empty.contains("<path"));
+        assert!(empty.contains("<line"));
+    }
+
+    #[test]
+    fn test_write_waveform_svg_beside_audio() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wav = temp_dir.path().join("take_edit.wav");
+        let samples = (0..8000).flat_map(|i| {
+            let sample = ((i as f32 / 20.0).sin() * 16000.0) as i16;
+            [sample, sample]
+        });
+        write_wav(&wav, pcm16(2, 8000), samples);
+
+        let svg_path = write_waveform_svg(&wav, 64, 32).unwrap();
+        assert_eq!(svg_path, temp_dir.path().join("take_edit.svg"));
+        let svg = std::fs::read_to_string(svg_path).unwrap();
+        assert_eq!(svg.matches(" L").count(), 2 * 64 - 1);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::Config;
+use crate::templates::yaml_quote;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
@@ -6,7 +6,7 @@ use std::path::Path;
 pub fn create_project_structure(
     project_path: &Path,
     folders: &[String],
-    config: &Config,
+    daw_folders: &[String],
 ) -> Result<(), Box<dyn Error>> {
     // Create main project directory
     fs::create_dir_all(project_path)?;
@@ -28,7 +28,7 @@ pub fn create_project_structure(
 
     // Create project-specific subdirectories
     let project_folder = project_path.join("project");
-    for daw in &config.daw_folders {
+    for daw in daw_folders {
         fs::create_dir_all(project_folder.join(daw))?;
     }
 
@@ -68,6 +68,7 @@ pub fn create_project_metadata(
     project_name: &str,
     artist: &str,
     display_name: Option<&str>,
+    status: &str,
 ) -> Result<(), Box<dyn Error>> {
     let metadata_path = project_path.join("README.md");
 
@@ -76,10 +77,10 @@ pub fn create_project_metadata(
 
     let content = format!(
         r#"---
-name: "{}"
-artist: "{}"
+name: {}
+artist: {}
 created: "{}"
-status: "active"
+status: {}
 tags: []
 art: []
 # Example art entries:
@@ -106,9 +107,10 @@ art: []
 
 [Document any visual inspiration, artwork, or graphics associated with this project]
 "#,
-        display_title,
-        artist,
+        yaml_quote(display_title),
+        yaml_quote(artist),
         chrono::Local::now().format("%Y-%m-%d"),
+        yaml_quote(status),
         display_title
     );
 
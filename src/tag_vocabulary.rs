@@ -0,0 +1,271 @@
+//! Project tag vocabulary used for tag autocomplete.
+//!
+//! Tags are counted across a project's sidecars so the player's inline editor
+//! and `zim add tag` can suggest existing tags instead of free-typed
+//! near-duplicates. The player builds the vocabulary once per session and
+//! adjusts it as tags are saved.
+
+use crate::cli::lint::find_sidecars;
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use zim_studio::utils::sidecar::split_frontmatter;
+
+#[derive(Debug, Clone, Default)]
+pub struct TagVocabulary {
+    counts: Vec<(String, usize)>, // Most used first, then alphabetical
+    files: usize,                 // Files counted, tagged or not
+}
+
+impl TagVocabulary {
+    /// Count tags across files, given each file's tags. A tag listed twice
+    /// for one file counts once.
+    pub fn from_tag_lists<'a>(lists: impl IntoIterator<Item = &'a [String]>) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut files = 0;
+        for tags in lists {
+            files += 1;
+            let unique: HashSet<&String> = tags.iter().collect();
+            for tag in unique {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let mut vocabulary = Self {
+            counts: counts.into_iter().collect(),
+            files,
+        };
+        vocabulary.sort();
+        vocabulary
+    }
+
+    /// Scan a directory tree for sidecars and count their tags
+    pub fn scan(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let tags: Vec<Vec<String>> = find_sidecars(dir)?
+            .iter()
+            .map(|sidecar| {
+                fs::read_to_string(sidecar)
+                    .map(|content| read_tags(&content))
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(Self::from_tag_lists(tags.iter().map(Vec::as_slice)))
+    }
+
+    /// How many files were counted
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    fn sort(&mut self) {
+        self.counts.retain(|(_, count)| *count > 0);
+        self.counts
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    }
+
+    /// Existing tags starting with `prefix` (case-insensitive), skipping any in `exclude`
+    pub fn suggestions(&self, prefix: &str, exclude: &[String]) -> Vec<&(String, usize)> {
+        let prefix = prefix.to_lowercase();
+        self.counts
+            .iter()
+            .filter(|(tag, _)| tag.to_lowercase().starts_with(&prefix))
+            .filter(|(tag, _)| !exclude.contains(tag))
+            .collect()
+    }
+
+    /// Existing tags that `tag` is likely a near-duplicate of: the same but
+    /// for case, or one a prefix of the other ("drum" and "drums")
+    pub fn similar(&self, tag: &str) -> Vec<&(String, usize)> {
+        let lower = tag.to_lowercase();
+        self.counts
+            .iter()
+            .filter(|(existing, _)| existing != tag)
+            .filter(|(existing, _)| {
+                let existing = existing.to_lowercase();
+                existing.starts_with(&lower) || lower.starts_with(&existing)
+            })
+            .collect()
+    }
+
+    /// Whether any file already carries `tag`
+    pub fn contains(&self, tag: &str) -> bool {
+        self.counts.iter().any(|(existing, _)| existing == tag)
+    }
+
+    /// Adjust counts after a file's tags changed from `old` to `new`
+    pub fn record_change(&mut self, old: &[String], new: &[String]) {
+        for tag in old.iter().filter(|t| !new.contains(t)) {
+            if let Some(entry) = self.counts.iter_mut().find(|(t, _)| t == tag) {
+                entry.1 = entry.1.saturating_sub(1);
+            }
+        }
+        for tag in new.iter().filter(|t| !old.contains(t)) {
+            match self.counts.iter_mut().find(|(t, _)| t == tag) {
+                Some(entry) => entry.1 += 1,
+                None => self.counts.push((tag.clone(), 1)),
+            }
+        }
+        self.sort();
+    }
+}
+
+/// The tags of one sidecar: a list, or a comma-separated string as `zim lint
+/// --fix` would split it. Sidecars without readable frontmatter have none.
+fn read_tags(content: &str) -> Vec<String> {
+    let Some((yaml, _)) = split_frontmatter(content) else {
+        return Vec::new();
+    };
+    let Ok(fields) = serde_yaml::from_str::<HashMap<String, Value>>(yaml) else {
+        return Vec::new();
+    };
+    let mut tags: Vec<String> = match fields.get("tags") {
+        Some(Value::Sequence(tags)) => tags
+            .iter()
+            .filter_map(|tag| match tag {
+                Value::String(tag) => Some(tag.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::String(text)) => text.split(',').map(|tag| tag.trim().to_string()).collect(),
+        _ => Vec::new(),
+    };
+    tags.retain(|tag| !tag.is_empty());
+    // A tag listed twice in one sidecar still counts once for that file
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn vocabulary(files: &[&[&str]]) -> TagVocabulary {
+        let lists: Vec<Vec<String>> = files
+            .iter()
+            .map(|tags| tags.iter().map(|t| t.to_string()).collect())
+            .collect();
+        TagVocabulary::from_tag_lists(lists.iter().map(Vec::as_slice))
+    }
+
+    #[test]
+    fn test_from_tag_lists_counts_and_orders() {
+        let vocabulary = vocabulary(&[
+            &["drums", "808", "drums"],
+            &["drums", "ambient"],
+            &["drums"],
+        ]);
+        assert_eq!(
+            vocabulary.counts,
+            [
+                ("drums".to_string(), 3),
+                ("808".to_string(), 1),
+                ("ambient".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let vocabulary = vocabulary(&[&["Drums", "drone"], &["drone", "ambient"]]);
+
+        let tags: Vec<&str> = vocabulary
+            .suggestions("dr", &[])
+            .iter()
+            .map(|(t, _)| t.as_str())
+            .collect();
+        assert_eq!(tags, vec!["drone", "Drums"]);
+
+        let tags: Vec<&str> = vocabulary
+            .suggestions("d", &["drone".to_string()])
+            .iter()
+            .map(|(t, _)| t.as_str())
+            .collect();
+        assert_eq!(tags, vec!["Drums"]);
+
+        assert_eq!(vocabulary.suggestions("", &[]).len(), 3);
+        assert!(vocabulary.suggestions("xyz", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_similar() {
+        let vocabulary = vocabulary(&[&["drums", "Pads", "drone"]]);
+        let similar = |tag| -> Vec<&str> {
+            vocabulary
+                .similar(tag)
+                .iter()
+                .map(|(t, _)| t.as_str())
+                .collect()
+        };
+        assert_eq!(similar("drum"), ["drums"]);
+        assert_eq!(similar("drumsticks"), ["drums"]);
+        assert_eq!(similar("pads"), ["Pads"]);
+        assert!(similar("drums").is_empty());
+        assert!(similar("bass").is_empty());
+        assert!(vocabulary.contains("drone"));
+        assert!(!vocabulary.contains("Drone"));
+    }
+
+    #[test]
+    fn test_record_change() {
+        let mut vocabulary = vocabulary(&[&["drums", "808"], &["drums"]]);
+
+        vocabulary.record_change(
+            &["drums".to_string(), "808".to_string()],
+            &["drums".to_string(), "kick".to_string()],
+        );
+        assert_eq!(
+            vocabulary.counts,
+            [("drums".to_string(), 2), ("kick".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_read_tags() {
+        assert_eq!(
+            read_tags("---\ntags: [\"drums\", \"loop\", \"drums\", 808]\n---\n"),
+            ["808", "drums", "loop"]
+        );
+        assert_eq!(
+            read_tags("---\ntags: \"ambient, pads,\"\n---\n"),
+            ["ambient", "pads"]
+        );
+        assert!(read_tags("---\ntags: []\n---\n").is_empty());
+        assert!(read_tags("---\ntitle: [unclosed\n---\n").is_empty());
+        assert!(read_tags("# No frontmatter\n").is_empty());
+    }
+
+    #[test]
+    fn test_scan_reads_sidecars() {
+        // Hidden directories are skipped, so scan a visible subdirectory
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(
+            project.join("a.wav.md"),
+            "---\ntitle: \"a\"\ntags: [\"pad\", \"warm\"]\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join("b.wav.md"),
+            "---\ntitle: b\ntags:\n- pad\n- '808'\n---\n",
+        )
+        .unwrap();
+
+        let vocabulary = TagVocabulary::scan(&project).unwrap();
+        assert_eq!(vocabulary.files(), 2);
+        assert_eq!(
+            vocabulary.counts,
+            [
+                ("pad".to_string(), 2),
+                ("808".to_string(), 1),
+                ("warm".to_string(), 1)
+            ]
+        );
+    }
+}
@@ -1,3 +1,45 @@
+use zim_studio::utils::sidecar::format_duration;
+pub use zim_studio::utils::sidecar::yaml_quote;
+
+/// An entry for the sidecar `art` list
+pub struct ArtEntry<'a> {
+    pub path: &'a str,
+    pub description: &'a str,
+    pub purpose: &'a str,
+}
+
+/// Format tags as a YAML flow sequence
+fn format_tags(tags: &[String]) -> String {
+    let quoted: Vec<String> = tags.iter().map(|tag| yaml_quote(tag)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// Format art entries as the sidecar's `art` field
+fn format_art_field(art: &[ArtEntry]) -> String {
+    if art.is_empty() {
+        return "art: []".to_string();
+    }
+    let entries: String = art
+        .iter()
+        .map(|entry| {
+            format!(
+                "\n  - path: {}\n    description: {}\n    purpose: {}",
+                yaml_quote(entry.path),
+                yaml_quote(entry.description),
+                yaml_quote(entry.purpose)
+            )
+        })
+        .collect();
+    format!("art:{entries}")
+}
+
+/// The sidecar's `artist` line, left out when the project names no artist
+fn format_artist_line(artist: Option<&str>) -> String {
+    artist
+        .map(|artist| format!("artist: {}\n", yaml_quote(artist)))
+        .unwrap_or_default()
+}
+
 pub struct SidecarMetadata<'a> {
     pub file_name: &'a str,
     pub file_path: &'a str,
@@ -7,11 +49,13 @@ pub struct SidecarMetadata<'a> {
     pub sample_rate: u32,
     pub channels: u16,
     pub bits_per_sample: u16,
-    pub duration_seconds: Option<f64>,
+    pub duration: Option<f64>,
     pub file_size: u64,
     pub modified: Option<&'a str>,
     pub project: Option<&'a str>,
+    pub artist: Option<&'a str>,
     pub uuid: Option<&'a str>,
+    pub art: &'a [ArtEntry<'a>],
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -24,36 +68,37 @@ pub fn generate_minimal_sidecar_with_fs_metadata(
     file_size: u64,
     modified: Option<&str>,
     project: Option<&str>,
+    artist: Option<&str>,
     uuid: Option<&str>,
+    art: &[ArtEntry],
 ) -> String {
-    let modified_str = modified.unwrap_or("unknown");
-    let project_str = project.unwrap_or("unknown");
-
-    // Format tags as YAML array
-    let tags_str = if tags.is_empty() {
-        "[]".to_string()
-    } else {
-        format!("[\"{}\"]", tags.join("\", \""))
-    };
+    let modified_str = yaml_quote(modified.unwrap_or("unknown"));
+    let project_str = yaml_quote(project.unwrap_or("unknown"));
+    let tags_str = format_tags(tags);
+    let artist_line = format_artist_line(artist);
 
     // Include UUID if available
     let uuid_line = if let Some(id) = uuid {
-        format!("uuid: \"{id}\"\n")
+        format!("uuid: {}\n", yaml_quote(id))
     } else {
         String::new()
     };
 
+    let art_field = format_art_field(art);
+    let (file_name, file_path) = (yaml_quote(file_name), yaml_quote(file_path));
+    let (title, description) = (yaml_quote(title), yaml_quote(description));
+
     format!(
         r#"---
-file: "{file_name}"
-path: "{file_path}"
-project: "{project_str}"
-{uuid_line}title: "{title}"
-description: "{description}"
+file: {file_name}
+path: {file_path}
+project: {project_str}
+{artist_line}{uuid_line}title: {title}
+description: {description}
 file_size: {file_size}
-modified: "{modified_str}"
+modified: {modified_str}
 tags: {tags_str}
-art: []
+{art_field}
 ---
 
 # Notes
@@ -64,53 +109,47 @@ art: []
 }
 
 pub fn generate_audio_sidecar_with_metadata(metadata: &SidecarMetadata) -> String {
-    let duration_str = metadata
-        .duration_seconds
-        .map(|d| format!("{d:.2}"))
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let modified_str = metadata.modified.unwrap_or("unknown");
-    let project_str = metadata.project.unwrap_or("unknown");
+    let duration_str = format_duration(metadata.duration);
 
-    // Format tags as YAML array
-    let tags_str = if metadata.tags.is_empty() {
-        "[]".to_string()
-    } else {
-        format!("[\"{}\"]", metadata.tags.join("\", \""))
-    };
+    let modified_str = yaml_quote(metadata.modified.unwrap_or("unknown"));
+    let project_str = yaml_quote(metadata.project.unwrap_or("unknown"));
+    let tags_str = format_tags(metadata.tags);
+    let artist_line = format_artist_line(metadata.artist);
 
     // Include UUID if available
     let uuid_line = if let Some(id) = metadata.uuid {
-        format!("uuid: \"{id}\"\n")
+        format!("uuid: {}\n", yaml_quote(id))
     } else {
         String::new()
     };
 
+    let art_field = format_art_field(metadata.art);
+
     format!(
         r#"---
-file: "{}"
-path: "{}"
-project: "{project_str}"
-{uuid_line}title: "{}"
-description: "{}"
+file: {}
+path: {}
+project: {project_str}
+{artist_line}{uuid_line}title: {}
+description: {}
 duration: {duration_str}
 sample_rate: {}
 channels: {}
 bit_depth: {}
 file_size: {}
-modified: "{modified_str}"
+modified: {modified_str}
 tags: {tags_str}
-art: []
+{art_field}
 ---
 
 # Notes
 
 [Add notes about this file here]
 "#,
-        metadata.file_name,
-        metadata.file_path,
-        metadata.title,
-        metadata.description,
+        yaml_quote(metadata.file_name),
+        yaml_quote(metadata.file_path),
+        yaml_quote(metadata.title),
+        yaml_quote(metadata.description),
         metadata.sample_rate,
         metadata.channels,
         metadata.bits_per_sample,
@@ -133,12 +172,14 @@ mod tests {
             1234567,
             Some("2024-01-15 10:30:00 UTC"),
             Some("my-project"),
+            Some("Someone"),
             None, // No UUID for this test
+            &[],
         );
 
         assert!(content.contains("file: \"test.mp3\""));
         assert!(content.contains("path: \"/path/to/test.mp3\""));
-        assert!(content.contains("project: \"my-project\""));
+        assert!(content.contains("project: \"my-project\"\nartist: \"Someone\"\n"));
         assert!(content.contains("title: \"test\""));
         assert!(content.contains("description: \"A source for my-project\""));
         assert!(content.contains("tags: [\"source\"]"));
@@ -159,11 +200,14 @@ mod tests {
             999,
             None,
             None,
+            None,
             None, // No UUID for this test
+            &[],
         );
 
         assert!(content.contains("modified: \"unknown\""));
         assert!(content.contains("project: \"unknown\""));
+        assert!(!content.contains("artist:"));
     }
 
     #[test]
@@ -177,11 +221,13 @@ mod tests {
             sample_rate: 44100,
             channels: 2,
             bits_per_sample: 16,
-            duration_seconds: Some(123.45),
+            duration: Some(123.45),
             file_size: 5432100,
             modified: Some("2024-01-15 10:30:00 UTC"),
             project: Some("awesome-project"),
+            artist: Some("Someone"),
             uuid: Some("test-uuid-12345"),
+            art: &[],
         };
 
         let content = generate_audio_sidecar_with_metadata(&metadata);
@@ -189,6 +235,7 @@ mod tests {
         assert!(content.contains("file: \"audio.wav\""));
         assert!(content.contains("path: \"/music/audio.wav\""));
         assert!(content.contains("project: \"awesome-project\""));
+        assert!(content.contains("artist: \"Someone\""));
         assert!(content.contains("uuid: \"test-uuid-12345\""));
         assert!(content.contains("title: \"audio\""));
         assert!(content.contains("description: \"A mix for awesome-project\""));
@@ -212,20 +259,61 @@ mod tests {
             sample_rate: 48000,
             channels: 1,
             bits_per_sample: 24,
-            duration_seconds: None,
+            duration: None,
             file_size: 1000000,
             modified: None,
             project: None,
+            artist: None,
             uuid: None,
+            art: &[ArtEntry {
+                path: "audio.flac.cover.jpg",
+                description: "Embedded cover art",
+                purpose: "cover_art",
+            }],
         };
 
         let content = generate_audio_sidecar_with_metadata(&metadata);
 
         assert!(content.contains("duration: unknown"));
+        assert!(content.contains(
+            "art:\n  - path: \"audio.flac.cover.jpg\"\n    description: \"Embedded cover art\"\n    purpose: \"cover_art\"\n---"
+        ));
         assert!(content.contains("modified: \"unknown\""));
         assert!(content.contains("project: \"unknown\""));
     }
 
+    #[test]
+    fn test_values_are_escaped() {
+        let metadata = SidecarMetadata {
+            file_name: "a \"b\".wav",
+            file_path: "C:\\music\\a.wav",
+            title: "a",
+            description: "line one\nline two",
+            tags: &["12\" Remix".to_string()],
+            sample_rate: 44100,
+            channels: 2,
+            bits_per_sample: 16,
+            duration: None,
+            file_size: 1,
+            modified: None,
+            project: Some("12\" Remix"),
+            artist: Some("Back\\slash"),
+            uuid: None,
+            art: &[],
+        };
+        let content = generate_audio_sidecar_with_metadata(&metadata);
+        let (yaml, _) = zim_studio::utils::sidecar::split_frontmatter(&content).unwrap();
+        let fields: serde_yaml::Mapping = serde_yaml::from_str(yaml).unwrap();
+        let field = |key: &str| fields.get(key).and_then(|v| v.as_str()).unwrap();
+
+        assert_eq!(field("file"), "a \"b\".wav");
+        assert_eq!(field("path"), "C:\\music\\a.wav");
+        assert_eq!(field("project"), "12\" Remix");
+        assert_eq!(field("artist"), "Back\\slash");
+        assert_eq!(field("description"), "line one\nline two");
+        assert_eq!(fields["tags"][0].as_str(), Some("12\" Remix"));
+    }
+
     #[test]
     fn test_yaml_frontmatter_format() {
         let content = generate_minimal_sidecar_with_fs_metadata(
@@ -237,7 +325,9 @@ mod tests {
             1000,
             None,
             None,
+            None,
             None, // No UUID for this test
+            &[],
         );
 
         // Check YAML frontmatter delimiters
@@ -0,0 +1,80 @@
+//! WAV fixtures shared by the unit tests
+
+use std::path::Path;
+
+/// 16-bit integer PCM, the format most fixtures are written in
+pub fn pcm16(channels: u16, sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+/// 32-bit float PCM
+pub fn float32(channels: u16, sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec {
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+        ..pcm16(channels, sample_rate)
+    }
+}
+
+/// Write interleaved samples to a new WAV file
+pub fn write_wav<S: hound::Sample>(
+    path: impl AsRef<Path>,
+    spec: hound::WavSpec,
+    samples: impl IntoIterator<Item = S>,
+) {
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for sample in samples {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// A WAV built chunk by chunk, for layouts hound won't write: the fmt chunk
+/// body, an optional fact chunk and the data
+pub fn riff_wav(fmt: &[u8], fact_frames: Option<u32>, data: &[u8]) -> Vec<u8> {
+    let mut chunks = Vec::new();
+    let mut chunk = |id: &[u8], body: &[u8]| {
+        chunks.extend(id);
+        chunks.extend((body.len() as u32).to_le_bytes());
+        chunks.extend(body);
+        if body.len() % 2 == 1 {
+            chunks.push(0);
+        }
+    };
+    chunk(b"fmt ", fmt);
+    if let Some(frames) = fact_frames {
+        chunk(b"fact", &frames.to_le_bytes());
+    }
+    chunk(b"data", data);
+    let mut wav = b"RIFF".to_vec();
+    wav.extend((chunks.len() as u32 + 4).to_le_bytes());
+    wav.extend(b"WAVE");
+    wav.extend(chunks);
+    wav
+}
+
+/// fmt body: tag, channels, rate, block align, bits, then cbSize and any extra bytes
+pub fn fmt_chunk(
+    tag: u16,
+    channels: u16,
+    rate: u32,
+    align: u16,
+    bits: u16,
+    extra: &[u8],
+) -> Vec<u8> {
+    let mut fmt = Vec::new();
+    fmt.extend(tag.to_le_bytes());
+    fmt.extend(channels.to_le_bytes());
+    fmt.extend(rate.to_le_bytes());
+    fmt.extend((rate * align as u32).to_le_bytes());
+    fmt.extend(align.to_le_bytes());
+    fmt.extend(bits.to_le_bytes());
+    fmt.extend((extra.len() as u16).to_le_bytes());
+    fmt.extend(extra);
+    fmt
+}
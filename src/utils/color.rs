@@ -0,0 +1,104 @@
+//! Terminal colors that can be switched off.
+//!
+//! Command output is styled through [`Colorize`], which offers the same
+//! methods as `owo_colors::OwoColorize` but writes plain text once colors
+//! are turned off with `--no-color` or the `NO_COLOR` environment variable.
+//! Progress bars and prompts follow the same setting through `console`.
+
+use owo_colors::Style;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLORS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turn colors off when `no_color` is set or `NO_COLOR` holds any non-empty
+/// value (see <https://no-color.org>)
+pub fn init(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    set_enabled(!(no_color || no_color_env));
+}
+
+pub fn set_enabled(enabled: bool) {
+    COLORS_ENABLED.store(enabled, Ordering::Relaxed);
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+}
+
+pub fn enabled() -> bool {
+    COLORS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// A value and the style to show it in when colors are on
+#[derive(Clone, Copy)]
+pub struct Painted<'a, T: ?Sized> {
+    value: &'a T,
+    style: Style,
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Painted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if enabled() {
+            fmt::Display::fmt(&self.style.style(self.value), f)
+        } else {
+            fmt::Display::fmt(self.value, f)
+        }
+    }
+}
+
+macro_rules! styles {
+    ($($name:ident),* $(,)?) => {
+        /// Styling for anything that can be displayed
+        pub trait Colorize {
+            $(
+                fn $name(&self) -> Painted<'_, Self> {
+                    Painted {
+                        value: self,
+                        style: Style::new().$name(),
+                    }
+                }
+            )*
+        }
+
+        impl<'a, T: ?Sized> Painted<'a, T> {
+            $(
+                pub fn $name(self) -> Painted<'a, T> {
+                    Painted {
+                        value: self.value,
+                        style: self.style.$name(),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+styles!(
+    red,
+    green,
+    yellow,
+    blue,
+    magenta,
+    cyan,
+    bright_black,
+    bold,
+    italic,
+    dimmed,
+);
+
+impl<T: fmt::Display + ?Sized> Colorize for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_can_be_disabled() {
+        let styled = || format!("{} {:>5}", "done".green().bold(), 42.red());
+        assert!(styled().contains('\x1b'));
+
+        set_enabled(false);
+        let plain = styled();
+        set_enabled(true);
+        assert_eq!(plain, "done    42");
+    }
+}
@@ -0,0 +1,76 @@
+//! Parsing of human-friendly durations such as `30m`, `24h` or `2d`.
+
+use std::time::Duration;
+
+/// Parse a duration made of one or more number-unit pairs, e.g. `90s`,
+/// `30m`, `24h`, `2d`, `1w` or `1h30m`. Units are case-insensitive.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("'{text}' is not a duration like 30m, 24h or 2d");
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match rest[..unit_len].to_lowercase().as_str() {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_len..];
+
+        total = value
+            .checked_mul(seconds_per_unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid)?;
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("24h"), Ok(Duration::from_secs(24 * 3600)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(2 * 86400)));
+        assert_eq!(parse_duration("1W"), Ok(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration(" 1h30m "), Ok(Duration::from_secs(5400)));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_invalid() {
+        for text in [
+            "",
+            "24",
+            "h",
+            "2x",
+            "1.5h",
+            "-1d",
+            "1h 30m",
+            "99999999999999999999w",
+        ] {
+            assert!(parse_duration(text).is_err(), "{text} should be rejected");
+        }
+    }
+}
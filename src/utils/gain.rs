@@ -0,0 +1,61 @@
+//! Conversions between linear gain and decibels.
+//!
+//! Gains are stored and applied as linear factors (1.0 is unity), but shown
+//! in dB since that is how engineers reason about level.
+
+/// Decibels for a linear gain; silence is negative infinity
+pub fn gain_to_db(gain: f32) -> f32 {
+    if gain <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * gain.log10()
+    }
+}
+
+/// Linear gain for a level in decibels
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// A gain in dB for display, e.g. "-6.0 dB", "+3.5 dB" or "-∞ dB"
+pub fn format_db(gain: f32) -> String {
+    let db = gain_to_db(gain);
+    if db == f32::NEG_INFINITY {
+        "-∞ dB".to_string()
+    } else if db.abs() < 0.05 {
+        "0.0 dB".to_string()
+    } else {
+        format!("{db:+.1} dB")
+    }
+}
+
+/// A gain as a percentage of unity alongside its dB value, e.g. "50% -6.0 dB"
+pub fn format_volume(gain: f32) -> String {
+    format!("{:.0}% {}", gain * 100.0, format_db(gain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain_db_conversion() {
+        assert_eq!(gain_to_db(1.0), 0.0);
+        assert!((gain_to_db(0.5) + 6.0206).abs() < 0.001);
+        assert!((gain_to_db(2.0) - 6.0206).abs() < 0.001);
+        assert_eq!(gain_to_db(0.0), f32::NEG_INFINITY);
+        assert!((db_to_gain(-6.0206) - 0.5).abs() < 0.0001);
+        assert_eq!(db_to_gain(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_format_volume() {
+        assert_eq!(format_db(0.5), "-6.0 dB");
+        assert_eq!(format_db(2.0), "+6.0 dB");
+        assert_eq!(format_db(0.0), "-∞ dB");
+        assert_eq!(format_db(db_to_gain(-0.01)), "0.0 dB");
+        assert_eq!(format_volume(1.0), "100% 0.0 dB");
+        assert_eq!(format_volume(0.5), "50% -6.0 dB");
+        assert_eq!(format_volume(0.0), "0% -∞ dB");
+    }
+}
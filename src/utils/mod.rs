@@ -1,5 +1,9 @@
+pub mod color;
+pub mod duration;
+pub mod gain;
 pub mod parallel_scan;
 pub mod progress;
 pub mod project;
 pub mod sidecar;
+pub mod trash;
 pub mod validation;
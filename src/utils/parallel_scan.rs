@@ -9,13 +9,25 @@
 //! is non-deterministic. This is acceptable for audio file collection where order doesn't
 //! matter, but should be considered if used for other purposes.
 
-use crate::constants::SKIP_DIRECTORIES;
+use crate::config::Config;
+use crate::constants::{AUDIO_EXTENSIONS, SKIP_DIRECTORIES, VIDEO_EXTENSIONS};
 use crate::zimignore::ZimIgnore;
 use rayon::prelude::*;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Extensions of the files that get sidecars: audio, plus reference video
+/// when the `video_sidecars` setting is on
+pub fn sidecar_extensions(include_video: bool) -> HashSet<&'static str> {
+    let mut extensions: HashSet<&str> = AUDIO_EXTENSIONS.iter().copied().collect();
+    if include_video {
+        extensions.extend(VIDEO_EXTENSIONS);
+    }
+    extensions
+}
 
 /// Check if a file or directory is hidden (starts with '.')
 pub fn is_hidden_file(path: &Path) -> bool {
@@ -29,6 +41,115 @@ pub fn should_skip_directory(name: &str) -> bool {
     SKIP_DIRECTORIES.contains(&name)
 }
 
+static FOLLOW_SYMLINKS_FLAG: OnceLock<bool> = OnceLock::new();
+
+/// Record `--follow-symlinks` (`Some(true)`) or `--no-follow-symlinks`
+/// (`Some(false)`) so every scan uses it in place of the config setting
+pub fn set_follow_symlinks_flag(follow: Option<bool>) {
+    if let Some(follow) = follow {
+        let _ = FOLLOW_SYMLINKS_FLAG.set(follow);
+    }
+}
+
+/// Directory names skipped while scanning: `SKIP_DIRECTORIES` merged with the
+/// `skip_dirs` config list. A `!name` entry scans a built-in one after all.
+/// Symlinked directories are followed unless `follow_symlinks` is turned off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkipDirs {
+    names: HashSet<String>,
+    follow_symlinks: bool,
+}
+
+impl Default for SkipDirs {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl SkipDirs {
+    pub fn new(user_dirs: &[String]) -> Self {
+        let mut names: HashSet<String> = SKIP_DIRECTORIES.iter().map(|s| s.to_string()).collect();
+        for entry in user_dirs {
+            let entry = entry.trim();
+            match entry.strip_prefix('!') {
+                Some(name) => {
+                    names.remove(name.trim());
+                }
+                None if !entry.is_empty() => {
+                    names.insert(entry.to_string());
+                }
+                None => {}
+            }
+        }
+        Self {
+            names,
+            follow_symlinks: true,
+        }
+    }
+
+    /// Descend into symlinked directories as well as real ones
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// The defaults plus the user's `skip_dirs` and `follow_symlinks`, or just
+    /// the defaults if the config can't be read. `--follow-symlinks` and
+    /// `--no-follow-symlinks` override the config.
+    pub fn from_config() -> Self {
+        let skip_dirs = Config::load()
+            .map(|config| Self::new(&config.skip_dirs).with_follow_symlinks(config.follow_symlinks))
+            .unwrap_or_default();
+        match FOLLOW_SYMLINKS_FLAG.get() {
+            Some(&follow) => skip_dirs.with_follow_symlinks(follow),
+            None => skip_dirs,
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn follow_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+}
+
+/// Canonical paths of the directories already scanned, so a symlink back to
+/// an ancestor (or to a folder reached another way) is only scanned once
+#[derive(Debug, Default)]
+pub struct VisitedDirs {
+    dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl VisitedDirs {
+    /// Record `dir` and return whether it's the first visit. A directory
+    /// that can't be resolved counts as new so the scan reports its error.
+    pub fn first_visit(&self, dir: &Path) -> bool {
+        match fs::canonicalize(dir) {
+            Ok(canonical) => self
+                .dirs
+                .lock()
+                .map(|mut dirs| dirs.insert(canonical))
+                .unwrap_or(true),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Whether a scan should descend into `path`, a directory entry. Symlinked
+/// directories are only followed when `skip_dirs` allows it, and any
+/// directory is entered at most once while following them.
+pub fn should_descend(path: &Path, skip_dirs: &SkipDirs, visited: &VisitedDirs) -> bool {
+    let is_symlink = fs::symlink_metadata(path)
+        .map(|meta| meta.file_type().is_symlink())
+        .unwrap_or(false);
+    if !skip_dirs.follow_symlinks() {
+        return !is_symlink;
+    }
+    visited.first_visit(path)
+}
+
 /// Collect all files matching the given extensions in a directory tree.
 ///
 /// This function recursively scans directories in parallel for better performance
@@ -47,6 +168,7 @@ pub fn should_skip_directory(name: &str) -> bool {
 /// * `dir` - Root directory to start scanning from
 /// * `audio_exts` - Set of file extensions to match (without leading dot)
 /// * `zimignore` - ZimIgnore instance for filtering ignored paths
+/// * `skip_dirs` - Directory names never descended into
 ///
 /// # Returns
 ///
@@ -55,9 +177,14 @@ pub fn collect_audio_files(
     dir: &Path,
     audio_exts: &HashSet<&str>,
     zimignore: &ZimIgnore,
+    skip_dirs: &SkipDirs,
 ) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let mut files = Vec::new();
-    scan_directory_parallel(dir, audio_exts, zimignore, &mut files)?;
+    let visited = VisitedDirs::default();
+    if skip_dirs.follow_symlinks() {
+        visited.first_visit(dir);
+    }
+    scan_directory_parallel(dir, audio_exts, zimignore, skip_dirs, &visited, &mut files)?;
     Ok(files)
 }
 
@@ -68,6 +195,8 @@ fn scan_directory_parallel(
     dir: &Path,
     audio_exts: &HashSet<&str>,
     zimignore: &ZimIgnore,
+    skip_dirs: &SkipDirs,
+    visited: &VisitedDirs,
     files: &mut Vec<PathBuf>,
 ) -> Result<(), Box<dyn Error>> {
     let entries = fs::read_dir(dir)?;
@@ -98,7 +227,7 @@ fn scan_directory_parallel(
                 Some(name) => name.to_string_lossy(),
                 None => continue, // Skip paths without a valid file name
             };
-            if !should_skip_directory(&dir_name) {
+            if !skip_dirs.contains(&dir_name) && should_descend(&path, skip_dirs, visited) {
                 directories.push(path);
             }
         } else if path.is_file()
@@ -122,9 +251,12 @@ fn scan_directory_parallel(
         // even if some encounter permission issues or network problems.
         let nested_files: Vec<Vec<PathBuf>> = directories
             .par_iter()
-            .filter_map(
-                |subdir| match collect_audio_files(subdir, audio_exts, zimignore) {
-                    Ok(files) => Some(files),
+            .filter_map(|subdir| {
+                let mut files = Vec::new();
+                match scan_directory_parallel(
+                    subdir, audio_exts, zimignore, skip_dirs, visited, &mut files,
+                ) {
+                    Ok(()) => Some(files),
                     Err(e) => {
                         eprintln!(
                             "Warning: Failed to scan directory '{}': {}",
@@ -133,8 +265,8 @@ fn scan_directory_parallel(
                         );
                         None
                     }
-                },
-            )
+                }
+            })
             .collect();
 
         // Merge results
@@ -145,7 +277,9 @@ fn scan_directory_parallel(
         // Sequential processing for single directory (avoid overhead)
         // Error handling is consistent with parallel path: log errors but continue
         for subdir in directories {
-            if let Err(e) = scan_directory_parallel(&subdir, audio_exts, zimignore, files) {
+            if let Err(e) =
+                scan_directory_parallel(&subdir, audio_exts, zimignore, skip_dirs, visited, files)
+            {
                 eprintln!(
                     "Warning: Failed to scan directory '{}': {}",
                     subdir.display(),
@@ -164,6 +298,18 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_sidecar_extensions() {
+        let audio = sidecar_extensions(false);
+        assert_eq!(audio.len(), AUDIO_EXTENSIONS.len());
+        assert!(!audio.contains("mp4"));
+
+        let all = sidecar_extensions(true);
+        assert!(all.contains("mov"));
+        assert!(all.contains("wav"));
+        assert_eq!(all.len(), AUDIO_EXTENSIONS.len() + VIDEO_EXTENSIONS.len());
+    }
+
     #[test]
     fn test_is_hidden_file() {
         assert!(is_hidden_file(Path::new(".hidden")));
@@ -184,7 +330,13 @@ mod tests {
         let audio_exts: HashSet<&str> = ["wav", "flac"].iter().cloned().collect();
         let zimignore = ZimIgnore::new();
 
-        let files = collect_audio_files(temp_dir.path(), &audio_exts, &zimignore).unwrap();
+        let files = collect_audio_files(
+            temp_dir.path(),
+            &audio_exts,
+            &zimignore,
+            &SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 0);
     }
 
@@ -199,7 +351,13 @@ mod tests {
         fs::write(temp_dir.path().join("readme.txt"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files = collect_audio_files(temp_dir.path(), &audio_exts, &zimignore).unwrap();
+        let files = collect_audio_files(
+            temp_dir.path(),
+            &audio_exts,
+            &zimignore,
+            &SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 2);
     }
 
@@ -215,7 +373,13 @@ mod tests {
         fs::write(subdir.join("nested.wav"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files = collect_audio_files(temp_dir.path(), &audio_exts, &zimignore).unwrap();
+        let files = collect_audio_files(
+            temp_dir.path(),
+            &audio_exts,
+            &zimignore,
+            &SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 2);
     }
 
@@ -228,7 +392,13 @@ mod tests {
         fs::write(temp_dir.path().join(".hidden.wav"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files = collect_audio_files(temp_dir.path(), &audio_exts, &zimignore).unwrap();
+        let files = collect_audio_files(
+            temp_dir.path(),
+            &audio_exts,
+            &zimignore,
+            &SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 1);
     }
 
@@ -248,7 +418,68 @@ mod tests {
         fs::write(skip_dir.join("test.wav"), b"fake").unwrap();
 
         let zimignore = ZimIgnore::new();
-        let files = collect_audio_files(temp_dir.path(), &audio_exts, &zimignore).unwrap();
+        let files = collect_audio_files(
+            temp_dir.path(),
+            &audio_exts,
+            &zimignore,
+            &SkipDirs::default(),
+        )
+        .unwrap();
         assert_eq!(files.len(), 1);
     }
+
+    #[test]
+    fn test_collect_audio_files_user_skip_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio_exts: HashSet<&str> = ["wav"].iter().cloned().collect();
+
+        for dir in ["music", "Freeze Files", "temp"] {
+            fs::create_dir(temp_dir.path().join(dir)).unwrap();
+            fs::write(temp_dir.path().join(dir).join("take.wav"), b"fake").unwrap();
+        }
+
+        let zimignore = ZimIgnore::new();
+        let skip_dirs = SkipDirs::new(&["Freeze Files".to_string(), "!temp".to_string()]);
+        assert!(skip_dirs.contains("node_modules"));
+        let mut files =
+            collect_audio_files(temp_dir.path(), &audio_exts, &zimignore, &skip_dirs).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                temp_dir.path().join("music/take.wav"),
+                temp_dir.path().join("temp/take.wav")
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_audio_files_follow_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let audio_exts: HashSet<&str> = ["wav"].iter().cloned().collect();
+
+        let shared = temp_dir.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::write(shared.join("kick.wav"), b"fake").unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(project.join("mix.wav"), b"fake").unwrap();
+        std::os::unix::fs::symlink(&shared, project.join("samples")).unwrap();
+        // A link back to the project itself would loop forever if followed blindly
+        std::os::unix::fs::symlink(&project, project.join("loop")).unwrap();
+
+        let zimignore = ZimIgnore::new();
+        let not_following = SkipDirs::default().with_follow_symlinks(false);
+        let files = collect_audio_files(&project, &audio_exts, &zimignore, &not_following).unwrap();
+        assert_eq!(files, [project.join("mix.wav")]);
+
+        let mut files =
+            collect_audio_files(&project, &audio_exts, &zimignore, &SkipDirs::default()).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [project.join("mix.wav"), project.join("samples/kick.wav")]
+        );
+    }
 }
@@ -4,10 +4,43 @@
 //! to ensure consistent user experience across all CLI commands.
 
 use crate::constants::SPINNER_CHARS;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// How much output a command should produce.
+///
+/// `Quiet` hides spinners, progress bars, and informational lines so commands
+/// can run from scripts or cron; warnings and errors are still reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+}
+
+impl Verbosity {
+    pub fn from_quiet(quiet: bool) -> Self {
+        if quiet { Self::Quiet } else { Self::Normal }
+    }
+
+    pub fn is_quiet(self) -> bool {
+        self == Self::Quiet
+    }
+}
+
+/// Hide a progress indicator when running quietly
+fn apply_verbosity(pb: ProgressBar, verbosity: Verbosity) -> ProgressBar {
+    if verbosity.is_quiet() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    pb
+}
 
 /// Create a standard progress spinner with consistent styling.
 ///
+/// # Arguments
+///
+/// * `verbosity` - The spinner is hidden when `Verbosity::Quiet`
+///
 /// # Returns
 ///
 /// A configured `ProgressBar` instance in spinner mode with cyan styling
@@ -16,14 +49,14 @@ use indicatif::{ProgressBar, ProgressStyle};
 /// # Example
 ///
 /// ```ignore
-/// use crate::utils::progress::create_progress_spinner;
+/// use crate::utils::progress::{Verbosity, create_progress_spinner};
 ///
-/// let spinner = create_progress_spinner();
+/// let spinner = create_progress_spinner(Verbosity::Normal);
 /// spinner.set_message("Scanning files...");
 /// // ... do work ...
 /// spinner.finish_and_clear();
 /// ```
-pub fn create_progress_spinner() -> ProgressBar {
+pub fn create_progress_spinner(verbosity: Verbosity) -> ProgressBar {
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
         ProgressStyle::default_spinner()
@@ -31,7 +64,7 @@ pub fn create_progress_spinner() -> ProgressBar {
             .unwrap()
             .tick_strings(SPINNER_CHARS),
     );
-    spinner
+    apply_verbosity(spinner, verbosity)
 }
 
 /// Create a standard progress bar with consistent styling.
@@ -39,6 +72,7 @@ pub fn create_progress_spinner() -> ProgressBar {
 /// # Arguments
 ///
 /// * `total` - The total number of items to process
+/// * `verbosity` - The bar is hidden when `Verbosity::Quiet`
 ///
 /// # Returns
 ///
@@ -48,16 +82,16 @@ pub fn create_progress_spinner() -> ProgressBar {
 /// # Example
 ///
 /// ```ignore
-/// use crate::utils::progress::create_progress_bar;
+/// use crate::utils::progress::{Verbosity, create_progress_bar};
 ///
-/// let pb = create_progress_bar(100);
+/// let pb = create_progress_bar(100, Verbosity::Normal);
 /// for i in 0..100 {
 ///     // ... do work ...
 ///     pb.inc(1);
 /// }
 /// pb.finish_with_message("Done");
 /// ```
-pub fn create_progress_bar(total: u64) -> ProgressBar {
+pub fn create_progress_bar(total: u64, verbosity: Verbosity) -> ProgressBar {
     let pb = ProgressBar::new(total);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -65,7 +99,19 @@ pub fn create_progress_bar(total: u64) -> ProgressBar {
             .unwrap()
             .progress_chars("█▓░"),
     );
-    pb
+    apply_verbosity(pb, verbosity)
+}
+
+/// Create a `MultiProgress` to hold a command's bars.
+///
+/// Adding a bar to a `MultiProgress` replaces the bar's own draw target, so
+/// a quiet command needs the whole group hidden, not just its bars.
+pub fn create_multi_progress(verbosity: Verbosity) -> MultiProgress {
+    if verbosity.is_quiet() {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    } else {
+        MultiProgress::new()
+    }
 }
 
 #[cfg(test)]
@@ -74,7 +120,7 @@ mod tests {
 
     #[test]
     fn test_create_progress_spinner() {
-        let spinner = create_progress_spinner();
+        let spinner = create_progress_spinner(Verbosity::Normal);
         // Just verify it creates without panicking
         spinner.set_message("Test message");
         spinner.finish_and_clear();
@@ -82,9 +128,31 @@ mod tests {
 
     #[test]
     fn test_create_progress_bar() {
-        let pb = create_progress_bar(100);
+        let pb = create_progress_bar(100, Verbosity::Normal);
         // Just verify it creates without panicking
         pb.set_position(50);
         pb.finish();
     }
+
+    #[test]
+    fn test_quiet_hides_progress() {
+        assert!(create_progress_spinner(Verbosity::Quiet).is_hidden());
+        assert!(create_progress_bar(10, Verbosity::Quiet).is_hidden());
+
+        let multi = create_multi_progress(Verbosity::Quiet);
+        assert!(multi.is_hidden());
+        assert!(
+            multi
+                .add(create_progress_bar(10, Verbosity::Quiet))
+                .is_hidden()
+        );
+    }
+
+    #[test]
+    fn test_verbosity_from_quiet() {
+        assert_eq!(Verbosity::from_quiet(true), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_quiet(false), Verbosity::Normal);
+        assert_eq!(Verbosity::default(), Verbosity::Normal);
+        assert!(Verbosity::Quiet.is_quiet());
+    }
 }
@@ -3,10 +3,13 @@
 //! This module provides functions for working with project structure,
 //! including finding project roots and determining file types.
 
-use std::path::Path;
+use crate::constants::PROJECT_METADATA_FILE;
+use crate::utils::sidecar::split_frontmatter;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 
 /// Maximum depth to traverse when looking for project root
-const MAX_PROJECT_TRAVERSAL_DEPTH: usize = 10;
+pub(crate) const MAX_PROJECT_TRAVERSAL_DEPTH: usize = 10;
 
 /// Find the project root by looking for the nearest .zimignore file.
 ///
@@ -32,6 +35,11 @@ const MAX_PROJECT_TRAVERSAL_DEPTH: usize = 10;
 /// assert_eq!(project_name, Some("my-song".to_string()));
 /// ```
 pub fn find_project_root(file_path: &Path) -> Option<String> {
+    find_project_dir(file_path).and_then(|dir| project_dir_name(&dir))
+}
+
+/// The directory holding the nearest `.zimignore` above `file_path`
+pub fn find_project_dir(file_path: &Path) -> Option<PathBuf> {
     // Start from the file's parent directory
     let mut current = file_path.parent();
     let mut depth = 0;
@@ -46,22 +54,7 @@ pub fn find_project_root(file_path: &Path) -> Option<String> {
 
         let zimignore_path = dir.join(".zimignore");
         if zimignore_path.exists() {
-            // Found a project root - return its directory name
-            // If this is the current working directory ("."), get the actual directory name
-            if dir == Path::new(".") {
-                // Get the absolute path to get the real directory name
-                if let Ok(abs_path) = std::env::current_dir() {
-                    return abs_path
-                        .file_name()
-                        .and_then(|name| name.to_str())
-                        .map(|s| s.to_string());
-                }
-            }
-
-            return dir
-                .file_name()
-                .and_then(|name| name.to_str())
-                .map(|s| s.to_string());
+            return Some(dir.to_path_buf());
         }
         current = dir.parent();
     }
@@ -69,6 +62,61 @@ pub fn find_project_root(file_path: &Path) -> Option<String> {
     None
 }
 
+fn project_dir_name(dir: &Path) -> Option<String> {
+    // If this is the current working directory ("."), get the actual directory name
+    if dir == Path::new(".")
+        && let Ok(abs_path) = std::env::current_dir()
+    {
+        return abs_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|s| s.to_string());
+    }
+
+    dir.file_name()
+        .and_then(|name| name.to_str())
+        .map(|s| s.to_string())
+}
+
+/// The project a file belongs to, as its sidecar should describe it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectInfo {
+    /// Display name from the project README.md, or else the directory name
+    pub name: String,
+    /// Artist from the project README.md, if it names one
+    pub artist: Option<String>,
+}
+
+/// The fields of a project README.md frontmatter used for sidecars
+#[derive(Deserialize)]
+struct ProjectFrontmatter {
+    name: Option<String>,
+    artist: Option<String>,
+}
+
+/// Find the project for `file_path` like [`find_project_root`], preferring
+/// the `name` and `artist` from the project's README.md frontmatter (as
+/// written by `zim new`) over the directory name. Empty fields are ignored.
+pub fn find_project_info(file_path: &Path) -> Option<ProjectInfo> {
+    let dir = find_project_dir(file_path)?;
+    let frontmatter = std::fs::read_to_string(dir.join(PROJECT_METADATA_FILE))
+        .ok()
+        .and_then(|content| {
+            let (yaml, _) = split_frontmatter(&content)?;
+            serde_yaml::from_str::<ProjectFrontmatter>(yaml).ok()
+        });
+    let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    let (name, artist) = match frontmatter {
+        Some(frontmatter) => (non_empty(frontmatter.name), non_empty(frontmatter.artist)),
+        None => (None, None),
+    };
+
+    Some(ProjectInfo {
+        name: name.or_else(|| project_dir_name(&dir))?,
+        artist,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +174,50 @@ mod tests {
         let result = find_project_root(&file_path);
         assert_eq!(result, Some("test-project".to_string()));
     }
+
+    #[test]
+    fn test_find_project_info() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("tape-loops");
+        fs::create_dir_all(project_dir.join("mixes")).unwrap();
+        fs::write(project_dir.join(".zimignore"), "# test").unwrap();
+        let file_path = project_dir.join("mixes/v1.wav");
+
+        // Without a README the directory name stands in
+        assert_eq!(
+            find_project_info(&file_path),
+            Some(ProjectInfo {
+                name: "tape-loops".to_string(),
+                artist: None
+            })
+        );
+
+        fs::write(
+            project_dir.join(PROJECT_METADATA_FILE),
+            "---\nname: \"Tape Loops Vol. 2\"\nartist: \"Someone\"\nstatus: \"demo\"\n---\n# Tape Loops\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_project_info(&file_path),
+            Some(ProjectInfo {
+                name: "Tape Loops Vol. 2".to_string(),
+                artist: Some("Someone".to_string())
+            })
+        );
+
+        // An empty artist is left out rather than written as ""
+        fs::write(
+            project_dir.join(PROJECT_METADATA_FILE),
+            "---\nname: \"\"\nartist: \"\"\n---\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_project_info(&file_path),
+            Some(ProjectInfo {
+                name: "tape-loops".to_string(),
+                artist: None
+            })
+        );
+        assert_eq!(find_project_info(&temp_dir.path().join("loose.wav")), None);
+    }
 }
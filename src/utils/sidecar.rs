@@ -3,20 +3,72 @@
 //! This module provides functions for constructing sidecar paths and cloning/updating
 //! sidecar files when audio files are copied or excerpted.
 
-use crate::constants::SIDECAR_EXTENSION;
+use crate::config::Config;
+use crate::constants::{
+    DURATION_FIELD, PROJECT_METADATA_FILE, REPLAY_GAIN_FIELD, SIDECAR_DIR, SIDECAR_EXTENSION,
+};
+use crate::utils::project::MAX_PROJECT_TRAVERSAL_DEPTH;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Constructs the sidecar file path for a given audio file.
+/// Where new sidecars are written, set by the `sidecar_location` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SidecarLocation {
+    /// `track.wav.md` next to `track.wav`
+    #[default]
+    Adjacent,
+    /// `.zim/track.wav.md` under the project root, see [`zim_dir_sidecar_path`]
+    ZimDir,
+}
+
+impl SidecarLocation {
+    /// Parse a `sidecar_location` value; anything unrecognized is `Adjacent`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "zim" => SidecarLocation::ZimDir,
+            _ => SidecarLocation::Adjacent,
+        }
+    }
+
+    /// The configured location, or `Adjacent` if the config can't be read.
+    /// Commands read this once and pass it to [`get_sidecar_path`].
+    pub fn from_config() -> Self {
+        Config::load()
+            .map(|config| Self::from_name(&config.sidecar_location))
+            .unwrap_or_default()
+    }
+}
+
+/// Resolves the sidecar file path for a given audio file.
+///
+/// An existing sidecar is found whether it sits next to the audio or in the
+/// `.zim/` directory, checking next to the audio first. When neither exists,
+/// the path is where a new sidecar goes under `location`.
 ///
 /// # Example
 /// ```ignore
 /// let audio = Path::new("/music/track.wav");
-/// let sidecar = get_sidecar_path(audio);
+/// let sidecar = get_sidecar_path(audio, SidecarLocation::Adjacent);
 /// assert_eq!(sidecar, Path::new("/music/track.wav.md"));
 /// ```
-pub fn get_sidecar_path(audio_path: &Path) -> PathBuf {
+pub fn get_sidecar_path(audio_path: &Path, location: SidecarLocation) -> PathBuf {
+    let adjacent = adjacent_sidecar_path(audio_path);
+    if adjacent.exists() {
+        return adjacent;
+    }
+    // Finding the .zim/ path walks up to the project root, so only look
+    // there when there's no sidecar next to the audio
+    let hidden = zim_dir_sidecar_path(audio_path);
+    match location {
+        SidecarLocation::Adjacent if !hidden.exists() => adjacent,
+        _ => hidden,
+    }
+}
+
+/// The sidecar path next to the audio file: `track.wav` -> `track.wav.md`
+pub fn adjacent_sidecar_path(audio_path: &Path) -> PathBuf {
     let mut sidecar_path = audio_path.to_path_buf();
     let current_name = sidecar_path
         .file_name()
@@ -27,6 +79,184 @@ pub fn get_sidecar_path(audio_path: &Path) -> PathBuf {
     sidecar_path
 }
 
+/// The sidecar path in the hidden `.zim/` directory, keyed by the audio's
+/// path relative to the sidecar root: `root/mixes/a.wav` ->
+/// `root/.zim/mixes/a.wav.md`.
+///
+/// The root is the project the audio belongs to: the nearest directory above
+/// it with a `.zimignore` or a `README.md` with frontmatter, as `zim new`
+/// writes. Audio outside a project uses its own directory.
+pub fn zim_dir_sidecar_path(audio_path: &Path) -> PathBuf {
+    let root = sidecar_root(audio_path);
+    let relative = audio_path
+        .strip_prefix(&root)
+        .unwrap_or_else(|_| Path::new(audio_path.file_name().unwrap_or_default()));
+    adjacent_sidecar_path(&root.join(SIDECAR_DIR).join(relative))
+}
+
+fn sidecar_root(audio_path: &Path) -> PathBuf {
+    let dir = audio_path.parent().unwrap_or(Path::new(""));
+    dir.ancestors()
+        .take(MAX_PROJECT_TRAVERSAL_DEPTH)
+        .find(|ancestor| is_project_root(ancestor))
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// Whether `dir` is marked as a project root. Folder READMEs from `zim new`
+/// have no frontmatter, so only the project's own README counts.
+pub fn is_project_root(dir: &Path) -> bool {
+    dir.join(".zimignore").exists()
+        || fs::read_to_string(dir.join(PROJECT_METADATA_FILE))
+            .is_ok_and(|content| split_frontmatter(&content).is_some())
+}
+
+/// The audio file a sidecar describes, from either location:
+/// `a.wav.md` -> `a.wav` and `root/.zim/mixes/a.wav.md` -> `root/mixes/a.wav`
+pub fn audio_path_for_sidecar(sidecar_path: &Path) -> PathBuf {
+    let audio = sidecar_path.with_extension("");
+    let components: Vec<_> = audio.components().collect();
+    match components
+        .iter()
+        .rposition(|c| c.as_os_str() == SIDECAR_DIR)
+    {
+        Some(index) => components[..index]
+            .iter()
+            .chain(&components[index + 1..])
+            .collect(),
+        None => audio,
+    }
+}
+
+/// The `path` a sidecar records for its audio file: `relative` as given, or
+/// the canonicalized absolute path when `absolute` is set. Files that can't
+/// be canonicalized keep the relative form.
+pub fn stored_audio_path(audio_path: &Path, relative: &str, absolute: bool) -> String {
+    if absolute && let Ok(path) = fs::canonicalize(audio_path) {
+        return path.to_string_lossy().into_owned();
+    }
+    relative.to_string()
+}
+
+/// Write a sidecar, creating its `.zim/` directories if needed
+pub fn write_sidecar(sidecar_path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = sidecar_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(sidecar_path, content)
+}
+
+/// Split sidecar content into its YAML frontmatter and the markdown body after it.
+///
+/// Returns `None` if the content does not open with a `---` delimited block.
+pub fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix("---\n")?;
+    let end = rest.find("\n---\n")?;
+    Some((&rest[..end], &rest[end + 5..])) // Skip past "\n---\n"
+}
+
+/// Replace fields in a sidecar's YAML frontmatter.
+///
+/// All other fields keep their values and order, and the markdown body is
+/// carried over unchanged. Fields not already present are appended.
+pub fn update_frontmatter_fields(
+    content: &str,
+    updates: Vec<(&str, serde_yaml::Value)>,
+) -> Result<String, Box<dyn Error>> {
+    let (yaml_content, markdown_content) =
+        split_frontmatter(content).ok_or("Sidecar file has no YAML frontmatter")?;
+
+    let mut yaml_value: serde_yaml::Value = serde_yaml::from_str(yaml_content)?;
+    let yaml_map = yaml_value
+        .as_mapping_mut()
+        .ok_or("Sidecar frontmatter is not a YAML mapping")?;
+
+    for (key, value) in updates {
+        yaml_map.insert(serde_yaml::Value::String(key.to_string()), value);
+    }
+
+    let yaml_string = serde_yaml::to_string(&yaml_value)?;
+    Ok(format!("---\n{yaml_string}---\n{markdown_content}"))
+}
+
+/// Read a saved loop region (`loop_start`/`loop_end`, in seconds) from a sidecar.
+///
+/// Returns `None` unless both fields are present, non-negative, and the start
+/// comes before the end.
+pub fn read_loop_region(content: &str) -> Option<(f64, f64)> {
+    let (yaml_content, _) = split_frontmatter(content)?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_content).ok()?;
+
+    let start = yaml.get("loop_start")?.as_f64()?;
+    let end = yaml.get("loop_end")?.as_f64()?;
+    (start >= 0.0 && start < end).then_some((start, end))
+}
+
+/// Store a loop region in a sidecar's frontmatter, rounded to milliseconds
+pub fn write_loop_region(content: &str, start: f64, end: f64) -> Result<String, Box<dyn Error>> {
+    let round_ms = |seconds: f64| serde_yaml::Value::from((seconds * 1000.0).round() / 1000.0);
+    update_frontmatter_fields(
+        content,
+        vec![("loop_start", round_ms(start)), ("loop_end", round_ms(end))],
+    )
+}
+
+/// Read the stored replay gain in dB, if the sidecar has a numeric one
+pub fn read_replay_gain(content: &str) -> Option<f64> {
+    let (yaml_content, _) = split_frontmatter(content)?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_content).ok()?;
+    yaml.get(REPLAY_GAIN_FIELD)?
+        .as_f64()
+        .filter(|gain| gain.is_finite())
+}
+
+/// Store a replay gain in a sidecar's frontmatter, rounded to hundredths of a dB
+pub fn write_replay_gain(content: &str, gain_db: f64) -> Result<String, Box<dyn Error>> {
+    update_frontmatter_fields(
+        content,
+        vec![(
+            REPLAY_GAIN_FIELD,
+            serde_yaml::Value::from((gain_db * 100.0).round() / 100.0),
+        )],
+    )
+}
+
+/// Durations are written to two decimal places, so values closer than this
+/// are the same duration
+pub const DURATION_TOLERANCE: f64 = 0.01;
+
+/// A double-quoted YAML scalar for `value`, with quotes, backslashes and
+/// control characters escaped. JSON string syntax is valid YAML here.
+pub fn yaml_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Render a duration as it appears in sidecar frontmatter: seconds to two
+/// decimal places, or `unknown`
+pub fn format_duration(seconds: Option<f64>) -> String {
+    seconds
+        .map(|d| format!("{d:.2}"))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// A duration as a frontmatter value, rounded the same way as `format_duration`
+pub fn duration_value(seconds: f64) -> serde_yaml::Value {
+    serde_yaml::Value::from((seconds * 100.0).round() / 100.0)
+}
+
+/// Read the stored duration from parsed sidecar fields, if it is a number
+pub fn stored_duration(fields: &HashMap<String, serde_yaml::Value>) -> Option<f64> {
+    fields.get(DURATION_FIELD).and_then(|v| v.as_f64())
+}
+
+/// Whether a stored duration differs from `seconds` by more than rounding
+/// noise. A missing or `unknown` duration counts as changed.
+pub fn duration_changed(stored: Option<f64>, seconds: f64) -> bool {
+    stored.is_none_or(|stored| (stored - seconds).abs() > DURATION_TOLERANCE)
+}
+
 /// Mode for cloning sidecar files
 #[derive(Debug, Clone)]
 pub enum SidecarCloneMode {
@@ -34,9 +264,10 @@ pub enum SidecarCloneMode {
     FullCopy,
     /// Copy sidecar and add selection/excerpt metadata
     Selection {
-        start_time: f32, // seconds
-        end_time: f32,   // seconds
-        duration: f32,   // total file duration in seconds
+        start_time: f32,    // seconds
+        end_time: f32,      // seconds
+        duration: f32,      // total file duration in seconds
+        inherit_tags: bool, // Keep the source's tags alongside `excerpt`
     },
 }
 
@@ -63,9 +294,10 @@ pub fn clone_sidecar(
     dest_audio: &Path,
     mode: SidecarCloneMode,
     tags_fallback: Option<&[String]>,
+    location: SidecarLocation,
 ) -> Result<(), Box<dyn Error>> {
-    let source_sidecar = get_sidecar_path(source_audio);
-    let dest_sidecar = get_sidecar_path(dest_audio);
+    let source_sidecar = get_sidecar_path(source_audio, location);
+    let dest_sidecar = get_sidecar_path(dest_audio, location);
 
     // If source sidecar doesn't exist, nothing to clone
     if !source_sidecar.exists() {
@@ -88,19 +320,25 @@ pub fn clone_sidecar(
             start_time,
             end_time,
             duration,
-        } => clone_selection(
-            &original_content,
-            source_audio,
-            dest_filename,
-            dest_dir,
-            start_time,
-            end_time,
-            duration,
-            tags_fallback,
-        )?,
+            inherit_tags,
+        } => {
+            // Without inheritance the excerpt starts from just `excerpt`
+            let tags_fallback = tags_fallback.filter(|_| inherit_tags);
+            clone_selection(
+                &original_content,
+                source_audio,
+                dest_filename,
+                dest_dir,
+                start_time,
+                end_time,
+                duration,
+                tags_fallback,
+                inherit_tags,
+            )?
+        }
     };
 
-    fs::write(&dest_sidecar, content)?;
+    write_sidecar(&dest_sidecar, &content)?;
     log::info!("Created sidecar file: {}", dest_sidecar.display());
 
     Ok(())
@@ -152,12 +390,17 @@ fn clone_selection(
     end_time: f32,
     _duration: f32,
     tags_fallback: Option<&[String]>,
+    inherit_tags: bool,
 ) -> Result<String, Box<dyn Error>> {
     let source_filename = source_audio
         .file_name()
         .and_then(|f| f.to_str())
         .unwrap_or("unknown source");
     let source_path = source_audio.to_str().unwrap_or("unknown");
+    let file_yaml = yaml_quote(dest_filename);
+    let path_yaml = yaml_quote(dest_dir);
+    let description_yaml = yaml_quote(&format!("Excerpt from {source_filename}"));
+    let source_yaml = yaml_quote(source_path);
 
     // Calculate time ranges
     let start_secs = start_time as u32;
@@ -182,7 +425,8 @@ fn clone_selection(
 
         // Parse original YAML to get tags
         let mut tags = vec!["excerpt".to_string()];
-        if let Ok(yaml_value) = serde_yaml::from_str::<serde_yaml::Value>(yaml_section)
+        if inherit_tags
+            && let Ok(yaml_value) = serde_yaml::from_str::<serde_yaml::Value>(yaml_section)
             && let Some(original_tags) = yaml_value.get("tags").and_then(|v| v.as_sequence())
         {
             for tag in original_tags {
@@ -199,13 +443,13 @@ fn clone_selection(
 
         return Ok(format!(
             r#"---
-file: "{dest_filename}"
-path: "{dest_dir}"
-title: "{dest_filename}"
-description: "Excerpt from {source_filename}"
+file: {file_yaml}
+path: {path_yaml}
+title: {file_yaml}
+description: {description_yaml}
 duration: {selection_duration_f32:.2}
 tags: {tags_yaml}
-source_file: "{source_path}"
+source_file: {source_yaml}
 source_time_start: {start_mins}:{start_secs_rem:02}
 source_time_end: {end_mins}:{end_secs_rem:02}
 source_duration: {sel_mins}:{sel_secs_rem:02}
@@ -234,13 +478,13 @@ extraction_type: "selection"
 
     Ok(format!(
         r#"---
-file: "{dest_filename}"
-path: "{dest_dir}"
-title: "{dest_filename}"
-description: "Excerpt from {source_filename}"
+file: {file_yaml}
+path: {path_yaml}
+title: {file_yaml}
+description: {description_yaml}
 duration: {selection_duration_f32:.2}
 tags: {tags_yaml}
-source_file: "{source_path}"
+source_file: {source_yaml}
 source_time_start: {start_mins}:{start_secs_rem:02}
 source_time_end: {end_mins}:{end_secs_rem:02}
 source_duration: {sel_mins}:{sel_secs_rem:02}
@@ -289,20 +533,253 @@ fn get_timestamp() -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_duration_rounding_and_tolerance() {
+        assert_eq!(format_duration(Some(123.456)), "123.46");
+        assert_eq!(format_duration(None), "unknown");
+        assert_eq!(duration_value(123.456), serde_yaml::Value::from(123.46));
+
+        // What was written is the rounded value, so the raw one is unchanged
+        assert!(!duration_changed(Some(123.46), 123.456));
+        assert!(duration_changed(Some(123.46), 124.0));
+        assert!(duration_changed(None, 124.0));
+    }
+
+    #[test]
+    fn test_stored_duration() {
+        let fields: HashMap<String, serde_yaml::Value> =
+            serde_yaml::from_str("duration: 12.5\nsample_rate: 44100").unwrap();
+        assert_eq!(stored_duration(&fields), Some(12.5));
+
+        let fields: HashMap<String, serde_yaml::Value> =
+            serde_yaml::from_str("duration: unknown").unwrap();
+        assert_eq!(stored_duration(&fields), None);
+    }
+
     #[test]
     fn test_get_sidecar_path() {
         let audio = PathBuf::from("/music/track.wav");
-        let sidecar = get_sidecar_path(&audio);
+        let sidecar = get_sidecar_path(&audio, SidecarLocation::Adjacent);
         assert_eq!(sidecar, PathBuf::from("/music/track.wav.md"));
     }
 
     #[test]
     fn test_get_sidecar_path_flac() {
         let audio = PathBuf::from("./samples/drum_loop.flac");
-        let sidecar = get_sidecar_path(&audio);
+        let sidecar = get_sidecar_path(&audio, SidecarLocation::Adjacent);
         assert_eq!(sidecar, PathBuf::from("./samples/drum_loop.flac.md"));
     }
 
+    #[test]
+    fn test_get_sidecar_path_checks_both_locations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join(".zimignore"), "").unwrap();
+        let audio = root.join("mixes").join("final.wav");
+        let adjacent = root.join("mixes").join("final.wav.md");
+        let hidden = root.join(".zim").join("mixes").join("final.wav.md");
+
+        // New sidecars go where the config says
+        assert_eq!(zim_dir_sidecar_path(&audio), hidden);
+        assert_eq!(
+            get_sidecar_path(&audio, SidecarLocation::Adjacent),
+            adjacent
+        );
+        assert_eq!(get_sidecar_path(&audio, SidecarLocation::ZimDir), hidden);
+
+        // An existing sidecar is found wherever it is
+        write_sidecar(&hidden, "---\n---\n").unwrap();
+        assert_eq!(get_sidecar_path(&audio, SidecarLocation::Adjacent), hidden);
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::rename(&hidden, &adjacent).unwrap();
+        assert_eq!(get_sidecar_path(&audio, SidecarLocation::ZimDir), adjacent);
+
+        assert_eq!(audio_path_for_sidecar(&hidden), audio);
+        assert_eq!(audio_path_for_sidecar(&adjacent), audio);
+    }
+
+    #[test]
+    fn test_zim_dir_sidecar_path_uses_project_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("mixes")).unwrap();
+        fs::write(root.join("README.md"), "---\nname: Song\n---\n").unwrap();
+        fs::write(root.join("mixes/README.md"), "# mixes\n").unwrap();
+        let audio = root.join("mixes").join("final.wav");
+        let hidden = root.join(".zim").join("mixes").join("final.wav.md");
+        assert_eq!(zim_dir_sidecar_path(&audio), hidden);
+
+        // A .zim/ made lower down doesn't move the root
+        fs::create_dir_all(root.join("mixes").join(".zim")).unwrap();
+        assert_eq!(zim_dir_sidecar_path(&audio), hidden);
+    }
+
+    #[test]
+    fn test_zim_dir_sidecar_path_without_project() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let audio = temp_dir.path().join("loose.flac");
+        assert_eq!(
+            zim_dir_sidecar_path(&audio),
+            temp_dir.path().join(".zim").join("loose.flac.md")
+        );
+        assert_eq!(
+            audio_path_for_sidecar(Path::new(".zim/loose.flac.md")),
+            PathBuf::from("loose.flac")
+        );
+    }
+
+    #[test]
+    fn test_split_frontmatter() {
+        let content = "---\nfile: a.wav\ntags: []\n---\n# Notes\n";
+        let (yaml, body) = split_frontmatter(content).unwrap();
+        assert_eq!(yaml, "file: a.wav\ntags: []");
+        assert_eq!(body, "# Notes\n");
+
+        assert!(split_frontmatter("# No frontmatter").is_none());
+        assert!(split_frontmatter("---\nfile: a.wav\n").is_none());
+    }
+
+    #[test]
+    fn test_loop_region_round_trip() {
+        let content = "---\nfile: a.wav\ntags: []\n---\n# Notes\n";
+        assert_eq!(read_loop_region(content), None);
+
+        let updated = write_loop_region(content, 1.23456, 4.5).unwrap();
+        assert_eq!(read_loop_region(&updated), Some((1.235, 4.5)));
+        assert!(updated.starts_with("---\nfile: a.wav\n"));
+        assert!(updated.ends_with("---\n# Notes\n"));
+
+        // Overwrites an existing region in place
+        let updated = write_loop_region(&updated, 0.0, 2.0).unwrap();
+        assert_eq!(read_loop_region(&updated), Some((0.0, 2.0)));
+    }
+
+    #[test]
+    fn test_read_loop_region_rejects_invalid() {
+        let region = |yaml: &str| read_loop_region(&format!("---\n{yaml}\n---\n"));
+        assert_eq!(region("loop_start: 1\nloop_end: 3"), Some((1.0, 3.0)));
+        assert_eq!(region("loop_start: 3.0\nloop_end: 1.0"), None);
+        assert_eq!(region("loop_start: -1.0\nloop_end: 1.0"), None);
+        assert_eq!(region("loop_start: 1.0"), None);
+        assert_eq!(region("loop_start: soon\nloop_end: 1.0"), None);
+        assert_eq!(read_loop_region("# no frontmatter"), None);
+    }
+
+    #[test]
+    fn test_replay_gain_round_trip() {
+        let content = "---\nfile: a.wav\ntags: []\n---\n# Notes\n";
+        assert_eq!(read_replay_gain(content), None);
+
+        let updated = write_replay_gain(content, -4.5678).unwrap();
+        assert_eq!(read_replay_gain(&updated), Some(-4.57));
+        assert!(updated.ends_with("---\n# Notes\n"));
+
+        let updated = write_replay_gain(&updated, 2.0).unwrap();
+        assert_eq!(read_replay_gain(&updated), Some(2.0));
+        assert_eq!(read_replay_gain("---\nreplay_gain: loud\n---\n"), None);
+    }
+
+    #[test]
+    fn test_update_frontmatter_fields() {
+        let content = "---\nfile: a.wav\ndescription: old\ntags: []\ncustom: keep\n---\n# Notes\n\nBody text\n";
+        let updated = update_frontmatter_fields(
+            content,
+            vec![
+                ("description", serde_yaml::Value::String("new".to_string())),
+                (
+                    "tags",
+                    serde_yaml::Value::Sequence(vec![serde_yaml::Value::String(
+                        "drums".to_string(),
+                    )]),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            updated,
+            "---\nfile: a.wav\ndescription: new\ntags:\n- drums\ncustom: keep\n---\n# Notes\n\nBody text\n"
+        );
+        assert!(update_frontmatter_fields("no frontmatter", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_clone_selection_tags() {
+        let source =
+            "---\nfile: take.wav\ntags: [\"drums\", \"excerpt\", \"live\"]\n---\n# Notes\n";
+        let clone = |content: &str, fallback: Option<&[String]>, inherit_tags| {
+            clone_selection(
+                content,
+                Path::new("/music/take.wav"),
+                "take_edit.wav",
+                "/music",
+                1.0,
+                3.0,
+                10.0,
+                fallback,
+                inherit_tags,
+            )
+            .unwrap()
+        };
+
+        let inherited = clone(source, None, true);
+        assert!(inherited.contains("tags: [\"excerpt\", \"drums\", \"live\"]\n"));
+        let fresh = clone(source, None, false);
+        assert!(fresh.contains("tags: [\"excerpt\"]\n"));
+
+        // Without frontmatter the browser's tags stand in for the source's
+        let fallback = ["bass".to_string()];
+        let inherited = clone("# Notes only\n", Some(&fallback), true);
+        assert!(inherited.contains("tags: [\"excerpt\", \"bass\"]\n"));
+    }
+
+    #[test]
+    fn test_clone_selection_escapes_source_path() {
+        let source = Path::new(r#"C:\Music\"live" take.wav"#);
+        let cloned = clone_selection(
+            "# Notes\n",
+            source,
+            "take_edit.wav",
+            "/music",
+            1.0,
+            3.0,
+            10.0,
+            None,
+            true,
+        )
+        .unwrap();
+        let (yaml, _) = cloned[4..].split_once("\n---\n").unwrap();
+        let fields: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(fields["source_file"].as_str(), source.to_str());
+    }
+
+    #[test]
+    fn test_clone_selection_extracted_at_is_rfc3339() {
+        // Both clone formats, with and without the source's frontmatter
+        for content in [
+            "---\nfile: take.wav\ntags: []\n---\n# Notes\n",
+            "# Notes only\n",
+        ] {
+            let clone = clone_selection(
+                content,
+                Path::new("/music/take.wav"),
+                "take_edit.wav",
+                "/music",
+                1.0,
+                3.0,
+                10.0,
+                None,
+                true,
+            )
+            .unwrap();
+            let (yaml, _) = split_frontmatter(&clone).unwrap();
+            let fields: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+            let extracted_at = fields["extracted_at"].as_str().unwrap();
+            let timestamp = chrono::DateTime::parse_from_rfc3339(extracted_at).unwrap();
+            assert_eq!(timestamp.offset().local_minus_utc(), 0);
+        }
+    }
+
     #[test]
     fn test_format_tags_empty() {
         assert_eq!(format_tags(&[]), "[]");
@@ -0,0 +1,99 @@
+//! Recoverable file deletion through the user's trash.
+//!
+//! Commands that delete files route through [`remove_file`] so a mistake on an
+//! irreplaceable recording can be undone from the desktop trash. When the
+//! trash isn't available (e.g. a headless server) the file is deleted
+//! permanently with a warning; `use_trash = false` always deletes permanently.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// What happened to a removed file
+#[derive(Debug, PartialEq)]
+pub enum Removal {
+    /// Moved into the trash
+    Trashed,
+    /// Permanently deleted, because trash is disabled or unavailable
+    Deleted,
+}
+
+/// Remove a file, moving it to the trash when `use_trash` is set
+pub fn remove_file(path: &Path, use_trash: bool) -> Result<Removal, Box<dyn Error>> {
+    if !use_trash {
+        fs::remove_file(path)?;
+        return Ok(Removal::Deleted);
+    }
+    remove_with(path, |path| trash::delete(path))
+}
+
+/// Trash a file with `trash_file`, deleting it permanently if that fails
+fn remove_with<F>(path: &Path, trash_file: F) -> Result<Removal, Box<dyn Error>>
+where
+    F: FnOnce(&Path) -> Result<(), trash::Error>,
+{
+    if !path.is_file() {
+        return Err(format!("Not a file: {}", path.display()).into());
+    }
+
+    match trash_file(path) {
+        Ok(()) => Ok(Removal::Trashed),
+        Err(e) => {
+            eprintln!(
+                "Warning: Could not move '{}' to the trash ({}); deleting it permanently",
+                path.display(),
+                e
+            );
+            fs::remove_file(path)?;
+            Ok(Removal::Deleted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_without_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("take.wav");
+        fs::write(&path, b"audio").unwrap();
+
+        assert_eq!(remove_file(&path, false).unwrap(), Removal::Deleted);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_unavailable_trash_falls_back_to_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("take.wav");
+        fs::write(&path, b"audio").unwrap();
+
+        let removal = remove_with(&path, |_| {
+            Err(trash::Error::Unknown {
+                description: "no trash".to_string(),
+            })
+        })
+        .unwrap();
+        assert_eq!(removal, Removal::Deleted);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_trashed_file_is_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("take.wav");
+        fs::write(&path, b"audio").unwrap();
+
+        assert_eq!(remove_with(&path, |_| Ok(())).unwrap(), Removal::Trashed);
+    }
+
+    #[test]
+    fn test_remove_missing_file_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing.wav");
+        assert!(remove_with(&path, |_| Ok(())).is_err());
+    }
+}
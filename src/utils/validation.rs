@@ -3,7 +3,7 @@
 //! This module provides common validation functions to ensure consistent
 //! error handling across the codebase.
 
-use owo_colors::OwoColorize;
+use crate::utils::color::Colorize;
 use std::error::Error;
 use std::path::Path;
 
@@ -1,7 +1,10 @@
 //! Minimal WAV file metadata manipulation for tracking file lineage
 //!
 //! This module provides functionality to read and write INFO LIST chunks
-//! in WAV files without external dependencies.
+//! in WAV files without external dependencies. It also reads and writes the
+//! sampler (`smpl`) chunk, where instruments and sample editors store loop
+//! points, and human-readable notes (title, subject, keywords) that other
+//! tools show from the INFO chunk.
 
 use chrono::Utc;
 use std::error::Error;
@@ -65,6 +68,288 @@ impl ZimMetadata {
     }
 }
 
+/// Sidecar notes to show in other tools, stored in the INFO chunk as
+/// INAM (title), ISBJ (description) and IKEY (tags). ICMT is left to the
+/// ZIM lineage data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InfoNotes {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl InfoNotes {
+    /// INFO field IDs written for the notes
+    const FIELD_IDS: [&'static [u8; 4]; 3] = [b"INAM", b"ISBJ", b"IKEY"];
+
+    pub fn is_empty(&self) -> bool {
+        self.fields().is_empty()
+    }
+
+    fn fields(&self) -> Vec<(&'static [u8; 4], String)> {
+        let [title_id, description_id, tags_id] = Self::FIELD_IDS;
+        let mut fields = Vec::new();
+        if let Some(title) = self.title.as_ref().filter(|t| !t.is_empty()) {
+            fields.push((title_id, title.clone()));
+        }
+        if let Some(description) = self.description.as_ref().filter(|d| !d.is_empty()) {
+            fields.push((description_id, description.clone()));
+        }
+        if !self.tags.is_empty() {
+            // Keywords are separated by semicolons in RIFF INFO
+            fields.push((tags_id, self.tags.join("; ")));
+        }
+        fields
+    }
+}
+
+/// A sampler loop from a WAV `smpl` chunk, in sample frames.
+/// `end` is the last frame played, as in the chunk itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleLoop {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Size of the RIFF header: "RIFF", the RIFF size and "WAVE"
+const RIFF_HEADER_SIZE: u64 = 12;
+
+/// Fail clearly on files too short to hold a RIFF header, such as empty
+/// files from an interrupted render
+fn check_riff_length(len: u64) -> Result<(), Box<dyn Error>> {
+    if len < RIFF_HEADER_SIZE {
+        return Err(format!("File too short to be a valid WAV ({len} bytes)").into());
+    }
+    Ok(())
+}
+
+/// RIFF or data chunk size written by recorders that stream a file before
+/// its length is known
+const SIZE_UNKNOWN: u32 = 0xFFFF_FFFF;
+
+/// Where the RIFF chunk ends: as its header says, or at the end of the file
+/// when the header holds the streaming placeholder
+fn riff_end(riff_size: u32, file_len: u64) -> u64 {
+    if riff_size == SIZE_UNKNOWN {
+        file_len
+    } else {
+        riff_size as u64 + 8 // RIFF size doesn't include RIFF header
+    }
+}
+
+/// `fmt` format tags
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Where a WAV's format description and audio data live. Used for layouts
+/// the decoder can't read, such as 64-bit float, which are copied as raw frames.
+#[derive(Debug, Clone)]
+pub struct WavLayout {
+    fmt_chunk: Vec<u8>, // Body of the fmt chunk, copied unchanged into excerpts
+    data_offset: u64,
+    data_len: u64,
+    fact_frames: Option<u64>, // Frame count from a fact chunk before the data
+}
+
+impl WavLayout {
+    fn fmt_u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.fmt_chunk[offset], self.fmt_chunk[offset + 1]])
+    }
+
+    /// Format tag, looking through WAVE_FORMAT_EXTENSIBLE to its sub-format
+    fn format_tag(&self) -> u16 {
+        let tag = self.fmt_u16(0);
+        if tag == WAVE_FORMAT_EXTENSIBLE && self.fmt_chunk.len() >= 26 {
+            self.fmt_u16(24)
+        } else {
+            tag
+        }
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.fmt_u16(2)
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        le_u32_at(&self.fmt_chunk, 4)
+    }
+
+    fn block_align(&self) -> u64 {
+        self.fmt_u16(12).max(1) as u64
+    }
+
+    pub fn is_float(&self) -> bool {
+        self.format_tag() == WAVE_FORMAT_IEEE_FLOAT
+    }
+
+    pub fn bits_per_sample(&self) -> u16 {
+        self.fmt_u16(14)
+    }
+
+    pub fn is_float64(&self) -> bool {
+        self.is_float() && self.bits_per_sample() == 64
+    }
+
+    /// Whether the samples are encoded (ADPCM, µ-law and so on) rather than
+    /// plain integer or float
+    pub fn is_compressed(&self) -> bool {
+        !matches!(self.format_tag(), WAVE_FORMAT_PCM | WAVE_FORMAT_IEEE_FLOAT)
+    }
+
+    /// A readable name for the format, for errors about formats that can't
+    /// be decoded
+    pub fn format_name(&self) -> String {
+        match self.format_tag() {
+            0x0002 => "Microsoft ADPCM".to_string(),
+            0x0006 => "A-law".to_string(),
+            0x0007 => "µ-law".to_string(),
+            0x0011 => "IMA ADPCM".to_string(),
+            0x0055 => "MP3".to_string(),
+            tag => format!("format 0x{tag:04X}"),
+        }
+    }
+
+    /// Sample frames according to the fact chunk, which float and compressed
+    /// WAVs carry and which can be shorter than a padded data chunk
+    pub fn fact_frames(&self) -> Option<u64> {
+        self.fact_frames
+    }
+
+    /// Number of sample frames in the data chunk
+    pub fn frames(&self) -> u64 {
+        self.data_len / self.block_align()
+    }
+}
+
+/// Locate the fmt and data chunks of a WAV file. A data chunk that runs
+/// past the end of the file is cut to the bytes actually present.
+pub fn read_wav_layout(path: &Path) -> Result<WavLayout, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    check_riff_length(file_len)?;
+    let mut reader = BufReader::new(file);
+
+    if read_fourcc(&mut reader)? != "RIFF" {
+        return Err("Not a RIFF file".into());
+    }
+    let _riff_size = read_u32_le(&mut reader)?;
+    if read_fourcc(&mut reader)? != "WAVE" {
+        return Err("Not a WAVE file".into());
+    }
+
+    let mut fmt_chunk = None;
+    let mut fact_frames = None;
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        let chunk_id = read_fourcc(&mut reader)?;
+        let chunk_size = read_u32_le(&mut reader)? as u64;
+        pos += 8;
+
+        if chunk_id == "fmt " {
+            if !(16..=1024).contains(&chunk_size) || chunk_size > file_len - pos {
+                return Err(format!("Invalid fmt chunk size: {chunk_size} bytes").into());
+            }
+            let mut body = vec![0u8; chunk_size as usize];
+            reader.read_exact(&mut body)?;
+            fmt_chunk = Some(body);
+        } else if chunk_id == "fact" && chunk_size >= 4 {
+            fact_frames = Some(read_u32_le(&mut reader)? as u64);
+            reader.seek(SeekFrom::Current(chunk_size as i64 - 4))?;
+        } else if chunk_id == "data" {
+            let fmt_chunk = fmt_chunk.ok_or("WAV file has no fmt chunk before its data")?;
+            // A file still being recorded, or cut short, holds less data than
+            // its header says; use what is there
+            return Ok(WavLayout {
+                fmt_chunk,
+                data_offset: pos,
+                data_len: chunk_size.min(file_len - pos),
+                fact_frames,
+            });
+        } else {
+            reader.seek(SeekFrom::Current(chunk_size as i64))?;
+        }
+
+        let padded_size = chunk_size + chunk_size % 2;
+        if chunk_size % 2 == 1 {
+            reader.seek(SeekFrom::Current(1))?;
+        }
+        pos += padded_size;
+    }
+
+    Err("Data chunk not found".into())
+}
+
+/// Read the interleaved samples of a 64-bit float WAV
+pub fn read_float64_samples(path: &Path, layout: &WavLayout) -> Result<Vec<f64>, Box<dyn Error>> {
+    if !layout.is_float64() {
+        return Err("Not a 64-bit float WAV".into());
+    }
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(layout.data_offset))?;
+    let mut data = vec![0u8; layout.data_len as usize];
+    file.read_exact(&mut data)?;
+    Ok(data
+        .chunks_exact(8)
+        .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect())
+}
+
+/// Copy a run of sample frames into a new WAV with the source's format,
+/// byte for byte, so any sample layout survives unchanged
+pub fn write_wav_excerpt(
+    source: &Path,
+    dest: &Path,
+    layout: &WavLayout,
+    start_frame: u64,
+    frames: u64,
+) -> Result<(), Box<dyn Error>> {
+    let frames = frames.min(layout.frames().saturating_sub(start_frame));
+    let data_len = frames * layout.block_align();
+
+    let mut input = File::open(source)?;
+    input.seek(SeekFrom::Start(
+        layout.data_offset + start_frame * layout.block_align(),
+    ))?;
+    let mut data = vec![0u8; data_len as usize];
+    input.read_exact(&mut data)?;
+
+    let fmt_len = layout.fmt_chunk.len() as u64;
+    // Formats other than plain PCM carry a fact chunk with the frame count
+    let has_fact = layout.format_tag() != WAVE_FORMAT_PCM;
+    let riff_size =
+        4 + 8 + fmt_len + fmt_len % 2 + if has_fact { 12 } else { 0 } + 8 + data_len + data_len % 2;
+
+    let mut writer = BufWriter::new(File::create(dest)?);
+    writer.write_all(b"RIFF")?;
+    write_u32_le(&mut writer, riff_size as u32)?;
+    writer.write_all(b"WAVE")?;
+    writer.write_all(b"fmt ")?;
+    write_u32_le(&mut writer, fmt_len as u32)?;
+    writer.write_all(&layout.fmt_chunk)?;
+    if fmt_len % 2 == 1 {
+        writer.write_all(&[0])?;
+    }
+    if has_fact {
+        writer.write_all(b"fact")?;
+        write_u32_le(&mut writer, 4)?;
+        write_u32_le(&mut writer, frames as u32)?;
+    }
+    writer.write_all(b"data")?;
+    write_u32_le(&mut writer, data_len as u32)?;
+    writer.write_all(&data)?;
+    if data_len % 2 == 1 {
+        writer.write_all(&[0])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Size of the `smpl` chunk before its loop list
+const SMPL_HEADER_SIZE: usize = 36;
+/// Size of one loop record in the `smpl` chunk
+const SMPL_LOOP_SIZE: usize = 24;
+
 /// Read a 4-byte chunk ID
 fn read_fourcc(reader: &mut impl Read) -> Result<String, Box<dyn Error>> {
     let mut buf = [0u8; 4];
@@ -99,6 +384,8 @@ pub fn calculate_audio_md5(path: &Path) -> Result<String, Box<dyn Error>> {
         .into());
     }
 
+    check_riff_length(metadata.len())?;
+
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
 
@@ -108,10 +395,17 @@ pub fn calculate_audio_md5(path: &Path) -> Result<String, Box<dyn Error>> {
         return Err("Not a RIFF file".into());
     }
 
-    let file_size = read_u32_le(&mut reader)?;
+    let max_pos = riff_end(read_u32_le(&mut reader)?, metadata.len());
 
     // Security: Validate file size matches actual file
-    if file_size as u64 + 8 != metadata.len() {
+    if max_pos > metadata.len() {
+        return Err(format!(
+            "WAV file is truncated: header declares {max_pos} bytes but the file has {}",
+            metadata.len()
+        )
+        .into());
+    }
+    if max_pos != metadata.len() {
         return Err("Invalid RIFF size field".into());
     }
 
@@ -122,7 +416,6 @@ pub fn calculate_audio_md5(path: &Path) -> Result<String, Box<dyn Error>> {
 
     // Track position to prevent reading past file size
     let mut pos = 12u64; // Already read 12 bytes (RIFF + size + WAVE)
-    let max_pos = file_size as u64 + 8; // RIFF size doesn't include RIFF header
 
     // Find data chunk
     while pos < max_pos {
@@ -130,14 +423,18 @@ pub fn calculate_audio_md5(path: &Path) -> Result<String, Box<dyn Error>> {
             Ok(id) => id,
             Err(_) => break, // EOF reached
         };
-        let chunk_size = match read_u32_le(&mut reader) {
+        let mut chunk_size = match read_u32_le(&mut reader) {
             Ok(size) => size,
             Err(_) => break, // EOF reached
         };
         pos += 8;
+        if chunk_id == "data" && chunk_size == SIZE_UNKNOWN {
+            // Still being recorded: the audio runs to the end of the file
+            chunk_size = u32::try_from(max_pos - pos)?;
+        }
 
         // Security: Validate chunk size more thoroughly
-        if chunk_size > file_size {
+        if chunk_size as u64 > max_pos - 8 {
             return Err("Invalid chunk size: exceeds file size".into());
         }
         if chunk_size as u64 > max_pos - pos {
@@ -198,6 +495,7 @@ pub fn read_metadata(path: &Path) -> Result<Option<ZimMetadata>, Box<dyn Error>>
         )
         .into());
     }
+    check_riff_length(metadata.len())?;
 
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
@@ -208,7 +506,7 @@ pub fn read_metadata(path: &Path) -> Result<Option<ZimMetadata>, Box<dyn Error>>
         return Err("Not a RIFF file".into());
     }
 
-    let file_size = read_u32_le(&mut reader)?;
+    let max_pos = riff_end(read_u32_le(&mut reader)?, metadata.len());
     let wave_id = read_fourcc(&mut reader)?;
     if wave_id != "WAVE" {
         return Err("Not a WAVE file".into());
@@ -216,7 +514,6 @@ pub fn read_metadata(path: &Path) -> Result<Option<ZimMetadata>, Box<dyn Error>>
 
     // Track position to prevent reading past file size
     let mut pos = 12u64; // Already read 12 bytes (RIFF + size + WAVE)
-    let max_pos = file_size as u64 + 8; // RIFF size doesn't include RIFF header
 
     // Look for LIST INFO chunk
     while pos < max_pos {
@@ -231,7 +528,7 @@ pub fn read_metadata(path: &Path) -> Result<Option<ZimMetadata>, Box<dyn Error>>
         pos += 8;
 
         // Validate chunk size
-        if chunk_size > file_size {
+        if chunk_size as u64 > max_pos - 8 {
             return Err("Invalid chunk size".into());
         }
 
@@ -260,6 +557,238 @@ pub fn read_metadata(path: &Path) -> Result<Option<ZimMetadata>, Box<dyn Error>>
     Ok(None) // No INFO chunk found
 }
 
+/// Read the sampler loops from a WAV file's `smpl` chunk.
+/// Returns an empty list when the file has no `smpl` chunk.
+pub fn read_sample_loops(path: &Path) -> Result<Vec<SampleLoop>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    if read_fourcc(&mut reader)? != "RIFF" {
+        return Err("Not a RIFF file".into());
+    }
+    let _riff_size = read_u32_le(&mut reader)?;
+    if read_fourcc(&mut reader)? != "WAVE" {
+        return Err("Not a WAVE file".into());
+    }
+
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        let chunk_id = read_fourcc(&mut reader)?;
+        let chunk_size = read_u32_le(&mut reader)?;
+        pos += 8;
+
+        if chunk_size as u64 > file_len - pos {
+            return Err("Invalid chunk size: exceeds remaining file space".into());
+        }
+
+        if chunk_id == "smpl" {
+            // Security: Limit smpl chunk size
+            const MAX_SMPL_SIZE: u32 = 1024 * 1024;
+            if chunk_size > MAX_SMPL_SIZE {
+                return Err(format!("smpl chunk too large: {chunk_size} bytes").into());
+            }
+            let mut data = vec![0u8; chunk_size as usize];
+            reader.read_exact(&mut data)?;
+            return parse_smpl_chunk(&data);
+        }
+
+        let padded_size = chunk_size as u64 + (chunk_size % 2) as u64;
+        reader.seek(SeekFrom::Current(padded_size as i64))?;
+        pos += padded_size;
+    }
+
+    Ok(Vec::new())
+}
+
+fn le_u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// Parse the loop records from the body of a `smpl` chunk
+fn parse_smpl_chunk(data: &[u8]) -> Result<Vec<SampleLoop>, Box<dyn Error>> {
+    if data.len() < SMPL_HEADER_SIZE {
+        return Err("smpl chunk too short".into());
+    }
+
+    let loop_count = le_u32_at(data, 28) as usize;
+    let available = (data.len() - SMPL_HEADER_SIZE) / SMPL_LOOP_SIZE;
+    if loop_count > available {
+        return Err(format!("smpl chunk lists {loop_count} loops but holds {available}").into());
+    }
+
+    Ok((0..loop_count)
+        .map(|i| {
+            let record = SMPL_HEADER_SIZE + i * SMPL_LOOP_SIZE;
+            SampleLoop {
+                start: le_u32_at(data, record + 8),
+                end: le_u32_at(data, record + 12),
+            }
+        })
+        .filter(|sample_loop| sample_loop.start <= sample_loop.end)
+        .collect())
+}
+
+/// Write a `smpl` chunk with a single forward loop into a WAV file in place,
+/// replacing any existing `smpl` chunk
+pub fn write_sample_loop(path: &Path, sample_loop: SampleLoop) -> Result<(), Box<dyn Error>> {
+    let wav_data = std::fs::read(path)?;
+    if wav_data.len() < 12 || &wav_data[0..4] != b"RIFF" || &wav_data[8..12] != b"WAVE" {
+        return Err("Not a valid WAV file".into());
+    }
+
+    let mut output = Vec::with_capacity(wav_data.len() + 8 + SMPL_HEADER_SIZE + SMPL_LOOP_SIZE);
+    output.extend_from_slice(&wav_data[0..12]);
+
+    let mut sample_rate = None;
+    let mut pos = 12;
+    while pos + 8 <= wav_data.len() {
+        let chunk_id = &wav_data[pos..pos + 4];
+        let chunk_size = le_u32_at(&wav_data, pos + 4) as usize;
+        let chunk_end = pos + 8 + chunk_size;
+        if chunk_end > wav_data.len() {
+            return Err("Invalid chunk size: exceeds file size".into());
+        }
+        let padded_end = (chunk_end + chunk_size % 2).min(wav_data.len());
+
+        if chunk_id == b"fmt " && chunk_size >= 8 {
+            sample_rate = Some(le_u32_at(&wav_data, pos + 12));
+        }
+        if chunk_id != b"smpl" {
+            output.extend_from_slice(&wav_data[pos..padded_end]);
+        }
+        pos = padded_end;
+    }
+
+    let sample_rate = sample_rate.ok_or("WAV file has no fmt chunk")?;
+    output.extend(create_smpl_chunk(sample_loop, sample_rate));
+
+    let riff_size = (output.len() - 8) as u32;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    replace_file(path, &output)
+}
+
+/// Replace a WAV file's contents by writing a temp file beside it and
+/// renaming it over the original, so a failed write never truncates the audio
+fn replace_file(path: &Path, data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let temp_path = path.with_extension("wav.tmp");
+    if let Err(e) = std::fs::write(&temp_path, data) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Write notes into a WAV file's INFO chunk in place, replacing any notes
+/// already there and keeping its other INFO fields, such as ZIM lineage
+pub fn write_info_notes(path: &Path, notes: &InfoNotes) -> Result<(), Box<dyn Error>> {
+    let wav_data = std::fs::read(path)?;
+    check_riff_length(wav_data.len() as u64)?;
+    if &wav_data[0..4] != b"RIFF" || &wav_data[8..12] != b"WAVE" {
+        return Err("Not a valid WAV file".into());
+    }
+
+    let mut output = Vec::with_capacity(wav_data.len() + 256);
+    output.extend_from_slice(&wav_data[0..12]);
+
+    let mut info_data = Vec::new();
+    let mut pos = 12;
+    while pos + 8 <= wav_data.len() {
+        let chunk_id = &wav_data[pos..pos + 4];
+        let chunk_size = le_u32_at(&wav_data, pos + 4) as usize;
+        let chunk_end = pos + 8 + chunk_size;
+        if chunk_end > wav_data.len() {
+            return Err("Invalid chunk size: exceeds file size".into());
+        }
+        let padded_end = (chunk_end + chunk_size % 2).min(wav_data.len());
+
+        if chunk_id == b"LIST" && chunk_size >= 4 && &wav_data[pos + 8..pos + 12] == b"INFO" {
+            // Keep the fields that aren't notes; the chunk is rewritten at the end
+            let mut field_pos = pos + 12;
+            while field_pos + 8 <= chunk_end {
+                let field_id = &wav_data[field_pos..field_pos + 4];
+                let field_size = le_u32_at(&wav_data, field_pos + 4) as usize;
+                let field_end = (field_pos + 8 + field_size + field_size % 2).min(chunk_end);
+                if !InfoNotes::FIELD_IDS
+                    .iter()
+                    .any(|id| id.as_slice() == field_id)
+                {
+                    info_data.extend_from_slice(&wav_data[field_pos..field_end]);
+                }
+                field_pos = field_end;
+            }
+        } else {
+            output.extend_from_slice(&wav_data[pos..padded_end]);
+        }
+        pos = padded_end;
+    }
+
+    for (id, text) in notes.fields() {
+        info_data.extend(info_field(id, &text));
+    }
+    if !info_data.is_empty() {
+        output.extend(b"LIST");
+        output.extend(&((info_data.len() + 4) as u32).to_le_bytes());
+        output.extend(b"INFO");
+        output.extend(info_data);
+    }
+
+    let riff_size = (output.len() - 8) as u32;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    replace_file(path, &output)
+}
+
+/// Encode one INFO sub-chunk, padded to an even length
+fn info_field(id: &[u8; 4], text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut field = Vec::with_capacity(8 + bytes.len() + 1);
+    field.extend(id);
+    field.extend(&(bytes.len() as u32).to_le_bytes());
+    field.extend(bytes);
+    if bytes.len() % 2 == 1 {
+        field.push(0); // Pad byte
+    }
+    field
+}
+
+/// Create a `smpl` chunk holding one infinitely repeating forward loop
+fn create_smpl_chunk(sample_loop: SampleLoop, sample_rate: u32) -> Vec<u8> {
+    let sample_period = 1_000_000_000 / sample_rate.max(1); // nanoseconds per frame
+    let fields: [u32; 15] = [
+        0,             // manufacturer
+        0,             // product
+        sample_period, // sample period
+        60,            // MIDI unity note (middle C)
+        0,             // MIDI pitch fraction
+        0,             // SMPTE format
+        0,             // SMPTE offset
+        1,             // number of loops
+        0,             // sampler data size
+        0,             // loop cue point ID
+        0,             // loop type: forward
+        sample_loop.start,
+        sample_loop.end,
+        0, // fraction
+        0, // play count: infinite
+    ];
+
+    let mut chunk = Vec::with_capacity(8 + fields.len() * 4);
+    chunk.extend(b"smpl");
+    chunk.extend(&((fields.len() * 4) as u32).to_le_bytes());
+    for field in fields {
+        chunk.extend(&field.to_le_bytes());
+    }
+    chunk
+}
+
 /// Parse INFO chunk to extract ZIM metadata
 fn parse_info_chunk(
     reader: &mut impl Read,
@@ -345,6 +874,7 @@ pub fn write_metadata(
     input_file.read_to_end(&mut wav_data)?;
 
     // Verify it's a RIFF WAVE file
+    check_riff_length(wav_data.len() as u64)?;
     if &wav_data[0..4] != b"RIFF" || &wav_data[8..12] != b"WAVE" {
         return Err("Not a valid WAV file".into());
     }
@@ -357,7 +887,7 @@ pub fn write_metadata(
     let mut data_chunk_pos = None;
     let mut chunks_before_data = Vec::new();
 
-    while pos < wav_data.len() - 8 {
+    while pos + 8 <= wav_data.len() {
         let chunk_id = &wav_data[pos..pos + 4];
         let chunk_size = u32::from_le_bytes([
             wav_data[pos + 4],
@@ -366,6 +896,17 @@ pub fn write_metadata(
             wav_data[pos + 7],
         ]);
 
+        // A cut-off file declares more bytes than it holds. Audio still being
+        // recorded has an unknown size and runs to the end of the file.
+        let streaming = chunk_id == b"data" && chunk_size == SIZE_UNKNOWN;
+        if !streaming && pos + 8 + chunk_size as usize > wav_data.len() {
+            return Err(format!(
+                "WAV file is truncated: '{}' chunk extends past the end of the file",
+                String::from_utf8_lossy(chunk_id)
+            )
+            .into());
+        }
+
         if chunk_id == b"data" {
             data_chunk_pos = Some(pos);
             break;
@@ -461,26 +1002,9 @@ fn create_info_chunk(metadata: &ZimMetadata) -> Result<Vec<u8>, Box<dyn Error>>
     // Also add human-readable fields
     let software = metadata.zim_version.to_string();
 
-    // Build INFO sub-chunks
-    let mut info_data = Vec::new();
-
-    // ISFT (Software)
-    info_data.extend(b"ISFT");
-    let software_bytes = software.as_bytes();
-    info_data.extend(&(software_bytes.len() as u32).to_le_bytes());
-    info_data.extend(software_bytes);
-    if software_bytes.len() % 2 == 1 {
-        info_data.push(0); // Pad byte
-    }
-
-    // ICMT (Comment with ZIM data)
-    info_data.extend(b"ICMT");
-    let zim_bytes = zim_data.as_bytes();
-    info_data.extend(&(zim_bytes.len() as u32).to_le_bytes());
-    info_data.extend(zim_bytes);
-    if zim_bytes.len() % 2 == 1 {
-        info_data.push(0); // Pad byte
-    }
+    // Build INFO sub-chunks: ISFT (Software) and ICMT (Comment with ZIM data)
+    let mut info_data = info_field(b"ISFT", &software);
+    info_data.extend(info_field(b"ICMT", &zim_data));
 
     // Build LIST chunk
     chunk.extend(b"LIST");
@@ -494,6 +1018,7 @@ fn create_info_chunk(metadata: &ZimMetadata) -> Result<Vec<u8>, Box<dyn Error>>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{pcm16, write_wav};
     use tempfile::tempdir;
 
     #[test]
@@ -521,18 +1046,239 @@ mod tests {
         assert_eq!(read_metadata.audio_md5, metadata.audio_md5);
     }
 
-    fn create_test_wav(path: &Path) {
-        use hound;
-        let spec = hound::WavSpec {
-            channels: 1,
-            sample_rate: 44100,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
+    #[test]
+    fn test_sample_loop_roundtrip() {
+        let dir = tempdir().unwrap();
+        let wav = dir.path().join("loop.wav");
+        create_test_wav(&wav);
+        assert!(read_sample_loops(&wav).unwrap().is_empty());
+
+        write_sample_loop(&wav, SampleLoop { start: 10, end: 89 }).unwrap();
+        // Writing again replaces rather than adds a chunk
+        write_sample_loop(&wav, SampleLoop { start: 0, end: 99 }).unwrap();
+        assert_eq!(
+            read_sample_loops(&wav).unwrap(),
+            vec![SampleLoop { start: 0, end: 99 }]
+        );
+
+        // The file is still a valid WAV with its audio intact, and the temp
+        // file it was written through is gone
+        let reader = hound::WavReader::open(&wav).unwrap();
+        assert_eq!(reader.duration(), 100);
+        assert!(!wav.with_extension("wav.tmp").exists());
+        let riff_size = u32::from_le_bytes(std::fs::read(&wav).unwrap()[4..8].try_into().unwrap());
+        assert_eq!(riff_size as u64 + 8, std::fs::metadata(&wav).unwrap().len());
+
+        // Tagging keeps the loop
+        let tagged = dir.path().join("tagged.wav");
+        let metadata = ZimMetadata::new_original("test-project", &wav);
+        write_metadata(&wav, &tagged, &metadata).unwrap();
+        assert_eq!(read_sample_loops(&tagged).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_empty_and_truncated_files_fail_cleanly() {
+        let dir = tempdir().unwrap();
+        let output = dir.path().join("out.wav");
+        let metadata = ZimMetadata::new_original("test-project", &output);
+
+        let empty = dir.path().join("empty.wav");
+        std::fs::write(&empty, []).unwrap();
+        for result in [
+            calculate_audio_md5(&empty).map(|_| ()),
+            read_metadata(&empty).map(|_| ()),
+            write_metadata(&empty, &output, &metadata),
+        ] {
+            assert!(result.unwrap_err().to_string().contains("too short"));
+        }
+
+        let full = dir.path().join("full.wav");
+        create_test_wav(&full);
+        let bytes = std::fs::read(&full).unwrap();
+        let truncated = dir.path().join("truncated.wav");
+        std::fs::write(&truncated, &bytes[..bytes.len() - 51]).unwrap();
+        assert!(
+            calculate_audio_md5(&truncated)
+                .unwrap_err()
+                .to_string()
+                .contains("truncated")
+        );
+        assert!(
+            write_metadata(&truncated, &output, &metadata)
+                .unwrap_err()
+                .to_string()
+                .contains("truncated")
+        );
+    }
+
+    #[test]
+    fn test_streaming_riff_size_uses_file_length() {
+        let dir = tempdir().unwrap();
+        let full = dir.path().join("full.wav");
+        create_test_wav(&full);
+        let mut bytes = std::fs::read(&full).unwrap();
+        bytes[4..8].copy_from_slice(&SIZE_UNKNOWN.to_le_bytes());
+        let streamed = dir.path().join("streamed.wav");
+        std::fs::write(&streamed, &bytes).unwrap();
+
+        assert_eq!(
+            calculate_audio_md5(&streamed).unwrap(),
+            calculate_audio_md5(&full).unwrap()
+        );
+        assert!(read_metadata(&streamed).is_ok());
+
+        // The data chunk's size can be a placeholder too
+        let data_size_at = bytes.windows(4).position(|w| w == b"data").unwrap() + 4;
+        bytes[data_size_at..data_size_at + 4].copy_from_slice(&SIZE_UNKNOWN.to_le_bytes());
+        std::fs::write(&streamed, &bytes).unwrap();
+        assert_eq!(
+            calculate_audio_md5(&streamed).unwrap(),
+            calculate_audio_md5(&full).unwrap()
+        );
+        let tagged = dir.path().join("tagged.wav");
+        let metadata = ZimMetadata::new_original("test-project", &streamed);
+        write_metadata(&streamed, &tagged, &metadata).unwrap();
+        assert!(read_metadata(&tagged).unwrap().is_some());
+    }
+
+    /// Text of every INFO field, in file order
+    fn read_info_fields(path: &Path) -> Vec<(String, String)> {
+        let data = std::fs::read(path).unwrap();
+        let mut fields = Vec::new();
+        let mut pos = 12;
+        while pos + 8 <= data.len() {
+            let size = le_u32_at(&data, pos + 4) as usize;
+            if &data[pos..pos + 4] == b"LIST" && &data[pos + 8..pos + 12] == b"INFO" {
+                let mut field_pos = pos + 12;
+                while field_pos + 8 <= pos + 8 + size {
+                    let field_size = le_u32_at(&data, field_pos + 4) as usize;
+                    let text = &data[field_pos + 8..field_pos + 8 + field_size];
+                    fields.push((
+                        String::from_utf8_lossy(&data[field_pos..field_pos + 4]).to_string(),
+                        String::from_utf8_lossy(text).to_string(),
+                    ));
+                    field_pos += 8 + field_size + field_size % 2;
+                }
+            }
+            pos += 8 + size + size % 2;
+        }
+        fields
+    }
+
+    #[test]
+    fn test_write_info_notes_keeps_lineage() {
+        let dir = tempdir().unwrap();
+        let untagged = dir.path().join("untagged.wav");
+        let wav = dir.path().join("chorus.wav");
+        create_test_wav(&untagged);
+        let metadata = ZimMetadata::new_original("album", &untagged);
+        write_metadata(&untagged, &wav, &metadata).unwrap();
+
+        let notes = InfoNotes {
+            title: Some("Chorus".to_string()),
+            description: Some("Big lift".to_string()),
+            tags: vec!["vocals".to_string(), "hook".to_string()],
         };
-        let mut writer = hound::WavWriter::create(path, spec).unwrap();
-        for _ in 0..100 {
-            writer.write_sample(0i16).unwrap();
+        write_info_notes(&wav, &notes).unwrap();
+        // Writing again replaces the notes rather than adding more
+        write_info_notes(&wav, &notes).unwrap();
+
+        let fields = read_info_fields(&wav);
+        let ids: Vec<&str> = fields.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["ISFT", "ICMT", "INAM", "ISBJ", "IKEY"]);
+        assert_eq!(fields[4].1, "vocals; hook");
+        assert_eq!(read_metadata(&wav).unwrap().unwrap().uuid, metadata.uuid);
+
+        // Audio is untouched and the RIFF size matches
+        assert_eq!(
+            calculate_audio_md5(&wav).unwrap(),
+            calculate_audio_md5(&untagged).unwrap()
+        );
+        assert_eq!(hound::WavReader::open(&wav).unwrap().duration(), 100);
+        assert!(!wav.with_extension("wav.tmp").exists());
+
+        // Notes on an untagged file
+        write_info_notes(&untagged, &notes).unwrap();
+        assert_eq!(read_info_fields(&untagged).len(), 3);
+        assert!(InfoNotes::default().is_empty());
+    }
+
+    /// A WAV of 64-bit IEEE float samples, which hound can't write
+    fn create_float64_wav(path: &Path, channels: u16, samples: &[f64]) {
+        let data_len = samples.len() as u32 * 8;
+        let mut wav = Vec::new();
+        wav.extend(b"RIFF");
+        wav.extend((4 + 24 + 8 + data_len).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(16u32.to_le_bytes());
+        wav.extend(WAVE_FORMAT_IEEE_FLOAT.to_le_bytes());
+        wav.extend(channels.to_le_bytes());
+        wav.extend(48000u32.to_le_bytes());
+        wav.extend((48000 * 8 * channels as u32).to_le_bytes());
+        wav.extend((8 * channels).to_le_bytes());
+        wav.extend(64u16.to_le_bytes());
+        wav.extend(b"data");
+        wav.extend(data_len.to_le_bytes());
+        for sample in samples {
+            wav.extend(sample.to_le_bytes());
         }
-        writer.finalize().unwrap();
+        std::fs::write(path, wav).unwrap();
+    }
+
+    #[test]
+    fn test_float64_excerpt() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("float64.wav");
+        let samples: Vec<f64> = (0..20).map(|i| i as f64 / 20.0 - 0.5).collect();
+        create_float64_wav(&source, 2, &samples);
+
+        let layout = read_wav_layout(&source).unwrap();
+        assert!(layout.is_float64());
+        assert_eq!(layout.channels(), 2);
+        assert_eq!(layout.sample_rate(), 48000);
+        assert_eq!(layout.frames(), 10);
+        assert_eq!(read_float64_samples(&source, &layout).unwrap(), samples);
+
+        // Frames 2..6, then a run past the end is cut short
+        let excerpt = dir.path().join("excerpt.wav");
+        write_wav_excerpt(&source, &excerpt, &layout, 2, 4).unwrap();
+        let excerpt_layout = read_wav_layout(&excerpt).unwrap();
+        assert_eq!(excerpt_layout.frames(), 4);
+        assert_eq!(
+            read_float64_samples(&excerpt, &excerpt_layout).unwrap(),
+            &samples[4..12]
+        );
+        let bytes = std::fs::read(&excerpt).unwrap();
+        assert_eq!(le_u32_at(&bytes, 4) as usize + 8, bytes.len());
+        assert_eq!(&bytes[36..40], b"fact");
+
+        write_wav_excerpt(&source, &excerpt, &layout, 8, 100).unwrap();
+        assert_eq!(read_wav_layout(&excerpt).unwrap().frames(), 2);
+
+        // Integer PCM needs no fact chunk
+        let pcm = dir.path().join("pcm.wav");
+        create_test_wav(&pcm);
+        let pcm_layout = read_wav_layout(&pcm).unwrap();
+        assert!(!pcm_layout.is_float64());
+        write_wav_excerpt(&pcm, &excerpt, &pcm_layout, 0, 50).unwrap();
+        assert_eq!(hound::WavReader::open(&excerpt).unwrap().duration(), 50);
+    }
+
+    #[test]
+    fn test_parse_smpl_chunk() {
+        let chunk = create_smpl_chunk(SampleLoop { start: 5, end: 4 }, 44100);
+        assert_eq!(le_u32_at(&chunk, 8 + 8), 22675);
+        // A loop that ends before it starts is ignored
+        assert!(parse_smpl_chunk(&chunk[8..]).unwrap().is_empty());
+
+        // A loop count larger than the chunk holds is an error
+        let mut truncated = chunk[8..8 + SMPL_HEADER_SIZE].to_vec();
+        truncated[28] = 2;
+        assert!(parse_smpl_chunk(&truncated).is_err());
+        assert!(parse_smpl_chunk(&[0u8; 10]).is_err());
+    }
+
+    fn create_test_wav(path: &Path) {
+        write_wav(path, pcm16(1, 44100), [0i16; 100]);
     }
 }
@@ -3,10 +3,18 @@
 //! This module provides functionality to parse .zimignore files and check
 //! whether files or directories should be ignored during zim update operations.
 //! The syntax is similar to .gitignore with support for glob patterns.
+//!
+//! Patterns from the global ignore file in the config directory
+//! (`global.zimignore`) apply to every project and are loaded first, so
+//! project `.zimignore` files can override them.
+//!
+//! The same pattern syntax backs [`PathFilter`], the one-off `--include` and
+//! `--exclude` filters accepted by commands that walk a project.
 
+use crate::config::Config;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// A compiled .zimignore pattern
 #[derive(Debug, Clone)]
@@ -187,11 +195,25 @@ impl ZimIgnore {
         should_ignore
     }
 
-    /// Load .zimignore files hierarchically from a directory up to the root
+    /// Load the global ignore file followed by .zimignore files hierarchically
+    /// from a directory up to the root
     pub fn load_for_directory<P: AsRef<Path>>(dir: P) -> Self {
+        let global_path = Config::global_zimignore_path().ok();
+        Self::load_with_global(dir, global_path.as_deref())
+    }
+
+    /// Load .zimignore files for a directory on top of an optional global file.
+    /// Global patterns come first so project patterns take precedence.
+    pub fn load_with_global<P: AsRef<Path>>(dir: P, global_path: Option<&Path>) -> Self {
         let mut combined = ZimIgnore::new();
         let dir = dir.as_ref();
 
+        if let Some(global_path) = global_path
+            && let Ok(global) = ZimIgnore::from_file(global_path)
+        {
+            combined.extend(&global);
+        }
+
         // Walk up the directory tree looking for .zimignore files
         let mut current = Some(dir);
         let mut zimignore_files = Vec::new();
@@ -216,6 +238,57 @@ impl ZimIgnore {
     }
 }
 
+/// One-off include/exclude filter for a single command run.
+///
+/// Patterns use the .zimignore syntax and match paths relative to the project
+/// root. A pattern matching a directory matches every file beneath it.
+#[derive(Debug, Default)]
+pub struct PathFilter {
+    root: PathBuf,
+    include: Vec<IgnorePattern>,
+    exclude: Vec<IgnorePattern>,
+}
+
+impl PathFilter {
+    pub fn new<P: AsRef<Path>>(root: P, include: &[String], exclude: &[String]) -> Self {
+        let parse = |patterns: &[String]| {
+            patterns
+                .iter()
+                .filter_map(|p| IgnorePattern::parse(p))
+                .collect()
+        };
+
+        Self {
+            root: root.as_ref().to_path_buf(),
+            include: parse(include),
+            exclude: parse(exclude),
+        }
+    }
+
+    /// True when no patterns were given, so every file is kept
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Check whether a file under the root passes the filter. A file is kept
+    /// if it matches any include pattern (or there are none) and no exclude.
+    pub fn matches(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let hits = |patterns: &[IgnorePattern]| {
+            patterns.iter().any(|pattern| {
+                pattern.matches(relative, false)
+                    || relative
+                        .ancestors()
+                        .skip(1)
+                        .filter(|dir| !dir.as_os_str().is_empty())
+                        .any(|dir| pattern.matches(dir, true))
+            })
+        };
+
+        (self.include.is_empty() || hits(&self.include)) && !hits(&self.exclude)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,4 +371,80 @@ project/live/
         assert!(pattern.matches(&PathBuf::from("project/live"), true));
         assert!(pattern.matches(&PathBuf::from("other/project/live"), true));
     }
+
+    #[test]
+    fn test_global_patterns_merge_with_project() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let global_path = temp_dir.path().join("global.zimignore");
+        fs::write(&global_path, ".DS_Store\n*.asd\n").unwrap();
+
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(project.join(".zimignore"), "*.als\n").unwrap();
+
+        let zimignore = ZimIgnore::load_with_global(&project, Some(&global_path));
+        assert!(zimignore.is_ignored(&PathBuf::from(".DS_Store"), false));
+        assert!(zimignore.is_ignored(&PathBuf::from("sources/kick.wav.asd"), false));
+        assert!(zimignore.is_ignored(&PathBuf::from("song.als"), false));
+        assert!(!zimignore.is_ignored(&PathBuf::from("song.wav"), false));
+
+        // A missing global file is not an error
+        let missing = temp_dir.path().join("missing.zimignore");
+        let zimignore = ZimIgnore::load_with_global(&project, Some(&missing));
+        assert!(!zimignore.is_ignored(&PathBuf::from(".DS_Store"), false));
+        assert!(zimignore.is_ignored(&PathBuf::from("song.als"), false));
+    }
+
+    #[test]
+    fn test_project_patterns_override_global() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let global_path = temp_dir.path().join("global.zimignore");
+        fs::write(&global_path, "*.asd\n!keep.wav\n").unwrap();
+
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(project.join(".zimignore"), "!important.asd\n*.wav\n").unwrap();
+
+        let zimignore = ZimIgnore::load_with_global(&project, Some(&global_path));
+
+        // Project negation re-includes a globally ignored file
+        assert!(zimignore.is_ignored(&PathBuf::from("other.asd"), false));
+        assert!(!zimignore.is_ignored(&PathBuf::from("important.asd"), false));
+
+        // A global negation cannot re-include a file the project ignores
+        assert!(zimignore.is_ignored(&PathBuf::from("keep.wav"), false));
+    }
+
+    #[test]
+    fn test_path_filter() {
+        let root = PathBuf::from("project");
+        let filter = PathFilter::new(&root, &[], &[]);
+        assert!(filter.is_empty());
+        assert!(filter.matches(&root.join("sources/kick.wav")));
+
+        let filter = PathFilter::new(&root, &["masters/".to_string()], &[]);
+        assert!(filter.matches(&root.join("masters/final.wav")));
+        assert!(filter.matches(&root.join("masters/alt/final.wav")));
+        assert!(!filter.matches(&root.join("sources/kick.wav")));
+
+        let filter = PathFilter::new(&root, &[], &["sources".to_string()]);
+        assert!(filter.matches(&root.join("masters/final.wav")));
+        assert!(!filter.matches(&root.join("sources/kick.wav")));
+    }
+
+    #[test]
+    fn test_path_filter_multiple_patterns() {
+        let root = PathBuf::from("project");
+        let filter = PathFilter::new(
+            &root,
+            &["masters/**".to_string(), "*.flac".to_string()],
+            &["*_old.*".to_string(), "/mixes/rough".to_string()],
+        );
+        assert!(filter.matches(&root.join("masters/final.wav")));
+        assert!(filter.matches(&root.join("sources/kick.flac")));
+        assert!(!filter.matches(&root.join("sources/kick.wav")));
+        assert!(!filter.matches(&root.join("masters/final_old.wav")));
+        assert!(!filter.matches(&root.join("mixes/rough/take.flac")));
+        assert!(filter.matches(&root.join("mixes/final/take.flac")));
+    }
 }
@@ -0,0 +1,39 @@
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Run `zim tree` on a small project and return its stdout
+fn tree_output(temp_dir: &TempDir, args: &[&str], no_color_env: bool) -> String {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_zim"));
+    command
+        .args(args)
+        .arg("tree")
+        .arg(temp_dir.path().join("project"))
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("HOME", temp_dir.path())
+        .env_remove("NO_COLOR");
+    if no_color_env {
+        command.env("NO_COLOR", "1");
+    }
+    let output = command.output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_no_color_output_has_no_ansi_escapes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mixes = temp_dir.path().join("project/mixes");
+    std::fs::create_dir_all(&mixes).unwrap();
+    std::fs::write(mixes.join("v1.wav"), b"").unwrap();
+
+    let colored = tree_output(&temp_dir, &[], false);
+    assert!(colored.contains('\x1b'));
+
+    for output in [
+        tree_output(&temp_dir, &["--no-color"], false),
+        tree_output(&temp_dir, &[], true),
+    ] {
+        assert!(!output.contains('\x1b'), "{output:?}");
+        assert!(output.contains("└── v1.wav  (no sidecar)"));
+    }
+}